@@ -0,0 +1,78 @@
+//! 读取系统剪贴板中的文本，用于跳转到从其他工具中复制来的时间戳
+
+use crate::app::{DisplayTimezone, TimeMatcher};
+use chrono::{DateTime, FixedOffset};
+use std::process::Command;
+
+/// 依次尝试的读取剪贴板的命令，分别对应 Wayland、X11 以及 macOS
+const PASTE_COMMANDS: [&[&str]; 4] = [
+  &["wl-paste", "--no-newline"],
+  &["xclip", "-selection", "clipboard", "-o"],
+  &["xsel", "--clipboard", "--output"],
+  &["pbpaste"],
+];
+
+/// 可以读出文本的剪贴板
+pub trait Clipboard {
+  /// 读出剪贴板中的文本，无法读取时返回错误信息
+  fn read_text(&mut self) -> Result<String, String>;
+}
+
+/// 通过外部命令读取的系统剪贴板，不引入额外的依赖
+#[derive(Default)]
+pub struct SystemClipboard;
+
+impl Clipboard for SystemClipboard {
+  fn read_text(&mut self) -> Result<String, String> {
+    for args in PASTE_COMMANDS {
+      if let Ok(output) = Command::new(args[0]).args(&args[1..]).output()
+        && output.status.success()
+      {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+      }
+    }
+    Err("cannot read the clipboard, install wl-paste, xclip, xsel or pbpaste".to_string())
+  }
+}
+
+/// 将剪贴板中的文本解析为时间点：优先按 RFC3339 解析，与日志中的时间戳一致，
+/// 否则按时间戳搜索中的时间点格式理解（见 `TimeMatcher::parse_timepoint`）
+pub fn parse_timestamp(
+  text: &str,
+  display_tz: &DisplayTimezone,
+) -> Result<DateTime<FixedOffset>, String> {
+  let text = text.trim();
+  if text.is_empty() {
+    return Err("the clipboard is empty".to_string());
+  }
+
+  DateTime::parse_from_rfc3339(text)
+    .or_else(|_| DateTime::parse_from_rfc3339(&text.replacen(' ', "T", 1)))
+    .or_else(|_| TimeMatcher::with_timezone(*display_tz).parse_timepoint(text))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_timestamp() {
+    let tz: DisplayTimezone = "+08:00".parse().unwrap();
+    let parse = |s: &str| parse_timestamp(s, &tz).map(|dt| dt.to_rfc3339());
+
+    assert_eq!(
+      parse("2026-01-17T10:22:55.642782+00:00\n"),
+      Ok("2026-01-17T10:22:55.642782+00:00".to_string())
+    );
+    assert_eq!(
+      parse("2026-01-17 10:22:55+00:00"),
+      Ok("2026-01-17T10:22:55+00:00".to_string())
+    );
+    assert_eq!(
+      parse("2026.01.17 10:22"),
+      Ok("2026-01-17T10:22:00+08:00".to_string())
+    );
+    assert!(parse("  ").is_err());
+    assert!(parse("not a timestamp").is_err());
+  }
+}
@@ -0,0 +1,53 @@
+//! 界面的配色方案，集中管理各个页面渲染时使用的颜色
+
+use crate::{app::page::log_page, ui::pager::Theme as PagerTheme};
+use std::str::FromStr;
+
+/// 配色方案，启动时选定，随后传递给各个页面的渲染流程
+#[derive(Copy, Clone, Debug)]
+pub struct ColorScheme {
+  /// 页面整体的风格，包括背景、边框、光标以及状态栏
+  pub pager_theme: PagerTheme,
+
+  /// 日志页面中各项内容的颜色
+  pub log_colors: log_page::Colors,
+}
+
+impl Default for ColorScheme {
+  fn default() -> Self {
+    Self::dark()
+  }
+}
+
+impl ColorScheme {
+  /// 适用于深色背景终端的配色，也即本程序原有的配色
+  pub fn dark() -> Self {
+    Self {
+      pager_theme: PagerTheme::default(),
+      log_colors: log_page::Colors::default(),
+    }
+  }
+
+  /// 适用于浅色背景终端的配色
+  pub fn light() -> Self {
+    Self {
+      pager_theme: PagerTheme::light(),
+      log_colors: log_page::Colors::light(),
+    }
+  }
+}
+
+impl FromStr for ColorScheme {
+  type Err = String;
+
+  /// 按名称选择内置的配色方案
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "dark" => Ok(Self::dark()),
+      "light" => Ok(Self::light()),
+      _ => Err(format!(
+        "unknown color scheme '{s}', expected 'dark' or 'light'"
+      )),
+    }
+  }
+}
@@ -0,0 +1,310 @@
+//! 命令面板中输入的命令，如 `tag systemd`、`export out.log`、`goto 10:30`、`level error`、`pid 1234`，
+//! 它们被翻译为日志展示区已有的控制，免去记忆按键
+
+use crate::{
+  app::{DisplayTimezone, controller::LogController},
+  log::{Exclusion, Label, LogLine, PidFilter},
+  ui::ViewPortEx,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use std::{path::PathBuf, str::FromStr};
+
+/// 只有时间的跳转目标依次尝试的格式，日期取已加载日志中光标处那条日志的日期
+const TIME_FORMATS: [&str; 2] = ["%H:%M:%S", "%H:%M"];
+
+/// 带有日期的跳转目标依次尝试的格式
+const DATE_TIME_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"];
+
+/// 跳转命令的目标
+#[derive(Debug, PartialEq)]
+pub enum GotoTarget {
+  /// 已加载日志所在那天的某个时间
+  Time(NaiveTime),
+
+  /// 某天的某个时间
+  DateTime(NaiveDateTime),
+
+  /// 已加载日志中的相对位置，范围为 \[0, 1]
+  Fraction(f64),
+}
+
+/// 命令面板中的一条命令
+#[derive(Debug, PartialEq)]
+pub enum Command {
+  /// 将日志展示区锁定到给定的标签，没有给出标签时取消锁定
+  Tag(Option<String>),
+
+  /// 将已加载的、未被过滤的日志导出到文件
+  Export(PathBuf),
+
+  /// 跳转到给定的时间或者相对位置
+  Goto(GotoTarget),
+
+  /// 只展示不低于给定等级的日志，`Label::Debug` 表示展示所有日志
+  Level(Label),
+
+  /// 隐藏包含给定内容的行，形如 `/REGEX/` 时隐藏匹配该正则表达式的行，没有给出内容时取消所有的排除
+  Exclude(Option<String>),
+
+  /// 只展示给定进程号（或进程号区间）的日志，没有给出时取消过滤
+  Pid(Option<PidFilter>),
+}
+
+impl FromStr for Command {
+  type Err = String;
+
+  /// 解析形如 `name args` 的命令，可以带有前导的 `:`
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let s = s.trim();
+    let s = s.strip_prefix(':').unwrap_or(s).trim_start();
+    let (name, arg) = match s.split_once(char::is_whitespace) {
+      None => (s, ""),
+      Some((name, arg)) => (name, arg.trim()),
+    };
+
+    match (name, arg) {
+      ("", _) => Err("empty command".to_string()),
+      ("tag", "") => Ok(Command::Tag(None)),
+      ("tag", tag) => Ok(Command::Tag(Some(tag.to_string()))),
+      ("export", "") => Err("'export' needs a file path, e.g. 'export out.log'".to_string()),
+      ("export", path) => Ok(Command::Export(PathBuf::from(path))),
+      ("goto", target) => parse_goto_target(target).map(Command::Goto).ok_or(format!(
+        "cannot go to '{target}', expected e.g. '10:30', '2026-01-17 10:30' or '50%'"
+      )),
+      ("level", level) => parse_level(level).map(Command::Level).ok_or(format!(
+        "unknown level '{level}', expected 'error', 'warn' or 'all'"
+      )),
+      ("exclude", "") => Ok(Command::Exclude(None)),
+      ("exclude", text) => text
+        .parse::<Exclusion>()
+        .map(|_| Command::Exclude(Some(text.to_string()))),
+      ("pid", "") => Ok(Command::Pid(None)),
+      ("pid", pid) => pid
+        .parse()
+        .map(|pid| Command::Pid(Some(pid)))
+        .map_err(|e| format!("{e}, expected e.g. '1234' or '100-200'")),
+      (name, _) => Err(format!(
+        "unknown command '{name}', expected 'tag', 'export', 'goto', 'level', 'exclude' or 'pid'"
+      )),
+    }
+  }
+}
+
+impl Command {
+  /// 将命令翻译为日志展示区的控制
+  pub fn apply(self, log_controller: &mut LogController) {
+    match self {
+      Command::Tag(tag) => log_controller.lock_tag(tag),
+      Command::Export(path) => log_controller.export(path),
+      Command::Goto(GotoTarget::Fraction(fraction)) => log_controller.locate_fraction(fraction),
+      Command::Goto(target) => {
+        // 只有时间时，按光标处（或者展示区中第一条带有时间戳的）日志的日期理解
+        let reference = log_controller
+          .cursor_log()
+          .and_then(LogLine::get_timestamp)
+          .or_else(|| {
+            (log_controller.view().data().iter()).find_map(|(_, log, _)| log.get_timestamp())
+          });
+        if let Some(dt) = target.resolve(&log_controller.style().display_tz, reference) {
+          log_controller.locate_time(dt);
+        }
+      }
+      Command::Level(level) => {
+        // 只切换隐藏状态与期望不同的等级
+        for label in Label::ALL {
+          let hidden = log_controller.hidden_labels().contains(&label);
+          if hidden != (severity(label) < severity(level)) {
+            log_controller.toggle_label(label);
+          }
+        }
+      }
+      Command::Exclude(Some(text)) => log_controller.exclude(text),
+      Command::Exclude(None) => log_controller.clear_exclusions(),
+      Command::Pid(pid_filter) => log_controller.set_pid_filter(pid_filter),
+    }
+  }
+}
+
+impl GotoTarget {
+  /// 按展示时区将跳转目标转换为时间点，相对位置没有对应的时间点。
+  /// 只有时间时，日期取参考时间点在展示时区中的那天，没有参考时间点时取今天
+  fn resolve(
+    &self,
+    display_tz: &DisplayTimezone,
+    reference: Option<DateTime<FixedOffset>>,
+  ) -> Option<DateTime<FixedOffset>> {
+    let base = match reference {
+      Some(dt) => display_tz.convert(dt),
+      None => display_tz.now(),
+    };
+    let dt = match self {
+      GotoTarget::Time(time) => base.date_naive().and_time(*time),
+      GotoTarget::DateTime(dt) => *dt,
+      GotoTarget::Fraction(_) => return None,
+    };
+    dt.and_local_timezone(*base.offset()).single()
+  }
+}
+
+/// 解析跳转目标：带 `%` 的是相对位置，否则是时间，或者日期与时间
+fn parse_goto_target(s: &str) -> Option<GotoTarget> {
+  if let Some(percent) = s.strip_suffix('%') {
+    let percent: f64 = percent.trim_end().parse().ok()?;
+    return percent
+      .is_finite()
+      .then(|| GotoTarget::Fraction((percent / 100.0).clamp(0.0, 1.0)));
+  }
+
+  if let Some(time) = TIME_FORMATS
+    .iter()
+    .find_map(|format| NaiveTime::parse_from_str(s, format).ok())
+  {
+    return Some(GotoTarget::Time(time));
+  }
+
+  DATE_TIME_FORMATS
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+    .or_else(|| {
+      NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_time(NaiveTime::MIN))
+    })
+    .map(GotoTarget::DateTime)
+}
+
+/// 解析日志等级的名称
+fn parse_level(s: &str) -> Option<Label> {
+  match s.to_lowercase().as_str() {
+    "error" | "err" => Some(Label::Error),
+    "warn" | "warning" => Some(Label::Warn),
+    "info" => Some(Label::Info),
+    "all" | "debug" => Some(Label::Debug),
+    _ => None,
+  }
+}
+
+/// 日志等级的严重程度，越大越严重。没有等级的日志和 info 等级的日志一样对待
+fn severity(label: Label) -> u8 {
+  match label {
+    Label::Debug => 0,
+    Label::Unknown | Label::Info => 1,
+    Label::Warn => 2,
+    Label::Error => 3,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_command() {
+    let parse = |s: &str| s.parse::<Command>();
+
+    assert_eq!(
+      parse(":tag systemd"),
+      Ok(Command::Tag(Some("systemd".to_string())))
+    );
+    assert_eq!(parse("tag"), Ok(Command::Tag(None)));
+    assert_eq!(
+      parse("  export  out dir/out.log "),
+      Ok(Command::Export(PathBuf::from("out dir/out.log")))
+    );
+    assert!(parse("export").is_err());
+
+    assert_eq!(
+      parse(":goto 10:30"),
+      Ok(Command::Goto(GotoTarget::Time(
+        NaiveTime::from_hms_opt(10, 30, 0).unwrap()
+      )))
+    );
+    assert_eq!(
+      parse("goto 2026-01-17 10:30:15"),
+      Ok(Command::Goto(GotoTarget::DateTime(
+        NaiveDate::from_ymd_opt(2026, 1, 17)
+          .unwrap()
+          .and_hms_opt(10, 30, 15)
+          .unwrap()
+      )))
+    );
+    assert_eq!(
+      parse("goto 2026-01-17"),
+      Ok(Command::Goto(GotoTarget::DateTime(
+        NaiveDate::from_ymd_opt(2026, 1, 17)
+          .unwrap()
+          .and_time(NaiveTime::MIN)
+      )))
+    );
+    assert_eq!(
+      parse("goto 25 %"),
+      Ok(Command::Goto(GotoTarget::Fraction(0.25)))
+    );
+    assert!(parse("goto").is_err());
+    assert!(parse("goto 25:00").is_err());
+
+    assert_eq!(parse(":level error"), Ok(Command::Level(Label::Error)));
+    assert_eq!(parse("level Warning"), Ok(Command::Level(Label::Warn)));
+    assert_eq!(parse("level all"), Ok(Command::Level(Label::Debug)));
+    assert_eq!(parse("level info"), Ok(Command::Level(Label::Info)));
+    assert!(parse("level fatal").is_err());
+    assert!(parse("level unknown").is_err());
+
+    assert_eq!(
+      parse("exclude  health check "),
+      Ok(Command::Exclude(Some("health check".to_string())))
+    );
+    assert_eq!(parse(":exclude"), Ok(Command::Exclude(None)));
+    assert_eq!(
+      parse("exclude /health.*ok/"),
+      Ok(Command::Exclude(Some("/health.*ok/".to_string())))
+    );
+    assert!(parse("exclude /[a/").is_err());
+
+    assert_eq!(
+      parse("pid 1234"),
+      Ok(Command::Pid(Some(PidFilter::exact(1234))))
+    );
+    assert_eq!(
+      parse("pid 100-200"),
+      Ok(Command::Pid(Some(PidFilter::range(100, 200))))
+    );
+    assert_eq!(parse("pid"), Ok(Command::Pid(None)));
+    assert!(parse("pid sshd").is_err());
+
+    assert!(parse(":").is_err());
+    assert!(parse("quit").is_err());
+  }
+
+  #[test]
+  fn test_resolve_goto_target() {
+    let tz: DisplayTimezone = "+08:00".parse().unwrap();
+    let dt = NaiveDate::from_ymd_opt(2026, 1, 17)
+      .unwrap()
+      .and_hms_opt(10, 30, 0)
+      .unwrap();
+
+    // 时间按展示时区理解
+    assert_eq!(
+      GotoTarget::DateTime(dt)
+        .resolve(&tz, None)
+        .map(|dt| dt.to_rfc3339()),
+      Some("2026-01-17T10:30:00+08:00".to_string())
+    );
+
+    // 只有时间时，日期取参考时间点在展示时区中的那天
+    let reference = DateTime::parse_from_rfc3339("2026-02-14T20:00:00-05:00").unwrap();
+    assert_eq!(
+      GotoTarget::Time(dt.time())
+        .resolve(&tz, Some(reference))
+        .map(|dt| dt.to_rfc3339()),
+      Some("2026-02-15T10:30:00+08:00".to_string())
+    );
+
+    // 没有参考时间点时，日期取展示时区的今天
+    let resolved = GotoTarget::Time(dt.time()).resolve(&tz, None).unwrap();
+    assert_eq!(resolved.date_naive(), tz.now().date_naive());
+    assert_eq!(resolved.time(), dt.time());
+    assert!(GotoTarget::Fraction(0.5).resolve(&tz, None).is_none());
+  }
+}
@@ -2,15 +2,21 @@ use crate::{app::LogHubRef, ui::ViewPort};
 
 mod app_controller;
 mod debug_controller;
+mod detail_controller;
 pub mod help_controller;
 pub mod log_controller;
+mod mark_controller;
 mod tag_controller;
+mod time_link_controller;
 
-pub use app_controller::AppController;
+pub use app_controller::{AppController, Stats};
 pub use debug_controller::DebugController;
+pub use detail_controller::DetailController;
 pub use help_controller::HelpController;
 pub use log_controller::LogController;
-pub use tag_controller::TagController;
+pub use mark_controller::MarkController;
+pub use tag_controller::{TagController, TagKind, TagMatchMode};
+pub use time_link_controller::TimeLinkController;
 
 /// 维护一个页面所需的操作接口、数据接口的逻辑控制器，实现 App 功能
 pub trait Controller {
@@ -18,6 +24,13 @@ pub trait Controller {
   /// 需要结合其他控制信息，实现功能，找出渲染所需的数据。
   fn run_once(&mut self, data: &mut LogHubRef);
 
+  /// 最近一次 [Controller::run_once] 是否改变了需要渲染的内容。
+  /// 没有键盘事件、且所有控制器都没有变化时，主循环将跳过渲染，以降低空闲时的 CPU 占用。
+  /// 默认总是认为有变化
+  fn is_dirty(&self) -> bool {
+    true
+  }
+
   /// 返回是否应该结束程序
   fn should_quit(&self) -> bool {
     false
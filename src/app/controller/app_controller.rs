@@ -1,21 +1,96 @@
 use crate::app::Controller;
 use crate::app::LogHubRef;
+use chrono::NaiveTime;
+
+/// 展示在状态栏中的实时统计数据
+#[derive(Default, Copy, Clone, PartialEq, Debug)]
+pub struct Stats {
+  /// 已加载的日志行数量
+  pub total_lines: usize,
+
+  /// 已发现的标签数量
+  pub tags: usize,
+
+  /// 正在生效的过滤条件数量
+  pub active_filters: usize,
+}
+
+impl Stats {
+  /// 格式化为状态栏中展示的文本
+  pub fn format(&self, now: NaiveTime) -> String {
+    format!(
+      "{} | {} lines | {} tags | {} filters",
+      now.format("%H:%M:%S"),
+      self.total_lines,
+      self.tags,
+      self.active_filters
+    )
+  }
+}
 
 #[derive(Default)]
 pub struct AppController {
   quit: bool,
+
+  /// 每帧更新的统计数据
+  stats: Stats,
+
+  /// 统计数据在最近一帧是否发生了变化
+  dirty: bool,
 }
 
 impl AppController {
   pub fn quit(&mut self) {
     self.quit = true;
   }
+
+  /// 最近一帧的统计数据
+  pub fn stats(&self) -> Stats {
+    self.stats
+  }
 }
 
 impl Controller for AppController {
-  fn run_once(&mut self, _: &mut LogHubRef) {}
+  fn run_once(&mut self, data: &mut LogHubRef) {
+    let total_lines = data.total_lines();
+    let data_board = data.data_board();
+    let stats = Stats {
+      total_lines,
+      tags: data_board.snapshot().tags,
+      active_filters: data_board.active_filters(),
+    };
+    self.dirty = self.stats != stats;
+    self.stats = stats;
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
 
   fn should_quit(&self) -> bool {
     self.quit
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_stats_format() {
+    let stats = Stats {
+      total_lines: 12345,
+      tags: 42,
+      active_filters: 2,
+    };
+    let now = NaiveTime::from_hms_opt(9, 5, 7).unwrap();
+    assert_eq!(
+      stats.format(now),
+      "09:05:07 | 12345 lines | 42 tags | 2 filters"
+    );
+    assert_eq!(
+      Stats::default().format(now),
+      "09:05:07 | 0 lines | 0 tags | 0 filters"
+    );
+  }
+}
@@ -1,6 +1,6 @@
 use crate::ui::CursorExpectation;
 use crate::{
-  app::{Controller, LogHubRef},
+  app::{Controller, LogHubRef, controller::log_controller::SearchCombine},
   debug,
   debug::Item as LogItem,
   log::LogDirection,
@@ -12,39 +12,154 @@ type Item = (usize, LogItem);
 // 展示区数据维护器
 crate::view_port!(ViewPort, Item);
 
+/// 调试信息的展示等级
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum DebugLevel {
+  /// 展示所有调试信息
+  #[default]
+  All,
+
+  /// 只展示错误信息
+  Error,
+
+  /// 只展示非错误的普通信息
+  Info,
+}
+
+impl DebugLevel {
+  /// 切换到下一个等级
+  pub fn next(self) -> Self {
+    match self {
+      DebugLevel::All => DebugLevel::Error,
+      DebugLevel::Error => DebugLevel::Info,
+      DebugLevel::Info => DebugLevel::All,
+    }
+  }
+
+  /// 展示用的名称
+  pub fn name(self) -> &'static str {
+    match self {
+      DebugLevel::All => "all",
+      DebugLevel::Error => "error",
+      DebugLevel::Info => "info",
+    }
+  }
+}
+
+/// 调试信息的过滤条件
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct DebugFilter {
+  /// 展示的等级
+  pub level: DebugLevel,
+
+  /// 调试信息需要包含的内容，为空时不过滤
+  pub search: String,
+}
+
+impl DebugFilter {
+  /// 调试信息是否符合过滤条件，内容按日志内容搜索的规则匹配
+  pub fn is_matched(&self, item: &LogItem) -> bool {
+    let level_matched = match self.level {
+      DebugLevel::All => true,
+      DebugLevel::Error => item.is_error,
+      DebugLevel::Info => !item.is_error,
+    };
+    level_matched && SearchCombine::All.is_matched(&item.content, &[self.search.as_str()])
+  }
+
+  /// 是否不过滤任何调试信息
+  pub fn is_empty(&self) -> bool {
+    self.level == DebugLevel::All && self.search.is_empty()
+  }
+}
+
 impl ViewPort {
-  /// 加锁调试数据缓冲区，取出展示区里需要的那些。返回日志总数量
-  fn fill(&mut self, mut index: usize) -> usize {
-    if let Some(buffer) = debug::BUFFER.lock().unwrap().as_ref() {
-      let buffer = buffer.data();
-      index = index.clamp(0, buffer.len().saturating_sub(1));
-
-      let mut iter_down = buffer.iter().enumerate().skip(index);
-      let mut iter_up = buffer.iter().enumerate().take(index).rev();
-
-      self.do_fill(|dir| match dir {
-        LogDirection::Forward => iter_down.next().map(|(a, b)| (a, b.clone())),
-        LogDirection::Backward => iter_up.next().map(|(a, b)| (a, b.clone())),
-      });
-
-      // 返回调试日志总数量
-      buffer.len()
-    } else {
-      0
+  /// 加锁调试数据缓冲区，取出展示区里符合过滤条件的那些。
+  /// 返回（符合条件的日志数量，顶层日志在其中的索引，累计写入的日志数量）
+  fn fill(
+    &mut self,
+    index: usize,
+    expectation: CursorExpectation,
+    filter: &DebugFilter,
+  ) -> (usize, usize, usize) {
+    match debug::BUFFER.lock().unwrap().as_ref() {
+      Some(buffer) => {
+        let (total, position) = self.fill_from(buffer.data(), index, expectation, filter);
+        (total, position, buffer.pushed())
+      }
+      None => (0, 0, 0),
     }
   }
+
+  /// 从给定的调试信息中，取出展示区里符合过滤条件的那些。返回（符合条件的日志数量，顶层日志在其中的索引）
+  fn fill_from(
+    &mut self,
+    buffer: &VecDeque<LogItem>,
+    index: usize,
+    expectation: CursorExpectation,
+    filter: &DebugFilter,
+  ) -> (usize, usize) {
+    // 处理光标越界加载期望。相邻的调试信息可能不符合过滤条件，需要找到该方向上下一条符合条件的，
+    // 否则会再次取到光标所在的那一条，光标就无法越过它们
+    let len = buffer.len();
+    let index = match expectation {
+      CursorExpectation::None => index,
+      CursorExpectation::MoreUp => (0..index.min(len))
+        .rev()
+        .find(|&i| filter.is_matched(&buffer[i]))
+        .unwrap_or(index),
+      CursorExpectation::MoreDown => (index.saturating_add(1)..len)
+        .find(|&i| filter.is_matched(&buffer[i]))
+        .unwrap_or(index),
+      CursorExpectation::Top => 0,
+      CursorExpectation::Bottom => usize::MAX,
+    };
+    let index = index.clamp(0, len.saturating_sub(1));
+
+    let matched = |(_, item): &(usize, &LogItem)| filter.is_matched(item);
+    let mut iter_down = buffer.iter().enumerate().skip(index).filter(matched);
+    let mut iter_up = buffer.iter().enumerate().take(index).rev().filter(matched);
+
+    self.do_fill(|dir| match dir {
+      LogDirection::Forward => iter_down.next().map(|(a, b)| (a, b.clone())),
+      LogDirection::Backward => iter_up.next().map(|(a, b)| (a, b.clone())),
+    });
+
+    // 统计符合条件的日志数量，以及顶层日志之前符合条件的数量
+    let front = self.data.front().map(|(idx, _)| *idx).unwrap_or(0);
+    let (mut total, mut position) = (0, 0);
+    for (idx, item) in buffer.iter().enumerate() {
+      if filter.is_matched(item) {
+        total += 1;
+        position += (idx < front) as usize;
+      }
+    }
+    (total, position)
+  }
 }
 
 /// 调试打印展示区的控制器
 pub struct DebugController {
   /// 展示区里的数据
   view_port: ViewPort,
+
+  /// 上一帧时累计写入的调试信息数量
+  pushed: Option<usize>,
+
+  /// 最近一帧展示的内容是否可能发生了变化
+  dirty: bool,
+
+  /// 调试信息的过滤条件
+  filter: DebugFilter,
 }
 
 impl Default for DebugController {
   fn default() -> Self {
     let mut res = Self {
       view_port: Default::default(),
+      pushed: None,
+      dirty: true,
+      filter: DebugFilter::default(),
     };
 
     res.view_port.ui.want_follow();
@@ -57,10 +172,44 @@ impl DebugController {
   pub fn view_mut(&mut self) -> &mut ViewPort {
     &mut self.view_port
   }
+
+  /// 获取调试信息的过滤条件
+  pub fn filter(&self) -> &DebugFilter {
+    &self.filter
+  }
+
+  /// 切换展示的调试信息等级
+  pub fn next_level(&mut self) {
+    self.filter.level = self.filter.level.next();
+    self.pushed = None;
+  }
+
+  /// 只展示包含给定内容的调试信息
+  pub fn search(&mut self, search: String) {
+    if self.filter.search != search {
+      self.filter.search = search;
+      self.pushed = None;
+    }
+  }
+
+  /// 获取当前搜索的内容
+  pub fn get_curr_search(&self) -> &str {
+    &self.filter.search
+  }
+
+  /// 清空调试信息缓存
+  pub fn clear(&mut self) {
+    if let Some(buffer) = debug::BUFFER.lock().unwrap().as_mut() {
+      buffer.clear();
+    }
+    self.pushed = None;
+  }
 }
 
 impl Controller for DebugController {
   fn run_once(&mut self, _: &mut LogHubRef) {
+    let changing = self.view_port.ui.is_changing();
+
     // 响应调试区的控制，取出其中最新
     let (cursor_index, cursor_expectation) = self
       .view_port
@@ -68,27 +217,99 @@ impl Controller for DebugController {
       .map(|((i, _), e)| (*i, e))
       .unwrap_or((usize::MAX, CursorExpectation::None));
 
-    // 处理光标越界加载期望
-    let cursor_index = match cursor_expectation {
-      CursorExpectation::None => cursor_index,
-      CursorExpectation::MoreUp => (cursor_index as isize - 1).max(0) as usize,
-      CursorExpectation::MoreDown => cursor_index.saturating_add(1),
-    };
-
-    // 取出数据，填充展示区，并启发数据区最大数量，以及顶层数据在整体中的索引，以展示纵向滚动条
-    let total_count = self.view_port.fill(cursor_index);
-    self.view_port.ui.update_vertical_scroll_state(
-      total_count,
+    // 按光标越界加载期望取出数据，填充展示区，并启发数据区最大数量，以及顶层数据在整体中的索引，以展示纵向滚动条
+    let (total_count, position, pushed) =
       self
         .view_port
-        .data
-        .front()
-        .map(|(idx, _)| *idx)
-        .unwrap_or(0),
-    );
+        .fill(cursor_index, cursor_expectation, &self.filter);
+    self.dirty = changing || self.pushed.replace(pushed) != Some(pushed);
+    self
+      .view_port
+      .ui
+      .update_vertical_scroll_state(total_count, position);
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
   }
 
   fn view_port(&mut self) -> Option<&mut ViewPortBase> {
     Some(self.view_port.ui_mut())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::Local;
+
+  #[test]
+  fn test_debug_filter() {
+    let item = |content: &str, is_error| LogItem {
+      date: Local::now(),
+      content: content.to_string(),
+      is_error,
+    };
+    let info = item("loaded 3 files", false);
+    let error = item("failed to load file", true);
+
+    let mut filter = DebugFilter::default();
+    assert!(filter.is_empty());
+    assert!(filter.is_matched(&info) && filter.is_matched(&error));
+
+    // 按等级过滤
+    filter.level = filter.level.next();
+    assert_eq!(filter.level, DebugLevel::Error);
+    assert!(!filter.is_matched(&info) && filter.is_matched(&error));
+    filter.level = filter.level.next();
+    assert_eq!(filter.level, DebugLevel::Info);
+    assert!(filter.is_matched(&info) && !filter.is_matched(&error));
+    filter.level = filter.level.next();
+    assert_eq!(filter.level, DebugLevel::All);
+
+    // 按内容过滤，可以与等级叠加
+    filter.search = "load".to_string();
+    assert!(!filter.is_empty());
+    assert!(filter.is_matched(&info) && filter.is_matched(&error));
+    filter.search = "files".to_string();
+    assert!(filter.is_matched(&info) && !filter.is_matched(&error));
+    filter.level = DebugLevel::Error;
+    assert!(!filter.is_matched(&info) && !filter.is_matched(&error));
+  }
+
+  #[test]
+  fn test_scroll_across_filtered_out_items() {
+    let item = |content: &str, is_error| LogItem {
+      date: Local::now(),
+      content: content.to_string(),
+      is_error,
+    };
+    let buffer: VecDeque<_> = [
+      item("info 0", false),
+      item("error 1", true),
+      item("error 2", true),
+      item("info 3", false),
+    ]
+    .into_iter()
+    .collect();
+    let filter = DebugFilter {
+      level: DebugLevel::Info,
+      ..Default::default()
+    };
+    let mut view_port = ViewPort::default();
+    view_port.ui.set_height(1);
+    let shown = |view_port: &ViewPort| view_port.data.front().map(|(idx, _)| *idx);
+
+    // 在顶部一行向上滚动时，越过不符合条件的调试信息
+    view_port.fill_from(&buffer, 3, CursorExpectation::None, &filter);
+    assert_eq!(shown(&view_port), Some(3));
+    view_port.fill_from(&buffer, 3, CursorExpectation::MoreUp, &filter);
+    assert_eq!(shown(&view_port), Some(0));
+
+    // 已经没有更早的调试信息时保持不动，向下滚动同样越过不符合条件的
+    view_port.fill_from(&buffer, 0, CursorExpectation::MoreUp, &filter);
+    assert_eq!(shown(&view_port), Some(0));
+    view_port.fill_from(&buffer, 0, CursorExpectation::MoreDown, &filter);
+    assert_eq!(shown(&view_port), Some(3));
+  }
+}
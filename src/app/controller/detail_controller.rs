@@ -0,0 +1,180 @@
+use crate::{
+  app::{Controller, DisplayTimezone, LogHubRef, controller::LogController},
+  log::{LogLine, facility_name, severity_name},
+};
+use chrono::SecondsFormat;
+use std::{cell::RefCell, rc::Rc};
+
+/// 展示光标所在日志完整信息的控制器。
+/// 紧凑的日志列表可能省略了部分字段、截断了过长的内容，该控制器取出日志解析后的各个字段，供详情页面展示
+pub struct DetailController {
+  /// 日志展示区，从中取出光标所在日志的索引
+  log_controller: Rc<RefCell<LogController>>,
+
+  /// 是否需要在本帧重新取出光标所在的日志
+  want_refresh: bool,
+
+  /// 光标所在日志的各个字段（名称，取值），最后一个字段总是日志内容。光标没有指向日志时为空
+  fields: Vec<(&'static str, String)>,
+
+  /// 详情内容向下滚动的行数
+  scroll: u16,
+
+  /// 最近一帧展示的内容是否可能发生了变化
+  dirty: bool,
+}
+
+impl DetailController {
+  pub fn new(log_controller: Rc<RefCell<LogController>>) -> Self {
+    Self {
+      log_controller,
+      want_refresh: false,
+      fields: vec![],
+      scroll: 0,
+      dirty: true,
+    }
+  }
+
+  /// 在下一帧重新取出光标所在的日志，并回到详情的开头
+  pub fn refresh(&mut self) {
+    self.want_refresh = true;
+    self.scroll = 0;
+  }
+
+  /// 光标所在日志的各个字段
+  pub fn fields(&self) -> &[(&'static str, String)] {
+    &self.fields
+  }
+
+  /// 详情内容向下滚动的行数
+  pub fn scroll(&self) -> u16 {
+    self.scroll
+  }
+
+  /// 向下滚动一行
+  pub fn scroll_down(&mut self) {
+    self.scroll = self.scroll.saturating_add(1);
+  }
+
+  /// 向上滚动一行
+  pub fn scroll_up(&mut self) {
+    self.scroll = self.scroll.saturating_sub(1);
+  }
+
+  /// 将日志解析后的各个字段格式化为（名称，取值），时间戳按展示时区给出完整精度，
+  /// 内容总是最后一个字段，且保持原样，不做截断。无法解析的行只有内容
+  pub fn format_fields(log: &LogLine, display_tz: &DisplayTimezone) -> Vec<(&'static str, String)> {
+    let mut fields = vec![];
+    match log {
+      LogLine::Good(line) => {
+        let timestamp = display_tz.convert(line.timestamp);
+        fields.push((
+          "timestamp",
+          timestamp.to_rfc3339_opts(SecondsFormat::Micros, false),
+        ));
+        if !line.hostname.is_empty() {
+          fields.push(("host", line.hostname.clone()));
+        }
+        fields.push(("tag", line.tag.clone()));
+        if let Some(pid) = log.get_pid() {
+          fields.push(("pid", pid.to_string()));
+        }
+        if let Some(facility) = log.get_facility() {
+          fields.push((
+            "facility",
+            facility_name(facility).unwrap_or("-").to_string(),
+          ));
+        }
+        if let Some(severity) = log.get_severity() {
+          fields.push((
+            "severity",
+            severity_name(severity).unwrap_or("-").to_string(),
+          ));
+        }
+        fields.push(("label", format!("{:?}", line.label).to_lowercase()));
+      }
+      LogLine::Bad(_) => {
+        fields.push(("status", "unparsed".to_string()));
+      }
+    }
+    if log.is_marked() {
+      fields.push(("marked", "yes".to_string()));
+    }
+    fields.push(("message", log.get_content().to_string()));
+    fields
+  }
+}
+
+impl Controller for DetailController {
+  fn run_once(&mut self, data: &mut LogHubRef) {
+    // 详情只在重新取出日志时变化，滚动由按键触发，总会重新渲染
+    self.dirty = self.want_refresh;
+    if !std::mem::take(&mut self.want_refresh) {
+      return;
+    }
+
+    // 从日志数据中取出光标所在的日志，而不是展示区中的拷贝
+    let (index, display_tz) = {
+      let log_controller = self.log_controller.borrow();
+      (
+        log_controller.cursor_index(),
+        log_controller.style().display_tz,
+      )
+    };
+    self.fields = index
+      .and_then(|index| data.get(index))
+      .map(|log| Self::format_fields(log, &display_tz))
+      .unwrap_or_default();
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_format_fields() {
+    let tz: DisplayTimezone = "+08:00".parse().unwrap();
+
+    let log = LogLine::new(
+      "<13>2026-01-17T02:22:55.642782+00:00 host sshd[42]: Accepted publickey for root".to_string(),
+    );
+    assert_eq!(
+      DetailController::format_fields(&log, &tz),
+      vec![
+        ("timestamp", "2026-01-17T10:22:55.642782+08:00".to_string()),
+        ("host", "host".to_string()),
+        ("tag", "sshd".to_string()),
+        ("pid", "42".to_string()),
+        ("facility", "user".to_string()),
+        ("severity", "notice".to_string()),
+        ("label", "unknown".to_string()),
+        ("message", "Accepted publickey for root".to_string()),
+      ]
+    );
+
+    // 没有 pid 时省略该字段，过长的内容保持完整
+    let message = "x".repeat(1000);
+    let log = LogLine::new(format!(
+      "2026-01-17T10:22:55.642782+08:00 host kernel: {}",
+      message
+    ));
+    let fields = DetailController::format_fields(&log, &tz);
+    assert!(fields.iter().all(|(name, _)| *name != "pid"));
+    assert_eq!(fields.last(), Some(&("message", message)));
+
+    // 无法解析的行只有内容
+    let log = LogLine::new("not a syslog line".to_string());
+    assert_eq!(
+      DetailController::format_fields(&log, &tz),
+      vec![
+        ("status", "unparsed".to_string()),
+        ("message", "not a syslog line".to_string()),
+      ]
+    );
+  }
+}
@@ -1,13 +1,14 @@
 use crate::ui::CursorExpectation;
 use crate::{
-  app::{Controller, LogHubRef},
+  app::{Controller, KeyAction, KeyMap, LogHubRef},
   log::LogDirection,
 };
+use std::borrow::Cow;
 
 #[derive(Clone)]
 pub enum HelpLine {
   Title(&'static str),
-  Item(&'static str),
+  Item(Cow<'static, str>),
   Separator,
 }
 
@@ -37,6 +38,9 @@ pub struct HelpController {
 
   /// 所有预定好的帮助信息
   help_lines: Vec<HelpLine>,
+
+  /// 最近一帧展示的内容是否可能发生了变化
+  dirty: bool,
 }
 
 impl HelpController {
@@ -47,67 +51,160 @@ impl HelpController {
 
 impl Default for HelpController {
   fn default() -> Self {
+    Self::new(&KeyMap::default())
+  }
+}
+
+impl HelpController {
+  /// 按照实际绑定的按键生成帮助内容
+  pub fn new(key_map: &KeyMap) -> Self {
     Self {
       view_port: ViewPort::default(),
       help_lines: vec![
         // 通用说明
         HelpLine::Title("Common Operation"),
-        HelpLine::Item("case insensitive"),
-        HelpLine::Item("use ◄ ▲ ▼ ► to navigate"),
-        HelpLine::Item("press 'enter' to confirm"),
-        HelpLine::Item("press 'esc' to cancel"),
-        HelpLine::Item("press 'q' to quit, finally will ask y/n to confirm or cancel"),
-        HelpLine::Item("press 'ctrl c' to quit anywhere without asking"),
+        HelpLine::Item("case insensitive".into()),
+        HelpLine::Item("use ◄ ▲ ▼ ► to navigate".into()),
+        HelpLine::Item("use 'j' 'k' to move, 'ctrl f' 'ctrl b' to page down or up".into()),
+        HelpLine::Item("use 'shift ▲' 'shift ▼' to scroll half a page up or down".into()),
+        HelpLine::Item("press 'g' to go to the top, 'G' to go to the bottom".into()),
+        HelpLine::Item("with --mouse, scroll the wheel to move and click to select a line".into()),
+        HelpLine::Item("use 'ctrl ◄' 'ctrl ►' to scroll horizontally by a large step".into()),
+        HelpLine::Item("press 'home' 'end' to scroll to the leftmost or rightmost".into()),
+        HelpLine::Item("press 'enter' to confirm".into()),
+        HelpLine::Item("press 'esc' to cancel".into()),
+        HelpLine::Item(
+          format!(
+            "press '{}' to quit, finally will ask y/n to confirm or cancel",
+            key_map.name_of(KeyAction::Quit)
+          )
+          .into(),
+        ),
+        HelpLine::Item("press 'ctrl c' to quit anywhere without asking".into()),
         HelpLine::Separator,
         // 标签页说明
         HelpLine::Title("Tags Filter"),
-        HelpLine::Item("press 't' to search tags"),
-        HelpLine::Item("press 'ctrl y' to set all"),
-        HelpLine::Item("press 'ctrl n' to unset all"),
-        HelpLine::Item("press 'ctrl h' to reverse all"),
-        HelpLine::Item("press 'ctrl t' to toggle the filter page"),
-        HelpLine::Item("press 'alt t' to toggle the fullscreen filter page"),
+        HelpLine::Item("press 't' to search tags".into()),
+        HelpLine::Item("press 'ctrl y' to set all".into()),
+        HelpLine::Item("press 'ctrl n' to unset all".into()),
+        HelpLine::Item("press 'ctrl h' to reverse all".into()),
+        HelpLine::Item("press 'ctrl g' to switch between substring and fuzzy matching".into()),
+        HelpLine::Item("press 'ctrl t' to toggle the filter page".into()),
+        HelpLine::Item(
+          "press 'alt t' to browse tags fullscreen, again to switch back to half".into(),
+        ),
+        HelpLine::Item(
+          "the tags are shown in columns with their line counts when the page is wide".into(),
+        ),
+        HelpLine::Separator,
+        // 主机名页说明
+        HelpLine::Title("Hosts Filter"),
+        HelpLine::Item("press 'o' to search hosts".into()),
+        HelpLine::Item("press 'ctrl y' 'ctrl n' 'ctrl h' as in tags filter".into()),
+        HelpLine::Item("press 'ctrl o' to toggle the filter page".into()),
         HelpLine::Separator,
         // 日志页说明
         HelpLine::Title("Logs View Port"),
-        HelpLine::Item("press 'm' to mark or unmark"),
-        HelpLine::Item("press '/' to search by content"),
-        HelpLine::Item("press '?' to search by timestamp (see bellow)"),
-        HelpLine::Item("press '[' to jump to prev log"),
-        HelpLine::Item("press ']' to jump to next log"),
+        HelpLine::Item("press 'm' to mark or unmark".into()),
+        HelpLine::Item("press 'alt m' to clear all marks, will ask y/n to confirm".into()),
+        HelpLine::Item("press 'b' to browse marks, 'enter' to jump to the selected one".into()),
+        HelpLine::Item("press 'ctrl k' to toggle the marks page".into()),
+        HelpLine::Item("press 'p' to pin the current log at the top, or unpin it".into()),
+        HelpLine::Item(
+          "press 'i' to inspect all fields of the current log, 'j' 'k' to scroll".into(),
+        ),
+        HelpLine::Item("press '/' to search by content".into()),
+        HelpLine::Item(
+          "while typing, 'ctrl a' adds another term, 'ctrl x' removes it, 'ctrl e' for AND/OR"
+            .into(),
+        ),
+        HelpLine::Item("press '?' to search by timestamp (see bellow)".into()),
+        HelpLine::Item("press '%' to jump to a percentage of the loaded logs, e.g. '50%'".into()),
+        HelpLine::Item("press 'v' to jump to the timestamp copied in the clipboard".into()),
+        HelpLine::Item(
+          "press ':' to run a command: 'tag NAME', 'export FILE', 'goto 10:30', 'level error'"
+            .into(),
+        ),
+        HelpLine::Item(
+          "  'exclude TEXT' hides lines containing TEXT, 'exclude /REGEX/' hides lines matching REGEX"
+            .into(),
+        ),
+        HelpLine::Item("  'exclude' shows the hidden lines again".into()),
+        HelpLine::Item(
+          "  'pid 1234' or 'pid 100-200' shows only those processes, 'pid' shows all".into(),
+        ),
+        HelpLine::Item("press '[' to jump to prev log".into()),
+        HelpLine::Item("press ']' to jump to next log".into()),
+        HelpLine::Item("press '{' '}' to jump to the prev or next log with a different tag".into()),
+        HelpLine::Item("press 'M' 'U' while searching to mark or unmark all matched logs".into()),
+        HelpLine::Item(
+          "press 'w' to limit content search to the last timestamp search, or not".into(),
+        ),
+        HelpLine::Item(
+          "press 'alt e' 'alt w' 'alt i' 'alt d' 'alt u' to hide or show error, warn, info, \
+           debug, unknown logs"
+            .into(),
+        ),
+        HelpLine::Item(
+          "press 'alt b' to show only unparsed lines (see --keep-bad-lines), or all".into(),
+        ),
+        HelpLine::Item("press 'c' to collapse or expand repeated consecutive logs".into()),
+        HelpLine::Item("press 'r' to show the newest logs on top, or back to oldest on top".into()),
+        HelpLine::Item(
+          "press 's' to split a view of the tag under the cursor, 'ctrl s' to toggle it".into(),
+        ),
+        HelpLine::Item(
+          "press 'l' to let the split view follow the cursor by timestamp, or not".into(),
+        ),
+        HelpLine::Item("press '1' to cycle the style presets".into()),
+        HelpLine::Item("press '2' '3' '4' to cycle only the timestamp, tag or pid style".into()),
+        HelpLine::Item(
+          "press '5' '6' '7' to cycle the gutter, timestamp precision or facility style".into(),
+        ),
+        HelpLine::Item("press '8' to show or hide the severity badge of logs with a PRI".into()),
         HelpLine::Separator,
         // 时间戳规则说明
         HelpLine::Title("Timestamp Condition Syntax"),
         HelpLine::Item(
-          "Perform fuzzy matching using the highest precision unit appeared in conditions",
+          "Perform fuzzy matching using the highest precision unit appeared in conditions".into(),
+        ),
+        HelpLine::Item("use ',' to separate conditions (AND rule)".into()),
+        HelpLine::Item("date: 2025.11.12, 2025-11-12, 11-12".into()),
+        HelpLine::Item("time: 11:12:13, 11:12".into()),
+        HelpLine::Item("timepoint: {date}, {time}, {data} {time}, {time} {date}".into()),
+        HelpLine::Item(
+          "duration: 3d 4h 5m 6s, 4h5s (missing some is ok, but order is strict)".into(),
         ),
-        HelpLine::Item("use ',' to separate conditions (AND rule)"),
-        HelpLine::Item("date: 2025.11.12, 2025-11-12, 11-12"),
-        HelpLine::Item("time: 11:12:13, 11:12"),
-        HelpLine::Item("timepoint: {date}, {time}, {data} {time}, {time} {date}"),
-        HelpLine::Item("duration: 3d 4h 5m 6s, 4h5s (missing some is ok, but order is strict)"),
         HelpLine::Separator,
-        HelpLine::Item("'= {duration}, {duration}': equal to the timepoint from now - duration"),
-        HelpLine::Item("'> {duration}': earlier than the timepoint from now - duration"),
-        HelpLine::Item("'< {duration}': later than the timepoint from now - duration"),
+        HelpLine::Item(
+          "'= {duration}, {duration}': equal to the timepoint from now - duration".into(),
+        ),
+        HelpLine::Item("'> {duration}': earlier than the timepoint from now - duration".into()),
+        HelpLine::Item("'< {duration}': later than the timepoint from now - duration".into()),
         HelpLine::Separator,
-        HelpLine::Item("'= {timepoint}, {timepoint}': equal to the timepoint"),
-        HelpLine::Item("'> {timepoint}': later than the timepoint"),
-        HelpLine::Item("'< {timepoint}': earlier than the timepoint"),
-        HelpLine::Item("'{timepoint} ~ {timepoint}': time range"),
+        HelpLine::Item("'= {timepoint}, {timepoint}': equal to the timepoint".into()),
+        HelpLine::Item("'> {timepoint}': later than the timepoint".into()),
+        HelpLine::Item("'< {timepoint}': earlier than the timepoint".into()),
+        HelpLine::Item("'{timepoint} ~ {timepoint}': time range".into()),
         HelpLine::Separator,
         // 调试页面规则说明
         HelpLine::Title("Debug Logs"),
-        HelpLine::Item("press 'd' to open and focus the debug page"),
-        HelpLine::Item("press 'ctrl d' to toggle the debug page"),
-        HelpLine::Item("press 'alt d' to toggle the fullscreen filter page"),
+        HelpLine::Item("press 'd' to open and focus the debug page".into()),
+        HelpLine::Item("press 'ctrl d' to toggle the debug page".into()),
+        HelpLine::Item("press '/' in the debug page to search the debug logs".into()),
+        HelpLine::Item("press 'ctrl e' in the debug page to show all, error or info logs".into()),
+        HelpLine::Item("press 'ctrl l' in the debug page to clear the debug logs".into()),
       ],
+      dirty: true,
     }
   }
 }
 
 impl Controller for HelpController {
   fn run_once(&mut self, _: &mut LogHubRef) {
+    // 帮助信息是固定的，只有控制会改变展示的内容
+    self.dirty = self.view_port.ui.is_changing();
+
     // 响应调试区的控制
     let (cursor_index, cursor_expectation) = self
       .view_port
@@ -120,6 +217,8 @@ impl Controller for HelpController {
       CursorExpectation::None => cursor_index,
       CursorExpectation::MoreUp => (cursor_index as isize - 1).max(0) as usize,
       CursorExpectation::MoreDown => cursor_index.saturating_add(1),
+      CursorExpectation::Top => 0,
+      CursorExpectation::Bottom => self.help_lines.len().saturating_sub(1),
     };
 
     // 取出数据，填充展示区，并启发数据区最大数量，以及顶层数据在整体中的索引，以展示纵向滚动条
@@ -135,6 +234,10 @@ impl Controller for HelpController {
     )
   }
 
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
   fn view_port(&mut self) -> Option<&mut ViewPortBase> {
     Some(self.view_port.ui_mut())
   }
@@ -1,43 +1,242 @@
 use crate::{
-  app::{Controller, Index, LogHubRef, LogItem, TimeMatcher},
-  log::{LogDirection, LogLine},
+  app::{
+    Controller, DisplayTimezone, Index, LogHubRef, LogItem, TimeBounds, TimeMatcher,
+    clipboard::{self, Clipboard},
+    rich::{RichSpans, find_all_matches},
+  },
+  log::{Index as LogIndex, Label, LogDirection, LogLine, PidFilter},
   ui::CursorExpectation,
 };
-use std::{path::PathBuf, sync::Arc};
+use chrono::{
+  DateTime, FixedOffset, TimeDelta,
+  format::{Item as FormatItem, StrftimeItems},
+};
+use futures::FutureExt;
+use ratatui::text::Span;
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::HashMap,
+  fs::File,
+  io::{BufWriter, Write},
+  iter::Peekable,
+  path::{Path, PathBuf},
+  str::FromStr,
+  sync::Arc,
+  time::{Duration, Instant},
+};
+use tokio::task::{self, JoinHandle};
 
 /// 描述一条日志的其他属性，表征 viewer 其他渲染需求
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Properties {
   pub timestamp_matched: bool,
+
+  /// 日志在已加载的所有日志中的近似行号（从 1 开始），不受过滤影响
+  pub ordinal: usize,
+
+  /// 折叠重复日志时，本行之外被折叠进来的相同日志的数量
+  pub duplicates: usize,
+
+  /// 日志内容高亮后的结果，日志没有变化时跨帧复用，搜索内容变化时重新计算
+  pub rich: Option<RichSpans>,
 }
 
 /// 展示区里维护的数据条目
 type Item = (Index, LogLine, Properties);
 
+/// 上一帧展示区里的日志。填充新的一帧时，索引与内容都没有变化的日志直接移入，
+/// 而不是重新深拷贝，大多数帧里展示区的内容并没有变化。按所指向的日志行建立索引
+struct RecycledLogs(HashMap<(usize, Option<LogIndex>), Item>);
+
+impl RecycledLogs {
+  fn new(items: VecDeque<Item>) -> Self {
+    Self(
+      items
+        .into_iter()
+        .map(|item| (item.0.line_key(), item))
+        .collect(),
+    )
+  }
+
+  /// 取出给定日志在展示区中的拷贝，以及它的高亮结果，上一帧中有相同的日志时复用它们
+  fn take(&mut self, index: &Index, log: &LogLine) -> (LogLine, Option<RichSpans>) {
+    let found = self
+      .0
+      .remove(&index.line_key())
+      .filter(|(_, old_log, _)| old_log == log && old_log.raw() == log.raw());
+    match found {
+      Some((_, mut old_log, old_props)) => {
+        // 跳转链接不参与比较，它们可能已经变化
+        if let (LogLine::Good(old_log), LogLine::Good(log)) = (&mut old_log, log) {
+          old_log.forward_link = log.forward_link;
+          old_log.backward_link = log.backward_link;
+        }
+        (old_log, old_props.rich)
+      }
+      None => (log.clone(), None),
+    }
+  }
+}
+
 // 定义日志展示区的可视化数据
 crate::view_port!(ViewPort, Item);
 
 impl ViewPort {
-  /// 根据已经配置好的光标位置，从指定索引处的日志开始填充数据区
-  fn fill(&mut self, data: &mut LogHubRef, index: Index) {
-    // 从指定索引位置处，取出正向与逆向的迭代器
-    let (mut iter_down, mut iter_up) = data.iter_at(index);
-    iter_up.next(); // 光标位置默认用的 iter_down 迭代器插入，因此 iter_up 需要先跳过这一行。
+  /// 根据已经配置好的光标位置，从指定索引处的日志开始填充数据区。
+  /// 若要求折叠重复日志，连续的相同日志只占据一行；若要求倒序，则新日志在上、旧日志在下
+  fn fill(
+    &mut self,
+    data: &mut LogHubRef,
+    index: Index,
+    collapse_duplicates: bool,
+    reverse_order: bool,
+    pinned: Option<&Index>,
+  ) {
+    // 折叠时，光标总是指向连续重复日志中最早的一条，以免它们被光标拆成两行
+    let index = if collapse_duplicates {
+      Self::first_of_duplicates(data, index)
+    } else {
+      index
+    };
+
+    {
+      // 上一帧的日志留待复用
+      let mut recycled = RecycledLogs::new(std::mem::take(&mut self.data));
+
+      // 从指定索引位置处，取出指向更新日志与更旧日志的迭代器
+      let (mut iter_newer, mut iter_older) = data.iter_at(index);
+
+      // 光标位置用展示区向下方向的迭代器插入，因此另一个迭代器需要先跳过这一行。
+      // 正序时向下是更新的日志，倒序时向下是更旧的日志
+      match reverse_order {
+        false => iter_older.next(),
+        true => iter_newer.next(),
+      };
+
+      // 使用 view port ui 的能力，逐一填充数据。固定在顶部的日志不再出现在可滚动的区域中
+      let not_pinned =
+        |(index, _): &LogItem| pinned.is_none_or(|pinned| !index.points_to_same(pinned));
+      let mut iter_newer = iter_newer.filter(not_pinned).peekable();
+      let mut iter_older = iter_older.filter(not_pinned).peekable();
+      self.do_fill(|dir| match (dir, reverse_order) {
+        (LogDirection::Forward, false) | (LogDirection::Backward, true) => {
+          Self::next_item(&mut iter_newer, &mut recycled, collapse_duplicates, false)
+        }
+        (LogDirection::Backward, false) | (LogDirection::Forward, true) => {
+          Self::next_item(&mut iter_older, &mut recycled, collapse_duplicates, true)
+        }
+      });
+    }
 
-    // 使用 view port ui 的能力，逐一填充数据
-    self.do_fill(|dir| match dir {
-      LogDirection::Forward => iter_down.next().map(Self::map_into_item),
-      LogDirection::Backward => iter_up.next().map(Self::map_into_item),
-    })
+    // 各行的序号是日志在已加载的所有日志中的近似行号，不受过滤影响，与固定在顶部的日志一致
+    for (index, _, props) in self.data.iter_mut() {
+      props.ordinal = data.position(index) + 1;
+    }
   }
 
-  fn map_into_item(item: (Index, &mut LogLine)) -> Item {
-    (item.0, item.1.clone(), Properties::default())
+  /// 从迭代器中取出下一行数据。折叠重复日志时，后续连续的相同日志将合并到本行中，
+  /// 且本行总是指向这些日志中最早的一条
+  fn next_item<'a>(
+    iter: &mut Peekable<impl Iterator<Item = LogItem<'a>>>,
+    recycled: &mut RecycledLogs,
+    collapse_duplicates: bool,
+    backward: bool,
+  ) -> Option<Item> {
+    let (index, log) = iter.next()?;
+    let (log, rich) = recycled.take(&index, log);
+    let mut item = (
+      index,
+      log,
+      Properties {
+        rich,
+        ..Default::default()
+      },
+    );
+    if !collapse_duplicates {
+      return Some(item);
+    }
+
+    while let Some((index, log)) = iter.next_if(|(_, log)| Self::is_duplicate(&item.1, log)) {
+      item.2.duplicates += 1;
+      if backward {
+        (item.1, item.2.rich) = recycled.take(&index, log);
+        item.0 = index;
+      }
+    }
+
+    Some(item)
+  }
+
+  /// 找到给定日志所在的连续重复日志中，最早的一条
+  fn first_of_duplicates(data: &mut LogHubRef, index: Index) -> Index {
+    let mut iter_up = data.iter_backward_from(index.clone());
+    let Some((mut first, log)) = iter_up.next() else {
+      return index;
+    };
+
+    let log = log.clone();
+    for (index, prev) in iter_up {
+      if !Self::is_duplicate(&log, prev) {
+        break;
+      }
+      first = index;
+    }
+    first
+  }
+
+  /// 两条日志的标签与内容都相同时，认为它们是重复的
+  fn is_duplicate(a: &LogLine, b: &LogLine) -> bool {
+    match (a, b) {
+      (LogLine::Good(a), LogLine::Good(b)) => a.tag == b.tag && a.message == b.message,
+      _ => false,
+    }
+  }
+}
+
+/// 经过校验的 chrono 时间戳格式，如 `%Y/%m/%d %H:%M:%S`
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimestampFormat(String);
+
+impl TimestampFormat {
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+}
+
+impl TryFrom<String> for TimestampFormat {
+  type Error = String;
+
+  fn try_from(s: String) -> Result<Self, Self::Error> {
+    s.parse()
+  }
+}
+
+impl From<TimestampFormat> for String {
+  fn from(format: TimestampFormat) -> Self {
+    format.0
+  }
+}
+
+impl FromStr for TimestampFormat {
+  type Err = String;
+
+  /// 校验格式字符串，拒绝 chrono 无法识别的格式说明符
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s.is_empty() {
+      return Err("timestamp format must not be empty".to_string());
+    }
+
+    match StrftimeItems::new(s).any(|item| item == FormatItem::Error) {
+      true => Err(format!("invalid timestamp format '{s}'")),
+      false => Ok(Self(s.to_string())),
+    }
   }
 }
 
 /// 时间戳展示风格
-#[derive(Default, PartialEq, Copy, Clone)]
+#[derive(Default, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TimestampStyle {
   /// 完整时间戳信息
   Full,
@@ -51,21 +250,83 @@ pub enum TimestampStyle {
 
   /// 仅展示时分秒
   RoughTime,
+
+  /// 12 小时制的时间，精确到毫秒
+  Hour12,
+
+  /// 用户自定义的格式
+  Custom(TimestampFormat),
 }
 
 impl TimestampStyle {
+  /// 切换到下一个风格，依次经过各个内置风格，以及用户配置的自定义格式（如有）
+  pub fn next(&mut self, custom: Option<&TimestampFormat>) {
+    *self = match (&self, custom) {
+      (TimestampStyle::Full, _) => TimestampStyle::Time,
+      (TimestampStyle::Time, _) => TimestampStyle::MonthDayTime,
+      (TimestampStyle::MonthDayTime, _) => TimestampStyle::RoughTime,
+      (TimestampStyle::RoughTime, _) => TimestampStyle::Hour12,
+      (TimestampStyle::Hour12, Some(format)) => TimestampStyle::Custom(format.clone()),
+      (TimestampStyle::Hour12, None) | (TimestampStyle::Custom(_), _) => TimestampStyle::Full,
+    }
+  }
+
+  /// 按本风格格式化时间戳，精度只作用于展示到秒以下的内置风格
+  pub fn format(&self, dt: &DateTime<FixedOffset>, precision: TimestampPrecision) -> String {
+    let fraction = precision.fraction();
+    match self {
+      TimestampStyle::Full => dt.to_rfc3339(),
+      TimestampStyle::Time => dt.format(&format!("%H:%M:%S{fraction}")).to_string(),
+      TimestampStyle::MonthDayTime => dt.format(&format!("%m-%d|%H:%M:%S{fraction}")).to_string(),
+      TimestampStyle::RoughTime => dt.format("%H:%M:%S").to_string(),
+      TimestampStyle::Hour12 => dt.format(&format!("%I:%M:%S{fraction} %p")).to_string(),
+      TimestampStyle::Custom(format) => dt.format(format.as_str()).to_string(),
+    }
+  }
+}
+
+/// 时间戳中秒以下部分的展示精度
+#[derive(Default, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampPrecision {
+  /// 精确到秒
+  Seconds,
+
+  /// 精确到毫秒
+  #[default]
+  Millis,
+
+  /// 精确到微秒
+  Micros,
+
+  /// 精确到纳秒
+  Nanos,
+}
+
+impl TimestampPrecision {
   pub fn next(&mut self) {
     *self = match self {
-      TimestampStyle::Full => TimestampStyle::Time,
-      TimestampStyle::Time => TimestampStyle::MonthDayTime,
-      TimestampStyle::MonthDayTime => TimestampStyle::RoughTime,
-      TimestampStyle::RoughTime => TimestampStyle::Full,
+      TimestampPrecision::Seconds => TimestampPrecision::Millis,
+      TimestampPrecision::Millis => TimestampPrecision::Micros,
+      TimestampPrecision::Micros => TimestampPrecision::Nanos,
+      TimestampPrecision::Nanos => TimestampPrecision::Seconds,
+    }
+  }
+
+  /// 秒以下部分对应的 chrono 格式说明符
+  fn fraction(&self) -> &'static str {
+    match self {
+      TimestampPrecision::Seconds => "",
+      TimestampPrecision::Millis => "%.3f",
+      TimestampPrecision::Micros => "%.6f",
+      TimestampPrecision::Nanos => "%.9f",
     }
   }
 }
 
 /// 标签展示风格
-#[derive(Default, PartialEq, Copy, Clone)]
+#[derive(Default, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TagStyle {
   /// 完整展示
   #[default]
@@ -97,7 +358,8 @@ impl TagStyle {
 }
 
 /// PID 展示风格
-#[derive(Default, PartialEq, Copy, Clone)]
+#[derive(Default, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PidStyle {
   /// 展示
   Shown,
@@ -116,58 +378,226 @@ impl PidStyle {
   }
 }
 
-/// 日志各项内容展示风格配置
-#[derive(Default, PartialEq, Copy, Clone)]
+/// syslog facility 展示风格，只有带 PRI 的日志才有 facility
+#[derive(Default, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FacilityStyle {
+  /// 展示
+  Shown,
+
+  /// 不展示
+  #[default]
+  Hidden,
+}
+
+impl FacilityStyle {
+  pub fn next(&mut self) {
+    *self = match self {
+      FacilityStyle::Shown => FacilityStyle::Hidden,
+      FacilityStyle::Hidden => FacilityStyle::Shown,
+    }
+  }
+}
+
+/// syslog severity 徽标的展示风格，只有带 PRI 的日志才有 severity
+#[derive(Default, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeverityStyle {
+  /// 在内容之前展示 `ERR`、`WRN` 这样的徽标
+  Shown,
+
+  /// 不展示
+  #[default]
+  Hidden,
+}
+
+impl SeverityStyle {
+  pub fn next(&mut self) {
+    *self = match self {
+      SeverityStyle::Shown => SeverityStyle::Hidden,
+      SeverityStyle::Hidden => SeverityStyle::Shown,
+    }
+  }
+}
+
+/// 行号栏展示风格
+#[derive(Default, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GutterStyle {
+  /// 不展示
+  #[default]
+  Hidden,
+
+  /// 展示日志在过滤后的日志流中的近似序号
+  Ordinal,
+}
+
+impl GutterStyle {
+  pub fn next(&mut self) {
+    *self = match self {
+      GutterStyle::Hidden => GutterStyle::Ordinal,
+      GutterStyle::Ordinal => GutterStyle::Hidden,
+    }
+  }
+
+  /// 给定日志总数，计算行号栏的宽度。宽度只随总数的位数增长，
+  /// 以免展示区内序号的位数不同时，整个展示区来回抖动
+  pub fn width(total: usize) -> usize {
+    total.max(1).ilog10() as usize + 1
+  }
+
+  /// 将序号格式化为右对齐的行号栏内容（至少 4 列宽），不展示时返回 None
+  pub fn format(&self, ordinal: usize, width: usize) -> Option<String> {
+    match self {
+      GutterStyle::Hidden => None,
+      GutterStyle::Ordinal => Some(format!("{:>width$}", ordinal, width = width.max(4))),
+    }
+  }
+}
+
+/// 多个内容搜索的组合方式
+#[derive(Default, PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchCombine {
+  /// 日志需要包含所有的搜索内容
+  #[default]
+  All,
+
+  /// 日志包含任一搜索内容即可
+  Any,
+}
+
+impl SearchCombine {
+  pub fn next(&mut self) {
+    *self = match self {
+      SearchCombine::All => SearchCombine::Any,
+      SearchCombine::Any => SearchCombine::All,
+    }
+  }
+
+  /// 检查内容是否按本组合方式匹配给定的搜索内容，没有搜索内容时总是匹配
+  pub fn is_matched(&self, content: &str, terms: &[&str]) -> bool {
+    terms.is_empty()
+      || match self {
+        SearchCombine::All => terms.iter().all(|term| content.contains(term)),
+        SearchCombine::Any => terms.iter().any(|term| content.contains(term)),
+      }
+  }
+
+  /// 组合方式的名称，用于在状态栏中连接各个搜索内容
+  pub fn name(&self) -> &'static str {
+    match self {
+      SearchCombine::All => "AND",
+      SearchCombine::Any => "OR",
+    }
+  }
+}
+
+/// 日志各项内容展示风格配置，可以随会话保存，但时区与自定义时间戳格式总是来自启动参数
+#[derive(Default, PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Style {
   pub timestamp_style: TimestampStyle,
   pub tag_style: TagStyle,
   pub pid_style: PidStyle,
-  type_index: usize,
+  pub facility_style: FacilityStyle,
+  pub severity_style: SeverityStyle,
+  pub gutter_style: GutterStyle,
+
+  /// 展示时间戳使用的时区，时间戳搜索也按该时区理解。它不随整体风格的切换而变化
+  #[serde(skip)]
+  pub display_tz: DisplayTimezone,
+
+  /// 用户配置的自定义时间戳格式，切换时间戳风格时会经过它
+  #[serde(skip)]
+  pub custom_timestamp: Option<TimestampFormat>,
+
+  /// 时间戳秒以下部分的展示精度，它不随整体风格的切换而变化
+  pub timestamp_precision: TimestampPrecision,
+
+  /// 整体风格所选的预设，见 [Style::preset]
+  preset: usize,
 }
 
+/// 整体风格的预设数量
+const STYLE_PRESETS: usize = 4;
+
 impl Style {
+  /// 整体风格切换时依次经过的预设，只决定时间戳、标签与进程号的展示风格，其余各项保持不变
+  fn preset(index: usize) -> (TimestampStyle, TagStyle, PidStyle) {
+    match index % STYLE_PRESETS {
+      0 => (
+        TimestampStyle::MonthDayTime,
+        TagStyle::Full,
+        PidStyle::Hidden,
+      ),
+      1 => (TimestampStyle::Time, TagStyle::OmitLeft, PidStyle::Hidden),
+      2 => (
+        TimestampStyle::RoughTime,
+        TagStyle::Hidden,
+        PidStyle::Hidden,
+      ),
+      _ => (TimestampStyle::Full, TagStyle::Full, PidStyle::Shown),
+    }
+  }
+
+  /// 当前各项风格与给定的预设有几项一致
+  fn preset_similarity(&self, index: usize) -> usize {
+    let (timestamp_style, tag_style, pid_style) = Self::preset(index);
+    (self.timestamp_style == timestamp_style) as usize
+      + (self.tag_style == tag_style) as usize
+      + (self.pid_style == pid_style) as usize
+  }
+
+  /// 当前各项风格是否与给定的预设一致
+  fn is_preset(&self, index: usize) -> bool {
+    self.preset_similarity(index) == 3
+  }
+
+  /// 与当前各项风格最接近（一致的项最多）的预设，相同时优先所选的预设，其次是序号小的
+  fn nearest_preset(&self) -> usize {
+    // max_by_key 在相同时取最后一个，因此倒序排列候选
+    let selected = self.preset % STYLE_PRESETS;
+    std::iter::once(selected)
+      .chain(0..STYLE_PRESETS)
+      .rev()
+      .max_by_key(|index| self.preset_similarity(*index))
+      .unwrap_or(selected)
+  }
+
+  /// 应用给定的预设
+  fn apply_preset(&mut self, index: usize) {
+    let (timestamp_style, tag_style, pid_style) = Self::preset(index);
+    self.timestamp_style = timestamp_style;
+    self.tag_style = tag_style;
+    self.pid_style = pid_style;
+    self.preset = index % STYLE_PRESETS;
+  }
+
+  /// 整体风格所选的预设
+  pub fn preset_index(&self) -> usize {
+    self.preset
+  }
+
+  /// 切换时间戳的展示风格
+  pub fn next_timestamp_style(&mut self) {
+    self.timestamp_style.next(self.custom_timestamp.as_ref());
+  }
+
+  /// 切换标签的展示风格，它不影响所选的预设
+  pub fn next_tag_style(&mut self) {
+    self.tag_style.next();
+  }
+
+  /// 切换整体风格。当前风格与某个预设一致时（优先所选的预设），应用它的下一个预设；
+  /// 否则说明某项风格被单独切换过，先回到最接近的预设
   pub fn next(&mut self) {
-    let style = match self.type_index {
-      0 => Style {
-        timestamp_style: TimestampStyle::MonthDayTime,
-        tag_style: TagStyle::Full,
-        pid_style: PidStyle::Hidden,
-        type_index: 0,
-      },
-      1 => Style {
-        timestamp_style: TimestampStyle::Time,
-        tag_style: TagStyle::OmitLeft,
-        pid_style: PidStyle::Hidden,
-        type_index: 1,
-      },
-      2 => Style {
-        timestamp_style: TimestampStyle::RoughTime,
-        tag_style: TagStyle::Hidden,
-        pid_style: PidStyle::Hidden,
-        type_index: 2,
-      },
-      3 => Style {
-        timestamp_style: TimestampStyle::Full,
-        tag_style: TagStyle::Full,
-        pid_style: PidStyle::Shown,
-        type_index: 3,
-      },
-      _ => {
-        unreachable!()
-      }
+    let nearest = self.nearest_preset();
+    let index = match self.is_preset(nearest) {
+      true => nearest + 1,
+      false => nearest,
     };
-
-    // 若当前选择的索引对应的风格与预设相同，则应用下一个风格，
-    // 否则先回归本索引应有的风格。
-    if style == *self {
-      self.type_index += 1;
-      if self.type_index > 3 {
-        self.type_index = 0;
-      }
-      self.next();
-    } else {
-      *self = style;
-    }
+    self.apply_preset(index);
   }
 }
 
@@ -178,12 +608,27 @@ enum Control {
   /// 对光标指向的数据切换 mark 状态
   ToggleMark,
 
+  /// 将光标指向的数据固定在展示区顶部，或者取消固定
+  TogglePin,
+
+  /// 标记（或取消标记）所有符合当前搜索的日志
+  MarkAllMatching(bool),
+
+  /// 清除所有日志的标记
+  ClearAllMarks,
+
   /// 定位下一条被 mark 的日志
   NextMarked,
 
   /// 定位上一条被 mark 的日志
   PrevMarked,
 
+  /// 下一条标签与光标所在日志不同的日志
+  NextTagChange,
+
+  /// 上一条标签与光标所在日志不同的日志
+  PrevTagChange,
+
   /// 定位最近的符合搜索结果的日志
   LocateContentSearch,
 
@@ -210,6 +655,10 @@ pub enum Error {
   NextMarkedNotFound,
   PrevMarkedNotFound,
 
+  // 跳转标签变化处时的相关错误
+  NextTagChangeNotFound,
+  PrevTagChangeNotFound,
+
   // 内容搜索相关错误
   NextContentSearchNotFound,
   PrevContentSearchNotFound,
@@ -218,6 +667,44 @@ pub enum Error {
   NextTimestampSearchNotFound,
   PrevTimestampSearchNotFound,
   TimestampSearchFormatError(String),
+
+  // 导出日志时的错误
+  ExportFailed(String),
+
+  // 跳转到剪贴板中的时间戳时，剪贴板无法读取、或者内容无法解析
+  ClipboardTimestampError(String),
+}
+
+/// 只有日志内容变化时，两次重新统计搜索匹配分布之间的最短间隔。
+/// 跟踪最新日志时内容每一帧都可能变化，每次都遍历所有日志的代价太高
+const MATCH_DENSITY_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 搜索匹配分布的缓存键，其中任何一项变化时都需要重新统计
+#[derive(Clone, PartialEq)]
+struct MatchDensityKey {
+  search: Vec<String>,
+  combine: SearchCombine,
+  time_window: Option<String>,
+  locked_tag: Option<String>,
+  filter_version: usize,
+  lines_version: usize,
+  buckets: usize,
+}
+
+impl MatchDensityKey {
+  /// 缓存的统计结果是否需要更新：除了日志内容以外的条件变化时立即更新，
+  /// 只有日志内容变化时，距离上一次统计超过最短间隔才更新
+  fn is_stale(&self, key: &MatchDensityKey, elapsed: Duration) -> bool {
+    let lines_only = MatchDensityKey {
+      lines_version: key.lines_version,
+      ..self.clone()
+    };
+    match (lines_only == *key, self.lines_version == key.lines_version) {
+      (false, _) => true,
+      (true, true) => false,
+      (true, false) => elapsed >= MATCH_DENSITY_REFRESH_INTERVAL,
+    }
+  }
 }
 
 /// 日志展示区的控制器
@@ -240,12 +727,109 @@ pub struct LogController {
   /// 搜索的内容。为 None 时，说明当前不处于搜索状态。
   content_search: Option<String>,
 
+  /// 叠加在输入中的搜索内容之上的其他搜索内容，按加入的先后排列
+  content_search_terms: Vec<String>,
+
+  /// 多个搜索内容的组合方式
+  search_combine: SearchCombine,
+
   /// 搜索时间戳的指令，本字段仅记录
   timestamp_search: String,
 
+  /// 行号栏的宽度，由已加载的日志总数决定
+  gutter_width: usize,
+
   /// 时间戳匹配器，仅进入搜索状态时有值。如果给定的搜索指令错误，会记录它
   /// 生成时的错误信息
   timestamp_matcher: Option<Result<TimeMatcher, String>>,
+
+  /// 限定内容搜索的时间窗口，及生成它的时间戳搜索指令。离开时间戳搜索状态后仍然保留，直到被关闭
+  search_time_window: Option<(String, TimeMatcher)>,
+
+  /// 内容搜索跳转后，是否横向滚动使匹配的内容可见。若不启用，则保持原有的横向滚动位置
+  scroll_to_content_match: bool,
+
+  /// 内容搜索跳转后，光标所在日志第一处匹配在日志内容中的列。行首还有行号、时间戳等内容，
+  /// 它们的宽度取决于页面的渲染，因此由页面加上这部分宽度后再横向滚动
+  content_match_column: Option<usize>,
+
+  /// 输入中的搜索内容最近一次变更的时刻，在防抖时间过去之后，才会重新定位匹配的日志
+  pending_content_search: Option<Instant>,
+
+  /// 输入搜索内容时的防抖时间，避免在大量日志中，每次键入都进行一次完整的查找
+  content_search_debounce: Duration,
+
+  /// 本帧需要切换隐藏状态的日志等级
+  toggled_labels: Vec<Label>,
+
+  /// 被隐藏的日志等级，按重要程度排序
+  hidden_labels: Vec<Label>,
+
+  /// 本帧是否需要切换只展示无法解析的行
+  toggled_bad_lines_only: bool,
+
+  /// 排除的内容，包含其中任一子串的行不会展示，与标签过滤一同作用于日志的遍历
+  exclusions: Vec<String>,
+
+  /// 本帧是否需要将排除的内容同步到日志数据中
+  exclusions_changed: bool,
+
+  /// 进程号的过滤条件，与标签过滤一同作用于日志的遍历
+  pid_filter: Option<PidFilter>,
+
+  /// 本帧是否需要将进程号的过滤条件同步到日志数据中
+  pid_filter_changed: bool,
+
+  /// 是否只展示无法解析的行
+  bad_lines_only: bool,
+
+  /// 已经读取到的无法解析的行数
+  bad_lines: usize,
+
+  /// 上一帧光标指向的数据索引，展示区高度变化后，以它为锚点重新填充展示区
+  anchor: Option<Index>,
+
+  /// 是否将连续的重复日志折叠为一行
+  collapse_duplicates: bool,
+
+  /// 固定展示在展示区顶部的日志
+  pinned: Option<Item>,
+
+  /// 外部要求光标跳转到的日志，如从标记列表中选中的日志
+  locate_target: Option<Index>,
+
+  /// 在全局的过滤条件之外，本展示区只展示该标签的日志
+  locked_tag: Option<String>,
+
+  /// 外部要求光标跳转到的时间，光标将指向时间最接近的日志
+  locate_time_target: Option<DateTime<FixedOffset>>,
+
+  /// 正在后台读取的剪贴板，读取外部命令可能较慢，不阻塞界面
+  clipboard_reading: Option<JoinHandle<Result<String, String>>>,
+
+  /// 外部要求光标跳转到的相对位置，范围为 \[0, 1]，只针对已加载的日志
+  locate_fraction_target: Option<f64>,
+
+  /// 外部要求将已加载的日志导出到的文件
+  export_target: Option<PathBuf>,
+
+  /// 是否倒序展示，也即新日志在上、旧日志在下
+  reverse_order: bool,
+
+  /// 上一帧的（日志内容版本，过滤版本，标记版本），用于判断展示的内容是否可能变化
+  versions: Option<(usize, usize, usize)>,
+
+  /// 上一帧展示的内容是否可能发生了变化
+  dirty: bool,
+
+  /// 跟踪最新日志时，两次填充展示区之间的最短间隔，期间到达的新日志合并到下一次填充中展示。为零时不合并
+  follow_batch_interval: Duration,
+
+  /// 最近一次填充展示区的时刻
+  last_fill: Option<Instant>,
+
+  /// 已加载的日志中，搜索匹配的分布（按展示区高度分桶，从旧到新），以及它的缓存键与统计的时刻
+  match_density: Option<(MatchDensityKey, Vec<usize>, Instant)>,
 }
 
 impl Default for LogController {
@@ -257,8 +841,40 @@ impl Default for LogController {
       control: Control::Idle,
       error: None,
       content_search: None,
+      content_search_terms: vec![],
+      search_combine: SearchCombine::default(),
       timestamp_search: String::new(),
+      gutter_width: 1,
       timestamp_matcher: None,
+      search_time_window: None,
+      scroll_to_content_match: true,
+      content_match_column: None,
+      pending_content_search: None,
+      content_search_debounce: Duration::from_millis(150),
+      toggled_labels: vec![],
+      hidden_labels: vec![],
+      toggled_bad_lines_only: false,
+      exclusions: vec![],
+      exclusions_changed: false,
+      pid_filter: None,
+      pid_filter_changed: false,
+      bad_lines_only: false,
+      bad_lines: 0,
+      anchor: None,
+      collapse_duplicates: false,
+      pinned: None,
+      locate_target: None,
+      locked_tag: None,
+      locate_time_target: None,
+      clipboard_reading: None,
+      locate_fraction_target: None,
+      export_target: None,
+      reverse_order: false,
+      versions: None,
+      dirty: true,
+      follow_batch_interval: Duration::ZERO,
+      last_fill: None,
+      match_density: None,
     };
 
     // 默认跟踪最新日志
@@ -282,6 +898,16 @@ impl LogController {
     &self.style
   }
 
+  /// 行号栏的宽度
+  pub fn gutter_width(&self) -> usize {
+    self.gutter_width
+  }
+
+  /// 取出内容搜索跳转后，等待页面横向滚动到的匹配在日志内容中的列
+  pub fn take_content_match_column(&mut self) -> Option<usize> {
+    self.content_match_column.take()
+  }
+
   pub fn take_error(&mut self) -> Option<Error> {
     self.error.take()
   }
@@ -297,10 +923,171 @@ impl LogController {
     }
   }
 
+  /// 将光标跳转到时间与给定时间最接近的日志
+  pub fn locate_time(&mut self, dt: DateTime<FixedOffset>) {
+    self.locate_time_target = Some(dt);
+    self.view_port.ui.do_not_follow();
+  }
+
+  /// 在后台读出剪贴板中的时间戳，读取完成后将光标跳转到时间与之最接近的日志，失败时报错
+  pub fn locate_clipboard_time(&mut self, mut clipboard: impl Clipboard + Send + 'static) {
+    self.clipboard_reading = Some(task::spawn_blocking(move || clipboard.read_text()));
+  }
+
+  /// 将光标跳转到已加载日志中给定的相对位置处，范围为 \[0, 1]
+  pub fn locate_fraction(&mut self, fraction: f64) {
+    self.locate_fraction_target = Some(fraction);
+    self.view_port.ui.do_not_follow();
+  }
+
+  /// 将已加载的、未被过滤的日志按 syslog 格式导出到给定的文件，失败时报错
+  pub fn export(&mut self, path: PathBuf) {
+    self.export_target = Some(path);
+  }
+
+  /// 将本展示区锁定到某个标签上，为 None 时取消锁定
+  pub fn lock_tag(&mut self, tag: Option<String>) {
+    self.locked_tag = tag;
+  }
+
+  /// 本展示区锁定的标签
+  pub fn locked_tag(&self) -> Option<&str> {
+    self.locked_tag.as_deref()
+  }
+
+  /// 光标所指向的日志
+  pub fn cursor_log(&self) -> Option<&LogLine> {
+    self
+      .view_port
+      .data
+      .get(self.view_port.ui.cursor())
+      .map(|(_, log, _)| log)
+  }
+
+  /// 光标所指向的日志在日志数据中的索引
+  pub fn cursor_index(&self) -> Option<Index> {
+    self
+      .view_port
+      .data
+      .get(self.view_port.ui.cursor())
+      .map(|(index, ..)| index.clone())
+  }
+
+  /// 切换某个日志等级的隐藏状态
+  pub fn toggle_label(&mut self, label: Label) {
+    self.toggled_labels.push(label);
+  }
+
+  /// 被隐藏的日志等级
+  pub fn hidden_labels(&self) -> &[Label] {
+    &self.hidden_labels
+  }
+
+  /// 切换是否只展示无法解析的行，用于排查解析不了的日志格式
+  pub fn toggle_bad_lines_only(&mut self) {
+    self.toggled_bad_lines_only = !self.toggled_bad_lines_only;
+  }
+
+  /// 隐藏包含给定内容的行，空的或者已经排除的内容被忽略
+  pub fn exclude(&mut self, text: String) {
+    if !text.is_empty() && !self.exclusions.contains(&text) {
+      self.exclusions.push(text);
+      self.exclusions_changed = true;
+    }
+  }
+
+  /// 取消所有的排除内容
+  pub fn clear_exclusions(&mut self) {
+    if !self.exclusions.is_empty() {
+      self.exclusions.clear();
+      self.exclusions_changed = true;
+    }
+  }
+
+  /// 排除的内容
+  pub fn exclusions(&self) -> &[String] {
+    &self.exclusions
+  }
+
+  /// 只展示符合进程号条件的日志，为空时取消过滤
+  pub fn set_pid_filter(&mut self, pid_filter: Option<PidFilter>) {
+    if self.pid_filter != pid_filter {
+      self.pid_filter = pid_filter;
+      self.pid_filter_changed = true;
+    }
+  }
+
+  /// 进程号的过滤条件
+  pub fn pid_filter(&self) -> Option<PidFilter> {
+    self.pid_filter
+  }
+
+  /// 是否只展示无法解析的行
+  pub fn is_bad_lines_only(&self) -> bool {
+    self.bad_lines_only
+  }
+
+  /// 已经读取到的无法解析的行数，包括没有被保留的行
+  pub fn bad_lines(&self) -> usize {
+    self.bad_lines
+  }
+
+  /// 切换是否将连续的重复日志折叠为一行
+  pub fn toggle_collapse_duplicates(&mut self) {
+    self.collapse_duplicates = !self.collapse_duplicates;
+  }
+
+  /// 是否正在折叠连续的重复日志
+  pub fn is_collapsing_duplicates(&self) -> bool {
+    self.collapse_duplicates
+  }
+
+  /// 设置是否倒序展示（新日志在上），跟踪最新日志时光标也随之置顶
+  pub fn set_reverse_order(&mut self, reverse_order: bool) {
+    self.reverse_order = reverse_order;
+    self.view_port.ui.set_follow_at_top(reverse_order);
+  }
+
+  /// 切换正序与倒序展示
+  pub fn toggle_reverse_order(&mut self) {
+    self.set_reverse_order(!self.reverse_order);
+  }
+
+  /// 是否正在倒序展示
+  pub fn is_reverse_order(&self) -> bool {
+    self.reverse_order
+  }
+
   pub fn toggle_mark(&mut self) {
     self.control = Control::ToggleMark;
   }
 
+  /// 将光标所在的日志固定在展示区顶部，已有固定的日志时则取消固定
+  pub fn toggle_pin(&mut self) {
+    self.control = Control::TogglePin;
+  }
+
+  /// 固定展示在展示区顶部的日志
+  pub fn pinned(&self) -> Option<&(Index, LogLine, Properties)> {
+    self.pinned.as_ref()
+  }
+
+  /// 标记（mark 为 true）或取消标记所有符合当前时间戳搜索、或内容搜索的日志
+  pub fn mark_all_matching(&mut self, mark: bool) {
+    self.control = Control::MarkAllMatching(mark);
+  }
+
+  /// 清除所有已加载日志的标记
+  pub fn clear_all_marks(&mut self) {
+    self.control = Control::ClearAllMarks;
+  }
+
+  /// 将光标跳转到指定的日志
+  pub fn locate(&mut self, index: Index) {
+    self.locate_target = Some(index);
+    self.view_port.ui.do_not_follow();
+  }
+
   pub fn next_mark(&mut self) {
     self.control = Control::NextMarked;
   }
@@ -309,12 +1096,117 @@ impl LogController {
     self.control = Control::PrevMarked;
   }
 
-  /// 设置搜索的内容，或者设置不搜索。
+  /// 跳转到下一条标签与光标所在日志不同的日志，便于浏览多个服务交错的日志
+  pub fn next_tag_change(&mut self) {
+    self.control = Control::NextTagChange;
+  }
+
+  /// 跳转到上一条标签与光标所在日志不同的日志
+  pub fn prev_tag_change(&mut self) {
+    self.control = Control::PrevTagChange;
+  }
+
+  /// 设置搜索的内容，或者设置不搜索。尚在防抖中的搜索被本次设置取代，不会再触发定位
   pub fn search_content(&mut self, search: Option<String>) {
+    if search.is_none() {
+      self.content_search_terms.clear();
+    }
+    self.pending_content_search = None;
     self.content_search = search;
     self.control = Control::LocateContentSearch;
   }
 
+  /// 将输入中的搜索内容叠加为一个搜索条件，之后可以继续输入新的搜索内容
+  pub fn add_search_term(&mut self) {
+    if let Some(search) = self.content_search.take()
+      && !search.is_empty()
+    {
+      self.content_search_terms.push(search);
+    }
+    self.content_search = Some(String::new());
+  }
+
+  /// 移除最近叠加的一个搜索条件
+  pub fn remove_search_term(&mut self) {
+    if self.content_search_terms.pop().is_some() {
+      self.control = Control::LocateContentSearch;
+    }
+  }
+
+  /// 切换多个搜索内容的组合方式
+  pub fn toggle_search_combine(&mut self) {
+    self.search_combine.next();
+    if !self.content_search_terms.is_empty() {
+      self.control = Control::LocateContentSearch;
+    }
+  }
+
+  /// 恢复上次会话的内容搜索，只设置搜索内容与组合方式，不移动光标
+  pub fn restore_content_search(
+    &mut self,
+    search: Option<String>,
+    terms: Vec<String>,
+    combine: SearchCombine,
+  ) {
+    self.content_search = search;
+    self.content_search_terms = terms;
+    self.search_combine = combine;
+  }
+
+  /// 叠加的搜索条件，不包括输入中的搜索内容
+  pub fn search_terms(&self) -> &[String] {
+    &self.content_search_terms
+  }
+
+  /// 多个搜索内容的组合方式
+  pub fn search_combine(&self) -> SearchCombine {
+    self.search_combine
+  }
+
+  /// 参与匹配的所有（非空的）搜索内容，包括叠加的搜索条件与输入中的搜索内容
+  pub fn search_needles(&self) -> Vec<&str> {
+    self
+      .content_search_terms
+      .iter()
+      .map(String::as_str)
+      .chain(self.content_search.as_deref())
+      .filter(|s| !s.is_empty())
+      .collect()
+  }
+
+  /// 用组合方式连接起来的所有搜索内容，用于在状态栏中展示
+  pub fn search_description(&self) -> String {
+    self
+      .search_needles()
+      .iter()
+      .map(|s| format!("'{s}'"))
+      .collect::<Vec<_>>()
+      .join(&format!(" {} ", self.search_combine.name()))
+  }
+
+  /// 在输入的过程中设置搜索的内容，经过防抖时间后，才会定位最近的匹配日志
+  pub fn search_content_incrementally(&mut self, search: String) {
+    self.content_search = Some(search);
+    self.pending_content_search = Some(Instant::now());
+  }
+
+  /// 立即定位尚在防抖中的搜索内容（如果有的话）
+  pub fn flush_content_search(&mut self) {
+    if self.pending_content_search.take().is_some() {
+      self.control = Control::LocateContentSearch;
+    }
+  }
+
+  /// 设置跟踪最新日志时，两次填充展示区之间的最短间隔
+  pub fn set_follow_batch_interval(&mut self, interval: Duration) {
+    self.follow_batch_interval = interval;
+  }
+
+  /// 设置输入搜索内容时的防抖时间
+  pub fn set_content_search_debounce(&mut self, debounce: Duration) {
+    self.content_search_debounce = debounce;
+  }
+
   /// 跳转到下一条搜索匹配的日志
   pub fn next_content_search(&mut self) {
     self.control = Control::NextContentSearch;
@@ -325,6 +1217,11 @@ impl LogController {
     self.control = Control::PrevContentSearch;
   }
 
+  /// 设置内容搜索跳转后，是否横向滚动到匹配的内容处
+  pub fn set_scroll_to_content_match(&mut self, enabled: bool) {
+    self.scroll_to_content_match = enabled;
+  }
+
   /// 获取搜素中的内容
   pub fn get_search_content(&self) -> &str {
     static EMPTY: String = String::new();
@@ -348,7 +1245,7 @@ impl LogController {
     self.control = Control::LocateTimestampSearch;
 
     // 创建匹配器，解析搜索指令，如果出错，记录成错误
-    let mut tm = TimeMatcher::new();
+    let mut tm = TimeMatcher::with_timezone(self.style.display_tz);
     match tm.parse(&self.timestamp_search) {
       Ok(_) => {
         self.timestamp_matcher = Some(Ok(tm));
@@ -373,6 +1270,29 @@ impl LogController {
   pub fn get_search_timestamp(&self) -> &str {
     &self.timestamp_search
   }
+
+  /// 将内容搜索限定在最近一次时间戳搜索的时间窗口内，或者取消限定。
+  /// 若没有可用的时间戳搜索指令，则不限定。返回切换后是否限定
+  pub fn toggle_search_time_window(&mut self) -> bool {
+    self.search_time_window = match self.search_time_window.take() {
+      Some(_) => None,
+      None => {
+        let mut tm = TimeMatcher::with_timezone(self.style.display_tz);
+        tm.parse(&self.timestamp_search)
+          .ok()
+          .map(|_| (self.timestamp_search.clone(), tm))
+      }
+    };
+    self.search_time_window.is_some()
+  }
+
+  /// 获取限定内容搜索的时间窗口所使用的时间戳搜索指令，未限定时为 None
+  pub fn get_search_time_window(&self) -> Option<&str> {
+    self
+      .search_time_window
+      .as_ref()
+      .map(|(cmd, _)| cmd.as_str())
+  }
 }
 
 /// 辅助进行日志条件搜索
@@ -380,6 +1300,10 @@ struct Searcher<'a, 'b> {
   data: &'a mut LogHubRef<'b>,
   index: Index,
   error: Option<Error>,
+
+  /// 查找所限定的时间窗口的边界。窗口是一段连续的时间时，日志大致按时间排序，
+  /// 查找越过边界后便不会再有匹配，可以提前停止。窗口外的日志由匹配器排除
+  bounds: Option<TimeBounds>,
 }
 
 impl<'a, 'b> Searcher<'a, 'b> {
@@ -388,9 +1312,15 @@ impl<'a, 'b> Searcher<'a, 'b> {
       data,
       index,
       error: None,
+      bounds: None,
     }
   }
 
+  fn with_window(mut self, window: Option<&TimeMatcher>) -> Self {
+    self.bounds = window.and_then(TimeMatcher::bounds);
+    self
+  }
+
   fn nearest<F>(&mut self, matcher: F) -> Index
   where
     F: Fn(&LogLine) -> bool,
@@ -399,8 +1329,14 @@ impl<'a, 'b> Searcher<'a, 'b> {
     let (iter_down, mut iter_up) = self.data.iter_at(index.clone());
     iter_up.next();
 
-    Self::search_first_matched(iter_down, &matcher)
-      .or(Self::search_first_matched(iter_up, &matcher))
+    let bounds = self.bounds.as_ref();
+    Self::search_first_matched(iter_down, &matcher, bounds, LogDirection::Forward)
+      .or(Self::search_first_matched(
+        iter_up,
+        &matcher,
+        bounds,
+        LogDirection::Backward,
+      ))
       .unwrap_or_else(|| index)
   }
 
@@ -412,7 +1348,8 @@ impl<'a, 'b> Searcher<'a, 'b> {
     let mut iter_down = self.data.iter_forward_from(index.clone());
     iter_down.next();
 
-    match Self::search_first_matched(iter_down, matcher) {
+    let bounds = self.bounds.as_ref();
+    match Self::search_first_matched(iter_down, matcher, bounds, LogDirection::Forward) {
       Some(index) => index,
       None => {
         self.error = Some(error);
@@ -429,7 +1366,8 @@ impl<'a, 'b> Searcher<'a, 'b> {
     let mut iter_up = self.data.iter_backward_from(index.clone());
     iter_up.next();
 
-    match Self::search_first_matched(iter_up, matcher) {
+    let bounds = self.bounds.as_ref();
+    match Self::search_first_matched(iter_up, matcher, bounds, LogDirection::Backward) {
       Some(index) => index,
       None => {
         self.error = Some(error);
@@ -438,12 +1376,31 @@ impl<'a, 'b> Searcher<'a, 'b> {
     }
   }
 
-  fn search_first_matched<'c, I, F>(iter: I, matcher: F) -> Option<Index>
+  fn search_first_matched<'c, I, F>(
+    iter: I,
+    matcher: F,
+    bounds: Option<&TimeBounds>,
+    direction: LogDirection,
+  ) -> Option<Index>
   where
     I: Iterator<Item = LogItem<'c>>,
     F: Fn(&LogLine) -> bool,
   {
+    // 向更新的日志查找时，越过的是窗口的结束，反之是窗口的开始
+    let past = match direction {
+      LogDirection::Forward => std::cmp::Ordering::Greater,
+      LogDirection::Backward => std::cmp::Ordering::Less,
+    };
+
     for (index, log) in iter {
+      // 越过时间窗口的边界后，停止查找。没有时间戳的日志不影响判断
+      if let Some(bounds) = bounds
+        && let Some(dt) = log.get_timestamp()
+        && bounds.compare(dt) == past
+      {
+        return None;
+      }
+
       if matcher(&log) {
         return Some(index);
       }
@@ -454,12 +1411,59 @@ impl<'a, 'b> Searcher<'a, 'b> {
 }
 
 impl LogController {
+  /// 若后台读取剪贴板已经完成，则跳转到其中的时间戳，失败时报错
+  fn apply_clipboard_time(&mut self) {
+    let Some(reading) = &mut self.clipboard_reading else {
+      return;
+    };
+    let Some(result) = reading.now_or_never() else {
+      return;
+    };
+    self.clipboard_reading = None;
+
+    match result
+      .unwrap_or_else(|e| Err(e.to_string()))
+      .and_then(|text| clipboard::parse_timestamp(&text, &self.style.display_tz))
+    {
+      Ok(dt) => self.locate_time(dt),
+      Err(e) => self.error = Some(Error::ClipboardTimestampError(e)),
+    }
+  }
+
+  /// 若输入中的搜索内容已经超过防抖时间没有变化，且本帧没有其他控制，则触发定位
+  fn apply_pending_content_search(&mut self, now: Instant) {
+    if let Control::Idle = self.control
+      && let Some(changed_at) = self.pending_content_search
+      && now.duration_since(changed_at) >= self.content_search_debounce
+    {
+      self.flush_content_search();
+    }
+  }
+
+  /// 设置所有符合匹配器的（未被过滤的）日志的标记状态，返回状态有变化的日志数量
+  fn mark_all(data: &mut LogHubRef, matcher: impl Fn(&LogLine) -> bool, mark: bool) -> usize {
+    let mut changed = 0;
+    for (_, log) in data.iter_forward_from_head() {
+      if log.is_marked() != mark && matcher(log) {
+        log.set_mark(mark);
+        changed += 1;
+      }
+    }
+    changed
+  }
+
   fn mark_matcher(&self) -> impl Fn(&LogLine) -> bool {
     LogLine::is_marked
   }
 
+  /// 内容搜索的匹配器，若限定了时间窗口，还要求日志的时间戳处于窗口内，没有时间戳的日志视作处于窗口外
   fn content_matcher(&self) -> impl Fn(&LogLine) -> bool {
-    |log: &LogLine| log.get_content().contains(&self.get_search_content())
+    let window = self.search_time_window.as_ref().map(|(_, tm)| tm);
+    let needles = self.search_needles();
+    move |log: &LogLine| {
+      self.search_combine.is_matched(log.get_content(), &needles)
+        && window.is_none_or(|tm| log.get_timestamp().is_some_and(|dt| tm.is_matched(dt)))
+    }
   }
 
   fn get_time_matcher(&self) -> Option<&TimeMatcher> {
@@ -490,27 +1494,212 @@ impl LogController {
     index
   }
 
-  /// 处理光标越界期望
+  /// 从给定的日志出发，朝着给定时间的方向查找，找到时间最接近的日志。
+  /// 日志基本按时间排序，因此时间差开始变大时即可停止查找
+  fn nearest_by_time(data: &mut LogHubRef, index: Index, dt: DateTime<FixedOffset>) -> Index {
+    let cursor_time = data.get(index.clone()).and_then(|log| log.get_timestamp());
+    let iter: Box<dyn Iterator<Item = LogItem>> = match cursor_time {
+      Some(cursor_time) if cursor_time > dt => Box::new(data.iter_backward_from(index.clone())),
+      _ => Box::new(data.iter_forward_from(index.clone())),
+    };
+
+    let mut nearest: Option<(Index, TimeDelta)> = None;
+    for (i, log) in iter {
+      let Some(timestamp) = log.get_timestamp() else {
+        continue;
+      };
+
+      let delta = (timestamp - dt).abs();
+      if nearest
+        .as_ref()
+        .is_some_and(|(_, nearest)| delta > *nearest)
+      {
+        break;
+      }
+      nearest = Some((i, delta));
+    }
+
+    nearest.map(|(i, _)| i).unwrap_or(index)
+  }
+
+  /// 将视觉上的光标越界期望翻转为倒序展示时对应的期望
+  fn reverse_expectation(expectation: CursorExpectation) -> CursorExpectation {
+    match expectation {
+      CursorExpectation::None => CursorExpectation::None,
+      CursorExpectation::MoreUp => CursorExpectation::MoreDown,
+      CursorExpectation::MoreDown => CursorExpectation::MoreUp,
+      CursorExpectation::Top => CursorExpectation::Bottom,
+      CursorExpectation::Bottom => CursorExpectation::Top,
+    }
+  }
+
+  /// 处理光标越界期望。折叠重复日志时，一次越界跨过整段连续的重复日志，
+  /// 否则落在同一段中的日志会被映射回这一段的第一条，光标将停滞不前
   fn process_cursor_expectation(
     data: &mut LogHubRef,
     index: Index,
     expectation: CursorExpectation,
+    collapse_duplicates: bool,
   ) -> Index {
     match expectation {
       CursorExpectation::None => index,
       CursorExpectation::MoreUp => {
-        let mut iter_up = data.iter_backward_from(index.clone());
-        iter_up.next();
-        iter_up.next().map(|(index, _)| index).unwrap_or(index)
+        let prev = {
+          let mut iter_up = data.iter_backward_from(index.clone());
+          iter_up.next();
+          iter_up.next().map(|(index, _)| index)
+        };
+        match prev {
+          Some(prev) if collapse_duplicates => ViewPort::first_of_duplicates(data, prev),
+          Some(prev) => prev,
+          None => index,
+        }
       }
       CursorExpectation::MoreDown => {
         let mut iter_down = data.iter_forward_from(index.clone());
-        iter_down.next();
-        iter_down.next().map(|(index, _)| index).unwrap_or(index)
+        let Some((_, log)) = iter_down.next() else {
+          return index;
+        };
+        let log = log.clone();
+        iter_down
+          .find(|(_, next)| !collapse_duplicates || !ViewPort::is_duplicate(&log, next))
+          .map(|(index, _)| index)
+          .unwrap_or(index)
+      }
+      CursorExpectation::Top => {
+        let first_index = data.first_index();
+        Self::ensure_cursor_valid(data, first_index)
       }
+      CursorExpectation::Bottom => {
+        let last_index = data.last_index();
+        Self::ensure_cursor_valid(data, last_index)
+      }
+    }
+  }
+
+  /// 将已加载的、未被过滤的日志逐行写入文件
+  fn export_to(data: &mut LogHubRef, path: &Path) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for (_, log) in data.iter_forward_from_head() {
+      writer.write_all(&log.to_syslog_bytes())?;
+      writer.write_all(b"\n")?;
+    }
+    writer.flush()
+  }
+
+  /// 将匹配的日志所在的位置（在 total 条日志中的序号）降采样为 buckets 个桶，返回每个桶内的匹配数量
+  pub fn bucketize_matches(positions: &[usize], total: usize, buckets: usize) -> Vec<usize> {
+    let mut counts = vec![0; buckets];
+    if buckets == 0 || total == 0 {
+      return counts;
+    }
+    for &position in positions {
+      counts[(position.min(total - 1) * buckets / total).min(buckets - 1)] += 1;
+    }
+    counts
+  }
+
+  /// 已加载的日志中搜索匹配的分布，每个桶对应展示区的一行，从旧到新排列。没有搜索或者没有匹配时为空
+  pub fn match_density(&self) -> &[usize] {
+    match &self.match_density {
+      Some((_, counts, _)) if counts.iter().any(|&n| n > 0) => counts,
+      _ => &[],
+    }
+  }
+
+  /// 统计已加载的（未被过滤的）日志中搜索匹配的分布。只在搜索内容、过滤条件、日志内容或者展示区高度变化时重新统计，
+  /// 其中日志内容的变化会被限流
+  fn refresh_match_density(&mut self, data: &mut LogHubRef) {
+    let needles = self.search_needles();
+    if needles.is_empty() {
+      self.match_density = None;
+      return;
+    }
+
+    let data_board = data.data_board();
+    let key = MatchDensityKey {
+      search: needles.iter().map(|s| s.to_string()).collect(),
+      combine: self.search_combine,
+      time_window: self.search_time_window.as_ref().map(|(s, _)| s.clone()),
+      locked_tag: self.locked_tag.clone(),
+      filter_version: data_board.get_filter_version(),
+      lines_version: data_board.get_lines_version(),
+      buckets: self.view_port.ui.height(),
+    };
+    if let Some((cached, _, at)) = &self.match_density
+      && !cached.is_stale(&key, at.elapsed())
+    {
+      return;
+    }
+
+    let mut total = 0;
+    let positions: Vec<usize> = {
+      let matcher = self.content_matcher();
+      data
+        .iter_forward_from_head()
+        .filter_map(|(_, log)| {
+          total += 1;
+          matcher(log).then_some(total - 1)
+        })
+        .collect()
+    };
+    let counts = Self::bucketize_matches(&positions, total, key.buckets);
+    self.match_density = Some((key, counts, Instant::now()));
+
+    // 被限流推迟的统计可能在内容不再变化的一帧中完成，此时同样需要重新绘制
+    self.dirty = true;
+  }
+
+  /// 计算内容中第一处匹配所在的列（按展示宽度计算），没有匹配时返回 None
+  fn first_match_column(content: &str, needles: &[&str]) -> Option<usize> {
+    find_all_matches(content, needles)
+      .first()
+      .map(|(start, _)| Span::raw(&content[..*start]).width())
+  }
+
+  /// 内容搜索跳转后，记下光标所在日志第一处匹配的列，由页面横向滚动使其可见
+  fn scroll_to_content_match(&mut self, data: &mut LogHubRef, index: Index) {
+    if !self.scroll_to_content_match || self.error.is_some() {
+      return;
+    }
+
+    self.content_match_column = data
+      .get(index)
+      .and_then(|log| Self::first_match_column(log.get_content(), &self.search_needles()));
+  }
+
+  /// 更新固定日志的内容（如标记状态）与序号
+  fn refresh_pinned(&mut self, data: &mut LogHubRef) {
+    if let Some((index, log, props)) = self.pinned.as_mut() {
+      if let Some((latest_index, latest)) = data.iter_forward_from(index.clone()).next()
+        && latest_index.points_to_same(index)
+      {
+        *log = latest.clone();
+      }
+      props.ordinal = data.position(index) + 1;
     }
   }
 
+  /// 设置日志内容的高亮结果，已有结果仍然有效的日志不再重新计算
+  fn set_rich_properties(&mut self) {
+    let needles: Vec<String> = self
+      .search_needles()
+      .into_iter()
+      .map(String::from)
+      .collect();
+    let needles: Vec<&str> = needles.iter().map(String::as_str).collect();
+    self.view_port.data.iter_mut().for_each(|(_, log, props)| {
+      if let LogLine::Good(log) = log
+        && !props
+          .rich
+          .as_ref()
+          .is_some_and(|rich| rich.is_valid_for(&log.message, &needles))
+      {
+        props.rich = Some(RichSpans::new(&log.message, &needles));
+      }
+    });
+  }
+
   /// 设置时间戳过滤属性。仅时间戳过滤状态启用时有效
   fn set_timestamp_matching_properties(&mut self) {
     match self.timestamp_matcher.as_ref() {
@@ -530,24 +1719,151 @@ impl LogController {
 
 impl Controller for LogController {
   fn run_once(&mut self, data: &mut LogHubRef) {
+    // 本帧的所有遍历只保留锁定的标签，结束后解除，以免影响其他控制器
+    data.lock_tag(self.locked_tag.clone());
+    self.run_locked(data);
+    data.lock_tag(None);
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  fn view_port(&mut self) -> Option<&mut ViewPortBase> {
+    Some(&mut self.view_port.ui)
+  }
+}
+
+impl LogController {
+  /// 在锁定了标签（如有）的日志数据上，进行一帧的处理
+  fn run_locked(&mut self, data: &mut LogHubRef) {
+    // 剪贴板在后台读取完成后，跳转到其中的时间戳
+    self.apply_clipboard_time();
+
     // 记录日志根目录
     self.log_files_root = Some(data.data_board().get_root_path().clone());
 
-    // TODO: 刷新上一帧 index 在这一帧的值，根据各个 log file 的增删情况来近似更新
-    // 取出变更历史，进行 fix(index)
+    // 响应日志等级的过滤变更，它和标签过滤一同作用于日志的遍历
+    for label in self.toggled_labels.drain(..) {
+      data.data_board().toggle_label(label);
+    }
+    self.hidden_labels = Label::ALL
+      .into_iter()
+      .filter(|label| data.data_board().get_hidden_labels().contains(label))
+      .collect();
+    if std::mem::take(&mut self.toggled_bad_lines_only) {
+      data.data_board().toggle_bad_lines_only();
+    }
+    self.bad_lines_only = data.data_board().is_bad_lines_only();
+    if std::mem::take(&mut self.exclusions_changed) {
+      data.data_board().set_exclusions(self.exclusions.clone());
+    } else if data.data_board().get_exclusions() != self.exclusions {
+      self.exclusions = data.data_board().get_exclusions().to_vec();
+    }
+    if std::mem::take(&mut self.pid_filter_changed) {
+      data.data_board().set_pid_filter(self.pid_filter);
+    } else {
+      self.pid_filter = data.data_board().get_pid_filter();
+    }
+    self.bad_lines = data.data_board().get_bad_lines();
+
+    // 只有存在需要处理的控制，或者日志的内容、过滤条件、标记发生了变化时，展示的内容才可能变化
+    let data_board = data.data_board();
+    let versions = (
+      data_board.get_lines_version(),
+      data_board.get_filter_version(),
+      data_board.get_marks_version(),
+    );
+    let previous = self.versions.replace(versions);
+    let controlling = self.view_port.ui.is_changing()
+      || !matches!(self.control, Control::Idle)
+      || self.pending_content_search.is_some()
+      || self.locate_target.is_some()
+      || self.locate_time_target.is_some()
+      || self.locate_fraction_target.is_some()
+      || self.export_target.is_some();
+    self.dirty = previous != Some(versions) || controlling;
+
+    // 跟踪最新日志时，若只有新日志到达，且距离上一次填充不足批量间隔，则推迟到间隔结束后一并展示，
+    // 避免日志密集写入时每帧都重新填充展示区而闪烁。保留旧的版本，使间隔结束后的一帧能够感知到变化
+    let now = Instant::now();
+    let only_new_lines = previous.is_some_and(|(lines, filter, marks)| {
+      lines != versions.0 && (filter, marks) == (versions.1, versions.2)
+    });
+    if only_new_lines
+      && !controlling
+      && self.view_port.ui.is_following()
+      && self
+        .last_fill
+        .is_some_and(|t| now.duration_since(t) < self.follow_batch_interval)
+    {
+      self.versions = previous;
+      self.dirty = false;
+      return;
+    }
+
+    // 取出当前光标应指向的数据索引，同时，对光标的位置完成配置。
+    // 展示区高度变化后，光标位置已被钳制，不再指向原来的日志，此时若没有其他控制，
+    // 则沿用上一帧光标指向的日志，使用户关注的日志在调整窗口大小时保持不动
+    let resized = self.view_port.ui.take_resized();
+    let (cursor_index, cursor_expectation) = match &self.anchor {
+      Some(anchor) if resized && matches!(self.view_port.ui.control, ViewPortControl::Idle) => {
+        (anchor.clone(), CursorExpectation::None)
+      }
+      _ => self
+        .view_port
+        .apply()
+        .map(|((i, ..), e)| (i.clone(), e))
+        .unwrap_or_else(|| (data.last_index(), CursorExpectation::None)),
+    };
 
-    // 取出当前光标应指向的数据索引，同时，对光标的位置完成配置
-    let (cursor_index, cursor_expectation) = self
-      .view_port
-      .apply()
-      .map(|((i, ..), e)| (i.clone(), e))
-      .unwrap_or_else(|| (data.last_index(), CursorExpectation::None));
+    // 上一帧之后加载了新的日志行时，索引中其他日志的位置可能已经过时，基于当前的数据重新计算
+    let cursor_index = match previous.is_some_and(|(lines, ..)| lines != versions.0) {
+      true => data.remap(cursor_index),
+      false => cursor_index,
+    };
 
     // 重定位索引，确保它光标总是指向可见的数据
     let cursor_index = Self::ensure_cursor_valid(data, cursor_index);
 
+    // 倒序展示时，视觉上的上下与日志的新旧相反，光标越界的期望也随之翻转
+    let cursor_expectation = match self.reverse_order {
+      false => cursor_expectation,
+      true => Self::reverse_expectation(cursor_expectation),
+    };
+
     // 处理光标越界的期望
-    let mut cursor_index = Self::process_cursor_expectation(data, cursor_index, cursor_expectation);
+    let mut cursor_index = Self::process_cursor_expectation(
+      data,
+      cursor_index,
+      cursor_expectation,
+      self.collapse_duplicates,
+    );
+
+    // 响应外部要求的跳转
+    if let Some(target) = self.locate_target.take() {
+      cursor_index = Self::ensure_cursor_valid(data, target);
+    }
+    // 还没有加载到日志时保留时间跳转目标（如恢复上次会话的光标），等到日志加载后再跳转
+    if data.total_lines() > 0
+      && let Some(dt) = self.locate_time_target.take()
+    {
+      // 先二分查找到目标附近，再在可见的日志中找到最近的一条
+      let located = data.locate_time(dt);
+      cursor_index = Self::nearest_by_time(data, located, dt);
+    }
+    if let Some(fraction) = self.locate_fraction_target.take() {
+      let target = data.index_at_fraction(fraction);
+      cursor_index = Self::ensure_cursor_valid(data, target);
+    }
+    if let Some(path) = self.export_target.take()
+      && let Err(e) = Self::export_to(data, &path)
+    {
+      self.error = Some(Error::ExportFailed(format!("{}: {}", path.display(), e)));
+    }
+
+    // 输入中的搜索内容稳定下来后，定位最近的匹配日志
+    self.apply_pending_content_search(Instant::now());
 
     // 响应控制
     match self.control {
@@ -555,14 +1871,54 @@ impl Controller for LogController {
       Control::ToggleMark => {
         if let Some(log) = data.get(cursor_index.clone()) {
           log.toggle_mark();
+          data.data_board().update_marks();
         }
       }
+      Control::MarkAllMatching(mark) => {
+        // 时间戳搜索优先，其次是内容搜索。标记在填充展示区之前完成，使展示区反映最新的标记状态
+        let changed = match self.get_time_matcher() {
+          Some(tm) => Self::mark_all(data, self.timestamp_matcher(tm), mark),
+          None if !self.search_needles().is_empty() => {
+            Self::mark_all(data, self.content_matcher(), mark)
+          }
+          None => 0,
+        };
+        if changed > 0 {
+          data.data_board().update_marks();
+        }
+      }
+      Control::ClearAllMarks => {
+        data.clear_all_marks();
+      }
+      Control::TogglePin => {
+        self.pinned = match self.pinned {
+          Some(_) => None,
+          None => data
+            .get(cursor_index.clone())
+            .map(|log| (cursor_index.clone(), log.clone(), Properties::default())),
+        };
+      }
       _ => {
         // 处理搜索
-        let mut searcher = Searcher::new(data, cursor_index.clone());
+        let window = match self.control {
+          Control::LocateContentSearch
+          | Control::NextContentSearch
+          | Control::PrevContentSearch => self.search_time_window.as_ref().map(|(_, tm)| tm),
+          _ => None,
+        };
+        let cursor_tag = match self.control {
+          Control::NextTagChange | Control::PrevTagChange => data
+            .get(cursor_index.clone())
+            .and_then(|log| log.get_tag().map(str::to_string)),
+          _ => None,
+        };
+        let tag_changed = |log: &LogLine| log.get_tag() != cursor_tag.as_deref();
+        let mut searcher = Searcher::new(data, cursor_index.clone()).with_window(window);
         cursor_index = match self.control {
           Control::NextMarked => searcher.next(self.mark_matcher(), Error::NextMarkedNotFound),
           Control::PrevMarked => searcher.prev(self.mark_matcher(), Error::PrevMarkedNotFound),
+          Control::NextTagChange => searcher.next(tag_changed, Error::NextTagChangeNotFound),
+          Control::PrevTagChange => searcher.prev(tag_changed, Error::PrevTagChangeNotFound),
           Control::LocateContentSearch => searcher.nearest(self.content_matcher()),
           Control::NextContentSearch => {
             searcher.next(self.content_matcher(), Error::NextContentSearchNotFound)
@@ -591,27 +1947,380 @@ impl Controller for LogController {
         };
 
         self.error = searcher.error;
+
+        // 内容搜索跳转时，将匹配的内容带入视野
+        if let Control::LocateContentSearch
+        | Control::NextContentSearch
+        | Control::PrevContentSearch = self.control
+        {
+          self.scroll_to_content_match(data, cursor_index.clone());
+        }
       }
     }
     self.control = Control::Idle;
 
     // 基于当前的光标位置，及其指向的数据索引，填充整个展示区
-    self.view_port.fill(data, cursor_index);
+    let total_lines = data.total_lines();
+    let fraction = data.fraction(&cursor_index);
+    self.view_port.fill(
+      data,
+      cursor_index,
+      self.collapse_duplicates,
+      self.reverse_order,
+      self.pinned.as_ref().map(|(index, ..)| index),
+    );
+    self.last_fill = Some(now);
+    self.refresh_pinned(data);
+    self.gutter_width = GutterStyle::width(total_lines);
+    self.anchor = self
+      .view_port
+      .data
+      .get(self.view_port.ui.cursor())
+      .map(|(index, ..)| index.clone());
+
+    // 更新纵向滚动条。更旧的日志是按需加载的，且没有考虑标签过滤，因此这只是基于已加载内容的近似估算
+    let scroll_range = total_lines.saturating_sub(self.view_port.data.len());
+    let fraction = match self.reverse_order {
+      false => fraction,
+      true => 1.0 - fraction,
+    };
+    self.view_port.ui.update_vertical_scroll_state(
+      total_lines,
+      (fraction * scroll_range as f64).round() as usize,
+    );
 
     // 设置时间戳过滤结果（如果有的话）
     self.set_timestamp_matching_properties();
 
-    // 如果存在数据顶到头，触发更老的日志加载
-    let first_index = self
-      .view()
-      .data
-      .front()
+    // 为内容变化、或者搜索内容变化的日志重新计算高亮
+    self.set_rich_properties();
+
+    // 如果存在数据顶到头，触发更老的日志加载。倒序时最旧的日志在展示区底部
+    let oldest = match self.reverse_order {
+      false => self.view().data.front(),
+      true => self.view().data.back(),
+    };
+    let first_index = oldest
       .map(|(first_index, ..)| first_index.clone())
       .unwrap_or(data.first_index());
     data.try_load_older_logs(&first_index);
+
+    // 更新搜索匹配的分布
+    self.refresh_match_density(data);
   }
+}
 
-  fn view_port(&mut self) -> Option<&mut ViewPortBase> {
-    Some(&mut self.view_port.ui)
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_bucketize_matches() {
+    // 100 条日志分为 4 桶，每桶 25 条
+    assert_eq!(
+      LogController::bucketize_matches(&[0, 1, 24, 25, 60, 99], 100, 4),
+      vec![3, 1, 1, 1]
+    );
+
+    // 日志少于桶数时，部分桶为空
+    assert_eq!(
+      LogController::bucketize_matches(&[0, 2], 3, 6),
+      vec![1, 0, 0, 0, 1, 0]
+    );
+
+    // 没有日志或者没有桶时，没有匹配
+    assert_eq!(LogController::bucketize_matches(&[], 0, 3), vec![0, 0, 0]);
+    assert!(LogController::bucketize_matches(&[1], 10, 0).is_empty());
+  }
+
+  #[test]
+  fn test_match_density_key_is_stale() {
+    let key = MatchDensityKey {
+      search: vec!["error".to_string()],
+      combine: SearchCombine::default(),
+      time_window: None,
+      locked_tag: None,
+      filter_version: 1,
+      lines_version: 1,
+      buckets: 10,
+    };
+    let short = Duration::from_millis(10);
+
+    // 完全相同时不需要更新
+    assert!(!key.is_stale(&key.clone(), MATCH_DENSITY_REFRESH_INTERVAL));
+
+    // 只有日志内容变化时，超过最短间隔才更新
+    let appended = MatchDensityKey {
+      lines_version: 2,
+      ..key.clone()
+    };
+    assert!(!key.is_stale(&appended, short));
+    assert!(key.is_stale(&appended, MATCH_DENSITY_REFRESH_INTERVAL));
+
+    // 其他条件变化时立即更新
+    let filtered = MatchDensityKey {
+      filter_version: 2,
+      ..appended.clone()
+    };
+    assert!(key.is_stale(&filtered, short));
+  }
+
+  #[test]
+  fn test_first_match_column() {
+    assert_eq!(
+      LogController::first_match_column("hello world", &["world"]),
+      Some(6)
+    );
+    assert_eq!(
+      LogController::first_match_column("hello world", &["xyz"]),
+      None
+    );
+    assert_eq!(
+      LogController::first_match_column("hello world", &[""]),
+      None
+    );
+
+    // 宽字符按展示宽度计算列
+    assert_eq!(
+      LogController::first_match_column("你好 world", &["world"]),
+      Some(5)
+    );
+  }
+
+  #[test]
+  fn test_incremental_content_search() {
+    let mut ctrl = LogController::default();
+    let log =
+      LogLine::new("2026-01-17T10:22:55.642782+08:00 host kernel: usb 1-1: reset".to_string());
+
+    // 防抖时间内不会触发定位
+    ctrl.search_content_incrementally("us".to_string());
+    let changed_at = ctrl.pending_content_search.unwrap();
+    ctrl.apply_pending_content_search(changed_at);
+    assert!(matches!(ctrl.control, Control::Idle));
+
+    // 继续键入，防抖重新计时，定位由最新的部分输入驱动
+    ctrl.search_content_incrementally("usb 2".to_string());
+    let changed_at = ctrl.pending_content_search.unwrap();
+    ctrl.apply_pending_content_search(changed_at + Duration::from_millis(10));
+    assert!(matches!(ctrl.control, Control::Idle));
+
+    ctrl.apply_pending_content_search(changed_at + ctrl.content_search_debounce);
+    assert!(matches!(ctrl.control, Control::LocateContentSearch));
+    assert!(ctrl.pending_content_search.is_none());
+    assert!(!ctrl.content_matcher()(&log));
+
+    // 按下回车时，立即定位
+    ctrl.control = Control::Idle;
+    ctrl.search_content_incrementally("usb 1".to_string());
+    ctrl.flush_content_search();
+    assert!(matches!(ctrl.control, Control::LocateContentSearch));
+    assert!(ctrl.content_matcher()(&log));
+
+    // 清除搜索后，防抖中的搜索不会再触发定位
+    ctrl.control = Control::Idle;
+    ctrl.search_content_incrementally("usb".to_string());
+    ctrl.search_content(None);
+    assert!(ctrl.pending_content_search.is_none());
+    ctrl.control = Control::Idle;
+    ctrl.apply_pending_content_search(Instant::now() + ctrl.content_search_debounce);
+    assert!(matches!(ctrl.control, Control::Idle));
+  }
+
+  #[test]
+  fn test_multiple_content_searches() {
+    let mut ctrl = LogController::default();
+    let log = |content: &str| {
+      LogLine::new(format!(
+        "2026-01-17T10:22:55.642782+08:00 host kernel: {content}"
+      ))
+    };
+    let both = log("read error: timeout");
+    let error = log("read error");
+    let timeout = log("connect timeout");
+
+    // 叠加的搜索条件与输入中的搜索内容默认需要同时包含
+    ctrl.search_content(Some("error".to_string()));
+    ctrl.add_search_term();
+    ctrl.search_content_incrementally("timeout".to_string());
+    assert_eq!(ctrl.search_needles(), vec!["error", "timeout"]);
+    assert_eq!(ctrl.search_description(), "'error' AND 'timeout'");
+    let matched = |ctrl: &LogController| {
+      let matcher = ctrl.content_matcher();
+      [&both, &error, &timeout].map(&matcher)
+    };
+    assert_eq!(matched(&ctrl), [true, false, false]);
+
+    // 切换为包含任一搜索内容即可
+    ctrl.control = Control::Idle;
+    ctrl.toggle_search_combine();
+    assert!(matches!(ctrl.control, Control::LocateContentSearch));
+    assert_eq!(ctrl.search_description(), "'error' OR 'timeout'");
+    assert_eq!(matched(&ctrl), [true, true, true]);
+
+    // 空的输入不参与匹配，移除叠加的条件后只剩输入中的搜索内容
+    ctrl.add_search_term();
+    ctrl.add_search_term();
+    assert_eq!(ctrl.search_terms(), ["error", "timeout"]);
+    ctrl.search_content_incrementally("connect".to_string());
+    ctrl.remove_search_term();
+    ctrl.remove_search_term();
+    assert_eq!(ctrl.search_needles(), vec!["connect"]);
+    assert_eq!(matched(&ctrl), [false, false, true]);
+
+    // 取消搜索时清空所有条件
+    ctrl.add_search_term();
+    ctrl.search_content(None);
+    assert!(ctrl.search_needles().is_empty());
+    assert_eq!(matched(&ctrl), [true, true, true]);
+  }
+
+  #[test]
+  fn test_style_cycles() {
+    let mut style = Style {
+      gutter_style: GutterStyle::Ordinal,
+      ..Default::default()
+    };
+    assert!(style.is_preset(0));
+
+    // 整体风格依次经过各个预设，其余各项风格保持不变
+    let presets = [
+      (
+        1,
+        TimestampStyle::Time,
+        TagStyle::OmitLeft,
+        PidStyle::Hidden,
+      ),
+      (
+        2,
+        TimestampStyle::RoughTime,
+        TagStyle::Hidden,
+        PidStyle::Hidden,
+      ),
+      (3, TimestampStyle::Full, TagStyle::Full, PidStyle::Shown),
+      (
+        0,
+        TimestampStyle::MonthDayTime,
+        TagStyle::Full,
+        PidStyle::Hidden,
+      ),
+    ];
+    for (index, timestamp_style, tag_style, pid_style) in presets {
+      style.next();
+      assert_eq!(style.preset_index(), index);
+      assert_eq!(style.timestamp_style, timestamp_style);
+      assert_eq!(style.tag_style, tag_style);
+      assert_eq!(style.pid_style, pid_style);
+      assert_eq!(style.gutter_style, GutterStyle::Ordinal);
+    }
+
+    // 单独切换标签风格，不影响所选的预设与其它各项风格
+    style.next();
+    style.next_tag_style();
+    assert_eq!(style.tag_style, TagStyle::OmitRight);
+    assert_eq!(style.preset_index(), 1);
+    assert_eq!(style.timestamp_style, TimestampStyle::Time);
+    for tag_style in [
+      TagStyle::OmitMiddle,
+      TagStyle::Hidden,
+      TagStyle::Full,
+      TagStyle::OmitLeft,
+    ] {
+      style.next_tag_style();
+      assert_eq!(style.tag_style, tag_style);
+    }
+    assert_eq!(style.preset_index(), 1);
+
+    // 单独切换过的风格回到预设后，整体风格仍然从所选的预设继续
+    assert!(style.is_preset(1));
+    style.next();
+    assert_eq!(style.preset_index(), 2);
+
+    // 与预设不一致时，先回到所选的预设
+    style.pid_style.next();
+    style.next();
+    assert_eq!(style.preset_index(), 2);
+    assert!(style.is_preset(2));
+    style.next();
+    assert_eq!(style.preset_index(), 3);
+  }
+
+  #[test]
+  fn test_style_next_from_off_preset() {
+    let style_of = |preset, timestamp_style, tag_style, pid_style| Style {
+      timestamp_style,
+      tag_style,
+      pid_style,
+      preset,
+      ..Default::default()
+    };
+    let next = |mut style: Style| {
+      style.next();
+      assert!(style.is_preset(style.preset_index()));
+      style.preset_index()
+    };
+
+    // 与另一个预设完全一致时，从该预设继续，而不是回到所选的预设
+    let style = style_of(
+      0,
+      TimestampStyle::RoughTime,
+      TagStyle::Hidden,
+      PidStyle::Hidden,
+    );
+    assert_eq!(next(style), 3);
+
+    // 不与任何预设一致时，回到一致的项最多的预设
+    let style = style_of(0, TimestampStyle::Time, TagStyle::OmitLeft, PidStyle::Shown);
+    assert_eq!(next(style), 1);
+    let style = style_of(1, TimestampStyle::Hour12, TagStyle::Full, PidStyle::Hidden);
+    assert_eq!(next(style), 0);
+
+    // 一致的项同样多时，优先所选的预设，其次是序号小的
+    let style = style_of(0, TimestampStyle::Time, TagStyle::Hidden, PidStyle::Shown);
+    assert_eq!(next(style), 1);
+    let style = style_of(3, TimestampStyle::Time, TagStyle::Hidden, PidStyle::Shown);
+    assert_eq!(next(style), 3);
+    let style = style_of(
+      2,
+      TimestampStyle::Hour12,
+      TagStyle::OmitMiddle,
+      PidStyle::Shown,
+    );
+    assert_eq!(next(style), 3);
+    let style = style_of(
+      2,
+      TimestampStyle::Hour12,
+      TagStyle::OmitMiddle,
+      PidStyle::Hidden,
+    );
+    assert_eq!(next(style), 2);
+
+    // 所选的预设超出范围（如来自损坏的会话文件）时，按取余后的预设处理
+    let style = style_of(
+      9,
+      TimestampStyle::Time,
+      TagStyle::OmitLeft,
+      PidStyle::Hidden,
+    );
+    assert_eq!(next(style), 2);
+  }
+
+  #[test]
+  fn test_gutter_format() {
+    assert_eq!(GutterStyle::width(0), 1);
+    assert_eq!(GutterStyle::width(9), 1);
+    assert_eq!(GutterStyle::width(10), 2);
+    assert_eq!(GutterStyle::width(123456), 6);
+
+    // 同一宽度下，不同位数的序号对齐
+    let width = GutterStyle::width(12345);
+    assert_eq!(GutterStyle::Ordinal.format(9, width).unwrap(), "    9");
+    assert_eq!(GutterStyle::Ordinal.format(12345, width).unwrap(), "12345");
+
+    // 宽度有最小值，超出宽度的序号不截断
+    assert_eq!(GutterStyle::Ordinal.format(7, 1).unwrap(), "   7");
+    assert_eq!(GutterStyle::Ordinal.format(123456, 2).unwrap(), "123456");
+
+    assert_eq!(GutterStyle::Hidden.format(7, 4), None);
   }
 }
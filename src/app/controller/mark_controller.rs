@@ -0,0 +1,111 @@
+use crate::{
+  app::{Controller, Index, LogHubRef},
+  log::{LogDirection, LogLine},
+  ui::CursorExpectation,
+};
+
+/// 展示区里维护的数据条目，第一项为它在所有标记中的序号
+type Item = (usize, Index, LogLine);
+
+// 展示区数据维护器
+crate::view_port!(ViewPort, Item);
+
+impl ViewPort {
+  /// 从指定序号处开始，用被标记的日志填充展示区
+  fn fill(&mut self, marks: &[(Index, LogLine)], index: usize) {
+    let index = index.min(marks.len().saturating_sub(1));
+    let item = |(i, (index, log)): (usize, &(Index, LogLine))| (i, index.clone(), log.clone());
+
+    let mut iter_down = marks.iter().enumerate().skip(index).map(item);
+    let mut iter_up = marks.iter().enumerate().take(index).rev().map(item);
+
+    self.do_fill(|dir| match dir {
+      LogDirection::Forward => iter_down.next(),
+      LogDirection::Backward => iter_up.next(),
+    });
+  }
+}
+
+/// 标记列表的控制器，列出所有被标记的日志，供快速跳转
+#[derive(Default)]
+pub struct MarkController {
+  /// 展示区里的数据
+  view_port: ViewPort,
+
+  /// 所有被标记的日志，按从旧到新的顺序排列
+  marks: Vec<(Index, LogLine)>,
+
+  /// 收集标记时的（标记版本，过滤版本），两者都没有变化时，无需重新收集
+  versions: Option<(usize, usize)>,
+
+  /// 最近一帧展示的内容是否可能发生了变化
+  dirty: bool,
+}
+
+impl MarkController {
+  /// 获得 view port 控制器
+  pub fn view_mut(&mut self) -> &mut ViewPort {
+    &mut self.view_port
+  }
+
+  /// 要求在下一帧重新收集被标记的日志
+  pub fn refresh(&mut self) {
+    self.versions = None;
+  }
+
+  /// 光标所指向的被标记日志的索引
+  pub fn selected(&self) -> Option<Index> {
+    self
+      .view_port
+      .data
+      .get(self.view_port.ui.cursor())
+      .map(|(_, index, _)| index.clone())
+  }
+}
+
+impl Controller for MarkController {
+  fn run_once(&mut self, data: &mut LogHubRef) {
+    // 标记或者过滤条件变化后，重新收集被标记的日志
+    let data_board = data.data_board();
+    let versions = (
+      data_board.get_marks_version(),
+      data_board.get_filter_version(),
+    );
+    self.dirty = self.view_port.ui.is_changing() || self.versions != Some(versions);
+    if self.versions != Some(versions) {
+      self.marks = data.marked_logs();
+      self.versions = Some(versions);
+    }
+
+    // 响应展示区的控制
+    let (cursor_index, cursor_expectation) = self
+      .view_port
+      .apply()
+      .map(|((i, ..), e)| (*i, e))
+      .unwrap_or((0, CursorExpectation::None));
+
+    // 处理光标越界加载期望
+    let cursor_index = match cursor_expectation {
+      CursorExpectation::None => cursor_index,
+      CursorExpectation::MoreUp => cursor_index.saturating_sub(1),
+      CursorExpectation::MoreDown => cursor_index.saturating_add(1),
+      CursorExpectation::Top => 0,
+      CursorExpectation::Bottom => usize::MAX,
+    };
+
+    // 填充展示区，并更新纵向滚动条
+    self.view_port.fill(&self.marks, cursor_index);
+    self.view_port.ui.update_vertical_scroll_state(
+      self.marks.len(),
+      self.view_port.data.front().map(|(i, ..)| *i).unwrap_or(0),
+    );
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  fn view_port(&mut self) -> Option<&mut ViewPortBase> {
+    Some(self.view_port.ui_mut())
+  }
+}
@@ -1,9 +1,13 @@
 use crate::{
   app::{Controller, LogHubRef},
-  log::LogDirection,
+  log::{DataBoard, LogDirection, TagsData},
   ui::{CursorEx, CursorExpectation},
 };
-use std::collections::BTreeMap;
+use ratatui::text::Span;
+use std::{
+  collections::{BTreeMap, HashMap},
+  sync::{Arc, Weak},
+};
 
 /// 展示区里维护的数据条目
 type Item = (String, bool);
@@ -53,9 +57,82 @@ enum Control {
   ToggleAll,
 }
 
+/// 标签搜索的匹配方式，都不区分大小写
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TagMatchMode {
+  /// 标签包含搜索的字符串
+  #[default]
+  Substring,
+
+  /// 搜索的字符依次出现在标签中，但不必连续，如 `sjd` 匹配 `systemd-journald`
+  Fuzzy,
+}
+
+impl TagMatchMode {
+  /// 展示用的名称
+  pub fn name(self) -> &'static str {
+    match self {
+      TagMatchMode::Substring => "substring",
+      TagMatchMode::Fuzzy => "fuzzy",
+    }
+  }
+
+  /// 标签是否和已经转为小写的搜索字符串匹配
+  pub fn is_match(self, tag: &str, search: &str) -> bool {
+    let tag = tag.to_lowercase();
+    match self {
+      TagMatchMode::Substring => tag.contains(search),
+      TagMatchMode::Fuzzy => {
+        let mut chars = tag.chars();
+        search.chars().all(|c| chars.any(|t| t == c))
+      }
+    }
+  }
+}
+
+/// 标签展示区所维护的数据种类
+#[derive(Default, Clone, Copy)]
+pub enum TagKind {
+  /// 日志的标签
+  #[default]
+  Tag,
+
+  /// 日志的主机名
+  Host,
+}
+
+impl TagKind {
+  /// 展示用的名称
+  pub fn name(self) -> &'static str {
+    match self {
+      TagKind::Tag => "Tags",
+      TagKind::Host => "Hosts",
+    }
+  }
+
+  /// 从数据黑板中取出对应的数据容器
+  fn data(self, data_board: &mut DataBoard) -> &mut TagsData {
+    match self {
+      TagKind::Tag => data_board.get_tags_mut(),
+      TagKind::Host => data_board.get_hosts_mut(),
+    }
+  }
+
+  /// 从数据黑板中取出各项累计读取到的日志行数，主机名没有该统计
+  fn counts(self, data_board: &DataBoard) -> Option<Arc<HashMap<String, usize>>> {
+    match self {
+      TagKind::Tag => Some(data_board.snapshot().tag_counts),
+      TagKind::Host => None,
+    }
+  }
+}
+
 /// 标签展示区的控制器
 #[derive(Default)]
 pub struct TagController {
+  /// 所维护的数据种类
+  kind: TagKind,
+
   /// 当帧需要处理的控制
   control: Control,
 
@@ -73,9 +150,42 @@ pub struct TagController {
 
   /// 本帧的搜索，将对比前后两帧的搜索内容，尽可能优化查找过程
   curr_search: String,
+
+  /// 标签搜索的匹配方式
+  match_mode: TagMatchMode,
+
+  /// 匹配方式变化后，需要在下一帧重新匹配所有的标签
+  rematch: bool,
+
+  /// 上一帧的标签版本
+  version: Option<usize>,
+
+  /// 上一帧数据黑板中各个标签的行数统计，只用于判断行数是否变化。
+  /// 不持有统计本身，使得数据黑板写入新的日志时可以原地更新，而不是复制整个统计
+  last_counts: Option<Weak<HashMap<String, usize>>>,
+
+  /// 展示区中各个标签累计读取到的日志行数，主机名没有该统计
+  counts: Option<HashMap<String, usize>>,
+
+  /// 多列展示时每列所需的宽度，足以容纳所有匹配的标签及其行数
+  column_width: usize,
+
+  /// 最近一帧展示的内容是否可能发生了变化
+  dirty: bool,
 }
 
 impl TagController {
+  pub fn new(kind: TagKind) -> Self {
+    Self {
+      kind,
+      ..Self::default()
+    }
+  }
+
+  pub fn kind(&self) -> TagKind {
+    self.kind
+  }
+
   pub fn toggle(&mut self) {
     self.control = Control::Toggle;
   }
@@ -100,13 +210,47 @@ impl TagController {
     &self.curr_search
   }
 
+  pub fn match_mode(&self) -> TagMatchMode {
+    self.match_mode
+  }
+
+  /// 设置标签搜索的匹配方式
+  pub fn set_match_mode(&mut self, match_mode: TagMatchMode) {
+    self.rematch |= self.match_mode != match_mode;
+    self.match_mode = match_mode;
+  }
+
+  /// 在子串匹配与模糊匹配之间切换，返回切换后的匹配方式
+  pub fn toggle_match_mode(&mut self) -> TagMatchMode {
+    self.set_match_mode(match self.match_mode {
+      TagMatchMode::Substring => TagMatchMode::Fuzzy,
+      TagMatchMode::Fuzzy => TagMatchMode::Substring,
+    });
+    self.match_mode
+  }
+
   pub fn view_mut(&mut self) -> &mut ViewPort {
     &mut self.view_port
   }
+
+  /// 展示区中各个标签累计读取到的日志行数，主机名没有该统计
+  pub fn counts(&self) -> Option<HashMap<String, usize>> {
+    self.counts.clone()
+  }
+
+  /// 多列展示时每列所需的宽度
+  pub fn column_width(&self) -> usize {
+    self.column_width
+  }
 }
 
 impl Controller for TagController {
   fn run_once(&mut self, data: &mut LogHubRef) {
+    let changing = self.view_port.ui.is_changing()
+      || !matches!(self.control, Control::Idle)
+      || self.curr_search != self.last_search
+      || self.rematch;
+
     // 响应列表区的操作，获取光标指向的数据。
     // 如果取不到光标数据，重新取第一个标签作为光标的位置。
     let cursor_data = self.view_port.apply().map(|(i, e)| (i.clone(), e));
@@ -114,6 +258,9 @@ impl Controller for TagController {
       .map(|((k, _), e)| (k, e))
       .unwrap_or((String::new(), CursorExpectation::None));
 
+    // 先移除不再出现在日志中的标签，以免选择控制只作用在展示区的副本上
+    self.apply_removed(data);
+
     // 响应选择控制，如果光标指向的数据存在的话。
     self.apply_control(
       data,
@@ -127,8 +274,25 @@ impl Controller for TagController {
     // 处理搜索的变更
     self.apply_search(data);
 
-    // 更新标签版本
-    data.data_board().get_tags_mut().update_version();
+    // 更新标签版本，出现了新的标签，或者标签的选择变化时，展示的内容才可能变化
+    let tags = self.kind.data(data.data_board());
+    tags.update_version();
+    let version = tags.get_version();
+    self.dirty = changing || self.version.replace(version) != Some(version);
+
+    // 更新各个标签的行数。数据黑板写入新的日志时，统计会被移动到新的位置，据此判断行数是否变化
+    let counts = self.kind.counts(data.data_board());
+    let counts_changed = match (&self.last_counts, &counts) {
+      (Some(last), Some(curr)) => last.as_ptr() != Arc::as_ptr(curr),
+      (last, curr) => last.is_some() != curr.is_some(),
+    };
+    self.last_counts = counts.as_ref().map(Arc::downgrade);
+    self.dirty |= counts_changed;
+
+    // 展示的内容可能变化时，重新计算每列的宽度
+    if self.dirty {
+      self.column_width = self.measure_column_width(counts.as_deref());
+    }
 
     // 重定位光标位置
     let cursor_key = self.relocate_cursor(cursor_key, cursor_expectation);
@@ -136,6 +300,16 @@ impl Controller for TagController {
     // 填充数据
     self.view_port.fill(&self.matched_tags, cursor_key);
 
+    // 只复制展示区中各个标签的行数，统计本身在本帧结束时释放
+    self.counts = counts.map(|counts| {
+      self
+        .view_port
+        .data
+        .iter()
+        .filter_map(|(tag, _)| Some((tag.clone(), *counts.get(tag)?)))
+        .collect()
+    });
+
     // 找到最大数据数量，以及展示区内第一条数据在整体中的位置，提供纵向滚动条的渲染数据
     let top_item_position = match self.view_port.data.front() {
       None => 0,
@@ -152,16 +326,28 @@ impl Controller for TagController {
       .update_vertical_scroll_state(self.matched_tags.len(), top_item_position);
   }
 
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
   fn view_port(&mut self) -> Option<&mut ViewPortBase> {
     Some(&mut self.view_port.ui)
   }
 }
 
 impl TagController {
+  /// 移除不再出现在日志中的标签，它们的选择状态由数据黑板记住，再次出现时作为新增的标签恢复
+  fn apply_removed(&mut self, data: &mut LogHubRef) {
+    for tag in self.kind.data(data.data_board()).take_removed() {
+      self.matched_tags.remove(&tag);
+      self.unmatched_tags.remove(&tag);
+    }
+  }
+
   /// 对展示区内的所有标签（被过滤出来的、包括界面外的不可见项），处理它们的激活或关闭
   fn apply_control(&mut self, data: &mut LogHubRef, cursor_key: Option<&String>) {
     // 数据黑板中的标签记录
-    let tags = data.data_board().get_tags_mut();
+    let tags = self.kind.data(data.data_board());
 
     match self.control {
       Control::Idle => {}
@@ -198,15 +384,21 @@ impl TagController {
 
   /// 响应搜索信息的变更
   fn apply_search(&mut self, data: &mut LogHubRef) {
-    if self.curr_search.len() > self.last_search.len()
-      && self.curr_search.starts_with(&self.last_search)
+    // 搜索不区分大小写，统一转为小写后比较。子串匹配时，转换后的前后两次搜索仍然满足前缀关系，
+    // 则匹配结果的增减依然是单调的，可以只检查其中一个集合。模糊匹配时总是重新匹配所有标签
+    let curr_search = self.curr_search.to_lowercase();
+    let last_search = self.last_search.to_lowercase();
+    let match_mode = self.match_mode;
+    let incremental = match_mode == TagMatchMode::Substring && !self.rematch;
+
+    if incremental && curr_search.len() > last_search.len() && curr_search.starts_with(&last_search)
     {
       // 搜搜字符串变长，条件更苛刻，检查是否有之前匹配的 key，现在不匹配了
       let matched_tags = std::mem::take(&mut self.matched_tags);
       self.matched_tags = matched_tags
         .into_iter()
         .filter_map(|(k, v)| {
-          if k.find(&self.curr_search).is_none() {
+          if !match_mode.is_match(&k, &curr_search) {
             self.unmatched_tags.insert(k, v);
             None
           } else {
@@ -214,15 +406,16 @@ impl TagController {
           }
         })
         .collect();
-    } else if self.curr_search.len() < self.last_search.len()
-      && self.last_search.starts_with(&self.curr_search)
+    } else if incremental
+      && curr_search.len() < last_search.len()
+      && last_search.starts_with(&curr_search)
     {
       // 搜索字符串变短，条件根宽松，检查是否有之前不匹配的 key，现在匹配了
       let unmatched_tags = std::mem::take(&mut self.unmatched_tags);
       self.unmatched_tags = unmatched_tags
         .into_iter()
         .filter_map(|(k, v)| {
-          if k.find(&self.curr_search).is_some() {
+          if match_mode.is_match(&k, &curr_search) {
             self.matched_tags.insert(k, v);
             None
           } else {
@@ -230,7 +423,7 @@ impl TagController {
           }
         })
         .collect();
-    } else if self.curr_search != self.last_search {
+    } else if self.rematch || curr_search != last_search {
       // 若搜索字符串和之前的搜索字符串变化太大，那么需要重新分析所有的标签
       let matched_tags = std::mem::take(&mut self.matched_tags);
       let unmatched_tags = std::mem::take(&mut self.unmatched_tags);
@@ -238,25 +431,48 @@ impl TagController {
       self.match_tags(unmatched_tags);
     }
 
-    // 取出新增的标签，根据搜索结果匹配到各个集合中
-    self.match_tags(
-      data
-        .data_board()
-        .get_tags_mut()
-        .take_updated()
-        .into_iter()
-        .map(|k| (k, true))
-        .collect(),
-    );
+    // 取出新增的标签及其选择状态（不一定是选中的），根据搜索结果匹配到各个集合中
+    let tags = self.kind.data(data.data_board());
+    let updated = tags
+      .take_updated()
+      .into_iter()
+      .map(|k| {
+        let v = tags.get(&k);
+        (k, v)
+      })
+      .collect();
+    self.match_tags(updated);
 
     // 记录新的变更
     self.last_search = self.curr_search.clone();
+    self.rematch = false;
+  }
+
+  /// 计算多列展示时每列所需的宽度：复选框、标签、行数，以及列之间的间隔
+  fn measure_column_width(&self, counts: Option<&HashMap<String, usize>>) -> usize {
+    const CHECKBOX_WIDTH: usize = 3;
+    const COLUMN_GAP: usize = 2;
+
+    let widest = self
+      .matched_tags
+      .keys()
+      .map(|tag| {
+        let count = counts
+          .and_then(|counts| counts.get(tag))
+          .map_or(0, |n| n.to_string().len() + 1);
+        Span::raw(tag.as_str()).width() + count
+      })
+      .max()
+      .unwrap_or(0);
+
+    CHECKBOX_WIDTH + widest + COLUMN_GAP
   }
 
   /// 将搜索字符串匹配标签值，并根据结果加入到对应的集合中
   fn match_tags(&mut self, tags: BTreeMap<String, bool>) {
+    let search = self.curr_search.to_lowercase();
     tags.into_iter().for_each(|(k, v)| {
-      if k.find(&self.curr_search).is_none() {
+      if !self.match_mode.is_match(&k, &search) {
         self.unmatched_tags.insert(k, v);
       } else {
         self.matched_tags.insert(k, v);
@@ -274,6 +490,20 @@ impl TagController {
         .unwrap_or(cursor_key);
     }
 
+    // 光标所在的标签不再和搜索匹配时（如搜索范围缩小了），改为指向和它最接近的标签，
+    // 优先取它之后的第一个，没有时取它之前的最后一个，以免光标跳回到顶部
+    let cursor_key = if self.matched_tags.contains_key(&cursor_key) {
+      cursor_key
+    } else {
+      self
+        .matched_tags
+        .range(cursor_key.clone()..)
+        .next()
+        .or_else(|| self.matched_tags.range(..cursor_key.clone()).next_back())
+        .map(|(k, _)| k.clone())
+        .unwrap_or(cursor_key)
+    };
+
     match cursor_expectation {
       CursorExpectation::None => cursor_key,
       CursorExpectation::MoreUp => self
@@ -288,6 +518,43 @@ impl TagController {
         .nth(1)
         .map(|(k, _)| k.clone())
         .unwrap_or(cursor_key),
+      CursorExpectation::Top => self
+        .matched_tags
+        .first_key_value()
+        .map(|(k, _)| k.clone())
+        .unwrap_or(cursor_key),
+      CursorExpectation::Bottom => self
+        .matched_tags
+        .last_key_value()
+        .map(|(k, _)| k.clone())
+        .unwrap_or(cursor_key),
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_fuzzy_match() {
+    let fuzzy = TagMatchMode::Fuzzy;
+    assert!(fuzzy.is_match("systemd-journald", "sjd"));
+    assert!(fuzzy.is_match("Systemd-Journald", "sjd"));
+    assert!(fuzzy.is_match("systemd-journald", "systemd"));
+    assert!(fuzzy.is_match("systemd-journald", ""));
+
+    // 字符需要按顺序出现，且每个字符只能被匹配一次
+    assert!(!fuzzy.is_match("systemd-journald", "dsj"));
+    assert!(!fuzzy.is_match("cron", "ccron"));
+    assert!(!fuzzy.is_match("kernel", "sjd"));
+  }
+
+  #[test]
+  fn test_substring_match() {
+    let substring = TagMatchMode::Substring;
+    assert!(substring.is_match("systemd-journald", "journal"));
+    assert!(substring.is_match("Systemd-Journald", "systemd"));
+    assert!(!substring.is_match("systemd-journald", "sjd"));
+  }
+}
@@ -0,0 +1,69 @@
+use crate::app::{Controller, LogHubRef, controller::LogController};
+use chrono::{DateTime, FixedOffset};
+use std::{cell::RefCell, rc::Rc};
+
+/// 按时间戳联动两个日志展示区：主展示区的光标移动后，
+/// 副展示区跳转到与主展示区光标所在日志时间最接近的日志。
+///
+/// 它需要在主、副两个展示区的控制器之间运行，使副展示区在同一帧中完成跳转。
+pub struct TimeLinkController {
+  /// 主展示区
+  primary: Rc<RefCell<LogController>>,
+
+  /// 跟随主展示区的副展示区
+  secondary: Rc<RefCell<LogController>>,
+
+  /// 是否启用联动
+  enabled: bool,
+
+  /// 上一次同步时，主展示区光标所在日志的时间
+  last_synced: Option<DateTime<FixedOffset>>,
+}
+
+impl TimeLinkController {
+  pub fn new(primary: Rc<RefCell<LogController>>, secondary: Rc<RefCell<LogController>>) -> Self {
+    Self {
+      primary,
+      secondary,
+      enabled: false,
+      last_synced: None,
+    }
+  }
+
+  /// 开关联动
+  pub fn toggle(&mut self) {
+    self.enabled = !self.enabled;
+    self.last_synced = None;
+  }
+
+  /// 是否启用了联动
+  pub fn is_enabled(&self) -> bool {
+    self.enabled
+  }
+}
+
+impl Controller for TimeLinkController {
+  fn run_once(&mut self, _: &mut LogHubRef) {
+    if !self.enabled {
+      return;
+    }
+
+    // 仅在主展示区光标所在日志的时间变化后同步，使副展示区在其余时间可以独立浏览
+    let timestamp = self
+      .primary
+      .borrow()
+      .cursor_log()
+      .and_then(|log| log.get_timestamp());
+    if let Some(timestamp) = timestamp
+      && self.last_synced != Some(timestamp)
+    {
+      self.secondary.borrow_mut().locate_time(timestamp);
+      self.last_synced = Some(timestamp);
+    }
+  }
+
+  /// 本控制器没有自己的展示内容，跳转的变化由副展示区报告
+  fn is_dirty(&self) -> bool {
+    false
+  }
+}
@@ -0,0 +1,52 @@
+//! 展示时间戳时使用的时区，日志渲染与时间戳搜索都基于同一个时区，以保证两者一致
+
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use std::str::FromStr;
+
+/// 展示时间戳使用的时区
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub enum DisplayTimezone {
+  /// 本机时区
+  #[default]
+  Local,
+
+  /// UTC
+  Utc,
+
+  /// 固定的时区偏移，如 +08:00
+  Fixed(FixedOffset),
+}
+
+impl DisplayTimezone {
+  /// 将时间点转换到本时区
+  pub fn convert(&self, dt: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+    match self {
+      DisplayTimezone::Local => dt.with_timezone(&Local).fixed_offset(),
+      DisplayTimezone::Utc => dt.with_timezone(&Utc).fixed_offset(),
+      DisplayTimezone::Fixed(offset) => dt.with_timezone(offset),
+    }
+  }
+
+  /// 本时区下的当前时间
+  pub fn now(&self) -> DateTime<FixedOffset> {
+    self.convert(Utc::now().fixed_offset())
+  }
+}
+
+impl FromStr for DisplayTimezone {
+  type Err = String;
+
+  /// 解析 `local`、`utc`，或者形如 `+08:00`、`-0530` 的时区偏移
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_ascii_lowercase().as_str() {
+      "local" => Ok(DisplayTimezone::Local),
+      "utc" | "z" => Ok(DisplayTimezone::Utc),
+      _ => s
+        .parse::<FixedOffset>()
+        .map(DisplayTimezone::Fixed)
+        .map_err(|_| {
+          format!("unknown timezone '{s}', expected 'local', 'utc' or an offset like '+08:00'")
+        }),
+    }
+  }
+}
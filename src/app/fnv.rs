@@ -0,0 +1,21 @@
+//! FNV-1a 哈希。它不依赖标准库哈希算法的实现，每次运行、每个版本的结果都一样，
+//! 适用于需要跨进程保持稳定的场景，如会话文件的名称、标签的颜色
+
+/// 计算一串字节的 64 位 FNV-1a 哈希
+pub fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+  bytes.into_iter().fold(0xcbf29ce484222325, |hash, byte| {
+    (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_fnv1a() {
+    assert_eq!(fnv1a(*b""), 0xcbf29ce484222325);
+    assert_eq!(fnv1a(*b"a"), 0xaf63dc4c8601ec8c);
+    assert_eq!(fnv1a(*b"foobar"), 0x85944171f73967e8);
+  }
+}
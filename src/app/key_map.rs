@@ -0,0 +1,474 @@
+//! 逻辑动作与按键的映射，使得按键可以在不重新编译的情况下被重新绑定
+
+use crate::ui::KeyEventEx;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{collections::HashMap, str::FromStr};
+
+/// 可以绑定按键的逻辑动作
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum KeyAction {
+  /// 聚焦标签过滤页面
+  OpenTags,
+
+  /// 开关标签过滤页面
+  ToggleTags,
+
+  /// 全屏打开并聚焦标签过滤页面，聚焦时在全屏与半屏之间切换
+  ToggleTagsFullscreen,
+
+  /// 聚焦主机名过滤页面
+  OpenHosts,
+
+  /// 开关主机名过滤页面
+  ToggleHosts,
+
+  /// 聚焦调试页面，聚焦时则取消聚焦
+  OpenDebug,
+
+  /// 开关调试页面
+  ToggleDebug,
+
+  /// 聚焦标记列表页面
+  OpenMarks,
+
+  /// 开关标记列表页面
+  ToggleMarks,
+
+  /// 聚焦分屏展示区，它只展示主展示区光标所在日志的标签
+  OpenSplit,
+
+  /// 开关分屏展示区
+  ToggleSplit,
+
+  /// 开关分屏展示区按时间戳跟随主展示区滚动
+  ToggleTimeLink,
+
+  /// 进入内容搜索
+  SearchContent,
+
+  /// 内容搜索输入中，将已输入的内容叠加为一个搜索条件，继续输入下一个
+  AddSearchTerm,
+
+  /// 内容搜索输入中，移除最近叠加的一个搜索条件
+  RemoveSearchTerm,
+
+  /// 内容搜索输入中，在要求包含所有搜索内容（AND）与包含任一搜索内容（OR）之间切换
+  ToggleSearchCombine,
+
+  /// 进入时间戳搜索
+  SearchTimestamp,
+
+  /// 输入百分比，跳转到已加载日志中的对应位置
+  JumpToPercent,
+
+  /// 跳转到剪贴板中的时间戳处
+  JumpToClipboardTime,
+
+  /// 打开命令面板，输入命令执行动作
+  CommandPalette,
+
+  /// 打开或关闭帮助页面
+  Help,
+
+  /// 退出程序，或者退出当前的子页面
+  Quit,
+
+  /// 切换整体的展示风格
+  NextStyle,
+
+  /// 切换时间戳的展示风格
+  NextTimestampStyle,
+
+  /// 切换标签的展示风格
+  NextTagStyle,
+
+  /// 切换进程号的展示风格
+  NextPidStyle,
+
+  /// 切换行号栏的展示风格
+  NextGutterStyle,
+
+  /// 切换 syslog facility 的展示风格
+  NextFacilityStyle,
+
+  /// 切换 syslog severity 徽标的展示风格
+  NextSeverityStyle,
+
+  /// 切换时间戳秒以下部分的展示精度
+  NextTimestampPrecision,
+
+  /// 跟踪最新日志
+  Follow,
+
+  /// 切换正序与倒序（新日志在上）展示
+  ToggleReverseOrder,
+
+  /// 标记或取消标记光标所在的日志
+  ToggleMark,
+
+  /// 清除所有日志的标记（需要确认）
+  ClearMarks,
+
+  /// 将光标所在的日志固定在顶部，或者取消固定
+  TogglePin,
+
+  /// 标记所有符合搜索的日志
+  MarkAllMatches,
+
+  /// 取消标记所有符合搜索的日志
+  UnmarkAllMatches,
+
+  /// 跳转到上一条被标记的、或者符合搜索的日志
+  PrevMatch,
+
+  /// 跳转到下一条被标记的、或者符合搜索的日志
+  NextMatch,
+
+  /// 全屏查看光标所在日志的完整信息
+  ShowDetail,
+
+  /// 跳转到上一条标签不同的日志
+  PrevTagChange,
+
+  /// 跳转到下一条标签不同的日志
+  NextTagChange,
+
+  /// 隐藏或展示 error 等级的日志
+  ToggleErrorLogs,
+
+  /// 隐藏或展示 warn 等级的日志
+  ToggleWarnLogs,
+
+  /// 隐藏或展示 info 等级的日志
+  ToggleInfoLogs,
+
+  /// 隐藏或展示 debug 等级的日志
+  ToggleDebugLogs,
+
+  /// 隐藏或展示 unknown 等级的日志
+  ToggleUnknownLogs,
+
+  /// 只展示无法解析的行，或者恢复展示所有日志
+  ToggleBadLinesOnly,
+
+  /// 折叠或展开连续的重复日志
+  ToggleCollapseDuplicates,
+
+  /// 将内容搜索限定在最近一次时间戳搜索的时间窗口内，或者取消限定
+  ToggleSearchTimeWindow,
+
+  /// 选中或取消选中光标所在的标签
+  ToggleTag,
+
+  /// 选中搜索范围内的所有标签
+  SetAllTags,
+
+  /// 取消选中搜索范围内的所有标签
+  UnsetAllTags,
+
+  /// 反选搜索范围内的所有标签
+  ToggleAllTags,
+
+  /// 在子串匹配与模糊匹配之间切换标签的搜索方式
+  ToggleTagMatchMode,
+
+  /// 切换调试页面所展示的调试信息等级
+  NextDebugLevel,
+
+  /// 清空调试页面中的所有调试信息
+  ClearDebugLogs,
+}
+
+/// 一个动作的按键绑定，从命令行中 `ACTION=KEY` 形式的字符串解析，如 `toggle-mark=x`、`follow=ctrl-f`
+#[derive(Clone, Debug)]
+pub struct KeyBinding {
+  pub action: KeyAction,
+  pub key: KeyEvent,
+}
+
+impl FromStr for KeyBinding {
+  type Err = String;
+
+  /// 动作名不区分大小写，单词之间可以用 '-' 或 '_' 分隔。按键是单个字符，可以带有 `ctrl-` 或 `alt-` 前缀，
+  /// 也可以是 `enter`、`tab`、`space`
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (action, key) = s
+      .split_once('=')
+      .ok_or_else(|| format!("invalid key binding '{s}', expected 'ACTION=KEY'"))?;
+    let action = KeyMap::default()
+      .action_named(action)
+      .ok_or_else(|| format!("unknown action '{action}' in key binding '{s}'"))?;
+    let key = parse_key(key).ok_or_else(|| format!("invalid key '{key}' in key binding '{s}'"))?;
+
+    Ok(Self { action, key })
+  }
+}
+
+/// 解析按键，如 `x`、`M`、`ctrl-x`、`alt-x`、`enter`
+fn parse_key(s: &str) -> Option<KeyEvent> {
+  let single = |s: &str| {
+    let mut chars = s.chars();
+    chars.next().filter(|_| chars.next().is_none())
+  };
+
+  if let Some(c) = s.strip_prefix("ctrl-").and_then(single) {
+    return Some(KeyEvent::ctrl(c));
+  }
+  if let Some(c) = s.strip_prefix("alt-").and_then(single) {
+    return Some(KeyEvent::alt(c));
+  }
+  match s {
+    "enter" => Some(KeyEvent::simple(KeyCode::Enter)),
+    "tab" => Some(KeyEvent::simple(KeyCode::Tab)),
+    "space" => Some(KeyEvent::simple(KeyCode::Char(' '))),
+    _ => single(s).map(|c| match c.is_ascii_uppercase() {
+      true => KeyEvent::shift(c),
+      false => KeyEvent::simple(KeyCode::Char(c)),
+    }),
+  }
+}
+
+/// 不经过映射、在各状态中固定使用的按键（上下移动、跳到首尾、确认或取消）
+const FIXED_KEYS: [char; 5] = ['j', 'k', 'g', 'y', 'n'];
+
+/// 逻辑动作到按键的映射，默认值即为本程序原有的按键
+#[derive(Clone)]
+pub struct KeyMap {
+  keys: HashMap<KeyAction, KeyEvent>,
+}
+
+impl Default for KeyMap {
+  fn default() -> Self {
+    let simple = |c: char| KeyEvent::simple(KeyCode::Char(c));
+
+    Self {
+      keys: HashMap::from([
+        (KeyAction::OpenTags, simple('t')),
+        (KeyAction::ToggleTags, KeyEvent::ctrl('t')),
+        (KeyAction::ToggleTagsFullscreen, KeyEvent::alt('t')),
+        (KeyAction::OpenHosts, simple('o')),
+        (KeyAction::ToggleHosts, KeyEvent::ctrl('o')),
+        (KeyAction::OpenDebug, simple('d')),
+        (KeyAction::ToggleDebug, KeyEvent::ctrl('d')),
+        (KeyAction::OpenMarks, simple('b')),
+        (KeyAction::ToggleMarks, KeyEvent::ctrl('k')),
+        (KeyAction::OpenSplit, simple('s')),
+        (KeyAction::ToggleSplit, KeyEvent::ctrl('s')),
+        (KeyAction::ToggleTimeLink, simple('l')),
+        (KeyAction::SearchContent, simple('/')),
+        (KeyAction::AddSearchTerm, KeyEvent::ctrl('a')),
+        (KeyAction::RemoveSearchTerm, KeyEvent::ctrl('x')),
+        (KeyAction::ToggleSearchCombine, KeyEvent::ctrl('e')),
+        (KeyAction::SearchTimestamp, simple('?')),
+        (KeyAction::JumpToPercent, simple('%')),
+        (KeyAction::JumpToClipboardTime, simple('v')),
+        (KeyAction::CommandPalette, simple(':')),
+        (KeyAction::Help, simple('h')),
+        (KeyAction::Quit, simple('q')),
+        (KeyAction::NextStyle, simple('1')),
+        (KeyAction::NextTimestampStyle, simple('2')),
+        (KeyAction::NextTagStyle, simple('3')),
+        (KeyAction::NextPidStyle, simple('4')),
+        (KeyAction::NextGutterStyle, simple('5')),
+        (KeyAction::NextTimestampPrecision, simple('6')),
+        (KeyAction::NextFacilityStyle, simple('7')),
+        (KeyAction::NextSeverityStyle, simple('8')),
+        (KeyAction::Follow, simple('f')),
+        (KeyAction::ToggleReverseOrder, simple('r')),
+        (KeyAction::ToggleMark, simple('m')),
+        (KeyAction::ClearMarks, KeyEvent::alt('m')),
+        (KeyAction::TogglePin, simple('p')),
+        (KeyAction::MarkAllMatches, KeyEvent::shift('M')),
+        (KeyAction::UnmarkAllMatches, KeyEvent::shift('U')),
+        (KeyAction::PrevMatch, simple('[')),
+        (KeyAction::NextMatch, simple(']')),
+        (KeyAction::ShowDetail, simple('i')),
+        (KeyAction::PrevTagChange, simple('{')),
+        (KeyAction::NextTagChange, simple('}')),
+        (KeyAction::ToggleErrorLogs, KeyEvent::alt('e')),
+        (KeyAction::ToggleWarnLogs, KeyEvent::alt('w')),
+        (KeyAction::ToggleInfoLogs, KeyEvent::alt('i')),
+        (KeyAction::ToggleDebugLogs, KeyEvent::alt('d')),
+        (KeyAction::ToggleUnknownLogs, KeyEvent::alt('u')),
+        (KeyAction::ToggleBadLinesOnly, KeyEvent::alt('b')),
+        (KeyAction::ToggleCollapseDuplicates, simple('c')),
+        (KeyAction::ToggleSearchTimeWindow, simple('w')),
+        (KeyAction::ToggleTag, KeyEvent::simple(KeyCode::Enter)),
+        (KeyAction::SetAllTags, KeyEvent::ctrl('y')),
+        (KeyAction::UnsetAllTags, KeyEvent::ctrl('n')),
+        (KeyAction::ToggleAllTags, KeyEvent::ctrl('h')),
+        (KeyAction::ToggleTagMatchMode, KeyEvent::ctrl('g')),
+        (KeyAction::NextDebugLevel, KeyEvent::ctrl('e')),
+        (KeyAction::ClearDebugLogs, KeyEvent::ctrl('l')),
+      ]),
+    }
+  }
+}
+
+impl KeyMap {
+  /// 重新绑定某个动作的按键
+  pub fn with(mut self, action: KeyAction, key: KeyEvent) -> Self {
+    self.keys.insert(action, key);
+    self
+  }
+
+  /// 获取动作所绑定的按键
+  pub fn get(&self, action: KeyAction) -> KeyEvent {
+    self.keys[&action]
+  }
+
+  /// 依次应用用户给出的按键绑定，与其他动作、或者固定使用的按键冲突时报错。
+  /// 绑定的是动作当前的按键时忽略
+  pub fn with_bindings<'a>(
+    mut self,
+    bindings: impl IntoIterator<Item = &'a KeyBinding>,
+  ) -> Result<Self, String> {
+    for KeyBinding { action, key } in bindings {
+      if self.get(*action).same_as(key) {
+        continue;
+      }
+      if let Some(conflict) = self.conflict_of(*action, key) {
+        return Err(format!(
+          "key '{}' for {action:?} is already bound to {conflict}",
+          key_name(key)
+        ));
+      }
+      self = self.with(*action, *key);
+    }
+    Ok(self)
+  }
+
+  /// 按名称找到动作，不区分大小写，忽略单词之间的 '-' 或 '_'
+  fn action_named(&self, name: &str) -> Option<KeyAction> {
+    let name: String = name.chars().filter(|c| !matches!(c, '-' | '_')).collect();
+    self
+      .keys
+      .keys()
+      .find(|action| format!("{action:?}").eq_ignore_ascii_case(&name))
+      .copied()
+  }
+
+  /// 获取动作所绑定按键的名称，用于在帮助与提示中展示
+  pub fn name_of(&self, action: KeyAction) -> String {
+    key_name(&self.get(action))
+  }
+
+  /// 找到与给定按键冲突的绑定，用于在重新绑定动作前拒绝冲突的按键。
+  /// 返回冲突的动作名，或者固定使用的按键名
+  pub fn conflict_of(&self, action: KeyAction, key: &KeyEvent) -> Option<String> {
+    let conflicted = |k: &KeyEvent| k.same_as(key) || key.same_as(k);
+
+    if let Some((other, _)) = self
+      .keys
+      .iter()
+      .find(|(a, k)| **a != action && conflicted(k))
+    {
+      return Some(format!("{other:?}"));
+    }
+
+    FIXED_KEYS
+      .iter()
+      .find(|c| conflicted(&KeyEvent::simple(KeyCode::Char(**c))))
+      .map(|c| format!("'{c}'"))
+  }
+}
+
+/// 按键的名称，如 `ctrl x`
+fn key_name(key: &KeyEvent) -> String {
+  let mut name = String::new();
+  if key.modifiers.contains(KeyModifiers::CONTROL) {
+    name.push_str("ctrl ");
+  }
+  if key.modifiers.contains(KeyModifiers::ALT) {
+    name.push_str("alt ");
+  }
+  name + &key.code.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_key_binding() {
+    let parse = |s: &str| {
+      s.parse::<KeyBinding>()
+        .map(|b| (b.action, key_name(&b.key)))
+    };
+
+    assert_eq!(
+      parse("toggle-mark=x"),
+      Ok((KeyAction::ToggleMark, "x".to_string()))
+    );
+    assert_eq!(
+      parse("ToggleMark=X"),
+      Ok((KeyAction::ToggleMark, "X".to_string()))
+    );
+    assert_eq!(
+      parse("follow=ctrl-f"),
+      Ok((KeyAction::Follow, "ctrl f".to_string()))
+    );
+    assert_eq!(
+      parse("clear_marks=alt-c"),
+      Ok((KeyAction::ClearMarks, "alt c".to_string()))
+    );
+    assert_eq!(
+      parse("toggle-tag=space"),
+      Ok((KeyAction::ToggleTag, "Space".to_string()))
+    );
+    assert_eq!(parse("quit=="), Ok((KeyAction::Quit, "=".to_string())));
+    assert!(parse("toggle-mark").is_err());
+    assert!(parse("no-such-action=x").is_err());
+    assert!(parse("toggle-mark=xy").is_err());
+    assert!(parse("toggle-mark=ctrl-").is_err());
+  }
+
+  #[test]
+  fn test_with_bindings() {
+    let bind = |s: &str| s.parse::<KeyBinding>().unwrap();
+    let x = KeyEvent::simple(KeyCode::Char('x'));
+
+    // 重新绑定后，动作使用新的按键，原来的按键空出来可以再绑定给其他动作
+    let key_map = KeyMap::default()
+      .with_bindings(&[bind("toggle-pin=x"), bind("follow=p")])
+      .unwrap();
+    assert!(key_map.get(KeyAction::TogglePin).same_as(&x));
+    assert_eq!(key_map.name_of(KeyAction::Follow), "p");
+
+    // 绑定动作原有的按键不算冲突，与其他动作、或者固定使用的按键冲突时报错
+    assert!(KeyMap::default().with_bindings(&[bind("quit=q")]).is_ok());
+    assert_eq!(
+      KeyMap::default().with_bindings(&[bind("quit=t")]).err(),
+      Some("key 't' for Quit is already bound to OpenTags".to_string())
+    );
+    assert!(
+      KeyMap::default()
+        .with_bindings(&[bind("follow=j")])
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn test_conflict_of() {
+    let key_map = KeyMap::default();
+    let simple = |c: char| KeyEvent::simple(KeyCode::Char(c));
+
+    // 与自身原有的绑定不算冲突
+    assert_eq!(key_map.conflict_of(KeyAction::Quit, &simple('q')), None);
+    assert_eq!(key_map.conflict_of(KeyAction::Quit, &simple('x')), None);
+
+    // 与其他动作、或者固定使用的按键冲突
+    assert_eq!(
+      key_map.conflict_of(KeyAction::Quit, &simple('t')),
+      Some("OpenTags".to_string())
+    );
+    assert_eq!(
+      key_map.conflict_of(KeyAction::Quit, &simple('G')),
+      Some("'g'".to_string())
+    );
+    assert_eq!(
+      key_map.conflict_of(KeyAction::Quit, &simple('y')),
+      Some("'y'".to_string())
+    );
+  }
+}
@@ -1,5 +1,10 @@
-use crate::log::{Config, DataBoard, Index as LogIndex, LogDirection, LogLine, RotatedLog};
-use std::path::PathBuf;
+use crate::file::{DirWatcher, is_stdin};
+use crate::log::{
+  Config, DataBoard, Index as LogIndex, LogDirection, LogFilter, LogLine, RotatedLog,
+  position_fraction,
+};
+use chrono::{DateTime, FixedOffset};
+use std::path::{Path, PathBuf};
 use std::{
   cmp::Ordering,
   collections::HashMap,
@@ -12,6 +17,9 @@ use tokio::{
 };
 use tokio_util::sync::CancellationToken;
 
+/// 预先建立跳转链路时，每一批最多访问的日志行数
+const LINK_WARMING_STEPS: usize = 4096;
+
 /// 所有日志文件的索引
 #[derive(Clone, Default)]
 pub struct Index {
@@ -24,6 +32,20 @@ pub struct Index {
   selection: usize,
 }
 
+impl Index {
+  /// 两个索引是否指向同一行日志。同一行日志在不同的遍历过程中，其他日志文件的索引可能不同，
+  /// 因此只比较被选中的那一份日志文件中的索引
+  pub fn points_to_same(&self, other: &Index) -> bool {
+    self.selection == other.selection
+      && self.indexes.get(self.selection) == other.indexes.get(other.selection)
+  }
+
+  /// 被选中的日志行的键，指向同一行日志（见 `points_to_same`）的索引具有相同的键
+  pub fn line_key(&self) -> (usize, Option<LogIndex>) {
+    (self.selection, self.indexes.get(self.selection).copied())
+  }
+}
+
 /// 日志文件，支持内容的查找操作，以及标记操作，
 pub struct LogHubRef<'a> {
   /// 所有的被跟踪的系统日志
@@ -31,6 +53,9 @@ pub struct LogHubRef<'a> {
 
   /// 数据看板，代表所有日志的统计数据，由所有日志更新时一同更新
   data_board: &'a mut DataBoard,
+
+  /// 在全局的过滤条件之外，遍历时只保留该标签的日志
+  locked_tag: Option<String>,
 }
 
 pub struct LogHub {
@@ -46,24 +71,62 @@ pub struct LogHub {
 
   /// 控制异步流程是否终止的 token
   stop_token: CancellationToken,
+
+  /// 日志存储的根目录
+  root: PathBuf,
+
+  /// 监控根目录下新出现的日志文件，仅在启用时有值
+  dir_watcher: Option<DirWatcher>,
+
+  /// 读取标准输入、以及监控中新发现的日志文件时使用的配置
+  config: Config,
 }
 
 impl LogHub {
   /// 基于给定的系统日志存储根目录，以及已知的系统日志名称（文件名，不含后缀），
-  /// 创建本对象
-  pub fn open(root: PathBuf, names: HashMap<String, Config>) -> Self {
+  /// 创建本对象。
+  ///
+  /// 若根目录为 `-`，则忽略给定的日志名称，只按 `config` 读取标准输入的内容。
+  /// 监控中新发现的日志文件也按 `config` 读取。
+  pub fn open(root: PathBuf, names: HashMap<String, Config>, config: Config) -> Self {
     // 创建各个系统日志对象，组成有序的数组，该顺序在整个进程内都不会再改变
-    let logs: Vec<RotatedLog> = names
+    let logs: Vec<RotatedLog> = if is_stdin(&root) {
+      vec![RotatedLog::new(root.clone(), config.clone())]
+    } else {
+      names
+        .into_iter()
+        .map(|(name, config)| RotatedLog::new(root.join(name + ".log"), config))
+        .collect()
+    };
+
+    Self::with_logs(root, logs, config)
+  }
+
+  /// 基于给定的若干日志文件的完整路径创建本对象，它们不必位于同一目录，也不必以 `.log` 结尾。
+  /// 每个文件都是一份独立的系统日志，在它自己所在的目录下查找被滚动的旧文件。
+  ///
+  /// 这些文件最深的公共父目录将作为日志存储的根目录，监控中在其下新发现的日志文件按 `config` 读取。
+  pub fn open_files(paths: Vec<(PathBuf, Config)>, config: Config) -> Self {
+    let root = common_parent(paths.iter().map(|(path, _)| path.as_path()));
+    let logs = paths
       .into_iter()
-      .map(|(name, config)| RotatedLog::new(root.join(name + ".log"), config))
+      .map(|(path, config)| RotatedLog::new(path, config))
       .collect();
 
+    Self::with_logs(root, logs, config)
+  }
+
+  /// 基于给定的日志存储根目录，以及已经创建好的系统日志对象，创建本对象
+  fn with_logs(root: PathBuf, logs: Vec<RotatedLog>, config: Config) -> Self {
     // 创建本 hub 对象
     let mut hub = Self {
       logs,
-      data_board: Arc::new(Mutex::new(DataBoard::new(root))),
+      data_board: Arc::new(Mutex::new(DataBoard::new(root.clone()))),
       log_handles: Vec::new(),
       stop_token: CancellationToken::new(),
+      root,
+      dir_watcher: None,
+      config,
     };
 
     // 启动异步刷新流程
@@ -73,6 +136,13 @@ impl LogHub {
     hub
   }
 
+  /// 监控根目录下新出现的、文件名符合给定 glob 模式（如 `*.log`）的日志文件，
+  /// 在下一次获取数据访问接口时，将它们作为新的系统日志加入进来
+  pub fn watch_new_logs(&mut self, pattern: &str) -> anyhow::Result<()> {
+    self.dir_watcher = Some(DirWatcher::new(&self.root, pattern)?);
+    Ok(())
+  }
+
   /// 停止所有异步刷新活动
   pub async fn close(&mut self) {
     self.stop_updating().await;
@@ -126,6 +196,23 @@ impl LogHub {
 
     // 将日志对象放回本类
     self.logs = results.into_iter().map(|(_, log)| log).collect();
+
+    // 加入新出现的日志，它们会在下一次刷新时一同启动异步刷新流程
+    self.add_new_logs();
+  }
+
+  /// 将目录监控中发现的新日志文件，作为新的系统日志加入到末尾，已有的日志顺序不变
+  fn add_new_logs(&mut self) {
+    let Some(dir_watcher) = self.dir_watcher.as_mut() else {
+      return;
+    };
+
+    for path in dir_watcher.take_new_paths() {
+      if self.logs.iter().all(|log| log.path() != &path) {
+        crate::println!("found new log {:?}", path);
+        self.logs.push(RotatedLog::new(path, self.config.clone()));
+      }
+    }
   }
 
   /// 异步刷新某个系统日志的流程
@@ -135,11 +222,21 @@ impl LogHub {
     data_board: Arc<Mutex<DataBoard>>,
     stop_token: CancellationToken,
   ) -> (usize, RotatedLog) {
-    if log.prepare().await {
+    if log.prepare(&data_board).await {
+      let mut warmed = false;
       loop {
-        tokio::select! {
+        // 优先处理新的内容，没有新内容时才分批预先建立跳转链路，使得新内容不会被预热拖慢
+        let idle = tokio::select! {
+          biased;
           _ = stop_token.cancelled() => break,
-          _ = log.update(data_board.clone()) => {}
+          _ = log.update(data_board.clone()) => false,
+          _ = std::future::ready(()), if !warmed => true,
+        };
+
+        // 每一批只在遍历期间持有数据黑板的锁，批与批之间释放，并让出执行权
+        if idle {
+          warmed = log.warm_links(&*data_board.lock().await, LINK_WARMING_STEPS);
+          task::yield_now().await;
         }
       }
     }
@@ -148,6 +245,19 @@ impl LogHub {
   }
 }
 
+/// 找出给定的若干文件最深的公共父目录，没有文件时返回空路径
+fn common_parent<'a>(mut paths: impl Iterator<Item = &'a Path>) -> PathBuf {
+  let Some(first) = paths.next() else {
+    return PathBuf::new();
+  };
+
+  let mut root = first.parent().unwrap_or(first).to_path_buf();
+  for path in paths {
+    while !path.starts_with(&root) && root.pop() {}
+  }
+  root
+}
+
 /// 导出日志数据操作器，在声明周期结束时，自动开始异步的更新流程
 pub struct LogHubDataGuard<'a> {
   hub: &'a mut LogHub,
@@ -163,7 +273,11 @@ impl<'a> LogHubDataGuard<'a> {
 
     Self {
       hub,
-      data: LogHubRef { logs, data_board },
+      data: LogHubRef {
+        logs,
+        data_board,
+        locked_tag: None,
+      },
       _data_board_guard: data_board_guard,
     }
   }
@@ -328,14 +442,19 @@ impl<'a> LogHubRef<'a> {
 
   /// 获取从指定索引处，开始正向遍历的迭代器
   pub fn iter_forward_from(&'_ mut self, index: Index) -> impl Iterator<Item = LogItem<'_>> {
-    let tags_ref = self.data_board.get_tags();
+    let filter = Self::filter(self.data_board, self.locked_tag.as_deref());
 
     Iter {
-      iters: index
-        .indexes
+      iters: Self::pad_indexes(index.indexes, self.logs, RotatedLog::first_index)
         .into_iter()
         .zip(self.logs.iter_mut())
-        .map(|(idx, log)| (idx, log.filtered_iter_forward_from(tags_ref, idx), None))
+        .map(|(idx, log)| {
+          (
+            idx,
+            log.filtered_iter_forward_from(filter.clone(), idx),
+            None,
+          )
+        })
         .collect(),
       cmp: LogLine::is_older,
       init_selection: index.selection,
@@ -345,14 +464,19 @@ impl<'a> LogHubRef<'a> {
 
   /// 获取从指定索引处，开始逆向遍历的迭代器
   pub fn iter_backward_from(&'_ mut self, index: Index) -> impl Iterator<Item = LogItem<'_>> {
-    let tags_ref = self.data_board.get_tags();
+    let filter = Self::filter(self.data_board, self.locked_tag.as_deref());
 
     Iter {
-      iters: index
-        .indexes
+      iters: Self::pad_indexes(index.indexes, self.logs, RotatedLog::last_index)
         .into_iter()
         .zip(self.logs.iter_mut())
-        .map(|(idx, log)| (idx, log.filtered_iter_backward_from(tags_ref, idx), None))
+        .map(|(idx, log)| {
+          (
+            idx,
+            log.filtered_iter_backward_from(filter.clone(), idx),
+            None,
+          )
+        })
         .collect(),
       cmp: LogLine::is_newer,
       init_selection: index.selection,
@@ -360,6 +484,31 @@ impl<'a> LogHubRef<'a> {
     }
   }
 
+  /// 设置遍历时锁定的标签，为 None 时只使用全局的过滤条件
+  pub fn lock_tag(&mut self, tag: Option<String>) {
+    self.locked_tag = tag;
+  }
+
+  /// 遍历时使用的过滤条件。锁定了标签时，它和全局的过滤条件一同作用，并拥有自己的跳转链路
+  fn filter<'b>(data_board: &'b mut DataBoard, locked_tag: Option<&'b str>) -> LogFilter<'b> {
+    match locked_tag {
+      None => LogFilter::from(&*data_board),
+      Some(tag) => LogFilter::locked_to_tag(data_board, tag),
+    }
+  }
+
+  /// 索引可能是在新的日志加入之前生成的，此时用给定的方式为这些新日志补齐索引
+  fn pad_indexes(
+    mut indexes: Vec<LogIndex>,
+    logs: &[RotatedLog],
+    f: impl Fn(&RotatedLog) -> LogIndex,
+  ) -> Vec<LogIndex> {
+    for log in logs.iter().skip(indexes.len()) {
+      indexes.push(f(log));
+    }
+    indexes
+  }
+
   /// 获取从第一条日志开始正向遍历的迭代器
   pub fn iter_forward_from_head(&'_ mut self) -> impl Iterator<Item = LogItem<'_>> {
     let index = self.first_index();
@@ -413,16 +562,150 @@ impl<'a> LogHubRef<'a> {
     }
   }
 
+  /// 收集所有被标记、且没有被过滤掉的日志，按从旧到新的顺序排列
+  pub fn marked_logs(&mut self) -> Vec<(Index, LogLine)> {
+    self
+      .iter_forward_from_head()
+      .filter(|(_, log)| log.is_marked())
+      .map(|(index, log)| (index, log.clone()))
+      .collect()
+  }
+
+  /// 清除所有已加载日志（包括被过滤掉的）的标记，返回被清除标记的日志数量
+  pub fn clear_all_marks(&mut self) -> usize {
+    let mut cleared = 0;
+    for log in self.logs.iter_mut() {
+      log.for_each_line_mut(|line| {
+        if line.is_marked() {
+          line.set_mark(false);
+          cleared += 1;
+        }
+      });
+    }
+
+    if cleared > 0 {
+      self.data_board.update_marks();
+    }
+    cleared
+  }
+
+  /// 已经加载的所有日志行数量（不考虑标签过滤）
+  pub fn total_lines(&self) -> usize {
+    self.logs.iter().map(RotatedLog::len).sum()
+  }
+
+  /// 估算给定索引在已加载的所有日志中是第几行（从 0 开始）。
+  /// 由于各个日志的索引只在被选中时才精确，且不考虑标签过滤，这只是一个近似值
+  pub fn position(&self, index: &Index) -> usize {
+    index
+      .indexes
+      .iter()
+      .zip(self.logs.iter())
+      .map(|(idx, log)| log.position(*idx))
+      .sum()
+  }
+
+  /// 估算给定索引在已加载的所有日志中的相对位置，范围为 \[0, 1]，同样只是一个近似值
+  pub fn fraction(&self, index: &Index) -> f64 {
+    position_fraction(self.position(index), self.total_lines())
+  }
+
+  /// 估算已加载的所有日志中处于给定相对位置的日志索引，是 fraction 的近似逆运算。
+  /// 各个日志分别取该相对位置处的日志，再从中选出最旧的一条；若之后没有可见的日志，则取最后一条
+  pub fn index_at_fraction(&mut self, fraction: f64) -> Index {
+    let index = Index {
+      indexes: self
+        .logs
+        .iter()
+        .map(|log| log.index_at_fraction(fraction))
+        .collect(),
+      selection: usize::MAX,
+    };
+
+    let found = self.iter_forward_from(index).next().map(|(index, _)| index);
+    found.unwrap_or_else(|| self.last_index())
+  }
+
+  /// 在已加载的所有日志中二分查找时间戳与给定时间最接近的日志，免去逐行归并。
+  /// 该日志被过滤掉时，取它之后第一条可见的日志；之后没有可见的日志时，取最后一条
+  pub fn locate_time(&mut self, dt: DateTime<FixedOffset>) -> Index {
+    // 各个日志中最接近的日志里，再选出全局最接近的，距离相同时取更早的
+    let Some(target) = self
+      .logs
+      .iter()
+      .filter_map(|log| log.find_by_timestamp(dt))
+      .map(|(_, timestamp)| timestamp)
+      .min_by_key(|timestamp| ((*timestamp - dt).abs(), *timestamp))
+    else {
+      return self.last_index();
+    };
+
+    // 各个日志分别从第一条不早于该时间的日志出发，没有这样的日志时从最后一条出发，
+    // 归并后第一条不早于该时间的日志即为所求，同时得到正确的 selection
+    let index = Index {
+      indexes: self
+        .logs
+        .iter()
+        .map(|log| {
+          log
+            .first_at_or_after(target)
+            .unwrap_or_else(|| log.last_index())
+        })
+        .collect(),
+      selection: usize::MAX,
+    };
+
+    let found = self
+      .iter_forward_from(index)
+      .find(|(_, log)| log.get_timestamp().is_some_and(|t| t >= target))
+      .map(|(index, _)| index);
+    found.unwrap_or_else(|| self.last_index())
+  }
+
+  /// 基于当前已加载的数据，重新计算索引中未被选中的各个日志的位置，被选中的日志行保持不变。
+  /// 索引中未被选中的日志记录的是生成索引时它们的下一条日志，此后在后台加载了更旧的文件、
+  /// 或者加入了新的日志时，新内容中晚于被选中日志行的部分会被跳过，因此需要重新计算
+  pub fn remap(&mut self, index: Index) -> Index {
+    // 没有选中任何日志，或者选中的日志行没有时间戳时，无法重新计算
+    let Some(timestamp) = index
+      .indexes
+      .get(index.selection)
+      .zip(self.logs.get(index.selection))
+      .and_then(|(idx, log)| log.get(*idx))
+      .and_then(LogLine::get_timestamp)
+    else {
+      return index;
+    };
+
+    // 其余日志从足够靠前的位置出发，归并遍历时早于被选中日志行的部分会被跳过
+    let start = Index {
+      indexes: self
+        .logs
+        .iter()
+        .enumerate()
+        .map(|(i, log)| match i == index.selection {
+          true => index.indexes[i],
+          false => log.last_before(timestamp),
+        })
+        .collect(),
+      selection: index.selection,
+    };
+
+    let remapped = self.iter_forward_from(start).next().map(|(index, _)| index);
+    remapped.unwrap_or(index)
+  }
+
   /// 尝试加载更旧的日志。将会从给定的日志索引中，找到已经顶到头的那些，
-  /// 要求它们进行加载。
+  /// 要求它们进行加载。每一个新的加载需求都会在数据看板中记为一次正在进行的加载。
   pub fn try_load_older_logs(&mut self, index: &Index) {
     index
       .indexes
       .iter()
       .zip(self.logs.iter_mut())
       .for_each(|(idx, log)| {
-        if idx == &log.first_index() {
+        if idx == &log.first_index() && !log.wants_older_log() {
           log.set_want_older_log();
+          self.data_board.begin_loading();
         }
       });
   }
@@ -0,0 +1,170 @@
+//! 不依赖终端界面的日志查询接口，用于将本库作为日志处理库使用
+
+use crate::app::LogHub;
+use crate::log::{Config, LogLine, TagsData};
+use chrono::{DateTime, FixedOffset};
+use std::{
+  collections::{HashMap, HashSet},
+  path::PathBuf,
+};
+use tokio::time::{Duration, sleep};
+
+/// 日志内容的过滤条件
+type Predicate = Box<dyn Fn(&LogLine) -> bool + Send + Sync>;
+
+/// 基于 `LogHub` 的日志查询器，可以按标签、主机名过滤日志，并叠加任意的内容、时间过滤条件。
+///
+/// ```no_run
+/// # async fn example() {
+/// use rs_syslog_viewer::app::LogQuery;
+///
+/// let mut query = LogQuery::open("/var/log".into(), ["syslog"])
+///   .with_tags(["kernel"])
+///   .with_content("usb");
+/// query.wait_loaded().await;
+///
+/// for log in query.run().await {
+///   println!("{}", log.to_syslog_string());
+/// }
+/// # }
+/// ```
+pub struct LogQuery {
+  hub: LogHub,
+
+  /// 只保留这些标签的日志，为空则不按标签过滤
+  tags: Option<HashSet<String>>,
+
+  /// 只保留这些主机名的日志，为空则不按主机名过滤
+  hosts: Option<HashSet<String>>,
+
+  /// 所有的过滤条件，日志需要同时满足它们
+  predicates: Vec<Predicate>,
+}
+
+impl LogQuery {
+  /// 打开根目录下给定名称（文件名，不含后缀）的系统日志，所有滚动的旧日志都会被预加载
+  pub fn open<I, S>(root: PathBuf, names: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    let names: HashMap<String, Config> = names
+      .into_iter()
+      .map(|name| (name.into(), Config::default().with_eager_preload()))
+      .collect();
+
+    Self::from_hub(LogHub::open(
+      root,
+      names,
+      Config::default().with_eager_preload(),
+    ))
+  }
+
+  /// 基于已经打开的日志创建查询器
+  pub fn from_hub(hub: LogHub) -> Self {
+    Self {
+      hub,
+      tags: None,
+      hosts: None,
+      predicates: vec![],
+    }
+  }
+
+  /// 只保留给定标签的日志
+  pub fn with_tags<I, S>(mut self, tags: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.tags = Some(tags.into_iter().map(Into::into).collect());
+    self
+  }
+
+  /// 只保留给定主机名的日志
+  pub fn with_hosts<I, S>(mut self, hosts: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.hosts = Some(hosts.into_iter().map(Into::into).collect());
+    self
+  }
+
+  /// 只保留内容中包含给定文本的日志
+  pub fn with_content(self, text: impl Into<String>) -> Self {
+    let text = text.into();
+    self.with_predicate(move |log| log.get_content().contains(&text))
+  }
+
+  /// 只保留时间戳处于 \[begin, end) 之间的日志，没有时间戳的日志总是被过滤掉
+  pub fn with_time_range(self, begin: DateTime<FixedOffset>, end: DateTime<FixedOffset>) -> Self {
+    self.with_predicate(move |log| {
+      log
+        .get_timestamp()
+        .is_some_and(|timestamp| begin <= timestamp && timestamp < end)
+    })
+  }
+
+  /// 叠加任意的过滤条件，日志需要同时满足所有的条件
+  pub fn with_predicate(
+    mut self,
+    predicate: impl Fn(&LogLine) -> bool + Send + Sync + 'static,
+  ) -> Self {
+    self.predicates.push(Box::new(predicate));
+    self
+  }
+
+  /// 等待日志加载完成。日志是在后台异步加载的，我们认为已加载的日志行数量不再变化时，加载就完成了
+  pub async fn wait_loaded(&mut self) {
+    let mut last_total_lines = None;
+
+    loop {
+      sleep(Duration::from_millis(200)).await;
+
+      let mut data = self.hub.data().await;
+      let first_index = data.first_index();
+      data.try_load_older_logs(&first_index);
+
+      let total_lines = data.total_lines();
+      if last_total_lines == Some(total_lines) {
+        break;
+      }
+      last_total_lines = Some(total_lines);
+    }
+  }
+
+  /// 按时间顺序取出目前已加载的日志中，所有满足过滤条件的日志
+  pub async fn run(&mut self) -> impl Iterator<Item = LogLine> + use<> {
+    let mut data = self.hub.data().await;
+
+    // 设置标签与主机名过滤
+    Self::select(data.data_board().get_tags_mut(), &self.tags);
+    Self::select(data.data_board().get_hosts_mut(), &self.hosts);
+
+    // 取出满足其他条件的日志
+    let logs: Vec<LogLine> = data
+      .iter_forward_from_head()
+      .filter(|(_, log)| self.predicates.iter().all(|predicate| predicate(log)))
+      .map(|(_, log)| log.clone())
+      .collect();
+
+    logs.into_iter()
+  }
+
+  /// 按给定的集合选中数据，未给定集合时全部选中
+  fn select(data: &mut TagsData, selected: &Option<HashSet<String>>) {
+    let all: Vec<String> = data.all().keys().cloned().collect();
+    for key in all {
+      match selected {
+        Some(selected) if !selected.contains(&key) => data.unset(&key),
+        _ => data.set(&key),
+      }
+    }
+    data.update_version();
+  }
+
+  /// 停止后台的日志加载
+  pub async fn close(&mut self) {
+    self.hub.close().await;
+  }
+}
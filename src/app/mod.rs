@@ -1,15 +1,30 @@
+mod clipboard;
+mod color_scheme;
+mod command;
 pub mod controller;
+mod display_tz;
+mod fnv;
+mod key_map;
 mod log_hub;
+mod log_query;
 pub mod page;
 mod rich;
+mod session;
 pub mod state;
 mod then;
 mod time_matcher;
 mod viewer;
 
+pub use clipboard::{Clipboard, SystemClipboard};
+pub use color_scheme::ColorScheme;
+pub use command::{Command, GotoTarget};
 pub use controller::Controller;
+pub use display_tz::DisplayTimezone;
+pub use key_map::{KeyAction, KeyBinding, KeyMap};
 pub use log_hub::{Index, LogHub, LogHubRef, LogItem};
-pub use rich::rich;
+pub use log_query::LogQuery;
+pub use rich::{HighlightRule, RichSpans, rich};
+pub use session::Session;
 pub use state::{StateBuilder, ViewPortStateEx};
-pub use time_matcher::TimeMatcher;
+pub use time_matcher::{TimeBounds, TimeMatcher};
 pub use viewer::{Config, Viewer};
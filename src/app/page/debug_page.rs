@@ -1,5 +1,8 @@
 use crate::{
-  app::controller::DebugController,
+  app::{
+    controller::DebugController,
+    rich::{apply_matches_on_spans, find_all_matches},
+  },
   debug::Item,
   ui::{Page, PageState, ViewPortRenderEx},
 };
@@ -19,20 +22,26 @@ pub struct DebugPage {
 
 impl Page for DebugPage {
   fn render(&self, area: Rect, buf: &mut Buffer, state: &PageState) {
-    self
-      .debug_controller
-      .borrow_mut()
+    let mut controller = self.debug_controller.borrow_mut();
+    let search = controller.get_curr_search().to_string();
+    controller
       .view_mut()
-      .render(area, buf, state.focus, |(_, v)| self.render_item(v))
+      .render(area, buf, state, |(_, v)| self.render_item(v, &search))
   }
 
   fn title(&'_ self) -> Cow<'_, str> {
-    "Debug Logs".into()
+    let controller = self.debug_controller.borrow();
+    let filter = controller.filter();
+    match filter.search.is_empty() {
+      _ if filter.is_empty() => "Debug Logs".into(),
+      true => format!("Debug Logs ({})", filter.level.name()).into(),
+      false => format!("Debug Logs ({}, '{}')", filter.level.name(), filter.search).into(),
+    }
   }
 }
 
 impl DebugPage {
-  fn render_item<'a>(&self, item: &'a Item) -> Line<'a> {
+  fn render_item<'a>(&self, item: &'a Item, search: &str) -> Line<'a> {
     let mut line = Line::default();
 
     line.push_span(
@@ -52,7 +61,12 @@ impl DebugPage {
     } else {
       Color::White
     };
-    line.push_span(Span::raw(&item.content).fg(color));
+    // 搜索的内容反色展示
+    let content = Span::raw(&item.content).fg(color);
+    let matches = find_all_matches(&item.content, &[search]);
+    for span in apply_matches_on_spans(vec![(content, (0, item.content.len()))], matches) {
+      line.push_span(span);
+    }
 
     line
   }
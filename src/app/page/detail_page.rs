@@ -0,0 +1,52 @@
+use crate::{
+  app::controller::DetailController,
+  ui::{Page, PageState},
+};
+use ratatui::{
+  buffer::Buffer,
+  layout::Rect,
+  style::Stylize,
+  text::{Line, Span},
+  widgets::{Paragraph, Widget, Wrap},
+};
+use std::{borrow::Cow, cell::RefCell, rc::Rc};
+
+pub struct DetailPage {
+  /// 本页面渲染依据的状态数据
+  pub detail_controller: Rc<RefCell<DetailController>>,
+}
+
+impl Page for DetailPage {
+  fn render(&self, area: Rect, buf: &mut Buffer, _: &PageState) {
+    let ctrl = self.detail_controller.borrow();
+    let fields = ctrl.fields();
+    if fields.is_empty() {
+      Paragraph::new("No log under the cursor.".dark_gray()).render(area, buf);
+      return;
+    }
+
+    // 各字段名称对齐展示，日志内容另起一段，按原有的换行以及页面宽度折行完整展示
+    let name_width = fields.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    let mut lines = vec![];
+    for (name, value) in fields {
+      if *name == "message" {
+        lines.push(Line::default());
+        lines.extend(value.lines().map(|line| Line::raw(line.to_string())));
+      } else {
+        lines.push(Line::from(vec![
+          Span::raw(format!("{:>width$}: ", name, width = name_width)).cyan(),
+          Span::raw(value.clone()),
+        ]));
+      }
+    }
+
+    Paragraph::new(lines)
+      .wrap(Wrap { trim: false })
+      .scroll((ctrl.scroll(), 0))
+      .render(area, buf);
+  }
+
+  fn title(&'_ self) -> Cow<'_, str> {
+    "Detail".into()
+  }
+}
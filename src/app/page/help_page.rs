@@ -19,11 +19,15 @@ pub struct HelpPage {
 
 impl Page for HelpPage {
   fn render(&self, area: Rect, buf: &mut Buffer, state: &PageState) {
-    self
-      .help_controller
-      .borrow_mut()
-      .view_mut()
-      .render(area, buf, true, |(_, v)| self.render_item(v))
+    self.help_controller.borrow_mut().view_mut().render(
+      area,
+      buf,
+      &PageState {
+        focus: true,
+        ..*state
+      },
+      |(_, v)| self.render_item(v),
+    )
   }
 
   fn title(&'_ self) -> Cow<'_, str> {
@@ -32,15 +36,15 @@ impl Page for HelpPage {
 }
 
 impl HelpPage {
-  fn render_item<'a>(&self, item: &HelpLine) -> Line<'a> {
+  fn render_item<'a>(&self, item: &'a HelpLine) -> Line<'a> {
     let mut line = Line::default();
-    match *item {
+    match item {
       HelpLine::Title(content) => {
-        line.push_span(Span::raw(content).white().bold().underlined());
+        line.push_span(Span::raw(*content).white().bold().underlined());
       }
       HelpLine::Item(content) => {
         line.push_span(Span::raw("• ").cyan().bold());
-        rich(&mut line, content, "");
+        rich(&mut line, content.as_ref(), &[]);
         // line.push_span(Span::raw(content).gray());
       }
       HelpLine::Separator => {
@@ -1,22 +1,36 @@
-use crate::app::controller::log_controller::{PidStyle, TagStyle, TimestampStyle};
+use crate::app::controller::log_controller::{FacilityStyle, PidStyle, SeverityStyle, TagStyle};
 use crate::{
   app::{
     controller::{
       LogController,
       log_controller::{Properties, Style},
     },
+    fnv::fnv1a,
     rich,
   },
-  log::{Label, LogLine},
-  ui::{Page, PageState, ViewPortRenderEx},
+  log::{Label, LogLine, facility_name},
+  ui::{ColorCapability, Page, PageState, ViewPortEx, ViewPortRenderEx},
 };
 use chrono::{DateTime, FixedOffset};
 use ratatui::{buffer::Buffer, layout::Rect, prelude::*, text::Span};
-use std::{borrow::Cow, cell::RefCell, rc::Rc};
+use std::{borrow::Cow, cell::RefCell, rc::Rc, str::FromStr};
 
+#[derive(Clone)]
 pub struct Config {
   short_tag_len: usize,
   long_tag_len: usize,
+
+  /// 是否按标签名为每个标签分配不同的颜色，而不是统一使用一种颜色
+  tag_colors: bool,
+
+  /// 每行日志最多展示的列数，超出的部分被截断并以 `…` 结尾，为空时不截断
+  max_line_width: Option<usize>,
+
+  /// 被标记的日志在行首展示的符号
+  marker: String,
+
+  /// 被标记的日志的突出方式
+  mark_style: MarkStyle,
 }
 
 impl Default for Config {
@@ -24,6 +38,148 @@ impl Default for Config {
     Self {
       short_tag_len: 10,
       long_tag_len: 18,
+      tag_colors: false,
+      max_line_width: None,
+      marker: "🚩".to_string(),
+      mark_style: MarkStyle::default(),
+    }
+  }
+}
+
+impl Config {
+  /// 设置是否按标签名为每个标签分配不同的颜色，便于区分不同的服务
+  pub fn with_tag_colors(mut self, tag_colors: bool) -> Self {
+    self.tag_colors = tag_colors;
+    self
+  }
+
+  /// 设置每行日志最多展示的列数，避免过长的行需要大量横向滚动。
+  /// 这只影响展示，搜索、复制与导出仍然使用完整的内容
+  pub fn with_max_line_width(mut self, max_line_width: Option<usize>) -> Self {
+    self.max_line_width = max_line_width;
+    self
+  }
+
+  /// 设置被标记的日志在行首展示的符号，便于在不支持 emoji 的终端或字体中使用
+  pub fn with_marker(mut self, marker: String) -> Self {
+    self.marker = marker;
+    self
+  }
+
+  /// 设置被标记的日志的突出方式
+  pub fn with_mark_style(mut self, mark_style: MarkStyle) -> Self {
+    self.mark_style = mark_style;
+    self
+  }
+}
+
+/// 不支持颜色的终端中，非 ASCII 的标记符号被替换为该符号
+const MONO_MARKER: &str = "*";
+
+/// 被标记的日志的突出方式
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum MarkStyle {
+  /// 斜体展示
+  #[default]
+  Italic,
+
+  /// 加粗展示
+  Bold,
+
+  /// 整行带有背景色
+  Band,
+}
+
+impl FromStr for MarkStyle {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "italic" => Ok(MarkStyle::Italic),
+      "bold" => Ok(MarkStyle::Bold),
+      "band" => Ok(MarkStyle::Band),
+      _ => Err(format!(
+        "unknown mark style '{s}', expected 'italic', 'bold' or 'band'"
+      )),
+    }
+  }
+}
+
+/// 深色背景下，为标签分配颜色的调色板。只使用基本颜色，使得在仅支持 16 色的终端上，不同的颜色仍然可以区分
+const DARK_TAG_PALETTE: &[Color] = &[
+  Color::Magenta,
+  Color::LightMagenta,
+  Color::LightRed,
+  Color::LightGreen,
+  Color::LightYellow,
+  Color::LightBlue,
+  Color::LightCyan,
+  Color::Green,
+  Color::Yellow,
+  Color::Red,
+];
+
+/// 浅色背景下，为标签分配颜色的调色板
+const LIGHT_TAG_PALETTE: &[Color] = &[
+  Color::Magenta,
+  Color::Red,
+  Color::Green,
+  Color::Blue,
+  Color::Cyan,
+  Color::Yellow,
+  Color::LightRed,
+  Color::LightBlue,
+  Color::DarkGray,
+];
+
+/// 日志各项内容的颜色
+#[derive(Copy, Clone, Debug)]
+pub struct Colors {
+  pub gutter: Color,
+  pub timestamp: Color,
+  pub tag: Color,
+  pub pid: Color,
+  pub pid_brackets: Color,
+  pub facility: Color,
+  pub bad_log: Color,
+  pub minimap: Color,
+  pub marked_band: Color,
+
+  /// 按标签名分配颜色时使用的调色板
+  pub tag_palette: &'static [Color],
+}
+
+impl Default for Colors {
+  fn default() -> Self {
+    Self {
+      gutter: Color::DarkGray,
+      timestamp: Color::Cyan,
+      tag: Color::Magenta,
+      pid: Color::Yellow,
+      pid_brackets: Color::White,
+      facility: Color::LightBlue,
+      bad_log: Color::Red,
+      minimap: Color::Yellow,
+      marked_band: Color::Rgb(72, 56, 0),
+      tag_palette: DARK_TAG_PALETTE,
+    }
+  }
+}
+
+impl Colors {
+  /// 适用于浅色背景的颜色
+  pub fn light() -> Self {
+    Self {
+      gutter: Color::Gray,
+      timestamp: Color::Blue,
+      tag: Color::Magenta,
+      pid: Color::Green,
+      pid_brackets: Color::Black,
+      facility: Color::DarkGray,
+      bad_log: Color::Red,
+      minimap: Color::Blue,
+      marked_band: Color::Rgb(255, 236, 179),
+      tag_palette: LIGHT_TAG_PALETTE,
     }
   }
 }
@@ -34,93 +190,253 @@ pub struct LogPage {
 
   /// 渲染配置
   pub config: Config,
+
+  /// 日志内容的颜色
+  pub colors: Colors,
 }
 
 impl Page for LogPage {
-  fn render(&self, area: Rect, buf: &mut Buffer, state: &PageState) {
-    let style = *self.log_controller.borrow().style();
-    let search = crate::unsafe_ref!(str, self.log_controller.borrow().get_search_content());
+  fn render(&self, mut area: Rect, buf: &mut Buffer, state: &PageState) {
+    // 有搜索匹配时，在最右侧留出一列展示匹配的分布
+    if area.width > 1 {
+      let ctrl = self.log_controller.borrow();
+      let density = ctrl.match_density();
+      if !density.is_empty() {
+        area.width -= 1;
+        let column = Rect::new(area.right(), area.y, 1, area.height);
+        self.render_minimap(column, buf, state, density, ctrl.is_reverse_order());
+      }
+    }
+
+    let style = self.log_controller.borrow().style().clone();
+    let gutter_width = self.log_controller.borrow().gutter_width();
+    let needles: Vec<String> = self
+      .log_controller
+      .borrow()
+      .search_needles()
+      .into_iter()
+      .map(String::from)
+      .collect();
+    let needles: Vec<&str> = needles.iter().map(String::as_str).collect();
+    let pinned = self.log_controller.borrow().pinned().cloned();
+
+    // 内容搜索跳转后，横向滚动使匹配的内容可见，滚动的列数还要算上日志内容之前的各项
+    let content_match_column = self.log_controller.borrow_mut().take_content_match_column();
+    if let Some(column) = content_match_column {
+      let mut ctrl = self.log_controller.borrow_mut();
+      let view = ctrl.view_mut();
+      let prefix = view.data().get(view.ui().cursor()).map(|(_, log, props)| {
+        self.prefix_width(log, &style, props, gutter_width, state.color_capability)
+      });
+      if let Some(prefix) = prefix {
+        view.ui_mut().scroll_horizontally_to_column(prefix + column);
+      }
+    }
 
     self
       .log_controller
       .borrow_mut()
       .view_mut()
-      .render(area, buf, state.focus, |(_, i, p)| {
-        self.render_log_line(i, style, search, &p)
+      .render_with_pinned(area, buf, state, pinned.as_ref(), |(_, i, p)| {
+        self.render_log_line(
+          i,
+          &style,
+          &needles,
+          &p,
+          gutter_width,
+          state.color_capability,
+        )
       });
   }
 
   fn title(&'_ self) -> Cow<'_, str> {
-    self.log_controller.borrow().logs_root().to_owned().into()
+    let ctrl = self.log_controller.borrow();
+    match ctrl.locked_tag() {
+      None => ctrl.logs_root().to_owned().into(),
+      Some(tag) => format!("{} [{}]", ctrl.logs_root(), tag).into(),
+    }
   }
 }
 
 impl LogPage {
+  /// 被标记的日志在行首展示的符号。不支持颜色的终端往往也无法展示 emoji，此时非 ASCII 的符号退化为 ASCII 符号
+  fn marker(&self, capability: ColorCapability) -> &str {
+    match capability {
+      ColorCapability::Mono if !self.config.marker.is_ascii() => MONO_MARKER,
+      _ => &self.config.marker,
+    }
+  }
+
+  /// 渲染搜索匹配分布的小地图，每行对应已加载日志中的一段，匹配越密集，符号越深
+  fn render_minimap(
+    &self,
+    area: Rect,
+    buf: &mut Buffer,
+    state: &PageState,
+    density: &[usize],
+    reverse: bool,
+  ) {
+    let height = area.height as usize;
+    let max = density.iter().copied().max().unwrap_or(0);
+    let style = state
+      .color_capability
+      .downgrade(ratatui::style::Style::default().fg(self.colors.minimap));
+
+    for row in 0..height {
+      // 分桶数量与页面高度不一定相同，每行取其覆盖的桶中最密集的一个
+      let begin = row * density.len() / height;
+      let end = ((row + 1) * density.len() / height).max(begin + 1);
+      let count = density[begin..end.min(density.len())]
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(0);
+      let y = match reverse {
+        false => area.y + row as u16,
+        true => area.bottom() - 1 - row as u16,
+      };
+      buf.set_string(area.x, y, minimap_symbol(count, max), style);
+    }
+  }
+
   /// 为给定的日志行，创建可渲染的列表项
   fn render_log_line<'a>(
     &self,
     log: &'a LogLine,
-    style: Style,
-    search: &str,
+    style: &Style,
+    needles: &[&str],
     properties: &Properties,
+    gutter_width: usize,
+    capability: ColorCapability,
   ) -> Line<'a> {
     let mut line = Line::default();
-
-    if log.is_marked() {
-      line.push_span("🚩 ");
-    }
+    self.push_prefix(&mut line, log, style, properties, gutter_width, capability);
 
     match log {
       // 正常日志
       LogLine::Good(log) => {
-        let mut timestamp_span = self.get_timestamp_span(&style, &log.timestamp).cyan();
-        if properties.timestamp_matched {
-          timestamp_span = timestamp_span.reversed();
+        // 优先使用控制器缓存的高亮结果
+        match &properties.rich {
+          Some(cached) if cached.is_valid_for(&log.message, needles) => {
+            cached.apply(&mut line, &log.message)
+          }
+          _ => rich(&mut line, &log.message, needles),
         }
 
-        line.push_span(timestamp_span);
-        line.push_span(Span::raw(" "));
-
-        if let Some(span) = self.get_tag_span(&style, &log.tag) {
-          line.push_span(span.magenta());
-          line.push_span(Span::raw(" "));
-        }
-
-        if let Some(span) = self.get_pid_span(&style, log.pid) {
-          line.push_span(Span::raw("[").bold().white());
-          line.push_span(span.yellow());
-          line.push_span(Span::raw("]").bold().white());
-          line.push_span(Span::raw(" "));
+        // 标注被折叠进本行的重复日志数量
+        if properties.duplicates > 0 {
+          line.push_span(Span::raw(format!(" (x{})", properties.duplicates + 1)).dim());
         }
-
-        match log.label {
-          Label::Unknown => {}
-          Label::Warn => line.push_span(Span::raw("⚠️")),
-          Label::Error => line.push_span(Span::raw("❌️")),
-        }
-
-        rich(&mut line, &log.message, search);
       }
 
       // 坏的日志
-      LogLine::Bad(log) => line.push_span(Span::raw(&log.content).red()),
+      LogLine::Bad(log) => line.push_span(Span::raw(log.display_content()).fg(self.colors.bad_log)),
     }
 
     if log.is_marked() {
-      line.style = line.style.italic();
+      line.style = match self.config.mark_style {
+        MarkStyle::Italic => line.style.italic(),
+        MarkStyle::Bold => line.style.bold(),
+        MarkStyle::Band => line.style.bg(self.colors.marked_band),
+      };
+    }
+
+    if let Some(max_width) = self.config.max_line_width {
+      truncate_line(&mut line, max_width);
     }
 
     line
   }
 
-  fn get_timestamp_span<'a>(&self, style: &Style, dt: &DateTime<FixedOffset>) -> Span<'a> {
-    let timestamp_str = match style.timestamp_style {
-      TimestampStyle::Full => dt.to_rfc3339(),
-      TimestampStyle::Time => dt.format("%H:%M:%S%.3f").to_string(),
-      TimestampStyle::MonthDayTime => dt.format("%m-%d|%H:%M:%S%.3f").to_string(),
-      TimestampStyle::RoughTime => dt.format("%H:%M:%S").to_string(),
+  /// 日志内容之前的各项（行号、标记符号、时间戳、标签、进程号等）所占的展示宽度
+  fn prefix_width(
+    &self,
+    log: &LogLine,
+    style: &Style,
+    properties: &Properties,
+    gutter_width: usize,
+    capability: ColorCapability,
+  ) -> usize {
+    let mut line = Line::default();
+    self.push_prefix(&mut line, log, style, properties, gutter_width, capability);
+    line.width()
+  }
+
+  /// 在行中放入日志内容之前的各项
+  fn push_prefix<'a>(
+    &self,
+    line: &mut Line<'a>,
+    log: &'a LogLine,
+    style: &Style,
+    properties: &Properties,
+    gutter_width: usize,
+    capability: ColorCapability,
+  ) {
+    if let Some(gutter) = style.gutter_style.format(properties.ordinal, gutter_width) {
+      line.push_span(Span::raw(gutter).fg(self.colors.gutter));
+      line.push_span(Span::raw(" "));
+    }
+
+    if log.is_marked() {
+      line.push_span(Span::raw(format!("{} ", self.marker(capability))));
+    }
+
+    let LogLine::Good(log) = log else {
+      return;
     };
-    Span::raw(timestamp_str)
+
+    let mut timestamp_span = self
+      .get_timestamp_span(style, &log.timestamp)
+      .fg(self.colors.timestamp);
+    if properties.timestamp_matched {
+      timestamp_span = timestamp_span.reversed();
+    }
+
+    line.push_span(timestamp_span);
+    line.push_span(Span::raw(" "));
+
+    if let Some(span) = self.get_tag_span(style, &log.tag) {
+      line.push_span(span.fg(self.tag_color(&log.tag)));
+      line.push_span(Span::raw(" "));
+    }
+
+    if let Some(span) = self.get_pid_span(style, log.pid) {
+      line.push_span(Span::raw("[").bold().fg(self.colors.pid_brackets));
+      line.push_span(span.fg(self.colors.pid));
+      line.push_span(Span::raw("]").bold().fg(self.colors.pid_brackets));
+      line.push_span(Span::raw(" "));
+    }
+
+    if let Some(span) = self.get_facility_span(style, log.facility) {
+      line.push_span(span.fg(self.colors.facility));
+      line.push_span(Span::raw(" "));
+    }
+
+    if style.severity_style == SeverityStyle::Shown {
+      line.push_span(severity_badge(log.severity, capability));
+      line.push_span(Span::raw(" "));
+    }
+
+    match log.label {
+      Label::Unknown | Label::Debug | Label::Info => {}
+      Label::Warn => line.push_span(Span::raw("⚠️")),
+      Label::Error => line.push_span(Span::raw("❌️")),
+    }
+  }
+
+  fn get_timestamp_span<'a>(&self, style: &Style, dt: &DateTime<FixedOffset>) -> Span<'a> {
+    Span::raw(format_timestamp(style, dt))
+  }
+
+  /// 标签的颜色。开启按标签分配颜色时，由标签名的哈希从调色板中选出，同一个标签总是同一种颜色
+  fn tag_color(&self, tag: &str) -> Color {
+    let palette = self.colors.tag_palette;
+    if !self.config.tag_colors || palette.is_empty() {
+      return self.colors.tag;
+    }
+
+    // 使用稳定的哈希，保证每次运行的结果都一样
+    palette[(fnv1a(tag.bytes()) % palette.len() as u64) as usize]
   }
 
   fn get_tag_span<'a>(&self, style: &Style, tag: &'a str) -> Option<Span<'a>> {
@@ -159,4 +475,492 @@ impl LogPage {
       PidStyle::Hidden => None,
     }
   }
+
+  /// facility 按最长的名称对齐成一列，没有 PRI 的日志以 `-` 占位
+  fn get_facility_span<'a>(&self, style: &Style, facility: Option<u8>) -> Option<Span<'a>> {
+    match style.facility_style {
+      FacilityStyle::Shown => {
+        let name = facility.and_then(facility_name).unwrap_or("-");
+        Some(Span::raw(format!("{name:<8}")))
+      }
+      FacilityStyle::Hidden => None,
+    }
+  }
+}
+
+/// syslog severity 的徽标，按严重程度着色，越严重越醒目。不支持颜色的终端上，err 及以上加粗展示；
+/// 没有 PRI 的日志以等宽的空白占位，使内容仍然对齐
+fn severity_badge<'a>(severity: Option<u8>, capability: ColorCapability) -> Span<'a> {
+  let base = ratatui::style::Style::new();
+  let (text, style) = match severity {
+    Some(0) => ("EMR", base.light_red().bold().reversed()),
+    Some(1) => ("ALR", base.light_red().bold()),
+    Some(2) => ("CRI", base.light_red().bold()),
+    Some(3) => ("ERR", base.red().bold()),
+    Some(4) => ("WRN", base.yellow()),
+    Some(5) => ("NTC", base.cyan()),
+    Some(6) => ("INF", base.green()),
+    Some(7) => ("DBG", base.dark_gray()),
+    _ => ("   ", base),
+  };
+  Span::styled(text, capability.downgrade(style))
+}
+
+/// 按展示风格与展示时区，格式化日志的时间戳
+fn format_timestamp(style: &Style, dt: &DateTime<FixedOffset>) -> String {
+  style
+    .timestamp_style
+    .format(&style.display_tz.convert(*dt), style.timestamp_precision)
+}
+
+/// 小地图中表示匹配密度的符号，按相对于最密集处的比例分为四档，没有匹配时留空
+fn minimap_symbol(count: usize, max: usize) -> &'static str {
+  const SYMBOLS: [&str; 4] = ["░", "▒", "▓", "█"];
+  match count {
+    0 => " ",
+    _ => SYMBOLS[((count * SYMBOLS.len()).div_ceil(max.max(1)) - 1).min(SYMBOLS.len() - 1)],
+  }
+}
+
+/// 将一行截断到最多 `max_width` 列（按展示宽度计算），被截断时以 `…` 结尾，它沿用被截断处的风格。
+/// 只在字符的边界处截断，不会切开多字节字符，放不下的宽字符整个舍去
+fn truncate_line(line: &mut Line, max_width: usize) {
+  if line.width() <= max_width {
+    return;
+  }
+
+  // 留出一列给省略号
+  let budget = max_width.saturating_sub(1);
+  let mut used = 0;
+  let mut spans = std::mem::take(&mut line.spans).into_iter();
+  for span in spans.by_ref() {
+    let width = span.width();
+    if used + width <= budget {
+      used += width;
+      line.spans.push(span);
+      continue;
+    }
+
+    // 在本段内找到最后一个放得下的字符
+    let mut end = 0;
+    for (i, c) in span.content.char_indices() {
+      let char_width = Span::raw(&span.content[i..i + c.len_utf8()]).width();
+      if used + char_width > budget {
+        break;
+      }
+      used += char_width;
+      end = i + c.len_utf8();
+    }
+
+    let content = match span.content {
+      Cow::Borrowed(s) => Cow::Borrowed(&s[..end]),
+      Cow::Owned(s) => Cow::Owned(s[..end].to_string()),
+    };
+    if !content.is_empty() {
+      line.spans.push(Span::styled(content, span.style));
+    }
+    line.spans.push(Span::styled("…", span.style));
+    break;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::app::{
+    ColorScheme, DisplayTimezone,
+    controller::log_controller::{
+      GutterStyle, TimestampFormat, TimestampPrecision, TimestampStyle,
+    },
+  };
+
+  /// 按给定的配色方案构建日志页面，渲染一行日志，返回各段的样式
+  fn render_with(scheme: &str) -> Vec<ratatui::style::Style> {
+    let scheme: ColorScheme = scheme.parse().unwrap();
+    let page = LogPage {
+      log_controller: Default::default(),
+      config: Default::default(),
+      colors: scheme.log_colors,
+    };
+
+    let log = LogLine::new("2026-01-17T10:22:55.642782+08:00 host kernel[12]: hello".to_string());
+    let mut style = Style::default();
+    style.pid_style = PidStyle::Shown;
+    page
+      .render_log_line(
+        &log,
+        &style,
+        &[],
+        &Properties::default(),
+        1,
+        ColorCapability::TrueColor,
+      )
+      .spans
+      .iter()
+      .map(|span| span.style)
+      .collect()
+  }
+
+  /// 以默认配置与配色构建日志页面
+  fn default_page() -> LogPage {
+    LogPage {
+      log_controller: Default::default(),
+      config: Default::default(),
+      colors: Colors::default(),
+    }
+  }
+
+  /// 用给定的页面与风格渲染一行日志，返回渲染出的文本
+  fn render_text(page: &LogPage, line: &str, style: &Style) -> String {
+    let log = LogLine::new(line.to_string());
+    page
+      .render_log_line(
+        &log,
+        style,
+        &[],
+        &Properties::default(),
+        1,
+        ColorCapability::TrueColor,
+      )
+      .spans
+      .iter()
+      .map(|s| s.content.as_ref())
+      .collect()
+  }
+
+  #[test]
+  fn test_color_scheme() {
+    let dark = render_with("dark");
+    assert_eq!(dark[0].fg, Some(Color::Cyan));
+    assert_eq!(dark[2].fg, Some(Color::Magenta));
+    assert_eq!(dark[5].fg, Some(Color::Yellow));
+
+    let light = render_with("light");
+    assert_eq!(light[0].fg, Some(Color::Blue));
+    assert_eq!(light[2].fg, Some(Color::Magenta));
+    assert_eq!(light[5].fg, Some(Color::Green));
+
+    // 光标高亮色随配色方案传递给各个页面
+    assert_eq!(ColorScheme::dark().pager_theme.cursor_bg(), Color::White);
+    assert_eq!(ColorScheme::light().pager_theme.cursor_bg(), Color::Gray);
+    assert!("solarized".parse::<ColorScheme>().is_err());
+  }
+
+  #[test]
+  fn test_truncate_line() {
+    let truncated = |spans: Vec<Span<'static>>, max_width: usize| {
+      let mut line = Line::from(spans);
+      truncate_line(&mut line, max_width);
+      line
+        .spans
+        .iter()
+        .map(|span| (span.content.to_string(), span.style.fg))
+        .collect::<Vec<_>>()
+    };
+    let spans = || {
+      vec![
+        Span::raw("10:22 ").cyan(),
+        Span::raw("kernel ").magenta(),
+        Span::raw("你好世界").red(),
+      ]
+    };
+
+    // 放得下时保持原样
+    assert_eq!(truncated(spans(), 21).len(), 3);
+
+    // 在段的边界处截断，省略号沿用被截断的段的风格
+    assert_eq!(
+      truncated(spans(), 7),
+      vec![
+        ("10:22 ".to_string(), Some(Color::Cyan)),
+        ("…".to_string(), Some(Color::Magenta)),
+      ]
+    );
+
+    // 在段内截断
+    assert_eq!(
+      truncated(spans(), 10),
+      vec![
+        ("10:22 ".to_string(), Some(Color::Cyan)),
+        ("ker".to_string(), Some(Color::Magenta)),
+        ("…".to_string(), Some(Color::Magenta)),
+      ]
+    );
+
+    // 不切开宽字符，放不下的宽字符整个舍去
+    for max_width in [16, 17] {
+      assert_eq!(
+        truncated(spans(), max_width)[2..],
+        [
+          ("你".to_string(), Some(Color::Red)),
+          ("…".to_string(), Some(Color::Red)),
+        ]
+      );
+    }
+  }
+
+  #[test]
+  fn test_prefix_width() {
+    let page = default_page();
+    let mut log =
+      LogLine::new("2026-01-17T10:22:55.642782+08:00 host kernel[12]: usb 1-1: reset".to_string());
+    log.set_mark(true);
+    let mut style = Style::default();
+    style.pid_style = PidStyle::Shown;
+    style.gutter_style = GutterStyle::Ordinal;
+    let props = Properties {
+      ordinal: 42,
+      ..Default::default()
+    };
+    let capability = ColorCapability::Mono;
+
+    // 行首各项的宽度加上匹配在内容中的列，正好落在渲染后的匹配处
+    let prefix = page.prefix_width(&log, &style, &props, 3, capability);
+    assert!(prefix > 0);
+    let line = page.render_log_line(&log, &style, &[], &props, 3, capability);
+    let text: String = line
+      .spans
+      .iter()
+      .map(|span| span.content.as_ref())
+      .collect();
+    let column = prefix + "usb 1-1: ".len();
+    assert!(text[column..].starts_with("reset"));
+  }
+
+  #[test]
+  fn test_marker() {
+    let mut log =
+      LogLine::new("2026-01-17T10:22:55.642782+08:00 host kernel[12]: hello".to_string());
+    log.set_mark(true);
+    let render = |config: Config, capability| {
+      let page = LogPage {
+        log_controller: Default::default(),
+        config,
+        colors: Colors::default(),
+      };
+      let line = page.render_log_line(
+        &log,
+        &Style::default(),
+        &[],
+        &Properties::default(),
+        1,
+        capability,
+      );
+      (line.spans[0].content.to_string(), line.style)
+    };
+
+    // 默认使用 emoji 并斜体展示，不支持颜色时退化为 ASCII 符号
+    let (marker, style) = render(Config::default(), ColorCapability::TrueColor);
+    assert_eq!(marker, "🚩 ");
+    assert!(style.add_modifier.contains(Modifier::ITALIC));
+    let (marker, _) = render(Config::default(), ColorCapability::Mono);
+    assert_eq!(marker, "* ");
+
+    // 行首展示配置的符号，ASCII 符号总是保持不变
+    let config = Config::default()
+      .with_marker(">>".to_string())
+      .with_mark_style(MarkStyle::Band);
+    for capability in [ColorCapability::TrueColor, ColorCapability::Mono] {
+      let (marker, style) = render(config.clone(), capability);
+      assert_eq!(marker, ">> ");
+      assert_eq!(style.bg, Some(Colors::default().marked_band));
+    }
+
+    assert_eq!("bold".parse::<MarkStyle>(), Ok(MarkStyle::Bold));
+    assert!("underline".parse::<MarkStyle>().is_err());
+  }
+
+  #[test]
+  fn test_tag_color() {
+    let mut page = default_page();
+
+    // 默认所有标签使用同一种颜色
+    assert_eq!(page.tag_color("kernel"), Color::Magenta);
+    assert_eq!(page.tag_color("sshd"), Color::Magenta);
+
+    // 同一个标签总是同一种颜色，且颜色都来自调色板
+    page.config = Config::default().with_tag_colors(true);
+    assert_eq!(page.tag_color("kernel"), page.tag_color("kernel"));
+    let tags = [
+      "kernel",
+      "sshd",
+      "systemd",
+      "cron",
+      "nginx",
+      "dockerd",
+      "NetworkManager",
+    ];
+    let colors: Vec<Color> = tags.iter().map(|tag| page.tag_color(tag)).collect();
+    assert!(colors.iter().all(|color| DARK_TAG_PALETTE.contains(color)));
+
+    // 不同的标签大多分到不同的颜色
+    let distinct: std::collections::HashSet<_> = colors.iter().collect();
+    assert!(distinct.len() >= 4);
+  }
+
+  #[test]
+  fn test_facility_column() {
+    let page = default_page();
+    let render = |line: &str, style: &Style| render_text(&page, line, style);
+
+    // <37> 即 facility auth、severity notice
+    let with_pri = "<37>2026-01-17T10:22:55.642782+08:00 host sshd[12]: hello";
+    let without_pri = "2026-01-17T10:22:55.642782+08:00 host sshd[12]: hello";
+
+    // 默认不展示 facility
+    let mut style = Style::default();
+    assert!(!render(with_pri, &style).contains("auth"));
+
+    // 展示时按最长的名称对齐，没有 PRI 的日志以 - 占位
+    style.facility_style.next();
+    assert!(render(with_pri, &style).contains(" auth     hello"));
+    assert!(render(without_pri, &style).contains(" -        hello"));
+  }
+
+  #[test]
+  fn test_severity_badge() {
+    let badge = |severity, capability| {
+      let span = severity_badge(severity, capability);
+      (span.content.to_string(), span.style.fg)
+    };
+
+    let expected = [
+      ("EMR", Color::LightRed),
+      ("ALR", Color::LightRed),
+      ("CRI", Color::LightRed),
+      ("ERR", Color::Red),
+      ("WRN", Color::Yellow),
+      ("NTC", Color::Cyan),
+      ("INF", Color::Green),
+      ("DBG", Color::DarkGray),
+    ];
+    for (index, (text, color)) in expected.into_iter().enumerate() {
+      let severity = Some(index as u8);
+      assert_eq!(
+        badge(severity, ColorCapability::TrueColor),
+        (text.to_string(), Some(color))
+      );
+      assert_eq!(
+        badge(severity, ColorCapability::Ansi16),
+        (text.to_string(), Some(color))
+      );
+
+      // 不支持颜色时去掉颜色，只有 err 及以上加粗
+      let span = severity_badge(severity, ColorCapability::Mono);
+      assert_eq!(span.style.fg, None);
+      assert_eq!(span.style.add_modifier.contains(Modifier::BOLD), index <= 3);
+    }
+
+    // 没有 PRI 的日志以等宽的空白占位
+    assert_eq!(
+      badge(None, ColorCapability::TrueColor),
+      ("   ".to_string(), None)
+    );
+  }
+
+  #[test]
+  fn test_severity_column() {
+    let page = default_page();
+    let render = |line: &str, style: &Style| render_text(&page, line, style);
+
+    // <11> 即 facility user、severity err，日志同时是 error 等级
+    let with_pri = "<11>2026-01-17T10:22:55.642782+08:00 host sshd[12]: hello";
+    let without_pri = "2026-01-17T10:22:55.642782+08:00 host sshd[12]: hello";
+
+    let mut style = Style::default();
+    assert!(!render(with_pri, &style).contains("ERR"));
+    style.severity_style.next();
+    assert!(render(with_pri, &style).contains(" ERR ❌️hello"));
+    assert!(render(without_pri, &style).contains("     hello"));
+  }
+
+  #[test]
+  fn test_display_timezone() {
+    let dt = DateTime::parse_from_rfc3339("2026-01-17T23:22:55.642+08:00").unwrap();
+    let format = |timestamp_style, tz: &str| {
+      let mut style = Style::default();
+      style.timestamp_style = timestamp_style;
+      style.display_tz = tz.parse().unwrap();
+      format_timestamp(&style, &dt)
+    };
+
+    assert_eq!(
+      format(TimestampStyle::Full, "+08:00"),
+      "2026-01-17T23:22:55.642+08:00"
+    );
+    assert_eq!(
+      format(TimestampStyle::Full, "utc"),
+      "2026-01-17T15:22:55.642+00:00"
+    );
+    assert_eq!(
+      format(TimestampStyle::MonthDayTime, "-05:30"),
+      "01-17|09:52:55.642"
+    );
+    assert_eq!(format(TimestampStyle::RoughTime, "UTC"), "15:22:55");
+    assert_eq!(
+      format(TimestampStyle::Time, "local"),
+      dt.with_timezone(&chrono::Local)
+        .format("%H:%M:%S%.3f")
+        .to_string()
+    );
+    assert_eq!(
+      "-0530".parse::<DisplayTimezone>(),
+      "-05:30".parse::<DisplayTimezone>()
+    );
+    assert!("mars".parse::<DisplayTimezone>().is_err());
+  }
+
+  #[test]
+  fn test_custom_timestamp_format() {
+    let dt = DateTime::parse_from_rfc3339("2026-01-17T23:22:55.642+08:00").unwrap();
+    let mut style = Style::default();
+    style.display_tz = "+08:00".parse().unwrap();
+
+    // 无法识别的格式说明符在构造时即被拒绝
+    assert!("%Y-%m-%d %Q".parse::<TimestampFormat>().is_err());
+    assert!("".parse::<TimestampFormat>().is_err());
+
+    let format: TimestampFormat = "%Y/%m/%d %a".parse().unwrap();
+    style.timestamp_style = TimestampStyle::Custom(format.clone());
+    assert_eq!(format_timestamp(&style, &dt), "2026/01/17 Sat");
+
+    style.timestamp_style = TimestampStyle::Hour12;
+    assert_eq!(format_timestamp(&style, &dt), "11:22:55.642 PM");
+
+    // 切换风格时，配置了自定义格式才会经过它
+    style.next_timestamp_style();
+    assert!(style.timestamp_style == TimestampStyle::Full);
+
+    style.custom_timestamp = Some(format.clone());
+    style.timestamp_style = TimestampStyle::Hour12;
+    style.next_timestamp_style();
+    assert!(style.timestamp_style == TimestampStyle::Custom(format));
+    style.next_timestamp_style();
+    assert!(style.timestamp_style == TimestampStyle::Full);
+  }
+
+  #[test]
+  fn test_timestamp_precision() {
+    let dt = DateTime::parse_from_rfc3339("2026-01-17T23:22:55.642782+08:00").unwrap();
+    let mut style = Style::default();
+    style.display_tz = "+08:00".parse().unwrap();
+    style.timestamp_style = TimestampStyle::Time;
+
+    assert_eq!(format_timestamp(&style, &dt), "23:22:55.642");
+
+    style.timestamp_precision.next();
+    assert_eq!(style.timestamp_precision, TimestampPrecision::Micros);
+    assert_eq!(format_timestamp(&style, &dt), "23:22:55.642782");
+
+    style.timestamp_style = TimestampStyle::MonthDayTime;
+    style.timestamp_precision = TimestampPrecision::Nanos;
+    assert_eq!(format_timestamp(&style, &dt), "01-17|23:22:55.642782000");
+
+    style.timestamp_precision = TimestampPrecision::Seconds;
+    assert_eq!(format_timestamp(&style, &dt), "01-17|23:22:55");
+
+    // 精度不随整体风格的切换而变化
+    style.next();
+    assert_eq!(style.timestamp_precision, TimestampPrecision::Seconds);
+  }
 }
@@ -0,0 +1,51 @@
+use crate::{
+  app::controller::MarkController,
+  log::LogLine,
+  ui::{Page, PageState, ViewPortRenderEx},
+};
+use ratatui::{
+  buffer::Buffer,
+  layout::Rect,
+  style::Stylize,
+  text::{Line, Span},
+};
+use std::{borrow::Cow, cell::RefCell, rc::Rc};
+
+pub struct MarkPage {
+  /// 本页面渲染依据的状态数据
+  pub mark_controller: Rc<RefCell<MarkController>>,
+}
+
+impl Page for MarkPage {
+  fn render(&self, area: Rect, buf: &mut Buffer, state: &PageState) {
+    self
+      .mark_controller
+      .borrow_mut()
+      .view_mut()
+      .render(area, buf, state, |(_, _, log)| self.render_mark(log))
+  }
+
+  fn title(&'_ self) -> Cow<'_, str> {
+    "Marks".into()
+  }
+}
+
+impl MarkPage {
+  /// 展示被标记日志的时间戳、标签与内容
+  fn render_mark<'a>(&self, log: &'a LogLine) -> Line<'a> {
+    let mut line = Line::default();
+
+    if let Some(timestamp) = log.get_timestamp() {
+      line.push_span(timestamp.format("%m-%d|%H:%M:%S").to_string().cyan());
+      line.push_span(Span::raw(" "));
+    }
+
+    if let Some(tag) = log.get_tag() {
+      line.push_span(tag.magenta());
+      line.push_span(Span::raw(": "));
+    }
+
+    line.push_span(Span::raw(log.get_content()));
+    line
+  }
+}
@@ -1,9 +1,13 @@
 mod debug_page;
+mod detail_page;
 mod help_page;
 pub mod log_page;
+mod mark_page;
 mod tag_page;
 
 pub use debug_page::DebugPage;
+pub use detail_page::DetailPage;
 pub use help_page::HelpPage;
 pub use log_page::LogPage;
+pub use mark_page::MarkPage;
 pub use tag_page::TagPage;
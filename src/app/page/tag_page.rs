@@ -1,11 +1,14 @@
 use crate::{
-  app::{controller::TagController, rich},
+  app::{
+    controller::{TagController, TagMatchMode},
+    rich,
+  },
   ui::{Page, PageState, ViewPortRenderEx},
 };
 use ratatui::{
   buffer::Buffer,
   layout::Rect,
-  style::{Color, Style, Styled},
+  style::{Color, Style, Styled, Stylize},
   text::Line,
 };
 use std::{borrow::Cow, cell::RefCell, rc::Rc};
@@ -19,21 +22,35 @@ impl Page for TagPage {
   fn render(&self, area: Rect, buf: &mut Buffer, state: &PageState) {
     let mut tag_controller = self.tag_controller.borrow_mut();
     let search = crate::unsafe_ref!(str, tag_controller.get_curr_search());
+    let counts = tag_controller.counts();
+    let column_width = tag_controller.column_width();
 
+    // 区域足够宽时（如全屏展示），分多列展示标签
     tag_controller
       .view_mut()
-      .render(area, buf, state.focus, |(k, v)| {
-        self.render_tag(k, *v, search)
+      .render_columns(area, buf, state, column_width, |(k, v)| {
+        let count = counts.as_ref().and_then(|counts| counts.get(k).copied());
+        self.render_tag(k, *v, count, search)
       });
   }
 
   fn title(&'_ self) -> Cow<'_, str> {
-    "Tags Filter".into()
+    let tag_controller = self.tag_controller.borrow();
+    match tag_controller.match_mode() {
+      TagMatchMode::Substring => format!("{} Filter", tag_controller.kind().name()).into(),
+      mode => format!("{} Filter ({})", tag_controller.kind().name(), mode.name()).into(),
+    }
   }
 }
 
 impl TagPage {
-  fn render_tag<'a>(&self, tag: &'a str, state: bool, search: &str) -> Line<'a> {
+  fn render_tag<'a>(
+    &self,
+    tag: &'a str,
+    state: bool,
+    count: Option<usize>,
+    search: &str,
+  ) -> Line<'a> {
     let mut line = Line::default();
 
     // 标识是否选中该标签的复选框
@@ -47,7 +64,12 @@ impl TagPage {
     line.push_span("]".set_style(checkbox_style));
 
     // 标签内容本身
-    rich(&mut line, tag, search);
+    rich(&mut line, tag, &[search]);
+
+    // 标签累计读取到的日志行数
+    if let Some(count) = count {
+      line.push_span(format!(" {count}").dark_gray());
+    }
 
     line
   }
@@ -1,24 +1,45 @@
 use lazy_static::lazy_static;
 use ratatui::{
   prelude::Modifier,
-  style::Style,
+  style::{Color, Style},
   text::{self, Span},
 };
 use regex::Regex;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::ops::Range;
+use std::str::FromStr;
+use std::sync::RwLock;
 
-/// 在文本中查找所有匹配的子字符串区间（不考虑重叠）
+/// 在文本中查找所有匹配任一子字符串的区间，相互重叠或相连的区间被合并
 ///
 /// # 参数
 /// - `content`: 要搜索的原始文本
-/// - `search`: 要查找的子字符串
+/// - `needles`: 要查找的子字符串，空的子字符串被忽略
 ///
 /// # 返回值
-/// - 返回 `Vec<(usize, usize)>`，每个元素是一个匹配的(start, end)区间
+/// - 返回 `Vec<(usize, usize)>`，每个元素是一个匹配的(start, end)区间，按起点排序
 /// - 区间是左闭右开的：[start, end)
-pub fn find_all_matches(content: &str, search: &str) -> Vec<(usize, usize)> {
+pub fn find_all_matches(content: &str, needles: &[&str]) -> Vec<(usize, usize)> {
+  let mut matches: Vec<(usize, usize)> = needles
+    .iter()
+    .flat_map(|search| find_matches(content, search))
+    .collect();
+  matches.sort_unstable();
+
+  // 合并重叠的区间，使得多个子字符串的匹配不会被重复反色
+  let mut merged: Vec<(usize, usize)> = Vec::with_capacity(matches.len());
+  for (start, end) in matches {
+    match merged.last_mut() {
+      Some(last) if start <= last.1 => last.1 = last.1.max(end),
+      _ => merged.push((start, end)),
+    }
+  }
+  merged
+}
+
+/// 在文本中查找单个子字符串的所有匹配区间（可能重叠）
+fn find_matches(content: &str, search: &str) -> Vec<(usize, usize)> {
   // 如果搜索字符串为空，返回空结果
   if search.is_empty() {
     return Vec::new();
@@ -143,6 +164,32 @@ pub fn apply_matches_on_spans<'a>(
   result_spans
 }
 
+/// 用户自定义的高亮规则，匹配正则表达式的内容将以给定的颜色展示
+#[derive(Clone, Debug)]
+pub struct HighlightRule {
+  pub pattern: String,
+  pub color: Color,
+}
+
+impl FromStr for HighlightRule {
+  type Err = String;
+
+  /// 从 `REGEX=COLOR` 形式的字符串中解析规则，正则表达式中可以包含 '='
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (pattern, color) = s
+      .rsplit_once('=')
+      .ok_or_else(|| format!("invalid highlight rule '{s}', expected 'REGEX=COLOR'"))?;
+    let color = color
+      .parse()
+      .map_err(|_| format!("invalid color '{color}' in highlight rule '{s}'"))?;
+
+    Ok(Self {
+      pattern: pattern.to_string(),
+      color,
+    })
+  }
+}
+
 #[derive(Default)]
 struct Highlighter {
   patterns: Vec<(usize, Regex)>,
@@ -151,11 +198,35 @@ struct Highlighter {
 
 impl Highlighter {
   fn new() -> Self {
+    Self::build(Self::default_patterns())
+  }
+
+  /// 在内置规则的基础上，加入用户的自定义规则。用户规则优先于内置规则，
+  /// 无效的正则表达式将被跳过
+  fn with_rules(rules: &[HighlightRule]) -> Self {
+    let mut patterns: Vec<(Regex, Style)> = rules
+      .iter()
+      .filter_map(|rule| match Regex::new(&rule.pattern) {
+        Ok(regex) => Some((regex, Style::default().fg(rule.color))),
+        Err(e) => {
+          crate::eprintln!("skip invalid highlight pattern '{}': {}", rule.pattern, e);
+          None
+        }
+      })
+      .collect();
+    patterns.extend(Self::default_patterns());
+
+    Self::build(patterns)
+  }
+
+  /// 内置的高亮规则
+  fn default_patterns() -> Vec<(Regex, Style)> {
     let url_style = Style::default().blue().underlined();
     let number_style = Style::default().green();
     let string_style = Style::default().magenta();
+    let level = |keywords: &str| Regex::new(&format!(r"(?i)\b(?:{keywords})\b")).unwrap();
 
-    Self::build(vec![
+    vec![
       // URL
       (
         Regex::new(r#"(?i)\b(?:https?|ftp|ftps|file|mailto|tel)://[^\s<>"']+"#).unwrap(),
@@ -169,6 +240,11 @@ impl Highlighter {
         Regex::new(r#"\b(?:\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}|\[[0-9a-fA-F:]+])\b"#).unwrap(),
         url_style,
       ),
+      // 日志等级关键字。它们排在 URL 之后，因此出现在 URL 中时，不会被重复高亮
+      (level("ERROR|FATAL"), Style::default().red().bold()),
+      (level("WARN|WARNING"), Style::default().yellow().bold()),
+      (level("INFO"), Style::default().green().bold()),
+      (level("DEBUG|TRACE"), Style::default().blue().bold()),
       // 时间
       (
         Regex::new(r"\b\d{2}:\d{2}(:\d{2}(\.\d+)?)?\b").unwrap(),
@@ -189,7 +265,7 @@ impl Highlighter {
       (Regex::new(r#""[^"]*""#).unwrap(), string_style),
       (Regex::new(r#""[^"]*""#).unwrap(), string_style),
       (Regex::new(r"'[^']*'").unwrap(), string_style),
-    ])
+    ]
   }
 
   fn build(styled_patterns: Vec<(Regex, Style)>) -> Self {
@@ -263,22 +339,193 @@ impl Highlighter {
 }
 
 lazy_static! {
-  static ref HIGHLIGHTER: Highlighter = Highlighter::new();
+  static ref HIGHLIGHTER: RwLock<Highlighter> = RwLock::new(Highlighter::new());
+}
+
+/// 设置用户自定义的高亮规则，它们将与内置规则一同作用于之后的 [rich] 调用
+pub fn set_highlight_rules(rules: &[HighlightRule]) {
+  *HIGHLIGHTER.write().unwrap() = Highlighter::with_rules(rules);
 }
 
 /// 将给定字符串，转换为有丰富颜色呈现的
-pub fn rich<'a>(line: &mut text::Line<'a>, content: &'a str, search: &str) {
-  // 为内容加上高亮样式
-  let spans = HIGHLIGHTER.highlight(content);
+pub fn rich<'a>(line: &mut text::Line<'a>, content: &'a str, needles: &[&str]) {
+  RichSpans::new(content, needles).apply(line, content);
+}
+
+/// 一段内容高亮后的结果，只记录各段的字节区间与样式，不借用内容本身，因此可以跨帧缓存，
+/// 免去每次渲染都重新运行所有的正则表达式
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RichSpans {
+  /// 计算时的（非空的）搜索内容
+  search: Vec<String>,
+
+  /// 计算时的内容长度
+  len: usize,
+
+  /// 各段的字节区间与样式
+  spans: Vec<(Range<usize>, Style)>,
+}
+
+impl RichSpans {
+  /// 为内容加上高亮样式，并将符合任一搜索内容的区间反色
+  pub fn new(content: &str, needles: &[&str]) -> Self {
+    let spans = HIGHLIGHTER.read().unwrap().highlight(content);
+    let matches = find_all_matches(content, needles);
+
+    // 各段依次相连，由各段的长度还原出它们的区间
+    let mut start = 0;
+    let spans = apply_matches_on_spans(spans, matches)
+      .into_iter()
+      .map(|span| {
+        let range = start..start + span.content.len();
+        start = range.end;
+        (range, span.style)
+      })
+      .collect();
+
+    Self {
+      search: needles
+        .iter()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect(),
+      len: content.len(),
+      spans,
+    }
+  }
+
+  /// 是否是给定内容在给定搜索下的结果。调用者需要保证内容没有变化，这里只能校验它的长度
+  pub fn is_valid_for(&self, content: &str, needles: &[&str]) -> bool {
+    self.len == content.len()
+      && self
+        .search
+        .iter()
+        .map(String::as_str)
+        .eq(needles.iter().copied().filter(|s| !s.is_empty()))
+  }
 
-  // 根据内容找到符合搜索内容的区间
-  let matches = find_all_matches(content, search);
+  /// 将结果按原有的区间与样式，添加到目标行里
+  pub fn apply<'a>(&self, line: &mut text::Line<'a>, content: &'a str) {
+    for (range, style) in &self.spans {
+      if let Some(s) = content.get(range.clone()) {
+        line.push_span(Span::styled(s, *style));
+      }
+    }
+  }
+}
 
-  // 将搜素匹配的内容加粗
-  let spans = apply_matches_on_spans(spans, matches);
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_level_keywords() {
+    let highlighter = Highlighter::new();
+    let spans =
+      highlighter.highlight("ERROR erroring: Warning, info http://debug.example.com/ERROR");
+    let styled: Vec<(&str, Style)> = spans
+      .iter()
+      .map(|(span, _)| (span.content.as_ref(), span.style))
+      .collect();
+
+    assert_eq!(styled[0], ("ERROR", Style::default().red().bold()));
+    assert_eq!(styled[1], (" erroring: ", Style::default()));
+    assert_eq!(styled[2], ("Warning", Style::default().yellow().bold()));
+    assert_eq!(styled[4], ("info", Style::default().green().bold()));
+
+    // URL 中的关键字不会被单独高亮
+    assert_eq!(
+      styled[6],
+      (
+        "http://debug.example.com/ERROR",
+        Style::default().blue().underlined()
+      )
+    );
+    assert_eq!(styled.len(), 7);
+  }
+
+  #[test]
+  fn test_custom_highlight_rule() {
+    let rules: Vec<HighlightRule> = ["REQ-[0-9]+=lightred", "(=red"]
+      .into_iter()
+      .map(|s| s.parse().unwrap())
+      .collect();
+    assert!("no-color".parse::<HighlightRule>().is_err());
+    assert!("x=not-a-color".parse::<HighlightRule>().is_err());
+
+    // 无效的正则表达式被跳过，其他规则仍然生效。使用局部的高亮器，不影响全局的高亮规则
+    let highlighter = Highlighter::with_rules(&rules);
+    let spans = highlighter.highlight("request REQ-42 done");
+    let (span, _) = spans
+      .iter()
+      .find(|(span, _)| span.content == "REQ-42")
+      .unwrap();
+    assert_eq!(span.style.fg, Some(Color::LightRed));
+  }
+
+  #[test]
+  fn test_rich_spans() {
+    let content = "ERROR connect to 10.0.0.1:8080 failed, error=\"timeout\"";
+    let render = |cached: &RichSpans| {
+      let mut line = text::Line::default();
+      cached.apply(&mut line, content);
+      line
+    };
+
+    // 缓存的结果与直接渲染的结果相同
+    let cached = RichSpans::new(content, &["error"]);
+    let mut line = text::Line::default();
+    rich(&mut line, content, &["error"]);
+    assert_eq!(render(&cached), line);
+    assert_eq!(render(&cached).to_string(), content);
+    assert!(
+      render(&cached)
+        .spans
+        .iter()
+        .any(|span| span.content == "error" && span.style.add_modifier.contains(Modifier::REVERSED))
+    );
+
+    // 搜索内容或者内容变化后，缓存失效
+    assert!(cached.is_valid_for(content, &["error"]));
+    assert!(!cached.is_valid_for(content, &["timeout"]));
+    assert!(!cached.is_valid_for("ERROR", &["error"]));
+
+    let cached = RichSpans::new(content, &[""]);
+    let mut line = text::Line::default();
+    rich(&mut line, content, &[]);
+    assert_eq!(render(&cached), line);
+    assert!(cached.is_valid_for(content, &[]));
+  }
+
+  #[test]
+  fn test_find_all_matches() {
+    assert_eq!(find_all_matches("aaa", &["aa"]), vec![(0, 3)]);
+    assert_eq!(find_all_matches("abc", &["", "x"]), vec![]);
+
+    // 多个子字符串的匹配按起点排序，重叠或相连的区间合并
+    assert_eq!(
+      find_all_matches("error: read timeout", &["timeout", "error", "or: r"]),
+      vec![(0, 8), (12, 19)]
+    );
+  }
 
-  // 添加所有内容到目标行里
-  for span in spans {
-    line.push_span(span);
+  #[test]
+  fn test_rich_spans_multiple_needles() {
+    let content = "connect timeout, retry after error";
+    let cached = RichSpans::new(content, &["error", "timeout"]);
+    let mut line = text::Line::default();
+    cached.apply(&mut line, content);
+
+    // 所有搜索内容都被反色
+    let reversed: Vec<_> = line
+      .spans
+      .iter()
+      .filter(|span| span.style.add_modifier.contains(Modifier::REVERSED))
+      .map(|span| span.content.as_ref())
+      .collect();
+    assert_eq!(reversed, vec!["timeout", "error"]);
+
+    assert!(cached.is_valid_for(content, &["error", "timeout"]));
+    assert!(!cached.is_valid_for(content, &["error"]));
   }
 }
@@ -0,0 +1,281 @@
+//! 浏览会话的保存与恢复：退出时记录标签选择、搜索内容、展示风格以及光标所在日志，下次启动时恢复
+
+use crate::{
+  app::{
+    controller::{
+      LogController,
+      log_controller::{SearchCombine, Style},
+    },
+    fnv::fnv1a,
+  },
+  file::is_stdin,
+  log::{DataBoard, TagsData},
+};
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  path::{Path, PathBuf},
+};
+
+/// 会话文件在状态目录下所在的子目录
+const SESSIONS_DIR: &str = "rs_syslog_viewer/sessions";
+
+/// 一次浏览会话的状态。缺少的字段取默认值，以便兼容旧版本保存的会话
+#[derive(Default, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+  /// 各个标签的选择状态
+  pub tags: BTreeMap<String, bool>,
+
+  /// 各个主机名的选择状态
+  pub hosts: BTreeMap<String, bool>,
+
+  /// 输入中的内容搜索
+  pub content_search: Option<String>,
+
+  /// 叠加的内容搜索条件
+  pub search_terms: Vec<String>,
+
+  /// 多个内容搜索的组合方式
+  pub search_combine: SearchCombine,
+
+  /// 最近一次时间戳搜索的指令
+  pub timestamp_search: String,
+
+  /// 日志的展示风格
+  pub style: Style,
+
+  /// 光标所在日志的时间戳。日志的索引在每次启动时都不同，因此按时间戳找回光标
+  #[serde(
+    serialize_with = "serialize_rfc3339",
+    deserialize_with = "deserialize_rfc3339"
+  )]
+  pub cursor: Option<DateTime<FixedOffset>>,
+}
+
+impl Session {
+  /// 浏览给定的日志目录与日志文件时使用的会话文件路径，位于 `$XDG_STATE_HOME`（未设置时为
+  /// `~/.local/state`）下，按实际浏览的日志区分。无法确定状态目录时返回空
+  pub fn path_for(root: &Path, files: &[PathBuf]) -> Option<PathBuf> {
+    let state_dir = std::env::var_os("XDG_STATE_HOME")
+      .map(PathBuf::from)
+      .filter(|dir| dir.is_absolute())
+      .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))?;
+    Some(
+      state_dir
+        .join(SESSIONS_DIR)
+        .join(format!("{:016x}.json", session_key(root, files))),
+    )
+  }
+
+  /// 从日志展示区与数据看板中收集会话状态
+  pub fn capture(log_controller: &LogController, data_board: &DataBoard) -> Self {
+    let selections = |tags: &TagsData| {
+      tags
+        .all()
+        .iter()
+        .map(|(tag, selected)| (tag.clone(), *selected))
+        .collect()
+    };
+    let content_search = Some(log_controller.get_search_content())
+      .filter(|s| !s.is_empty())
+      .map(str::to_string);
+
+    Self {
+      tags: selections(data_board.get_tags()),
+      hosts: selections(data_board.get_hosts()),
+      content_search,
+      search_terms: log_controller.search_terms().to_vec(),
+      search_combine: log_controller.search_combine(),
+      timestamp_search: log_controller.get_search_timestamp().to_string(),
+      style: log_controller.style().clone(),
+      cursor: log_controller
+        .cursor_log()
+        .and_then(|log| log.get_timestamp()),
+    }
+  }
+
+  /// 将会话状态恢复到日志展示区与数据看板中。还没有出现的标签在出现时恢复选择状态。
+  /// 返回保存的光标时间戳，它需要等日志加载完成后再恢复
+  pub fn restore(
+    self,
+    log_controller: &mut LogController,
+    data_board: &mut DataBoard,
+  ) -> Option<DateTime<FixedOffset>> {
+    for (tag, selected) in &self.tags {
+      data_board.get_tags_mut().remember(tag, *selected);
+    }
+    data_board.get_tags_mut().update_version();
+    for (host, selected) in &self.hosts {
+      data_board.get_hosts_mut().remember(host, *selected);
+    }
+    data_board.get_hosts_mut().update_version();
+
+    log_controller.restore_content_search(
+      self.content_search,
+      self.search_terms,
+      self.search_combine,
+    );
+    if !self.timestamp_search.is_empty() {
+      log_controller.set_search_timestamp(Some(self.timestamp_search));
+    }
+
+    // 时区与自定义时间戳格式总是来自本次的启动参数
+    let mut style = self.style;
+    let current = log_controller.style_mut();
+    style.display_tz = current.display_tz;
+    style.custom_timestamp = current.custom_timestamp.clone();
+    *current = style;
+
+    self.cursor
+  }
+
+  /// 读取会话文件
+  pub fn load(path: &Path) -> Result<Self, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+  }
+
+  /// 写入会话文件，按需创建所在的目录
+  pub fn save(&self, path: &Path) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, content).map_err(|e| e.to_string())
+  }
+}
+
+/// 由实际浏览的日志目录与日志文件（不论给出的顺序）得出会话的键（FNV-1a 哈希），
+/// 使得浏览不同日志的会话互不覆盖。读取标准输入时不区分来源
+fn session_key(root: &Path, files: &[PathBuf]) -> u64 {
+  let paths: BTreeSet<PathBuf> = std::iter::once(root)
+    .chain(files.iter().map(PathBuf::as_path))
+    .filter(|path| !path.as_os_str().is_empty())
+    .map(|path| match is_stdin(path) {
+      true => path.to_path_buf(),
+      false => path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+    })
+    .collect();
+
+  fnv1a(
+    paths
+      .iter()
+      .flat_map(|path| path.as_os_str().as_encoded_bytes().iter().chain(b"\0"))
+      .copied(),
+  )
+}
+
+/// 以 RFC3339 字符串保存时间戳，与导出的日志中的时间戳格式一致
+fn serialize_rfc3339<S: Serializer>(
+  timestamp: &Option<DateTime<FixedOffset>>,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  timestamp
+    .map(|timestamp| timestamp.to_rfc3339_opts(SecondsFormat::Micros, false))
+    .serialize(serializer)
+}
+
+/// 读取以 RFC3339 字符串保存的时间戳
+fn deserialize_rfc3339<'de, D: Deserializer<'de>>(
+  deserializer: D,
+) -> Result<Option<DateTime<FixedOffset>>, D::Error> {
+  Option::<String>::deserialize(deserializer)?
+    .map(|s| DateTime::parse_from_rfc3339(&s).map_err(D::Error::custom))
+    .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::app::controller::log_controller::{GutterStyle, TimestampStyle};
+
+  #[test]
+  fn test_session_round_trip() {
+    let mut session = Session {
+      tags: [("kernel".to_string(), true), ("sshd".to_string(), false)].into(),
+      hosts: [("host-a".to_string(), false)].into(),
+      content_search: Some("timeout".to_string()),
+      search_terms: vec!["error".to_string()],
+      search_combine: SearchCombine::Any,
+      timestamp_search: "> 10:30".to_string(),
+      cursor: DateTime::parse_from_rfc3339("2026-01-17T10:22:55.642782+08:00").ok(),
+      ..Default::default()
+    };
+    session.style.next();
+    session.style.timestamp_style = TimestampStyle::Custom("%H:%M".parse().unwrap());
+    session.style.gutter_style = GutterStyle::Ordinal;
+
+    // 写入文件后再读出，内容不变
+    let path = std::env::temp_dir().join(format!(
+      "rs_syslog_viewer_session_{}.json",
+      std::process::id()
+    ));
+    session.save(&path).unwrap();
+    let loaded = Session::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded, session);
+
+    // 光标以 RFC3339 字符串保存
+    let json = serde_json::to_value(&session).unwrap();
+    assert_eq!(json["cursor"], "2026-01-17T10:22:55.642782+08:00");
+
+    // 恢复到控制器与数据看板后，再次收集得到相同的状态（光标要等日志加载后才能恢复）
+    let mut log_controller = LogController::default();
+    log_controller.style_mut().display_tz = "+08:00".parse().unwrap();
+    let mut data_board = DataBoard::default();
+    assert_eq!(
+      loaded.restore(&mut log_controller, &mut data_board),
+      session.cursor
+    );
+    for tag in ["kernel", "sshd", "cron"] {
+      data_board.update_tag(tag, true);
+    }
+    data_board.update_host("host-a");
+
+    let mut captured = Session::capture(&log_controller, &data_board);
+    assert_eq!(captured.style.display_tz, "+08:00".parse().unwrap());
+    captured.style.display_tz = Default::default();
+    assert_eq!(captured.tags.get("sshd"), Some(&false));
+    assert_eq!(captured.tags.get("cron"), Some(&true));
+    assert_eq!(
+      Session {
+        tags: session.tags.clone(),
+        cursor: session.cursor,
+        ..captured
+      },
+      session
+    );
+
+    // 无法解析的会话文件报错
+    assert!(Session::load(Path::new("/nonexistent/session.json")).is_err());
+    assert!(
+      serde_json::from_str::<Session>("{\"style\": {\"timestamp_style\": {\"custom\": \"%Q\"}}}")
+        .is_err()
+    );
+    assert!(serde_json::from_str::<Session>("{\"cursor\": \"yesterday\"}").is_err());
+  }
+
+  #[test]
+  fn test_session_key() {
+    let root = std::env::temp_dir();
+    let files = |names: &[&str]| names.iter().map(|name| root.join(name)).collect::<Vec<_>>();
+
+    // 日志文件给出的顺序不影响会话
+    assert_eq!(
+      session_key(Path::new(""), &files(&["a.log", "b.log"])),
+      session_key(Path::new(""), &files(&["b.log", "a.log"]))
+    );
+
+    // 浏览不同日志的会话互不相同，包括没有日志目录、读取标准输入的情况
+    let keys: BTreeSet<u64> = [
+      session_key(&root, &[]),
+      session_key(Path::new(""), &files(&["a.log"])),
+      session_key(&root, &files(&["a.log"])),
+      session_key(Path::new("-"), &[]),
+    ]
+    .into();
+    assert_eq!(keys.len(), 4);
+  }
+}
@@ -2,27 +2,41 @@ use crate::{
   app::Controller,
   ui::{KeyEventEx, State, ViewPort},
 };
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEventKind};
 use std::{cell::RefCell, rc::Rc};
 
+mod clear_marks_state;
+mod command_state;
 mod debug_operation_state;
+mod debug_searching_state;
+mod detail_state;
 mod help_state;
 mod log_content_searched_state;
 mod log_content_searching_state;
 mod log_navigation_state;
+mod log_percent_jumping_state;
+mod log_split_state;
 mod log_state_kit;
 mod log_timestamp_searched_state;
 mod log_timestamp_searching_state;
+mod mark_operation_state;
 mod quit_state;
 mod tag_operation_state;
 
+pub use clear_marks_state::ClearMarksState;
+pub use command_state::CommandState;
 pub use debug_operation_state::DebugOperationState;
+pub use debug_searching_state::DebugSearchingState;
+pub use detail_state::DetailState;
 pub use help_state::HelpState;
 pub use log_content_searched_state::LogContentSearchedState;
 pub use log_content_searching_state::LogContentSearchingState;
 pub use log_navigation_state::LogNavigationState;
+pub use log_percent_jumping_state::LogPercentJumpingState;
+pub use log_split_state::LogSplitState;
 pub use log_timestamp_searched_state::LogTimestampSearchedState;
 pub use log_timestamp_searching_state::LogTimestampSearchingState;
+pub use mark_operation_state::MarkOperationState;
 pub use quit_state::QuitState;
 pub use tag_operation_state::TagOperationState;
 
@@ -31,6 +45,9 @@ pub trait StateBuilder {
   fn build(self) -> State;
 }
 
+/// 鼠标滚轮滚动一格时，光标移动的行数
+const MOUSE_SCROLL_STEPS: isize = 3;
+
 /// 用于扩展 State 以支持数据导航能力
 pub trait ViewPortStateEx {
   fn view_port(self, ctrl: Rc<RefCell<dyn Controller>>, scrollable: bool) -> Self;
@@ -40,11 +57,11 @@ impl ViewPortStateEx for State {
   fn view_port(mut self, ctrl: Rc<RefCell<dyn Controller>>, scrollable: bool) -> Self {
     fn action(
       state: State,
-      code: KeyCode,
+      key: KeyEvent,
       ctrl: Rc<RefCell<dyn Controller>>,
       mut act: impl FnMut(&mut ViewPort) + 'static,
     ) -> State {
-      state.action(KeyEvent::simple(code), move |_| {
+      state.action(key, move |_| {
         if let Some(view_port) = ctrl.borrow_mut().view_port() {
           act(view_port);
         }
@@ -55,21 +72,104 @@ impl ViewPortStateEx for State {
     if scrollable {
       if let Some(view_port) = ctrl.borrow_mut().view_port() {
         view_port.enable_horizontal_scroll();
-        self = action(self, KeyCode::Left, ctrl.clone(), |v| {
+        self = action(self, KeyEvent::simple(KeyCode::Left), ctrl.clone(), |v| {
           v.want_scroll_horizontally(-1)
         });
-        self = action(self, KeyCode::Right, ctrl.clone(), |v| {
+        self = action(self, KeyEvent::simple(KeyCode::Right), ctrl.clone(), |v| {
           v.want_scroll_horizontally(1)
         });
+        self = action(
+          self,
+          KeyEvent::new(KeyCode::Left, KeyModifiers::CONTROL),
+          ctrl.clone(),
+          |v| v.want_jump_horizontally(-1),
+        );
+        self = action(
+          self,
+          KeyEvent::new(KeyCode::Right, KeyModifiers::CONTROL),
+          ctrl.clone(),
+          |v| v.want_jump_horizontally(1),
+        );
+        self = action(self, KeyEvent::simple(KeyCode::Home), ctrl.clone(), |v| {
+          v.scroll_horizontal_home()
+        });
+        self = action(self, KeyEvent::simple(KeyCode::End), ctrl.clone(), |v| {
+          v.scroll_horizontal_end()
+        });
       }
     }
 
-    self = action(self, KeyCode::Up, ctrl.clone(), |v| v.want_move_cursor(-1));
-    self = action(self, KeyCode::Down, ctrl.clone(), |v| v.want_move_cursor(1));
-    self = action(self, KeyCode::PageUp, ctrl.clone(), |v| v.want_page_up());
-    self = action(self, KeyCode::PageDown, ctrl.clone(), |v| {
+    self = action(self, KeyEvent::simple(KeyCode::Up), ctrl.clone(), |v| {
+      v.want_move_cursor(-1)
+    });
+    self = action(self, KeyEvent::simple(KeyCode::Down), ctrl.clone(), |v| {
+      v.want_move_cursor(1)
+    });
+    self = action(self, KeyEvent::simple(KeyCode::PageUp), ctrl.clone(), |v| {
+      v.want_page_up()
+    });
+    self = action(
+      self,
+      KeyEvent::simple(KeyCode::PageDown),
+      ctrl.clone(),
+      |v| v.want_page_down(),
+    );
+    self = action(
+      self,
+      KeyEvent::new(KeyCode::Up, KeyModifiers::SHIFT),
+      ctrl.clone(),
+      |v| v.want_half_page_up(),
+    );
+    self = action(
+      self,
+      KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT),
+      ctrl.clone(),
+      |v| v.want_half_page_down(),
+    );
+
+    // 鼠标滚轮移动光标，左键点击将光标定位到点击的行
+    let c = ctrl.clone();
+    self = self.mouse_action(move |_, event| {
+      if let Some(view_port) = c.borrow_mut().view_port() {
+        match event.kind {
+          MouseEventKind::ScrollUp => view_port.want_move_cursor(-MOUSE_SCROLL_STEPS),
+          MouseEventKind::ScrollDown => view_port.want_move_cursor(MOUSE_SCROLL_STEPS),
+          MouseEventKind::Down(MouseButton::Left) => {
+            view_port.want_click(event.column, event.row);
+          }
+          _ => {}
+        }
+      }
+    });
+
+    // vim 风格的导航按键。大写的 G 需要先于 g 注册，因为 g 的匹配是忽略大小写的
+    self = action(
+      self,
+      KeyEvent::simple(KeyCode::Char('k')),
+      ctrl.clone(),
+      |v| v.want_move_cursor(-1),
+    );
+    self = action(
+      self,
+      KeyEvent::simple(KeyCode::Char('j')),
+      ctrl.clone(),
+      |v| v.want_move_cursor(1),
+    );
+    self = action(self, KeyEvent::ctrl('b'), ctrl.clone(), |v| {
+      v.want_page_up()
+    });
+    self = action(self, KeyEvent::ctrl('f'), ctrl.clone(), |v| {
       v.want_page_down()
     });
+    self = action(self, KeyEvent::shift('G'), ctrl.clone(), |v| {
+      v.want_go_bottom()
+    });
+    self = action(
+      self,
+      KeyEvent::simple(KeyCode::Char('g')),
+      ctrl.clone(),
+      |v| v.want_go_top(),
+    );
     self
   }
 }
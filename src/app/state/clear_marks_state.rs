@@ -0,0 +1,40 @@
+use crate::{
+  app::controller::LogController,
+  ui::{KeyEventEx, State},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use std::{cell::RefCell, rc::Rc};
+
+/// 询问是否清除所有标记的状态
+pub struct ClearMarksState {
+  /// 日志数据控制器
+  log_controller: Rc<RefCell<LogController>>,
+
+  /// 被构建的状态
+  state: State,
+}
+
+impl ClearMarksState {
+  pub fn new(log_controller: Rc<RefCell<LogController>>) -> Self {
+    Self {
+      log_controller,
+      state: State::new("clear marks"),
+    }
+  }
+
+  /// 构建状态，确认清除后跳转到给定的状态
+  pub fn build(self, confirmed_state: usize) -> State {
+    let ctrl = self.log_controller;
+    self
+      .state
+      .enter_action(|pager| pager.status().set_critical("Clear all marks or not ? y/N"))
+      .goto_action(
+        KeyEvent::simple(KeyCode::Char('y')),
+        confirmed_state,
+        move |_| {
+          ctrl.borrow_mut().clear_all_marks();
+          true
+        },
+      )
+  }
+}
@@ -0,0 +1,46 @@
+use crate::{
+  app::{Command, controller::LogController},
+  ui::{KeyEventEx, State},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use std::{cell::RefCell, rc::Rc};
+
+/// 命令面板的输入状态，回车后解析并执行命令
+pub struct CommandState {
+  /// 日志数据控制器
+  log_controller: Rc<RefCell<LogController>>,
+
+  /// 被构建的状态
+  state: State,
+}
+
+impl CommandState {
+  pub fn new(log_controller: Rc<RefCell<LogController>>) -> Self {
+    Self {
+      log_controller,
+      state: State::new("command"),
+    }
+  }
+
+  /// 构建状态，命令执行后跳转到给定的状态。命令无法解析时停留在本状态，并展示错误
+  pub fn build(self, done_state: usize) -> State {
+    let ctrl = self.log_controller;
+    self.state.input("Command", |_| {}).goto_action(
+      KeyEvent::simple(KeyCode::Enter),
+      done_state,
+      move |pager| {
+        let input = pager.status().get_input().cloned().unwrap_or_default();
+        match input.parse::<Command>() {
+          Ok(command) => {
+            command.apply(&mut ctrl.borrow_mut());
+            true
+          }
+          Err(e) => {
+            pager.status().set_critical(e);
+            false
+          }
+        }
+      },
+    )
+  }
+}
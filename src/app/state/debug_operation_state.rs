@@ -1,5 +1,5 @@
 use crate::{
-  app::{StateBuilder, ViewPortStateEx, controller::DebugController},
+  app::{KeyAction, KeyMap, StateBuilder, ViewPortStateEx, controller::DebugController},
   ui::State,
 };
 use std::{cell::RefCell, rc::Rc};
@@ -11,19 +11,35 @@ pub struct DebugOperationState {
 
   /// 被构建的状态
   state: State,
+
+  /// 按键映射
+  key_map: KeyMap,
 }
 
 impl DebugOperationState {
-  pub fn new(debug_controller: Rc<RefCell<DebugController>>) -> Self {
+  pub fn new(debug_controller: Rc<RefCell<DebugController>>, key_map: &KeyMap) -> Self {
     Self {
       debug_controller,
       state: State::new("debug operation"),
+      key_map: key_map.clone(),
     }
   }
 }
 
 impl StateBuilder for DebugOperationState {
   fn build(self) -> State {
-    self.state.view_port(self.debug_controller, true)
+    let c1 = self.debug_controller.clone();
+    let c2 = c1.clone();
+
+    // 过滤条件展示在页面标题中，不占用正在输入的状态栏
+    self
+      .state
+      .action(self.key_map.get(KeyAction::NextDebugLevel), move |_| {
+        c1.borrow_mut().next_level()
+      })
+      .action(self.key_map.get(KeyAction::ClearDebugLogs), move |_| {
+        c2.borrow_mut().clear()
+      })
+      .view_port(self.debug_controller, true)
   }
 }
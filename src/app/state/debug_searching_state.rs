@@ -0,0 +1,40 @@
+use crate::{
+  app::{StateBuilder, ViewPortStateEx, controller::DebugController},
+  ui::State,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// 搜索调试信息的状态，还在输入中
+pub struct DebugSearchingState {
+  /// 调试数据控制器
+  debug_controller: Rc<RefCell<DebugController>>,
+
+  /// 被构建的状态
+  state: State,
+}
+
+impl DebugSearchingState {
+  pub fn new(debug_controller: Rc<RefCell<DebugController>>) -> Self {
+    Self {
+      debug_controller,
+      state: State::new("debug searching"),
+    }
+  }
+}
+
+impl StateBuilder for DebugSearchingState {
+  fn build(self) -> State {
+    let c1 = self.debug_controller.clone();
+    let c2 = c1.clone();
+
+    self
+      .state
+      .input("Debug", move |s| c1.borrow_mut().search(s.to_string()))
+      .view_port(self.debug_controller, true) // 输入状态下，其实横向滚动操作是无效的，这里仅展示下滚动条。
+      .enter_action(move |pager| {
+        pager
+          .status()
+          .reset_input(c2.borrow().get_curr_search().to_string());
+      })
+  }
+}
@@ -0,0 +1,51 @@
+use crate::{
+  app::{StateBuilder, controller::DetailController},
+  ui::{KeyEventEx, State},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use std::{cell::RefCell, rc::Rc};
+
+/// 查看光标所在日志详情的状态
+pub struct DetailState {
+  /// 日志详情控制器
+  detail_controller: Rc<RefCell<DetailController>>,
+
+  /// 被构建的状态
+  state: State,
+}
+
+impl DetailState {
+  pub fn new(detail_controller: Rc<RefCell<DetailController>>) -> Self {
+    Self {
+      detail_controller,
+      state: State::new("detail"),
+    }
+  }
+}
+
+impl StateBuilder for DetailState {
+  fn build(self) -> State {
+    let c1 = self.detail_controller;
+    let c2 = c1.clone();
+    let c3 = c1.clone();
+    let c4 = c1.clone();
+    let c5 = c1.clone();
+
+    // 进入状态时取出光标所在的日志，之后可以上下滚动查看过长的内容
+    self
+      .state
+      .enter_action(move |_| c1.borrow_mut().refresh())
+      .action(KeyEvent::simple(KeyCode::Char('j')), move |_| {
+        c2.borrow_mut().scroll_down()
+      })
+      .action(KeyEvent::simple(KeyCode::Down), move |_| {
+        c3.borrow_mut().scroll_down()
+      })
+      .action(KeyEvent::simple(KeyCode::Char('k')), move |_| {
+        c4.borrow_mut().scroll_up()
+      })
+      .action(KeyEvent::simple(KeyCode::Up), move |_| {
+        c5.borrow_mut().scroll_up()
+      })
+  }
+}
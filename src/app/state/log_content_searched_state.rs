@@ -1,21 +1,27 @@
 use super::log_state_kit::LogStateKit;
 use crate::app::controller::log_controller::Error;
 use crate::{
-  app::{StateBuilder, ViewPortStateEx, controller::LogController},
-  ui::{KeyEventEx, State},
+  app::{KeyAction, KeyMap, StateBuilder, ViewPortStateEx, controller::LogController},
+  ui::State,
 };
-use crossterm::event::{KeyCode, KeyEvent};
 use std::{cell::RefCell, rc::Rc};
 
 /// 在已经搜索完成的结果中，进行导航的状态
 pub struct LogContentSearchedState {
   kit: LogStateKit,
+
+  /// 按键映射
+  key_map: KeyMap,
 }
 
 impl LogContentSearchedState {
-  pub fn new(log_controller: Rc<RefCell<LogController>>) -> LogContentSearchedState {
+  pub fn new(
+    log_controller: Rc<RefCell<LogController>>,
+    key_map: &KeyMap,
+  ) -> LogContentSearchedState {
     Self {
       kit: LogStateKit::new(log_controller, "log content searched"),
+      key_map: key_map.clone(),
     }
   }
 }
@@ -27,12 +33,18 @@ impl StateBuilder for LogContentSearchedState {
 
     self
       .kit
-      .action(KeyEvent::simple(KeyCode::Char(']')), move |ctrl| {
+      .action(self.key_map.get(KeyAction::NextMatch), move |ctrl| {
         ctrl.next_content_search()
       })
-      .action(KeyEvent::simple(KeyCode::Char('[')), move |ctrl| {
+      .action(self.key_map.get(KeyAction::PrevMatch), move |ctrl| {
         ctrl.prev_content_search()
       })
+      .action(self.key_map.get(KeyAction::MarkAllMatches), |ctrl| {
+        ctrl.mark_all_matching(true)
+      })
+      .action(self.key_map.get(KeyAction::UnmarkAllMatches), |ctrl| {
+        ctrl.mark_all_matching(false)
+      })
       .error(|e| match e {
         Error::NextContentSearchNotFound => {
           Some("No next log is found. (use [ to find previous one)".to_string())
@@ -45,9 +57,11 @@ impl StateBuilder for LogContentSearchedState {
       .state
       .view_port(c1, true)
       .enter_action(move |pager| {
+        let mut ctrl = c2.borrow_mut();
+        ctrl.flush_content_search();
         pager.status().set_tips(format!(
-          "Use ][ to navigate searching '{}'",
-          c2.borrow().get_search_content()
+          "Use ][ to navigate searching {}",
+          ctrl.search_description()
         ))
       })
   }
@@ -1,7 +1,7 @@
 use super::log_state_kit::LogStateKit;
-use crate::ui::ViewPortEx;
+use crate::ui::{Pager, ViewPortEx};
 use crate::{
-  app::{StateBuilder, ViewPortStateEx, controller::LogController},
+  app::{KeyAction, KeyMap, StateBuilder, ViewPortStateEx, controller::LogController},
   ui::State,
 };
 use std::{cell::RefCell, rc::Rc};
@@ -9,35 +9,79 @@ use std::{cell::RefCell, rc::Rc};
 /// 搜索日志的状态，还在输入中
 pub struct LogContentSearchingState {
   kit: LogStateKit,
+
+  /// 按键映射
+  key_map: KeyMap,
 }
 
 impl LogContentSearchingState {
-  pub fn new(log_controller: Rc<RefCell<LogController>>) -> LogContentSearchingState {
+  pub fn new(
+    log_controller: Rc<RefCell<LogController>>,
+    key_map: &KeyMap,
+  ) -> LogContentSearchingState {
     Self {
       kit: LogStateKit::new(log_controller, "log content searching"),
+      key_map: key_map.clone(),
     }
   }
 }
 
+/// 重置输入框，提示符中列出已经叠加的搜索条件，如 `Logs ['error' AND]`
+fn reset_input(pager: &mut Pager, ctrl: &LogController) {
+  let terms = ctrl.search_terms();
+  let prompt = if terms.is_empty() {
+    "Logs".to_string()
+  } else {
+    let terms: Vec<String> = terms.iter().map(|s| format!("'{s}'")).collect();
+    let combine = ctrl.search_combine().name();
+    format!("Logs [{} {combine}]", terms.join(&format!(" {combine} ")))
+  };
+  let status = pager.status();
+  status.set_input(prompt);
+  status.reset_input(ctrl.get_search_content().to_string());
+}
+
 impl StateBuilder for LogContentSearchingState {
   fn build(self) -> State {
     let c1 = self.kit.log_controller.clone();
     let c2 = c1.clone();
     let c3 = c1.clone();
+    let c4 = c1.clone();
+    let c5 = c1.clone();
+    let c6 = c1.clone();
 
     self
       .kit
       .state
       .input("Logs", move |s| {
-        c1.borrow_mut().search_content(Some(s.to_string()))
+        c1.borrow_mut().search_content_incrementally(s.to_string())
       })
       .view_port(c2, true) // 输入状态下，其实横向滚动操作是无效的，这里仅展示下滚动条。
+      .action(self.key_map.get(KeyAction::AddSearchTerm), move |pager| {
+        let mut ctrl = c4.borrow_mut();
+        ctrl.add_search_term();
+        reset_input(pager, &ctrl);
+      })
+      .action(
+        self.key_map.get(KeyAction::RemoveSearchTerm),
+        move |pager| {
+          let mut ctrl = c5.borrow_mut();
+          ctrl.remove_search_term();
+          reset_input(pager, &ctrl);
+        },
+      )
+      .action(
+        self.key_map.get(KeyAction::ToggleSearchCombine),
+        move |pager| {
+          let mut ctrl = c6.borrow_mut();
+          ctrl.toggle_search_combine();
+          reset_input(pager, &ctrl);
+        },
+      )
       .enter_action(move |pager| {
         let mut ctrl = c3.borrow_mut();
         ctrl.view_mut().ui_mut().do_not_follow();
-        pager
-          .status()
-          .reset_input(ctrl.get_search_content().to_string())
+        reset_input(pager, &ctrl);
       })
   }
 }
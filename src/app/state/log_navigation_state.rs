@@ -1,22 +1,29 @@
 use super::log_state_kit::LogStateKit;
 use crate::app::controller::log_controller::Error;
+use crate::log::Label;
 use crate::ui::ViewPortEx;
 use crate::{
-  app::{StateBuilder, ViewPortStateEx, controller::LogController},
-  ui::{KeyEventEx, State},
+  app::{
+    KeyAction, KeyMap, StateBuilder, SystemClipboard, ViewPortStateEx, controller::LogController,
+  },
+  ui::State,
 };
-use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
 use std::{cell::RefCell, rc::Rc};
 
 /// 处理日志浏览导航的状态
 pub struct LogNavigationState {
   kit: LogStateKit,
+
+  /// 按键映射
+  key_map: KeyMap,
 }
 
 impl LogNavigationState {
-  pub fn new(log_controller: Rc<RefCell<LogController>>) -> Self {
+  pub fn new(log_controller: Rc<RefCell<LogController>>, key_map: &KeyMap) -> Self {
     Self {
       kit: LogStateKit::new(log_controller, "log navigation"),
+      key_map: key_map.clone(),
     }
   }
 }
@@ -24,35 +31,84 @@ impl LogNavigationState {
 impl StateBuilder for LogNavigationState {
   fn build(self) -> State {
     let c1 = self.kit.log_controller.clone();
+    let c2 = c1.clone();
+    let key = |action| self.key_map.get(action);
 
     self
       .kit
-      .action(KeyEvent::simple(KeyCode::Char('1')), |ctrl| {
-        ctrl.style_mut().next()
-      })
-      .action(KeyEvent::simple(KeyCode::Char('2')), |ctrl| {
-        ctrl.style_mut().timestamp_style.next()
+      .action(key(KeyAction::NextStyle), |ctrl| ctrl.style_mut().next())
+      .action(key(KeyAction::NextTimestampStyle), |ctrl| {
+        ctrl.style_mut().next_timestamp_style()
       })
-      .action(KeyEvent::simple(KeyCode::Char('3')), |ctrl| {
-        ctrl.style_mut().tag_style.next()
+      .action(key(KeyAction::NextTagStyle), |ctrl| {
+        ctrl.style_mut().next_tag_style()
       })
-      .action(KeyEvent::simple(KeyCode::Char('4')), |ctrl| {
+      .action(key(KeyAction::NextPidStyle), |ctrl| {
         ctrl.style_mut().pid_style.next()
       })
-      .action(KeyEvent::simple(KeyCode::Char('f')), |ctrl| {
+      .action(key(KeyAction::NextFacilityStyle), |ctrl| {
+        ctrl.style_mut().facility_style.next()
+      })
+      .action(key(KeyAction::NextSeverityStyle), |ctrl| {
+        ctrl.style_mut().severity_style.next()
+      })
+      .action(key(KeyAction::NextGutterStyle), |ctrl| {
+        ctrl.style_mut().gutter_style.next()
+      })
+      .action(key(KeyAction::NextTimestampPrecision), |ctrl| {
+        ctrl.style_mut().timestamp_precision.next()
+      })
+      .action(key(KeyAction::Follow), |ctrl| {
         ctrl.view_mut().ui_mut().want_follow()
       })
-      .action(KeyEvent::simple(KeyCode::Char('m')), |ctrl| {
-        ctrl.toggle_mark()
+      .action(key(KeyAction::ToggleMark), |ctrl| ctrl.toggle_mark())
+      .action(key(KeyAction::TogglePin), |ctrl| ctrl.toggle_pin())
+      .action(key(KeyAction::ToggleErrorLogs), |ctrl| {
+        ctrl.toggle_label(Label::Error)
+      })
+      .action(key(KeyAction::ToggleWarnLogs), |ctrl| {
+        ctrl.toggle_label(Label::Warn)
+      })
+      .action(key(KeyAction::ToggleInfoLogs), |ctrl| {
+        ctrl.toggle_label(Label::Info)
+      })
+      .action(key(KeyAction::ToggleDebugLogs), |ctrl| {
+        ctrl.toggle_label(Label::Debug)
       })
-      .action(KeyEvent::simple(KeyCode::Char('[')), |ctrl| {
+      .action(key(KeyAction::ToggleUnknownLogs), |ctrl| {
+        ctrl.toggle_label(Label::Unknown)
+      })
+      .action(key(KeyAction::ToggleBadLinesOnly), |ctrl| {
+        ctrl.toggle_bad_lines_only()
+      })
+      .action(key(KeyAction::ToggleCollapseDuplicates), |ctrl| {
+        ctrl.toggle_collapse_duplicates()
+      })
+      .action(key(KeyAction::ToggleReverseOrder), |ctrl| {
+        ctrl.toggle_reverse_order()
+      })
+      .action(key(KeyAction::ToggleSearchTimeWindow), |ctrl| {
+        ctrl.toggle_search_time_window();
+      })
+      .action(key(KeyAction::JumpToClipboardTime), |ctrl| {
+        ctrl.locate_clipboard_time(SystemClipboard)
+      })
+      .action(key(KeyAction::PrevMatch), |ctrl| {
         ctrl.prev_mark();
         ctrl.view_mut().ui_mut().do_not_follow();
       })
-      .action(KeyEvent::simple(KeyCode::Char(']')), |ctrl| {
+      .action(key(KeyAction::NextMatch), |ctrl| {
         ctrl.next_mark();
         ctrl.view_mut().ui_mut().do_not_follow();
       })
+      .action(key(KeyAction::PrevTagChange), |ctrl| {
+        ctrl.prev_tag_change();
+        ctrl.view_mut().ui_mut().do_not_follow();
+      })
+      .action(key(KeyAction::NextTagChange), |ctrl| {
+        ctrl.next_tag_change();
+        ctrl.view_mut().ui_mut().do_not_follow();
+      })
       .error(|e| match e {
         Error::NextMarkedNotFound => {
           Some("No next marked log is found. (use [ to find previous one)".to_string())
@@ -60,9 +116,61 @@ impl StateBuilder for LogNavigationState {
         Error::PrevMarkedNotFound => {
           Some("No previous marked log is found. (use ] to find next one)".to_string())
         }
+        Error::NextTagChangeNotFound => {
+          Some("No further tag change is found. (use { to find previous one)".to_string())
+        }
+        Error::PrevTagChangeNotFound => {
+          Some("No earlier tag change is found. (use } to find next one)".to_string())
+        }
+        Error::ExportFailed(e) => Some(format!("Failed to export logs: {}", e)),
+        Error::ClipboardTimestampError(e) => {
+          Some(format!("Cannot jump to the clipboard timestamp: {}", e))
+        }
         _ => None,
       })
       .state
+      .manual_action(move |pager| {
+        // 在状态栏中展示被隐藏的日志等级、排除的内容、过滤的进程号、无法解析的行数，以及是否折叠了重复日志、是否倒序展示、内容搜索的时间窗口
+        let ctrl = c2.borrow();
+        let hidden_labels = ctrl.hidden_labels().iter().map(label_name).join(",");
+        let mut indicators = vec![];
+        if !hidden_labels.is_empty() {
+          indicators.push(format!("hidden: {}", hidden_labels));
+        }
+        if !ctrl.exclusions().is_empty() {
+          let exclusions = ctrl.exclusions().iter().map(|s| format!("'{s}'")).join(",");
+          indicators.push(format!("excluded: {}", exclusions));
+        }
+        if let Some(pid_filter) = ctrl.pid_filter() {
+          indicators.push(format!("pid: {}", pid_filter));
+        }
+        match (ctrl.is_bad_lines_only(), ctrl.bad_lines()) {
+          (true, n) => indicators.push(format!("bad lines only: {}", n)),
+          (false, 0) => {}
+          (false, n) => indicators.push(format!("bad lines: {}", n)),
+        }
+        if ctrl.is_collapsing_duplicates() {
+          indicators.push("collapsed".to_string());
+        }
+        if ctrl.is_reverse_order() {
+          indicators.push("reversed".to_string());
+        }
+        if let Some(window) = ctrl.get_search_time_window() {
+          indicators.push(format!("within: {}", window));
+        }
+        pager.status().set_indicator(indicators.join(" | "));
+      })
       .view_port(c1, true)
   }
 }
+
+/// 日志等级在状态栏中展示的名称
+fn label_name(label: &Label) -> &'static str {
+  match label {
+    Label::Error => "error",
+    Label::Warn => "warn",
+    Label::Info => "info",
+    Label::Debug => "debug",
+    Label::Unknown => "unknown",
+  }
+}
@@ -0,0 +1,62 @@
+use super::log_state_kit::LogStateKit;
+use crate::{
+  app::{StateBuilder, ViewPortStateEx, controller::LogController},
+  ui::State,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// 输入百分比，并跳转到已加载日志中对应位置的状态
+pub struct LogPercentJumpingState {
+  kit: LogStateKit,
+}
+
+impl LogPercentJumpingState {
+  pub fn new(log_controller: Rc<RefCell<LogController>>) -> Self {
+    Self {
+      kit: LogStateKit::new(log_controller, "log percent jumping"),
+    }
+  }
+}
+
+impl StateBuilder for LogPercentJumpingState {
+  fn build(self) -> State {
+    let c1 = self.kit.log_controller.clone();
+    let c2 = c1.clone();
+
+    self
+      .kit
+      .state
+      .input("Percent", move |s| {
+        if let Some(fraction) = parse_percent(s) {
+          c1.borrow_mut().locate_fraction(fraction);
+        }
+      })
+      .view_port(c2, true)
+  }
+}
+
+/// 解析形如 `50`、`50%`、`12.5%` 的百分比，返回 \[0, 1] 范围内的相对位置
+fn parse_percent(s: &str) -> Option<f64> {
+  let s = s.trim();
+  let percent: f64 = s.strip_suffix('%').unwrap_or(s).trim_end().parse().ok()?;
+  percent
+    .is_finite()
+    .then(|| (percent / 100.0).clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_percent() {
+    assert_eq!(parse_percent("50%"), Some(0.5));
+    assert_eq!(parse_percent(" 25 "), Some(0.25));
+    assert_eq!(parse_percent("12.5 %"), Some(0.125));
+    assert_eq!(parse_percent("150%"), Some(1.0));
+    assert_eq!(parse_percent("-1%"), Some(0.0));
+    assert_eq!(parse_percent(""), None);
+    assert_eq!(parse_percent("%"), None);
+    assert_eq!(parse_percent("abc"), None);
+  }
+}
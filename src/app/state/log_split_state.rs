@@ -0,0 +1,61 @@
+use super::LogNavigationState;
+use crate::{
+  app::{KeyMap, StateBuilder, controller::LogController},
+  ui::State,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// 在分屏展示区中，浏览锁定到某个标签的日志的状态
+pub struct LogSplitState {
+  /// 分屏展示区的控制器
+  split_controller: Rc<RefCell<LogController>>,
+
+  /// 主展示区的控制器，分屏锁定的标签取自它光标所在的日志
+  log_controller: Rc<RefCell<LogController>>,
+
+  /// 按键映射
+  key_map: KeyMap,
+}
+
+impl LogSplitState {
+  pub fn new(
+    split_controller: Rc<RefCell<LogController>>,
+    log_controller: Rc<RefCell<LogController>>,
+    key_map: &KeyMap,
+  ) -> Self {
+    Self {
+      split_controller,
+      log_controller,
+      key_map: key_map.clone(),
+    }
+  }
+}
+
+impl StateBuilder for LogSplitState {
+  fn build(self) -> State {
+    let split = self.split_controller.clone();
+    let main = self.log_controller;
+
+    // 分屏展示区拥有独立的光标，导航方式与主展示区相同
+    LogNavigationState::new(self.split_controller, &self.key_map)
+      .build()
+      .enter_action(move |pager| {
+        // 进入时，将分屏锁定到主展示区光标所在日志的标签上，并沿用主展示区的展示风格
+        let main = main.borrow();
+        let mut split = split.borrow_mut();
+        if let Some(tag) = main.cursor_log().and_then(|log| log.get_tag()) {
+          split.lock_tag(Some(tag.to_string()));
+        }
+        *split.style_mut() = main.style().clone();
+
+        match split.locked_tag() {
+          Some(tag) => pager.status().set_tips(format!(
+            "Showing logs of '{tag}' (press 's' or 'esc' to unfocus)"
+          )),
+          None => pager
+            .status()
+            .set_tips("No tag under the cursor to split by ! (press 's' or 'esc' to unfocus)"),
+        }
+      })
+  }
+}
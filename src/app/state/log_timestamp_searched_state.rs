@@ -2,21 +2,24 @@ use super::log_state_kit::LogStateKit;
 use crate::app::controller::log_controller::Error;
 use crate::ui::ViewPortEx;
 use crate::{
-  app::{StateBuilder, ViewPortStateEx, controller::LogController},
-  ui::{KeyEventEx, State},
+  app::{KeyAction, KeyMap, StateBuilder, ViewPortStateEx, controller::LogController},
+  ui::State,
 };
-use crossterm::event::{KeyCode, KeyEvent};
 use std::{cell::RefCell, rc::Rc};
 
 /// 在已经设置完时间戳搜索条件的情况下，进行时间戳搜索与导航
 pub struct LogTimestampSearchedState {
   kit: LogStateKit,
+
+  /// 按键映射
+  key_map: KeyMap,
 }
 
 impl LogTimestampSearchedState {
-  pub fn new(log_controller: Rc<RefCell<LogController>>) -> Self {
+  pub fn new(log_controller: Rc<RefCell<LogController>>, key_map: &KeyMap) -> Self {
     Self {
       kit: LogStateKit::new(log_controller, "log timestamp searched"),
+      key_map: key_map.clone(),
     }
   }
 }
@@ -29,12 +32,18 @@ impl StateBuilder for LogTimestampSearchedState {
 
     self
       .kit
-      .action(KeyEvent::simple(KeyCode::Char(']')), move |ctrl| {
+      .action(self.key_map.get(KeyAction::NextMatch), move |ctrl| {
         ctrl.next_timestamp_search()
       })
-      .action(KeyEvent::simple(KeyCode::Char('[')), move |ctrl| {
+      .action(self.key_map.get(KeyAction::PrevMatch), move |ctrl| {
         ctrl.prev_timestamp_search()
       })
+      .action(self.key_map.get(KeyAction::MarkAllMatches), |ctrl| {
+        ctrl.mark_all_matching(true)
+      })
+      .action(self.key_map.get(KeyAction::UnmarkAllMatches), |ctrl| {
+        ctrl.mark_all_matching(false)
+      })
       .error(|e| match e {
         Error::TimestampSearchFormatError(msg) => Some(msg),
         Error::NextTimestampSearchNotFound => {
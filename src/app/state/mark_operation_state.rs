@@ -0,0 +1,54 @@
+use crate::{
+  app::{
+    StateBuilder, ViewPortStateEx,
+    controller::{LogController, MarkController},
+  },
+  ui::{KeyEventEx, State},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use std::{cell::RefCell, rc::Rc};
+
+/// 浏览标记列表，并跳转到选中的标记日志的状态
+pub struct MarkOperationState {
+  /// 标记列表控制器
+  mark_controller: Rc<RefCell<MarkController>>,
+
+  /// 日志展示区控制器，用于跳转到选中的日志
+  log_controller: Rc<RefCell<LogController>>,
+
+  /// 被构建的状态
+  state: State,
+}
+
+impl MarkOperationState {
+  pub fn new(
+    mark_controller: Rc<RefCell<MarkController>>,
+    log_controller: Rc<RefCell<LogController>>,
+  ) -> Self {
+    Self {
+      mark_controller,
+      log_controller,
+      state: State::new("mark operation"),
+    }
+  }
+}
+
+impl StateBuilder for MarkOperationState {
+  fn build(self) -> State {
+    let marks = self.mark_controller.clone();
+    let logs = self.log_controller.clone();
+    let c1 = self.mark_controller.clone();
+
+    self
+      .state
+      // 进入时重新收集标记。加载或淘汰日志不会改变标记与过滤的版本，
+      // 列表中可能缺少新加载的日志中的标记，或者残留已被淘汰的日志
+      .enter_action(move |_| c1.borrow_mut().refresh())
+      .action(KeyEvent::simple(KeyCode::Enter), move |_| {
+        if let Some(index) = marks.borrow().selected() {
+          logs.borrow_mut().locate(index);
+        }
+      })
+      .view_port(self.mark_controller, true)
+  }
+}
@@ -1,8 +1,8 @@
 use crate::{
-  app::{StateBuilder, ViewPortStateEx, controller::TagController},
-  ui::{KeyEventEx, State},
+  app::{KeyAction, KeyMap, StateBuilder, ViewPortStateEx, controller::TagController},
+  ui::State,
 };
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use std::{cell::RefCell, rc::Rc};
 
 pub struct TagOperationState {
@@ -11,13 +11,17 @@ pub struct TagOperationState {
 
   /// 被构建的状态
   state: State,
+
+  /// 按键映射
+  key_map: KeyMap,
 }
 
 impl TagOperationState {
-  pub fn new(tag_controller: Rc<RefCell<TagController>>) -> Self {
+  pub fn new(tag_controller: Rc<RefCell<TagController>>, key_map: &KeyMap) -> Self {
     Self {
       tag_controller,
       state: State::new("tag operation"),
+      key_map: key_map.clone(),
     }
   }
 
@@ -35,15 +39,22 @@ impl StateBuilder for TagOperationState {
     let c1 = self.tag_controller.clone();
     let c2 = c1.clone();
     let c3 = c1.clone();
+    let name = c1.borrow().kind().name();
+    let key_map = self.key_map.clone();
+    let key = |action| key_map.get(action);
 
     self
-      .action(KeyEvent::simple(KeyCode::Enter), |ctrl| ctrl.toggle())
-      .action(KeyEvent::ctrl('y'), |ctrl| ctrl.set_all())
-      .action(KeyEvent::ctrl('n'), |ctrl| ctrl.unset_all())
-      .action(KeyEvent::ctrl('h'), |ctrl| ctrl.toggle_all())
+      .action(key(KeyAction::ToggleTag), |ctrl| ctrl.toggle())
+      .action(key(KeyAction::SetAllTags), |ctrl| ctrl.set_all())
+      .action(key(KeyAction::UnsetAllTags), |ctrl| ctrl.unset_all())
+      .action(key(KeyAction::ToggleAllTags), |ctrl| ctrl.toggle_all())
+      // 匹配方式展示在页面标题中，不占用正在输入的状态栏
+      .action(key(KeyAction::ToggleTagMatchMode), |ctrl| {
+        ctrl.toggle_match_mode();
+      })
       .state
       .view_port(c1, false)
-      .input("Tags", move |s| c2.borrow_mut().search(s.to_string()))
+      .input(name, move |s| c2.borrow_mut().search(s.to_string()))
       .enter_action(move |pager| {
         pager
           .status()
@@ -1,7 +1,9 @@
-use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, Timelike};
+use crate::app::DisplayTimezone;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, Timelike};
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::cmp::Ordering;
 use std::ops::Neg;
 
 /// 时间比较操作符
@@ -42,8 +44,8 @@ struct TimeCond {
 }
 
 impl TimeCond {
+  /// 检查已经转换到展示时区的时间点是否满足本条件
   fn is_matched(&self, dt: DateTime<FixedOffset>) -> bool {
-    let dt = dt.with_timezone(&Local);
     match self.op {
       TimeCmpOp::Equal => {
         self.year == dt.year()
@@ -73,6 +75,45 @@ impl TimeCond {
   }
 }
 
+/// 时间点的各个部分（年、月、日、时、分、秒），按字典序比较即为时间的先后
+type TimeParts = [i64; 6];
+
+/// 时间点各个部分的最小取值
+const TIME_PARTS_MIN: TimeParts = [i32::MIN as i64, 1, 1, 0, 0, 0];
+
+/// 时间点各个部分的最大取值
+const TIME_PARTS_MAX: TimeParts = [i32::MAX as i64, 12, 31, 23, 59, 59];
+
+/// 一段连续时间的边界，首尾都包含在内，精确到秒
+#[derive(Copy, Clone, Debug)]
+pub struct TimeBounds {
+  tz: DisplayTimezone,
+  start: TimeParts,
+  end: TimeParts,
+}
+
+impl TimeBounds {
+  /// 比较时间点与本时间段：早于时间段时返回 Less，晚于时间段时返回 Greater，处于其中时返回 Equal
+  pub fn compare(&self, dt: DateTime<FixedOffset>) -> Ordering {
+    let dt = self.tz.convert(dt);
+    let parts = [
+      dt.year() as i64,
+      dt.month() as i64,
+      dt.day() as i64,
+      dt.hour() as i64,
+      dt.minute() as i64,
+      dt.second() as i64,
+    ];
+    if parts < self.start {
+      Ordering::Less
+    } else if parts > self.end {
+      Ordering::Greater
+    } else {
+      Ordering::Equal
+    }
+  }
+}
+
 /// 匹配时间点的部分内容
 trait TimepointPartParser {
   fn parse(&self, s: &str, con: &mut TimeCond) -> bool;
@@ -143,24 +184,89 @@ lazy_static! {
 
 /// 时间信息匹配器。分析给定的字符串，将其解析为时间判断条件。
 pub struct TimeMatcher {
-  now: DateTime<Local>,
+  now: DateTime<FixedOffset>,
+  tz: DisplayTimezone,
   conditions: Vec<TimeCond>,
 }
 
 impl TimeMatcher {
   /// 使用当前时间点，创建本时间条件解析与匹配器，每次处理循环里都得新建
   pub fn new() -> Self {
+    Self::with_timezone(DisplayTimezone::default())
+  }
+
+  /// 同 `new`，但条件中的时间按给定的展示时区理解，与界面上展示的时间戳保持一致
+  pub fn with_timezone(tz: DisplayTimezone) -> Self {
     Self {
-      now: Local::now(),
+      now: tz.now(),
+      tz,
       conditions: Vec::new(),
     }
   }
 
+  /// 将单个时间点（如 `11:30`、`2025.11.09 11:30:15`）解析为展示时区下的确切时刻，
+  /// 缺少日期时取今天，缺少时间时取零点
+  pub fn parse_timepoint(&self, term: &str) -> Result<DateTime<FixedOffset>, String> {
+    let con = self.parse_term_as_timepoint(term.trim(), TimeCmpOp::Equal)?;
+    NaiveDate::from_ymd_opt(con.year, con.month, con.day)
+      .and_then(|date| {
+        date.and_hms_opt(
+          con.hour.unwrap_or(0),
+          con.minute.unwrap_or(0),
+          con.second.unwrap_or(0),
+        )
+      })
+      .and_then(|dt| dt.and_local_timezone(*self.now.offset()).single())
+      .ok_or(format!("Wrong format: timepoint '{term}' does not exist !"))
+  }
+
   /// 检查给定的时间点是否匹配已有的规则
   pub fn is_matched(&self, dt: DateTime<FixedOffset>) -> bool {
+    let dt = self.tz.convert(dt);
     self.conditions.iter().all(|con| con.is_matched(dt))
   }
 
+  /// 若所有条件共同匹配的是一段连续的时间，返回它的边界。各条件是按时间的各个部分分别比较的，
+  /// 匹配的时间可能周期性地重复（如 `> 10:30` 每天都匹配一段），此时返回 None
+  pub fn bounds(&self) -> Option<TimeBounds> {
+    // 收窄各个部分的取值范围，条件中没有给出的部分不受限制
+    let (mut start, mut end) = (TIME_PARTS_MIN, TIME_PARTS_MAX);
+    for con in &self.conditions {
+      let parts = [
+        Some(con.year as i64),
+        Some(con.month as i64),
+        Some(con.day as i64),
+        con.hour.map(i64::from),
+        con.minute.map(i64::from),
+        con.second.map(i64::from),
+      ];
+      for (i, part) in parts.into_iter().enumerate() {
+        let Some(part) = part else { continue };
+        match con.op {
+          TimeCmpOp::Equal => {
+            start[i] = start[i].max(part);
+            end[i] = end[i].min(part);
+          }
+          TimeCmpOp::Earlier => end[i] = end[i].min(part),
+          TimeCmpOp::Later => start[i] = start[i].max(part),
+        }
+      }
+    }
+
+    // 第一个取值不唯一的部分之后，其余部分都不受限制时，匹配的时间才是连续的
+    if (0..6).any(|i| start[i] > end[i]) {
+      return None;
+    }
+    let free = (0..6).find(|&i| start[i] < end[i]).map_or(6, |i| i + 1);
+    (free..6)
+      .all(|i| start[i] == TIME_PARTS_MIN[i] && end[i] == TIME_PARTS_MAX[i])
+      .then_some(TimeBounds {
+        tz: self.tz,
+        start,
+        end,
+      })
+  }
+
   /// 解析给定字符串，转换为时间判断条件。如果解析出错，返回错误信息，可供渲染。
   ///
   /// 格式支持：
@@ -472,6 +578,26 @@ mod tests {
     assert!(tm.is_matched(now.fixed_offset()));
   }
 
+  #[test]
+  fn test_parse_timepoint() {
+    let tm = TimeMatcher::with_timezone("+08:00".parse().unwrap());
+
+    let dt = tm
+      .parse_timepoint(" 2025.09.10 11:22:33 ")
+      .expect("should parse");
+    assert_eq!(dt.to_rfc3339(), "2025-09-10T11:22:33+08:00");
+
+    // 缺少日期时取今天，缺少时间时取零点
+    let dt = tm.parse_timepoint("11:22").expect("should parse");
+    assert_eq!(dt.date_naive(), tm.now.date_naive());
+    assert_eq!(dt.time().to_string(), "11:22:00");
+    let dt = tm.parse_timepoint("2025-09-10").expect("should parse");
+    assert_eq!(dt.to_rfc3339(), "2025-09-10T00:00:00+08:00");
+
+    assert!(tm.parse_timepoint("2025.02.30").is_err());
+    assert!(tm.parse_timepoint("1d").is_err());
+  }
+
   #[test]
   fn test_match_time_range() {
     let mut tm = TimeMatcher::new();
@@ -492,4 +618,58 @@ mod tests {
     assert!(tm.is_matched(now.fixed_offset() + Duration::days(3)));
     assert!(!tm.is_matched(now.fixed_offset() + Duration::days(4)));
   }
+
+  #[test]
+  fn test_bounds() {
+    let bounds = |cmd: &str| {
+      let mut tm = TimeMatcher::with_timezone("+08:00".parse().unwrap());
+      tm.parse(cmd).unwrap();
+      tm.bounds()
+    };
+    let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap();
+
+    // 同一天内的时间范围是连续的，边界精确到秒
+    let b = bounds("2026.03.01 09:00:01 ~ 2026.03.01 09:00:03").expect("contiguous");
+    assert_eq!(b.compare(at("2026-03-01T09:00:00.9+08:00")), Ordering::Less);
+    assert_eq!(
+      b.compare(at("2026-03-01T09:00:03.9+08:00")),
+      Ordering::Equal
+    );
+    assert_eq!(
+      b.compare(at("2026-03-01T09:00:04+08:00")),
+      Ordering::Greater
+    );
+    assert_eq!(b.compare(at("2026-03-01T01:00:02Z")), Ordering::Equal);
+
+    // 单个时间点按其精度匹配一段连续的时间
+    let b = bounds("2026.03.01 10:30").expect("contiguous");
+    assert_eq!(b.compare(at("2026-03-01T10:30:59+08:00")), Ordering::Equal);
+    assert_eq!(
+      b.compare(at("2026-03-01T10:31:00+08:00")),
+      Ordering::Greater
+    );
+    assert!(bounds("> 2026.01.01").is_some());
+
+    // 每天、每月都匹配一段的条件，以及不可能满足的条件，都不是连续的
+    assert!(bounds("> 2026.03.01 10:30").is_none());
+    assert!(bounds("2026.03.01 ~ 2026.04.10").is_none());
+    assert!(bounds("2026.03.01, 2026.03.02").is_none());
+  }
+
+  #[test]
+  fn test_match_in_display_timezone() {
+    let dt = DateTime::parse_from_rfc3339("2026-01-17T23:22:55+08:00").unwrap();
+    let matched = |tz: &str, cmd: &str| {
+      let mut tm = TimeMatcher::with_timezone(tz.parse().unwrap());
+      tm.parse(cmd).unwrap();
+      tm.is_matched(dt)
+    };
+
+    // 条件中的时间按展示时区理解
+    assert!(matched("+08:00", "2026.01.17 23:22"));
+    assert!(!matched("+08:00", "2026.01.17 15:22"));
+    assert!(matched("utc", "2026.01.17 15:22"));
+    assert!(!matched("utc", "2026.01.17 23:22"));
+    assert!(matched("-05:00", "2026.01.17 10:22:55"));
+  }
 }
@@ -1,28 +1,41 @@
 use crate::ui::Event;
 use crate::{
   app::{
-    Controller, LogHub, StateBuilder,
-    controller::{AppController, DebugController, HelpController, LogController, TagController},
-    page::{DebugPage, HelpPage, LogPage, TagPage, log_page},
+    ColorScheme, Controller, DisplayTimezone, HighlightRule, KeyAction, KeyMap, LogHub, Session,
+    StateBuilder,
+    controller::{
+      AppController, DebugController, DetailController, HelpController, LogController,
+      MarkController, TagController, TagKind, TimeLinkController,
+      log_controller::{TimestampFormat, TimestampStyle},
+    },
+    page::{DebugPage, DetailPage, HelpPage, LogPage, MarkPage, TagPage, log_page},
+    rich,
     state::{
-      DebugOperationState, HelpState, LogContentSearchedState, LogContentSearchingState,
-      LogNavigationState, LogTimestampSearchedState, LogTimestampSearchingState, QuitState,
-      TagOperationState,
+      ClearMarksState, CommandState, DebugOperationState, DebugSearchingState, DetailState,
+      HelpState, LogContentSearchedState, LogContentSearchingState, LogNavigationState,
+      LogPercentJumpingState, LogSplitState, LogTimestampSearchedState, LogTimestampSearchingState,
+      MarkOperationState, QuitState, TagOperationState,
     },
   },
   debug,
-  log::Config as LogConfig,
+  log::{Config as LogConfig, DataBoard},
   ui::{
-    KeyEventEx, Pager, State, StateMachine, pager::Theme as PagerTheme,
+    ColorCapability, KeyEventEx, Pager, ScrollStyle, State, StateMachine, ViewPortEx,
     state_machine::Config as SmConfig,
   },
 };
+use chrono::{DateTime, FixedOffset, Local};
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::{
+  event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent},
+  execute,
+};
 use ratatui::DefaultTerminal;
 use std::{
   collections::HashMap,
-  path::PathBuf,
+  io::stdout,
+  path::{Path, PathBuf},
+  time::Duration,
   {cell::RefCell, rc::Rc},
 };
 
@@ -34,8 +47,20 @@ pub struct Config {
   /// 各个系统日志及其读取配置
   pub logs_configs: HashMap<String, LogConfig>,
 
-  /// 页面整体的风格
-  pub pager_theme: PagerTheme,
+  /// 以完整路径给出的日志文件及其读取配置，它们不必位于日志目录下，也不必以 `.log` 结尾
+  pub logs_files: Vec<(PathBuf, LogConfig)>,
+
+  /// 日志目录为 `-` 时读取标准输入的配置，以及监控中新发现的日志文件的读取配置
+  pub log_config: LogConfig,
+
+  /// 若设置，则监控日志目录下新出现的、符合该 glob 模式的日志文件
+  pub logs_watch_pattern: Option<String>,
+
+  /// 配色方案
+  pub color_scheme: ColorScheme,
+
+  /// 终端的颜色能力，默认在设置了 `NO_COLOR` 环境变量时不使用颜色
+  pub color_capability: ColorCapability,
 
   /// 状态机的配置
   pub sm_config: SmConfig,
@@ -45,6 +70,51 @@ pub struct Config {
 
   /// 日志页面的渲染配置
   pub log_page_config: log_page::Config,
+
+  /// 按键映射
+  pub key_map: KeyMap,
+
+  /// 用户自定义的高亮规则
+  pub highlight_rules: Vec<HighlightRule>,
+
+  /// 是否在状态栏右侧展示时钟与实时统计数据
+  pub show_stats: bool,
+
+  /// 是否捕获鼠标事件，开启后可以用鼠标滚动与点击日志，但终端自身的文本选择将不可用
+  pub mouse_capture: bool,
+
+  /// 展示时间戳使用的时区，时间戳搜索也按该时区理解
+  pub display_tz: DisplayTimezone,
+
+  /// 自定义的时间戳格式，配置后作为初始的时间戳风格，并加入时间戳风格的切换
+  pub timestamp_format: Option<TimestampFormat>,
+
+  /// 是否倒序展示日志，也即新日志在上、旧日志在下
+  pub reverse_order: bool,
+
+  /// 启动时是否跟踪最新的日志。只读取末尾若干行的事后分析场景下，没有新日志可以跟踪
+  pub follow: bool,
+
+  /// 跟踪最新日志时，两次刷新展示区之间的最短间隔，期间到达的新日志合并后一并展示。为零时每帧都刷新
+  pub follow_batch_interval: Duration,
+
+  /// 内容搜索跳转后，是否横向滚动使匹配的内容可见，否则保持原有的横向滚动位置
+  pub scroll_to_content_match: bool,
+
+  /// 日志展示区整页翻动时保留的上下文行数，为空时光标在翻页后置顶或置底
+  pub page_overlap: Option<usize>,
+
+  /// 日志展示区横向快速跳转（ctrl + 左右方向键）时，一次移动的列数
+  pub horizontal_scroll_jump: usize,
+
+  /// 日志展示区中光标的滚动风格
+  pub scroll_style: ScrollStyle,
+
+  /// 退出程序前是否需要按 y/n 确认，否则按下退出键（或者没有子页面可关闭时按 esc）立即退出
+  pub confirm_quit: bool,
+
+  /// 是否在退出时将浏览会话保存到状态目录下，并在启动时恢复
+  pub persist_session: bool,
 }
 
 impl Default for Config {
@@ -52,10 +122,29 @@ impl Default for Config {
     Self {
       logs_root: Default::default(),
       logs_configs: Default::default(),
-      pager_theme: Default::default(),
+      logs_files: Default::default(),
+      log_config: LogConfig::default(),
+      logs_watch_pattern: None,
+      color_scheme: Default::default(),
+      color_capability: ColorCapability::detect(),
       sm_config: Default::default(),
       debug_buffer_size: 200,
       log_page_config: Default::default(),
+      key_map: Default::default(),
+      highlight_rules: vec![],
+      mouse_capture: false,
+      show_stats: false,
+      display_tz: DisplayTimezone::default(),
+      timestamp_format: None,
+      reverse_order: false,
+      follow: true,
+      follow_batch_interval: Duration::ZERO,
+      scroll_to_content_match: true,
+      page_overlap: None,
+      horizontal_scroll_jump: 20,
+      scroll_style: ScrollStyle::default(),
+      confirm_quit: true,
+      persist_session: false,
     }
   }
 }
@@ -73,24 +162,60 @@ pub struct Viewer {
 
   /// 所有的控制器
   controllers: Vec<Rc<RefCell<dyn Controller>>>,
+
+  /// 分屏日志区的控制器，只在分屏页面展示时运行
+  split_controller: Rc<RefCell<LogController>>,
+
+  /// 应用控制器，提供状态栏中展示的统计数据
+  app_controller: Rc<RefCell<AppController>>,
+
+  /// 是否在状态栏中展示统计数据
+  show_stats: bool,
+
+  /// 日志展示区的控制器，退出时从中收集浏览会话
+  log_controller: Rc<RefCell<LogController>>,
+
+  /// 浏览会话文件的路径，为空时不保存、也不恢复会话
+  session_file: Option<PathBuf>,
+
+  /// 上次会话中光标所在日志的时间戳，以及上一帧的日志行版本，等到日志加载稳定后再恢复
+  session_cursor: Option<(DateTime<FixedOffset>, Option<usize>)>,
+
+  /// 读写浏览会话时的错误，等到终端恢复后再输出到标准错误
+  session_errors: Vec<String>,
 }
 
 const TAG_PAGE: usize = 1;
 const DEBUG_PAGE: usize = 2;
 const HELP_PAGE: usize = 3;
+const HOST_PAGE: usize = 4;
+const MARK_PAGE: usize = 5;
+const SPLIT_PAGE: usize = 6;
+const DETAIL_PAGE: usize = 7;
 
 /// 辅助构建状态机的类
 struct StateMachineBuilder {
   sm_config: SmConfig,
+  key_map: KeyMap,
+  confirm_quit: bool,
+  app_controller: Rc<RefCell<AppController>>,
   quit_state: State,
+  clear_marks_state: ClearMarksState,
+  command_state: CommandState,
   log_nav_state: State,
   tag_nav_state: State,
+  host_nav_state: State,
   debug_nav_state: State,
+  debug_searching_state: State,
+  mark_nav_state: State,
+  split_nav_state: State,
   log_content_searching_state: State,
   log_content_searched_state: State,
   log_timestamp_searching_state: State,
   log_timestamp_searched_state: State,
+  log_percent_jumping_state: State,
   help_state: State,
+  detail_state: State,
 }
 
 impl StateMachineBuilder {
@@ -104,6 +229,22 @@ impl StateMachineBuilder {
     const LOG_TIMESTAMP_SEARCHING_STATE: usize = 6;
     const LOG_TIMESTAMP_SEARCHED_STATE: usize = 7;
     const HELP_STATE: usize = 8;
+    const HOST_NAV_STATE: usize = 9;
+    const MARK_NAV_STATE: usize = 10;
+    const CLEAR_MARKS_STATE: usize = 11;
+    const SPLIT_NAV_STATE: usize = 12;
+    const LOG_PERCENT_JUMPING_STATE: usize = 13;
+    const DETAIL_STATE: usize = 14;
+    const COMMAND_STATE: usize = 15;
+    const DEBUG_SEARCHING_STATE: usize = 16;
+
+    let key_map = self.key_map.clone();
+    let key = |action| key_map.get(action);
+    let detail_tips = format!(
+      "press 'j' 'k' to scroll, '{}' or 'esc' or '{}' to close",
+      key_map.name_of(KeyAction::ShowDetail),
+      key_map.name_of(KeyAction::Quit)
+    );
 
     StateMachine::new(self.sm_config)
       // -------------------------------------------------
@@ -117,42 +258,77 @@ impl StateMachineBuilder {
             pager.status().set_tips("press 'h' for help");
           })
           // 按 t 或 ctrl+t 聚焦与开关标签过滤页面
+          .goto_action(key(KeyAction::OpenTags), TAG_NAV_STATE, |pager| {
+            pager.open_left(TAG_PAGE);
+            true
+          })
+          .action(key(KeyAction::ToggleTags), |pager| {
+            pager.toggle_left(TAG_PAGE)
+          })
+          // 按 alt+t 全屏聚焦标签过滤页面
           .goto_action(
-            KeyEvent::simple(KeyCode::Char('t')),
+            key(KeyAction::ToggleTagsFullscreen),
             TAG_NAV_STATE,
             |pager| {
-              pager.open_left(TAG_PAGE);
+              pager.open_full(TAG_PAGE);
               true
             },
           )
-          .action(KeyEvent::ctrl('t'), |pager| pager.toggle_left(TAG_PAGE))
+          // 按 o 或 ctrl+o 聚焦与开关主机名过滤页面
+          .goto_action(key(KeyAction::OpenHosts), HOST_NAV_STATE, |pager| {
+            pager.open_left(HOST_PAGE);
+            true
+          })
+          .action(key(KeyAction::ToggleHosts), |pager| {
+            pager.toggle_left(HOST_PAGE)
+          })
           // 按 d 或 ctrl+d 聚焦与开关标签过滤页面
-          .goto_action(
-            KeyEvent::simple(KeyCode::Char('d')),
-            DEBUG_NAV_STATE,
-            |pager| {
-              pager.open_right(DEBUG_PAGE);
-              true
-            },
-          )
-          .action(KeyEvent::ctrl('d'), |pager| pager.toggle_right(DEBUG_PAGE))
+          .goto_action(key(KeyAction::OpenDebug), DEBUG_NAV_STATE, |pager| {
+            pager.open_right(DEBUG_PAGE);
+            true
+          })
+          .action(key(KeyAction::ToggleDebug), |pager| {
+            pager.toggle_right(DEBUG_PAGE)
+          })
+          // 按 b 或 ctrl+k 聚焦与开关标记列表页面
+          .goto_action(key(KeyAction::OpenMarks), MARK_NAV_STATE, |pager| {
+            pager.open_right(MARK_PAGE);
+            true
+          })
+          .action(key(KeyAction::ToggleMarks), |pager| {
+            pager.toggle_right(MARK_PAGE)
+          })
+          // 按 s 或 ctrl+s 聚焦与开关分屏展示区
+          .goto_action(key(KeyAction::OpenSplit), SPLIT_NAV_STATE, |pager| {
+            pager.open_right(SPLIT_PAGE);
+            true
+          })
+          .action(key(KeyAction::ToggleSplit), |pager| {
+            pager.toggle_right(SPLIT_PAGE)
+          })
           // 按 / 进入内容搜索状态
-          .goto(
-            KeyEvent::simple(KeyCode::Char('/')),
-            LOG_CONTENT_SEARCHING_STATE,
-          )
+          .goto(key(KeyAction::SearchContent), LOG_CONTENT_SEARCHING_STATE)
           // 按 ? 进入时间戳搜索状态
           .goto(
-            KeyEvent::simple(KeyCode::Char('?')),
+            key(KeyAction::SearchTimestamp),
             LOG_TIMESTAMP_SEARCHING_STATE,
           )
-          // 按 esc 关闭子页面，或者进入关闭程序的询问
-          .goto_action(KeyEvent::simple(KeyCode::Esc), QUIT_STATE, |pager| {
-            !pager.close_top()
-          })
-          .goto(KeyEvent::simple(KeyCode::Char('q')), QUIT_STATE)
+          // 按 % 进入按百分比跳转的输入状态
+          .goto(key(KeyAction::JumpToPercent), LOG_PERCENT_JUMPING_STATE)
+          // 按 : 打开命令面板
+          .goto(key(KeyAction::CommandPalette), COMMAND_STATE)
+          // 按 alt+m 进入清除所有标记的询问
+          .goto(key(KeyAction::ClearMarks), CLEAR_MARKS_STATE)
           // 按 h 打开帮助页面
-          .goto(KeyEvent::simple(KeyCode::Char('h')), HELP_STATE),
+          .goto(key(KeyAction::Help), HELP_STATE)
+          // 按 i 全屏查看光标所在日志的详情
+          .goto(key(KeyAction::ShowDetail), DETAIL_STATE)
+          // 按 esc 关闭子页面，或者退出程序
+          .quit_actions(
+            key(KeyAction::Quit),
+            QUIT_STATE,
+            (!self.confirm_quit).then_some(self.app_controller),
+          ),
       )
       // -------------------------------------------------
       // 询问是否要关闭的状态
@@ -164,12 +340,43 @@ impl StateMachineBuilder {
           .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE),
       )
       // -------------------------------------------------
+      // 询问是否要清除所有标记的状态
+      .state(
+        CLEAR_MARKS_STATE,
+        self
+          .clear_marks_state
+          .build(LOG_NAV_STATE)
+          .goto(KeyEvent::simple(KeyCode::Char('n')), LOG_NAV_STATE)
+          .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE),
+      )
+      // -------------------------------------------------
       // 标签导航状态
       .state(
         TAG_NAV_STATE,
         self
           .tag_nav_state
           .enter_action(|pager| pager.focus(TAG_PAGE))
+          // 按 alt+t 在全屏与左半边之间切换，离开时不再全屏，以免遮挡日志
+          .action(key(KeyAction::ToggleTagsFullscreen), |pager| {
+            match pager.is_full(TAG_PAGE) {
+              true => pager.open_left(TAG_PAGE),
+              false => pager.open_full(TAG_PAGE),
+            }
+          })
+          .leave_action(|pager| {
+            if pager.is_full(TAG_PAGE) {
+              pager.open_left(TAG_PAGE);
+            }
+          })
+          .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE),
+      )
+      // -------------------------------------------------
+      // 主机名导航状态
+      .state(
+        HOST_NAV_STATE,
+        self
+          .host_nav_state
+          .enter_action(|pager| pager.focus(HOST_PAGE))
           .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE),
       )
       // -------------------------------------------------
@@ -182,11 +389,47 @@ impl StateMachineBuilder {
             pager.focus(DEBUG_PAGE);
             pager
               .status()
-              .set_tips("press 'd' or 'esc' or 'q' to unfocus");
+              .set_tips("press '/' to search, 'esc' to unfocus");
           })
+          // 按 / 进入调试信息的搜索状态
+          .goto(key(KeyAction::SearchContent), DEBUG_SEARCHING_STATE)
+          .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE),
+      )
+      // -------------------------------------------------
+      // 调试信息搜索的输入状态，输入的同时即过滤，确认或取消后回到调试界面导航
+      .state(
+        DEBUG_SEARCHING_STATE,
+        self
+          .debug_searching_state
+          .goto(KeyEvent::simple(KeyCode::Esc), DEBUG_NAV_STATE)
+          .goto(KeyEvent::simple(KeyCode::Enter), DEBUG_NAV_STATE),
+      )
+      // -------------------------------------------------
+      // 标记列表状态，回车跳转到选中的标记日志
+      .state(
+        MARK_NAV_STATE,
+        self
+          .mark_nav_state
+          .enter_action(|pager| {
+            pager.focus(MARK_PAGE);
+            pager
+              .status()
+              .set_tips("press 'enter' to jump to the mark, 'b' or 'esc' to unfocus");
+          })
+          .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE)
+          .goto(key(KeyAction::OpenMarks), LOG_NAV_STATE)
+          .goto(key(KeyAction::Quit), LOG_NAV_STATE),
+      )
+      // -------------------------------------------------
+      // 分屏展示区导航状态
+      .state(
+        SPLIT_NAV_STATE,
+        self
+          .split_nav_state
+          .enter_action(|pager| pager.focus(SPLIT_PAGE))
           .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE)
-          .goto(KeyEvent::simple(KeyCode::Char('d')), LOG_NAV_STATE)
-          .goto(KeyEvent::simple(KeyCode::Char('q')), LOG_NAV_STATE),
+          .goto(key(KeyAction::OpenSplit), LOG_NAV_STATE)
+          .goto(key(KeyAction::Quit), LOG_NAV_STATE),
       )
       // -------------------------------------------------
       // 日志内容搜索输入状态
@@ -241,19 +484,92 @@ impl StateMachineBuilder {
           .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE),
       )
       // -------------------------------------------------
+      // 按百分比跳转的输入状态，输入的同时即跳转，确认或取消后回到日志导航
+      .state(
+        LOG_PERCENT_JUMPING_STATE,
+        self
+          .log_percent_jumping_state
+          .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE)
+          .goto(KeyEvent::simple(KeyCode::Enter), LOG_NAV_STATE),
+      )
+      // -------------------------------------------------
+      // 命令面板的输入状态，命令执行后回到日志导航
+      .state(
+        COMMAND_STATE,
+        self
+          .command_state
+          .build(LOG_NAV_STATE)
+          .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE),
+      )
+      // -------------------------------------------------
       // 渲染帮助页面
       .state(
         HELP_STATE,
         self
           .help_state
           .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE)
-          .goto(KeyEvent::simple(KeyCode::Char('h')), LOG_NAV_STATE)
-          .goto(KeyEvent::simple(KeyCode::Char('q')), LOG_NAV_STATE)
+          .goto(key(KeyAction::Help), LOG_NAV_STATE)
+          .goto(key(KeyAction::Quit), LOG_NAV_STATE)
           .enter_action(|pager| pager.open_full(HELP_PAGE))
           .leave_action(|pager| {
             pager.close(HELP_PAGE);
           }),
       )
+      // -------------------------------------------------
+      // 渲染光标所在日志的详情页面
+      .state(
+        DETAIL_STATE,
+        self
+          .detail_state
+          .goto(KeyEvent::simple(KeyCode::Esc), LOG_NAV_STATE)
+          .goto(key(KeyAction::ShowDetail), LOG_NAV_STATE)
+          .goto(key(KeyAction::Quit), LOG_NAV_STATE)
+          .enter_action(move |pager| {
+            pager.open_full(DETAIL_PAGE);
+            pager.status().set_tips(detail_tips.clone());
+          })
+          .leave_action(|pager| {
+            pager.close(DETAIL_PAGE);
+          }),
+      )
+  }
+}
+
+/// 扩展根状态退出程序的能力
+trait QuitStateEx {
+  /// 按退出键、或者没有子页面可关闭时按 esc 退出程序。
+  /// 给出程序控制器时立即退出，否则进入询问是否退出的状态
+  fn quit_actions(
+    self,
+    quit_key: KeyEvent,
+    quit_state: usize,
+    app_controller: Option<Rc<RefCell<AppController>>>,
+  ) -> Self;
+}
+
+impl QuitStateEx for State {
+  fn quit_actions(
+    self,
+    quit_key: KeyEvent,
+    quit_state: usize,
+    app_controller: Option<Rc<RefCell<AppController>>>,
+  ) -> Self {
+    let Some(c1) = app_controller else {
+      return self
+        .goto_action(KeyEvent::simple(KeyCode::Esc), quit_state, |pager| {
+          !pager.close_top()
+        })
+        .goto(quit_key, quit_state);
+    };
+
+    let c2 = c1.clone();
+    self
+      .action(KeyEvent::simple(KeyCode::Esc), move |pager| {
+        if !pager.close_top() {
+          c1.borrow_mut().quit();
+        }
+      })
+      .action(quit_key, move |_| c2.borrow_mut().quit())
   }
 }
 
@@ -262,81 +578,254 @@ impl Viewer {
   pub fn run(config: Config) -> Result<()> {
     color_eyre::install()?;
     debug::enable_debug(config.debug_buffer_size);
-    ratatui::run(|terminal| {
+    let mut session_errors = Vec::new();
+    let res = ratatui::run(|terminal| {
       // 创建 runtime
       let rt = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
         .expect("Failed to create runtime");
 
+      // 按需开启鼠标捕获。panic 时 ratatui 只会恢复终端的基本模式，因此在它的 panic hook 之前关闭鼠标捕获
+      let mouse_capture = config.mouse_capture;
+      if mouse_capture {
+        execute!(stdout(), EnableMouseCapture)?;
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+          let _ = execute!(stdout(), DisableMouseCapture);
+          hook(info);
+        }));
+      }
+
       // 在 runtime 中运行异步代码
-      rt.block_on(async {
-        // 构建 viewer
+      let res = rt.block_on(async {
+        // 构建 viewer，并恢复上次的浏览会话（如有）
         let mut viewer = Viewer::build(config);
+        viewer.restore_session().await;
 
         // 运行核心循环流程
         let res = viewer.main_loop(terminal).await;
 
-        // 回收资源
+        // 保存本次的浏览会话，并回收资源
+        viewer.save_session().await;
         viewer.log_hub.close().await;
+        session_errors = std::mem::take(&mut viewer.session_errors);
 
         // 返回核心流程的运行结果
         res
-      })
-    })
+      });
+
+      if mouse_capture {
+        execute!(stdout(), DisableMouseCapture)?;
+      }
+
+      res
+    });
+
+    // 终端恢复后，界面中的输出不再可见，读写会话时的错误输出到标准错误
+    for e in session_errors {
+      std::eprintln!("{e}");
+    }
+
+    res
   }
 
   /// 构造可视化器
   fn build(config: Config) -> Self {
     // ------------------------------------------
-    // 创建日志数据，此时文件已经在异步流程中读取了
-    let log_hub = LogHub::open(config.logs_root, config.logs_configs);
+    // 创建日志数据，此时文件已经在异步流程中读取了。
+    // 浏览会话按实际浏览的日志目录、或者日志文件区分
+    let mut session_errors = Vec::new();
+    let mut session_path = |root: &Path, files: &[PathBuf]| {
+      let path = Session::path_for(root, files);
+      if path.is_none() {
+        session_errors
+          .push("cannot save the session: neither XDG_STATE_HOME nor HOME is set".into());
+      }
+      path
+    };
+    let (mut log_hub, session_file) = if config.logs_files.is_empty() {
+      let session_file = config
+        .persist_session
+        .then(|| session_path(&config.logs_root, &[]))
+        .flatten();
+      (
+        LogHub::open(config.logs_root, config.logs_configs, config.log_config),
+        session_file,
+      )
+    } else {
+      // 给出了完整路径的日志文件时，日志目录下的具名日志也转换为完整路径，一同打开
+      let mut files: Vec<_> = config
+        .logs_configs
+        .into_iter()
+        .map(|(name, log_config)| (config.logs_root.join(name + ".log"), log_config))
+        .collect();
+      files.extend(config.logs_files);
+      let paths: Vec<_> = files.iter().map(|(path, _)| path.clone()).collect();
+      let session_file = config
+        .persist_session
+        .then(|| session_path(Path::new(""), &paths))
+        .flatten();
+      (LogHub::open_files(files, config.log_config), session_file)
+    };
+    if let Some(pattern) = &config.logs_watch_pattern
+      && let Err(e) = log_hub.watch_new_logs(pattern)
+    {
+      crate::eprintln!("failed to watch new logs: {}", e);
+    }
+
+    // ------------------------------------------
+    // 加入用户自定义的高亮规则
+    if !config.highlight_rules.is_empty() {
+      rich::set_highlight_rules(&config.highlight_rules);
+    }
 
     // ------------------------------------------
     // 创造各个控制器
     let app_controller = Rc::new(RefCell::new(AppController::default()));
     let log_controller = Rc::new(RefCell::new(LogController::default()));
+    log_controller.borrow_mut().style_mut().display_tz = config.display_tz;
+    log_controller
+      .borrow_mut()
+      .set_reverse_order(config.reverse_order);
+    log_controller
+      .borrow_mut()
+      .set_follow_batch_interval(config.follow_batch_interval);
+    if !config.follow {
+      log_controller
+        .borrow_mut()
+        .view_mut()
+        .ui_mut()
+        .do_not_follow();
+    }
+    if let Some(format) = config.timestamp_format {
+      let mut log_controller = log_controller.borrow_mut();
+      let style = log_controller.style_mut();
+      style.timestamp_style = TimestampStyle::Custom(format.clone());
+      style.custom_timestamp = Some(format);
+    }
+    let split_controller = Rc::new(RefCell::new(LogController::default()));
+    for controller in [&log_controller, &split_controller] {
+      let mut controller = controller.borrow_mut();
+      controller
+        .view_mut()
+        .ui_mut()
+        .set_page_overlap(config.page_overlap)
+        .set_horizontal_scroll_jump(config.horizontal_scroll_jump)
+        .set_scroll_style(config.scroll_style);
+      controller.set_scroll_to_content_match(config.scroll_to_content_match);
+    }
+    let time_link_controller = Rc::new(RefCell::new(TimeLinkController::new(
+      log_controller.clone(),
+      split_controller.clone(),
+    )));
     let tag_controller = Rc::new(RefCell::new(TagController::default()));
+    let host_controller = Rc::new(RefCell::new(TagController::new(TagKind::Host)));
     let debug_controller = Rc::new(RefCell::new(DebugController::default()));
-    let help_controller = Rc::new(RefCell::new(HelpController::default()));
+    let mark_controller = Rc::new(RefCell::new(MarkController::default()));
+    let help_controller = Rc::new(RefCell::new(HelpController::new(&config.key_map)));
+    let detail_controller = Rc::new(RefCell::new(DetailController::new(log_controller.clone())));
 
     // ------------------------------------------
     // 记录所有控制器
     let controllers: Vec<Rc<RefCell<dyn Controller>>> = vec![
       app_controller.clone(),
       log_controller.clone(),
+      time_link_controller.clone(),
       tag_controller.clone(),
+      host_controller.clone(),
       debug_controller.clone(),
+      mark_controller.clone(),
       help_controller.clone(),
+      detail_controller.clone(),
     ];
 
     // ------------------------------------------
     // 构建状态机与状态
     let sm = StateMachineBuilder {
       sm_config: config.sm_config,
+      key_map: config.key_map.clone(),
+      confirm_quit: config.confirm_quit,
+      app_controller: app_controller.clone(),
       quit_state: QuitState::new(app_controller.clone()).build(),
-      log_nav_state: LogNavigationState::new(log_controller.clone()).build(),
-      tag_nav_state: TagOperationState::new(tag_controller.clone()).build(),
-      debug_nav_state: DebugOperationState::new(debug_controller.clone()).build(),
-      log_content_searching_state: LogContentSearchingState::new(log_controller.clone()).build(),
-      log_content_searched_state: LogContentSearchedState::new(log_controller.clone()).build(),
+      clear_marks_state: ClearMarksState::new(log_controller.clone()),
+      command_state: CommandState::new(log_controller.clone()),
+      log_nav_state: LogNavigationState::new(log_controller.clone(), &config.key_map)
+        .build()
+        .action(
+          config.key_map.get(KeyAction::ToggleTimeLink),
+          move |pager| {
+            let mut link = time_link_controller.borrow_mut();
+            link.toggle();
+            pager.status().set_tips(match link.is_enabled() {
+              true => "split view follows the cursor by timestamp",
+              false => "split view scrolls independently",
+            });
+          },
+        ),
+      tag_nav_state: TagOperationState::new(tag_controller.clone(), &config.key_map).build(),
+      host_nav_state: TagOperationState::new(host_controller.clone(), &config.key_map).build(),
+      debug_nav_state: DebugOperationState::new(debug_controller.clone(), &config.key_map).build(),
+      debug_searching_state: DebugSearchingState::new(debug_controller.clone()).build(),
+      mark_nav_state: MarkOperationState::new(mark_controller.clone(), log_controller.clone())
+        .build(),
+      split_nav_state: LogSplitState::new(
+        split_controller.clone(),
+        log_controller.clone(),
+        &config.key_map,
+      )
+      .build(),
+      log_content_searching_state: LogContentSearchingState::new(
+        log_controller.clone(),
+        &config.key_map,
+      )
+      .build(),
+      log_content_searched_state: LogContentSearchedState::new(
+        log_controller.clone(),
+        &config.key_map,
+      )
+      .build(),
       log_timestamp_searching_state: LogTimestampSearchingState::new(log_controller.clone())
         .build(),
-      log_timestamp_searched_state: LogTimestampSearchedState::new(log_controller.clone()).build(),
+      log_timestamp_searched_state: LogTimestampSearchedState::new(
+        log_controller.clone(),
+        &config.key_map,
+      )
+      .build(),
+      log_percent_jumping_state: LogPercentJumpingState::new(log_controller.clone()).build(),
       help_state: HelpState::new(help_controller.clone()).build(),
+      detail_state: DetailState::new(detail_controller.clone()).build(),
     }
     .build();
 
     // ------------------------------------------
     // 构建页面
-    let pager = Pager::new(config.pager_theme)
+    let pager = Pager::new(config.color_scheme.pager_theme)
+      .with_color_capability(config.color_capability)
       .add_page_as_root(LogPage {
-        log_controller,
-        config: config.log_page_config,
+        log_controller: log_controller.clone(),
+        config: config.log_page_config.clone(),
+        colors: config.color_scheme.log_colors,
       })
+      .add_page(
+        SPLIT_PAGE,
+        LogPage {
+          log_controller: split_controller.clone(),
+          config: config.log_page_config,
+          colors: config.color_scheme.log_colors,
+        },
+      )
       .add_page(TAG_PAGE, TagPage { tag_controller })
+      .add_page(
+        HOST_PAGE,
+        TagPage {
+          tag_controller: host_controller,
+        },
+      )
       .add_page(DEBUG_PAGE, DebugPage { debug_controller })
-      .add_page(HELP_PAGE, HelpPage { help_controller });
+      .add_page(MARK_PAGE, MarkPage { mark_controller })
+      .add_page(HELP_PAGE, HelpPage { help_controller })
+      .add_page(DETAIL_PAGE, DetailPage { detail_controller });
 
     // ------------------------------------------
     // 构造并返回本类对象
@@ -345,6 +834,79 @@ impl Viewer {
       pager,
       sm,
       controllers,
+      split_controller,
+      app_controller,
+      show_stats: config.show_stats,
+      log_controller,
+      session_file,
+      session_cursor: None,
+      session_errors,
+    }
+  }
+
+  /// 从会话文件中恢复上次的浏览会话，会话文件不存在时什么也不做
+  async fn restore_session(&mut self) {
+    let Some(path) = self.session_file.as_ref().filter(|path| path.exists()) else {
+      return;
+    };
+
+    match Session::load(path) {
+      Ok(session) => {
+        let mut data = self.log_hub.data().await;
+        self.session_cursor = session
+          .restore(&mut self.log_controller.borrow_mut(), data.data_board())
+          .map(|cursor| (cursor, None));
+      }
+      Err(e) => self.session_errors.push(format!(
+        "failed to restore the session from {}: {}",
+        path.display(),
+        e
+      )),
+    }
+  }
+
+  /// 日志加载稳定（没有正在打开的日志文件，且上一帧以来没有新的日志行）后，
+  /// 取出上次会话中光标所在日志的时间戳。用户按下的键已经被响应时，不再恢复；
+  /// 终端大小变化、鼠标操作等其他事件不影响恢复
+  fn settle_session_cursor(
+    session_cursor: &mut Option<(DateTime<FixedOffset>, Option<usize>)>,
+    data_board: &DataBoard,
+    event: Event,
+  ) -> Option<DateTime<FixedOffset>> {
+    let (cursor, lines_version) = session_cursor.take()?;
+    match event {
+      Event::Key => return None,
+      Event::Tick => {}
+      _ => {
+        *session_cursor = Some((cursor, lines_version));
+        return None;
+      }
+    }
+
+    let current = data_board.get_lines_version();
+    if current > 0 && !data_board.is_loading() && lines_version == Some(current) {
+      return Some(cursor);
+    }
+    *session_cursor = Some((cursor, Some(current)));
+    None
+  }
+
+  /// 将本次的浏览会话写入会话文件
+  async fn save_session(&mut self) {
+    let Some(path) = &self.session_file else {
+      return;
+    };
+
+    let session = {
+      let mut data = self.log_hub.data().await;
+      Session::capture(&self.log_controller.borrow(), data.data_board())
+    };
+    if let Err(e) = session.save(path) {
+      self.session_errors.push(format!(
+        "failed to save the session to {}: {}",
+        path.display(),
+        e
+      ));
     }
   }
 
@@ -361,6 +923,9 @@ impl Viewer {
         return Ok(());
       }
 
+      // 本帧是否需要重新渲染。有事件发生时总是渲染，否则只在展示的内容发生变化时渲染
+      let mut dirty = event != Event::Tick;
+
       {
         // 取出日志数据。此时，异步的读取流程会被停止
         let mut log_hub = self.log_hub.data().await;
@@ -372,7 +937,37 @@ impl Viewer {
           if ctrl.should_quit() {
             return Ok(());
           }
+          dirty |= ctrl.is_dirty();
         }
+
+        // 分屏页面隐藏时，不必为它遍历日志
+        if self.pager.is_visible(SPLIT_PAGE) {
+          let mut ctrl = self.split_controller.borrow_mut();
+          ctrl.run_once(&mut log_hub);
+          dirty |= ctrl.is_dirty();
+        }
+
+        // 上次会话的光标等到日志加载稳定后再恢复，以免跳转到只加载了一部分的日志中
+        if let Some(cursor) =
+          Self::settle_session_cursor(&mut self.session_cursor, log_hub.data_board(), event)
+        {
+          self.log_controller.borrow_mut().locate_time(cursor);
+        }
+
+        // 控制器可能在本帧中请求了加载更旧的日志，它们将在随后的异步流程中被加载
+        let loading = log_hub.data_board().is_loading();
+        // 加载中的旋转提示需要逐帧刷新
+        dirty |= loading || self.pager.status().is_loading() != loading;
+        self.pager.status().set_loading(loading);
+
+        // 被跟踪的日志长时间没有新内容时，在状态栏中提醒
+        let notice = log_hub
+          .data_board()
+          .get_longest_idle()
+          .map(|(path, since)| idle_notice(path, since.elapsed()))
+          .unwrap_or_default();
+        dirty |= self.pager.status().notice() != notice;
+        self.pager.status().set_notice(notice);
       } // 日志数据处理结束，异步读取流程将自动运行。
 
       // 如果有事件发生，则执行当前状态的自定义动作。
@@ -381,8 +976,214 @@ impl Viewer {
         self.sm.run_manual_actions(&mut self.pager);
       }
 
-      // 渲染页面，此时用的数据已经拷贝到各个控制器中
-      terminal.draw(|frame| self.pager.render(frame))?;
+      // 每一帧（包括 Tick）都刷新状态栏中的时钟与统计数据
+      if self.show_stats {
+        let stats = self
+          .app_controller
+          .borrow()
+          .stats()
+          .format(Local::now().time());
+        dirty |= self.pager.status().stats() != stats;
+        self.pager.status().set_stats(stats);
+      }
+
+      // 渲染页面，此时用的数据已经拷贝到各个控制器中。没有任何变化时跳过渲染，以降低空闲时的 CPU 占用
+      if dirty {
+        terminal.draw(|frame| self.pager.render(frame))?;
+      }
     }
   }
 }
+
+/// 生成日志长时间没有新内容时的提醒，如 "no new logs in syslog for 5m"
+fn idle_notice(path: &Path, elapsed: Duration) -> String {
+  let name = path
+    .file_name()
+    .map(|name| name.to_string_lossy())
+    .unwrap_or_else(|| path.to_string_lossy());
+  let secs = elapsed.as_secs();
+  let elapsed = match secs {
+    0..60 => format!("{secs}s"),
+    60..3600 => format!("{}m", secs / 60),
+    _ => format!("{}h{}m", secs / 3600, secs / 60 % 60),
+  };
+  format!("no new logs in {name} for {elapsed}")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::app::KeyBinding;
+
+  /// 构建只关心退出流程的状态机
+  fn build_sm(confirm_quit: bool) -> (StateMachine, Rc<RefCell<AppController>>) {
+    build_sm_with(confirm_quit, KeyMap::default(), Default::default())
+  }
+
+  /// 以给定的按键映射，构建只关心退出流程与调试界面的状态机
+  fn build_sm_with(
+    confirm_quit: bool,
+    key_map: KeyMap,
+    debug_controller: Rc<RefCell<DebugController>>,
+  ) -> (StateMachine, Rc<RefCell<AppController>>) {
+    let app_controller = Rc::new(RefCell::new(AppController::default()));
+    let log_controller = Rc::new(RefCell::new(LogController::default()));
+    let sm = StateMachineBuilder {
+      sm_config: SmConfig::default(),
+      key_map: key_map.clone(),
+      confirm_quit,
+      app_controller: app_controller.clone(),
+      quit_state: QuitState::new(app_controller.clone()).build(),
+      clear_marks_state: ClearMarksState::new(log_controller.clone()),
+      command_state: CommandState::new(log_controller.clone()),
+      log_nav_state: State::new("log"),
+      tag_nav_state: State::new("tag"),
+      host_nav_state: State::new("host"),
+      debug_nav_state: DebugOperationState::new(debug_controller.clone(), &key_map).build(),
+      debug_searching_state: DebugSearchingState::new(debug_controller).build(),
+      mark_nav_state: State::new("mark"),
+      split_nav_state: State::new("split"),
+      log_content_searching_state: State::new("searching"),
+      log_content_searched_state: State::new("searched"),
+      log_timestamp_searching_state: State::new("timestamp searching"),
+      log_timestamp_searched_state: State::new("timestamp searched"),
+      log_percent_jumping_state: State::new("percent"),
+      help_state: State::new("help"),
+      detail_state: State::new("detail"),
+    }
+    .build();
+    (sm, app_controller)
+  }
+
+  #[test]
+  fn test_debug_page_search() {
+    let debug_controller = Rc::new(RefCell::new(DebugController::default()));
+    let (mut sm, _) = build_sm_with(true, KeyMap::default(), debug_controller.clone());
+    let mut pager = Pager::default();
+    sm.first_run(&mut pager);
+    let mut press = |code| {
+      sm.manage_once(&mut pager, KeyEvent::simple(code));
+    };
+
+    // 聚焦调试界面后，字符按键用于导航，不会被输入到搜索框里
+    press(KeyCode::Char('d'));
+    press(KeyCode::Char('j'));
+    press(KeyCode::Char('G'));
+    assert_eq!(debug_controller.borrow().get_curr_search(), "");
+
+    // 按 / 后输入的内容才用于搜索，确认后回到导航，继续按键不再改变搜索内容
+    press(KeyCode::Char('/'));
+    press(KeyCode::Char('o'));
+    press(KeyCode::Char('k'));
+    press(KeyCode::Enter);
+    press(KeyCode::Char('j'));
+    assert_eq!(debug_controller.borrow().get_curr_search(), "ok");
+
+    // 再次进入搜索时，接着上次的内容编辑
+    press(KeyCode::Char('/'));
+    press(KeyCode::Backspace);
+    press(KeyCode::Esc);
+    assert_eq!(debug_controller.borrow().get_curr_search(), "o");
+  }
+
+  #[test]
+  fn test_quit_confirmation() {
+    let esc = KeyEvent::simple(KeyCode::Esc);
+    let mut pager = Pager::default();
+
+    // 默认需要确认，按 esc 后还要再按 y 才退出
+    let (mut sm, app) = build_sm(true);
+    sm.first_run(&mut pager);
+    sm.manage_once(&mut pager, esc);
+    assert!(!app.borrow().should_quit());
+    sm.manage_once(&mut pager, KeyEvent::simple(KeyCode::Char('y')));
+    assert!(app.borrow().should_quit());
+
+    // 不需要确认时，按 esc 或者退出键立即退出
+    let quit = KeyMap::default().get(KeyAction::Quit);
+    for key in [esc, quit] {
+      let (mut sm, app) = build_sm(false);
+      sm.first_run(&mut pager);
+      sm.manage_once(&mut pager, key);
+      assert!(app.borrow().should_quit());
+    }
+
+    // 有子页面打开时，esc 只关闭子页面
+    let (mut sm, app) = build_sm(false);
+    let mut pager = Pager::default().add_page(
+      HELP_PAGE,
+      HelpPage {
+        help_controller: Default::default(),
+      },
+    );
+    sm.first_run(&mut pager);
+    pager.open_full(HELP_PAGE);
+    sm.manage_once(&mut pager, esc);
+    assert!(!app.borrow().should_quit());
+    sm.manage_once(&mut pager, esc);
+    assert!(app.borrow().should_quit());
+  }
+  #[test]
+  fn test_rebound_keys() {
+    let bind = |s: &str| s.parse::<KeyBinding>().unwrap();
+    let key_map = KeyMap::default()
+      .with_bindings(&[
+        bind("open-debug=z"),
+        bind("search-content=e"),
+        bind("quit=x"),
+      ])
+      .unwrap();
+    let debug_controller = Rc::new(RefCell::new(DebugController::default()));
+    let (mut sm, app) = build_sm_with(false, key_map, debug_controller.clone());
+    let mut pager = Pager::default();
+    sm.first_run(&mut pager);
+    let mut press = |code| {
+      sm.manage_once(&mut pager, KeyEvent::simple(code));
+    };
+
+    // 原来的按键不再生效
+    press(KeyCode::Char('d'));
+    press(KeyCode::Char('q'));
+    assert!(!app.borrow().should_quit());
+
+    // 重新绑定的按键打开调试界面，并进入搜索
+    press(KeyCode::Char('z'));
+    press(KeyCode::Char('/'));
+    press(KeyCode::Char('a'));
+    assert_eq!(debug_controller.borrow().get_curr_search(), "");
+    for c in "eok".chars() {
+      press(KeyCode::Char(c));
+    }
+    assert_eq!(debug_controller.borrow().get_curr_search(), "ok");
+
+    // 回到日志导航后，按重新绑定的退出键退出程序
+    press(KeyCode::Esc);
+    press(KeyCode::Esc);
+    press(KeyCode::Char('x'));
+    assert!(app.borrow().should_quit());
+  }
+
+  #[test]
+  fn test_settle_session_cursor() {
+    let cursor = DateTime::parse_from_rfc3339("2026-02-15T12:00:00+08:00").unwrap();
+    let mut data_board = DataBoard::default();
+    let mut updates = crate::log::DataBoardUpdates::default();
+    updates.add_bad_line();
+    data_board.apply_updates(updates, true);
+
+    // 日志行数连续两帧不变后恢复，期间终端大小变化、鼠标操作不会取消恢复
+    let mut session_cursor = Some((cursor, None));
+    let mut settle = |event| Viewer::settle_session_cursor(&mut session_cursor, &data_board, event);
+    assert_eq!(settle(Event::Tick), None);
+    assert_eq!(settle(Event::Some), None);
+    assert_eq!(settle(Event::Tick), Some(cursor));
+    assert_eq!(settle(Event::Tick), None);
+
+    // 响应了按键之后，不再恢复
+    let mut session_cursor = Some((cursor, None));
+    let mut settle = |event| Viewer::settle_session_cursor(&mut session_cursor, &data_board, event);
+    assert_eq!(settle(Event::Tick), None);
+    assert_eq!(settle(Event::Key), None);
+    assert_eq!(settle(Event::Tick), None);
+  }
+}
@@ -12,6 +12,9 @@ pub struct Item {
 pub struct Buffer {
   data: VecDeque<Item>,
   limit: usize,
+
+  /// 累计写入的调试信息数量，包括已经被挤出缓存的
+  pushed: usize,
 }
 
 impl Buffer {
@@ -19,6 +22,7 @@ impl Buffer {
     Self {
       data: VecDeque::with_capacity(limit),
       limit,
+      pushed: 0,
     }
   }
 
@@ -27,11 +31,21 @@ impl Buffer {
       self.data.pop_front();
     }
     self.data.push_back(item);
+    self.pushed += 1;
   }
 
   pub fn data(&self) -> &VecDeque<Item> {
     &self.data
   }
+
+  pub fn pushed(&self) -> usize {
+    self.pushed
+  }
+
+  /// 清空缓存中的调试信息，累计写入的数量保持不变
+  pub fn clear(&mut self) {
+    self.data.clear();
+  }
 }
 
 pub static BUFFER: Mutex<Option<Buffer>> = Mutex::new(None);
@@ -41,7 +55,14 @@ pub fn enable_debug(buffer_size: usize) {
 }
 
 pub fn log_message(content: String, is_error: bool) {
-  match BUFFER.lock().unwrap().as_mut() {
+  log_message_to(&BUFFER, content, is_error);
+}
+
+/// 将调试信息写入指定的缓存，缓存未启用时直接输出
+fn log_message_to(buffer: &Mutex<Option<Buffer>>, content: String, is_error: bool) {
+  match buffer.lock().unwrap().as_mut() {
+    // 终端处于 raw 模式时，直接写标准输出会破坏界面，这种情况下丢弃消息
+    None if crossterm::terminal::is_raw_mode_enabled().unwrap_or(false) => {}
     None => match is_error {
       true => eprintln!("{}", content),
       false => println!("{}", content),
@@ -67,3 +88,28 @@ macro_rules! eprintln {
     crate::debug::log_message(format!($($arg)*), true)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_messages_append_to_buffer() {
+    // 使用局部的缓存，不受并行运行的其他测试写入全局缓存的调试信息影响
+    let buffer = Mutex::new(Some(Buffer::new(2)));
+    log_message_to(&buffer, "info 1".to_string(), false);
+    log_message_to(&buffer, "error 2".to_string(), true);
+    log_message_to(&buffer, "info 3".to_string(), false);
+
+    // 超出容量时挤出最早的信息，累计写入的数量包括被挤出的
+    let buffer = buffer.lock().unwrap();
+    let buffer = buffer.as_ref().unwrap();
+    let data: Vec<_> = buffer
+      .data()
+      .iter()
+      .map(|item| (item.content.as_str(), item.is_error))
+      .collect();
+    assert_eq!(data, vec![("error 2", true), ("info 3", false)]);
+    assert_eq!(buffer.pushed(), 3);
+  }
+}
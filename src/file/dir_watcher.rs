@@ -0,0 +1,65 @@
+//! 监控目录中新出现的文件
+
+use anyhow::Result;
+use glob::Pattern;
+use notify::{
+  Event as NotifyEvent, EventKind, RecommendedWatcher, RecursiveMode, Watcher as NotifyWatcher,
+  event::{ModifyKind, RenameMode},
+};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+
+/// 监控一个目录（不递归），收集其中新创建的、文件名符合给定 glob 模式的文件路径
+pub struct DirWatcher {
+  /// 新文件路径的接收通道
+  rx: mpsc::UnboundedReceiver<PathBuf>,
+
+  /// 目录监控器，析构时停止监控
+  _watcher: RecommendedWatcher,
+}
+
+impl DirWatcher {
+  pub fn new(dir: &Path, pattern: &str) -> Result<Self> {
+    let pattern = Pattern::new(pattern)?;
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let mut watcher: RecommendedWatcher =
+      notify::recommended_watcher(move |res: notify::Result<NotifyEvent>| match res {
+        // 新建文件，或者有文件被移动到该目录下
+        Ok(event) => {
+          if let EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) =
+            event.kind
+          {
+            event
+              .paths
+              .into_iter()
+              .filter(|path| {
+                path
+                  .file_name()
+                  .and_then(|name| name.to_str())
+                  .is_some_and(|name| pattern.matches(name))
+              })
+              .for_each(|path| {
+                let _ = tx.send(path);
+              });
+          }
+        }
+        Err(e) => crate::eprintln!("Error when watching directory: {}", e),
+      })?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    Ok(Self {
+      rx,
+      _watcher: watcher,
+    })
+  }
+
+  /// 取出目前为止发现的所有新文件路径，不会等待
+  pub fn take_new_paths(&mut self) -> Vec<PathBuf> {
+    let mut paths = vec![];
+    while let Ok(path) = self.rx.try_recv() {
+      paths.push(path);
+    }
+    paths
+  }
+}
@@ -1,33 +1,118 @@
 //! 文件事件定义，包括：
-//! 1. 读取的新的一行字符串，新行可能从头部插入，也可以从尾部插入；
+//! 1. 读取的新的一行原始字节（不一定是合法的 UTF-8），新行可能从头部插入，也可以从尾部插入；
 //! 2. 文件的重命名；
-//! 3. 文件的删除。
+//! 3. 文件的删除；
+//! 4. 文件被原地截断（如某些服务通过截断而非重命名来滚动日志）；
+//! 5. 跟踪的文件长时间没有新内容（如服务已经停止）；
+//! 6. 往头部方向的读取已经到达文件开头。
 
-use std::path::PathBuf;
-use tokio::{io::Result, sync::mpsc};
+use crate::file::reader::Overflow;
+use std::{path::PathBuf, sync::Arc, time::Instant};
+use tokio::{
+  io::Result,
+  sync::{Mutex, mpsc},
+};
 
 #[derive(Debug)]
 pub enum Event {
-  NewHead(String),
-  NewTail(String),
+  NewHead(Vec<u8>),
+  NewTail(Vec<u8>),
   Renamed(PathBuf),
   Removed,
+  Truncated,
+
+  /// 往头部方向的读取已经到达文件开头，之后不会再有新的头行
+  HeadEnd,
+
+  /// 超过配置的时长没有新内容，携带最后一次出现新内容（或者开始跟踪）的时间
+  Idle(Instant),
+}
+
+/// 内容事件的接收端。它被发送端共享，以便在通道已满时取出并丢弃最旧的事件
+pub type EventReceiver = Arc<Mutex<mpsc::Receiver<Event>>>;
+
+/// 内容事件的发送端，与接收端放在同一份共享数据中，以免每个读取状态都变大
+#[derive(Clone)]
+pub struct EventSender(Arc<(mpsc::Sender<Event>, EventReceiver)>);
+
+/// 创建一个容纳 `size` 个内容事件的通道
+pub fn channel(size: usize) -> (EventSender, EventReceiver) {
+  let (tx, rx) = mpsc::channel::<Event>(size);
+  let rx = Arc::new(Mutex::new(rx));
+  (EventSender(Arc::new((tx, rx.clone()))), rx)
 }
 
 impl Event {
-  pub async fn send_head(tx: &mpsc::Sender<Event>, buffer: &[u8]) -> Result<()> {
-    let line = Event::NewHead(String::from_utf8_lossy(buffer).to_string());
-    if let Err(e) = tx.send(line).await {
+  pub async fn send_head(tx: &EventSender, buffer: &[u8], overflow: Overflow) -> Result<()> {
+    let line = Event::NewHead(buffer.to_vec());
+    if let Err(e) = Self::send_content(tx, line, overflow).await {
       crate::eprintln!("Failed to send head line: {}", e);
     }
     Ok(())
   }
 
-  pub async fn send_tail(tx: &mpsc::Sender<Event>, buffer: &[u8]) -> Result<()> {
-    let line = Event::NewTail(String::from_utf8_lossy(buffer).to_string());
-    if let Err(e) = tx.send(line).await {
+  pub async fn send_tail(tx: &EventSender, buffer: &[u8], overflow: Overflow) -> Result<()> {
+    let line = Event::NewTail(buffer.to_vec());
+    if let Err(e) = Self::send_content(tx, line, overflow).await {
       crate::eprintln!("Failed to send tail line: {}", e);
     }
     Ok(())
   }
+
+  /// 按通道已满时的处理策略，发送内容事件
+  async fn send_content(
+    tx: &EventSender,
+    event: Event,
+    overflow: Overflow,
+  ) -> std::result::Result<(), mpsc::error::SendError<Event>> {
+    use mpsc::error::{SendError, TrySendError};
+    let (sender, rx) = &*tx.0;
+
+    match overflow {
+      Overflow::Block => sender.send(event).await,
+      Overflow::DropNewest => match sender.try_send(event) {
+        Err(TrySendError::Closed(event)) => Err(SendError(event)),
+        _ => Ok(()),
+      },
+      Overflow::DropOldest => {
+        let mut event = event;
+        loop {
+          match sender.try_send(event) {
+            Ok(()) => return Ok(()),
+            Err(TrySendError::Closed(event)) => return Err(SendError(event)),
+            Err(TrySendError::Full(e)) => event = e,
+          }
+
+          // 取出并丢弃最旧的事件。截断事件不能丢弃，它之后的内容都属于截断后的文件，
+          // 此时丢弃这些内容，保证截断事件仍然排在新内容之前
+          let mut rx = rx.lock().await;
+          if let Ok(Event::Truncated) = rx.try_recv() {
+            let mut truncated = Event::Truncated;
+            loop {
+              while rx.try_recv().is_ok() {}
+              match sender.try_send(truncated) {
+                Err(TrySendError::Full(e)) => truncated = e,
+                Err(TrySendError::Closed(e)) => return Err(SendError(e)),
+                Ok(()) => break,
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+
+  pub async fn send_head_end(tx: &EventSender) -> Result<()> {
+    if let Err(e) = tx.0.0.send(Event::HeadEnd).await {
+      crate::eprintln!("Failed to send head end event: {}", e);
+    }
+    Ok(())
+  }
+
+  pub async fn send_truncated(tx: &EventSender) -> Result<()> {
+    if let Err(e) = tx.0.0.send(Event::Truncated).await {
+      crate::eprintln!("Failed to send truncated event: {}", e);
+    }
+    Ok(())
+  }
 }
@@ -2,12 +2,16 @@
 
 use crate::file::{
   Event, Reader,
+  event::{self, EventReceiver},
   reader::{self, ReaderBase},
   reader::{Config, State},
   watcher::{ChangedEvent, MetadataEvent},
 };
 use anyhow::Result;
-use std::{os::fd::AsRawFd, path::Path};
+use std::{
+  os::{fd::AsRawFd, unix::fs::MetadataExt},
+  path::Path,
+};
 use tokio::{fs::File, sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
@@ -18,15 +22,21 @@ pub struct HeadReader {
   /// 文件对象（保持打开状态，确保 fd 不变更）
   file: File,
 
+  /// 打开时文件的 inode
+  inode: u64,
+
   /// 读取文件的状态
   state: State,
 
   /// 用于控制读取取消的 token
   cancel_token: CancellationToken,
 
-  /// 用于收发事件的通道
-  tx: mpsc::Sender<Event>,
-  rx: mpsc::Receiver<Event>,
+  /// 用于接收内容事件的通道，发送端由读取状态持有
+  rx: EventReceiver,
+
+  /// 用于收发元数据事件（重命名、删除）的通道，它独立于内容事件，不会因内容堆积而阻塞
+  meta_tx: mpsc::UnboundedSender<Event>,
+  meta_rx: mpsc::UnboundedReceiver<Event>,
 
   /// 监听文件路径变化的 join handler
   jh_watching: Option<JoinHandle<()>>,
@@ -41,21 +51,27 @@ impl Reader for HeadReader {
     // 无论文件如何重命名，我们都能找到它
     let file = File::open(path).await?;
     let fd = file.as_raw_fd();
+    let inode = file.metadata().await?.ino();
 
     // 创建通信通道
-    let (tx, rx) = mpsc::channel::<Event>(config.channel_size);
+    let (tx, rx) = event::channel(config.channel_size);
+    let (meta_tx, meta_rx) = mpsc::unbounded_channel::<Event>();
 
     // 初始化读取状态数据
-    let state = State::new_head(path, fd, config.buffer_size, tx.clone()).await?;
+    let state = State::new_head(path, fd, config.buffer_size, tx.clone())
+      .await?
+      .with_overflow(config.overflow);
 
     // 返回文件读取器
     Ok(HeadReader {
       config,
       file,
+      inode,
       state,
       cancel_token: CancellationToken::new(),
-      tx,
       rx,
+      meta_tx,
+      meta_rx,
       jh_watching: None,
       jh_reading: None,
     })
@@ -81,7 +97,12 @@ impl ReaderBase for HeadReader {
   }
 
   async fn changed(&mut self) -> Option<Vec<Event>> {
-    reader::poll_events(&mut self.rx, self.config.recv_buffer_size).await
+    reader::poll_events_with_metadata(&mut self.meta_rx, &self.rx, self.config.recv_buffer_size)
+      .await
+  }
+
+  fn inode(&self) -> Option<u64> {
+    Some(self.inode)
   }
 }
 
@@ -93,12 +114,13 @@ impl HeadReader {
 
     // 创建文件系统监视器，监控重命名或删除事件，忽略变更事件
     let mut watcher = self.state.watcher(config.poll_interval)?;
+    watcher.start()?;
 
     // 准备 cancel token
     let cancel_token = self.cancel_token.clone();
 
-    // 取出事件发送通道，用于发送 metadata 变化事件
-    let tx = self.tx.clone();
+    // 取出元数据事件发送通道，用于发送 metadata 变化事件
+    let meta_tx = self.meta_tx.clone();
 
     Ok(tokio::spawn(async move {
       'watch_loop: loop {
@@ -113,14 +135,16 @@ impl HeadReader {
               break 'watch_loop;
             },
             Ok(ChangedEvent::Metadata(event)) => {
-              if (event.send(&tx).await) {
-                  break 'watch_loop;
+              if event.send(&meta_tx) {
+                break 'watch_loop;
               }
             },
             _ => {}
           },
         }
       }
+
+      let _ = watcher.stop().await;
     }))
   }
 
@@ -150,8 +174,13 @@ impl HeadReader {
           break;
         }
 
-        // 没有读取到新内容，则退出
+        // 没有读取到新内容，则退出。按需将最后一个没有换行符的行也发送出去
         if state.position() == last_position {
+          if config.flush_partial_at_eof
+            && let Err(e) = state.flush_tail().await
+          {
+            crate::eprintln!("Error while flushing the last line: {e}");
+          }
           break;
         }
       }
@@ -1,10 +1,14 @@
+mod dir_watcher;
 mod event;
 mod head_reader;
 pub mod reader;
+mod stream_reader;
 mod tail_reader;
 mod watcher;
 
+pub use dir_watcher::DirWatcher;
 pub use event::Event;
 pub use head_reader::HeadReader;
 pub use reader::Reader;
+pub use stream_reader::{STDIN_PATH, StreamReader, is_stdin};
 pub use tail_reader::TailReader;
@@ -2,6 +2,7 @@
 
 use crate::file::{
   Event,
+  event::{EventReceiver, EventSender},
   watcher::{MetadataEvent, Watcher},
 };
 use ::anyhow::{Result, anyhow};
@@ -10,6 +11,7 @@ use std::{
   io::SeekFrom,
   os::fd::RawFd,
   path::{Path, PathBuf},
+  str::FromStr,
   time::Duration,
 };
 use tokio::{
@@ -18,6 +20,42 @@ use tokio::{
   sync::mpsc,
 };
 
+/// 内容事件的通道已满（消费者处理不过来）时的处理策略。
+/// 重命名、删除等元数据事件走单独的通道，不受该策略影响
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Overflow {
+  /// 等待消费者取走事件，读取流程随之暂停
+  #[default]
+  Block,
+
+  /// 直接丢弃新的内容事件，读取流程不会被阻塞
+  DropNewest,
+
+  /// 丢弃通道中最旧的内容事件，为新的内容腾出空间，读取流程不会被阻塞
+  DropOldest,
+}
+
+impl FromStr for Overflow {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    match s {
+      "block" => Ok(Overflow::Block),
+      "drop-newest" => Ok(Overflow::DropNewest),
+      "drop-oldest" => Ok(Overflow::DropOldest),
+      _ => Err(format!(
+        "unknown overflow policy '{s}', expected 'block', 'drop-newest' or 'drop-oldest'"
+      )),
+    }
+  }
+}
+
+/// 不完整的行在暂存区中最多保留的字节数，更长的部分将被截断
+pub const MAX_LINE_LENGTH: usize = 1024 * 1024;
+
+/// 自动选择缓冲区大小时，从文件末尾采样的字节数
+const BUFFER_SAMPLE_SIZE: u64 = 64 * 1024;
+
 /// 读取文件所需的配置
 #[derive(Clone)]
 pub struct Config {
@@ -25,6 +63,17 @@ pub struct Config {
   pub poll_interval: Duration,
   pub channel_size: usize,
   pub recv_buffer_size: usize,
+  pub overflow: Overflow,
+
+  /// 跟踪文件尾部时，超过该时长没有新内容，则发出一次 [Event::Idle]，为空则不检测
+  pub idle_timeout: Option<Duration>,
+
+  /// 从尾部读取时，只往前读取这么多行，之后不再读取，也不跟踪文件的变化。为空则正常跟踪
+  pub tail_lines: Option<usize>,
+
+  /// 从头部读取到文件结尾时，是否将最后一个没有换行符的行也作为完整行发送。
+  /// 只对不会再被更新的文件（[crate::file::HeadReader]）生效，跟踪中的文件总是等待它写完
+  pub flush_partial_at_eof: bool,
 }
 
 impl Default for Config {
@@ -34,6 +83,10 @@ impl Default for Config {
       poll_interval: Duration::from_millis(100),
       channel_size: 2000,
       recv_buffer_size: 100,
+      overflow: Overflow::default(),
+      idle_timeout: Some(Duration::from_secs(300)),
+      tail_lines: None,
+      flush_partial_at_eof: false,
     }
   }
 }
@@ -41,7 +94,8 @@ impl Default for Config {
 /// 读取过程中所需要的状态管理数据
 #[derive(Default)]
 pub struct State {
-  // 上一次读取的文件偏移位置
+  // 上一次读取的文件偏移位置。读取总是经由 fd 路径进行，文件被重命名后它仍然有效，
+  // 不会从头重新读取已经发送过的内容
   last_position: u64,
 
   // 上一次读取可能没有完整的行内容
@@ -57,16 +111,20 @@ pub struct State {
   file: Option<File>,
 
   // 发送行的通道
-  tx: Option<mpsc::Sender<Event>>,
+  tx: Option<EventSender>,
+
+  // 通道已满时的处理策略
+  overflow: Overflow,
+
+  // 还允许发送的行数，不限制时为 u32::MAX
+  line_budget: u32,
+
+  // 暂存区中的行是否因为过长而被截断过
+  partial_truncated: bool,
 }
 
 impl State {
-  pub async fn new_head(
-    path: &Path,
-    fd: RawFd,
-    buffer_size: u64,
-    tx: mpsc::Sender<Event>,
-  ) -> Result<Self> {
+  pub async fn new_head(path: &Path, fd: RawFd, buffer_size: u64, tx: EventSender) -> Result<Self> {
     // 基于给定的 fd 打开文件，这是 FileReader 先打开、并且一直持有的 fd，无论向前、向后读取，都使用该 fd，
     // 保证它们读到的同一份文件
     let fd_path = PathBuf::from(format!("/proc/self/fd/{}", fd));
@@ -77,20 +135,18 @@ impl State {
     // 返回处于头部的状态数据
     Ok(Self {
       last_position: 0,
-      partial_buffer: Vec::with_capacity(buffer_size as usize),
+      partial_buffer: Vec::with_capacity((buffer_size as usize).min(MAX_LINE_LENGTH)),
       raw_path: path.into(),
       fd_path,
       file: Some(file),
       tx: Some(tx),
+      overflow: Overflow::default(),
+      line_budget: u32::MAX,
+      partial_truncated: false,
     })
   }
 
-  pub async fn new_tail(
-    path: &Path,
-    fd: RawFd,
-    buffer_size: u64,
-    tx: mpsc::Sender<Event>,
-  ) -> Result<Self> {
+  pub async fn new_tail(path: &Path, fd: RawFd, buffer_size: u64, tx: EventSender) -> Result<Self> {
     let mut new_state = Self::new_head(path, fd, buffer_size, tx).await?;
 
     // 从尾部往前跳一段距离，我们从这里开始向前、向后读取
@@ -104,11 +160,62 @@ impl State {
     Ok(new_state)
   }
 
+  /// 处于文件末尾的状态数据，用于从末尾开始向前读取。
+  /// 文件末尾的换行符会被跳过，否则它会被当作一个空行读出
+  pub async fn new_end(path: &Path, fd: RawFd, buffer_size: u64, tx: EventSender) -> Result<Self> {
+    let mut new_state = Self::new_head(path, fd, buffer_size, tx).await?;
+
+    if let Some(file) = &mut new_state.file {
+      let len = file.metadata().await?.len();
+      new_state.last_position = len;
+      if len > 0 {
+        let mut last = [0u8];
+        file.seek(SeekFrom::Start(len - 1)).await?;
+        file.read_exact(&mut last).await?;
+        if last[0] == b'\n' {
+          new_state.last_position = len - 1;
+        }
+      }
+      file.seek(SeekFrom::Start(new_state.last_position)).await?;
+    }
+
+    Ok(new_state)
+  }
+
+  /// 设置通道已满时的处理策略
+  pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+    self.overflow = overflow;
+    self
+  }
+
+  /// 限制之后最多发送的行数
+  pub fn with_line_budget(mut self, lines: usize) -> Self {
+    self.line_budget = u32::try_from(lines).unwrap_or(u32::MAX);
+    self
+  }
+
   /// 当前是否已经读取到了头部
   pub fn has_reached_head(&self) -> bool {
     self.last_position == 0
   }
 
+  /// 是否已经发送完允许发送的所有行
+  pub fn is_out_of_budget(&self) -> bool {
+    self.line_budget == 0
+  }
+
+  /// 消耗一行的发送额度，返回该行是否可以发送
+  fn take_line_budget(&mut self) -> bool {
+    match self.line_budget {
+      0 => false,
+      u32::MAX => true,
+      _ => {
+        self.line_budget -= 1;
+        true
+      }
+    }
+  }
+
   /// 返回当前文件的位置
   pub fn position(&self) -> u64 {
     self.last_position
@@ -117,8 +224,9 @@ impl State {
   /// 追加新内容到暂存区的头部，过长的部分将被截断
   pub fn save_head_partial(&mut self, buffer: &[u8]) {
     let curr_len = self.partial_buffer.len();
-    let size = buffer.len().min(self.partial_buffer.capacity());
-    let curr_len = (self.partial_buffer.capacity() - size).min(curr_len);
+    let size = buffer.len().min(MAX_LINE_LENGTH);
+    let curr_len = (MAX_LINE_LENGTH - size).min(curr_len);
+    self.partial_truncated |= curr_len + size < self.partial_buffer.len() + buffer.len();
     self.partial_buffer.resize(curr_len + size, 0);
     self.partial_buffer.copy_within(..curr_len, size);
     self.partial_buffer[..size].copy_from_slice(&buffer[..size]);
@@ -127,40 +235,103 @@ impl State {
   /// 追加新内容到暂存区的尾部，多余部分将被截断
   pub fn save_tail_partial(&mut self, buffer: &[u8]) {
     let curr_len = self.partial_buffer.len();
-    let size = self.partial_buffer.capacity() - curr_len;
+    let size = MAX_LINE_LENGTH - curr_len;
     let size = size.min(buffer.len());
+    self.partial_truncated |= size < buffer.len();
     self.partial_buffer.resize(curr_len + size, 0);
     self.partial_buffer[curr_len..].copy_from_slice(&buffer[..size]);
   }
 
+  /// 过长的行被截断时，截断处可能落在一个多字节的 UTF-8 字符中间，去掉这个不完整的字符，
+  /// 避免它被解码成替换字符。没有被截断的行保持原样，即使它本身不是合法的 UTF-8
+  fn trim_truncated_partial(&mut self) {
+    if std::mem::take(&mut self.partial_truncated) {
+      let len = complete_utf8_len(&self.partial_buffer);
+      self.partial_buffer.truncate(len);
+    }
+  }
+
   pub async fn send_head(&mut self) -> Result<()> {
-    self.send_head_for(&self.partial_buffer).await?;
+    self.trim_truncated_partial();
+    let buffer = std::mem::take(&mut self.partial_buffer);
+    self.send_head_for(&buffer).await?;
+    self.partial_buffer = buffer;
     self.partial_buffer.clear();
     Ok(())
   }
 
-  pub async fn send_head_for(&self, buffer: &[u8]) -> Result<()> {
+  pub async fn send_head_for(&mut self, buffer: &[u8]) -> Result<()> {
+    if !self.take_line_budget() {
+      return Ok(());
+    }
+    if let Some(tx) = &self.tx {
+      Event::send_head(tx, buffer, self.overflow).await?;
+    }
+
+    Ok(())
+  }
+
+  /// 通知往头部方向的读取已经到达文件开头
+  pub async fn send_head_end(&mut self) -> Result<()> {
     if let Some(tx) = &self.tx {
-      Event::send_head(tx, buffer).await?;
+      Event::send_head_end(tx).await?;
     }
 
     Ok(())
   }
 
   pub async fn send_tail(&mut self) -> Result<()> {
-    self.send_tail_for(&self.partial_buffer).await?;
+    self.trim_truncated_partial();
+    let buffer = std::mem::take(&mut self.partial_buffer);
+    self.send_tail_for(&buffer).await?;
+    self.partial_buffer = buffer;
     self.partial_buffer.clear();
     Ok(())
   }
 
-  pub async fn send_tail_for(&self, buffer: &[u8]) -> Result<()> {
+  /// 将暂存区中没有换行符结尾的行作为完整行发送，暂存区为空时什么也不做
+  pub async fn flush_tail(&mut self) -> Result<()> {
+    if !self.partial_buffer.is_empty() {
+      self.send_tail().await?;
+    }
+    Ok(())
+  }
+
+  pub async fn send_tail_for(&mut self, buffer: &[u8]) -> Result<()> {
+    if !self.take_line_budget() {
+      return Ok(());
+    }
     if let Some(tx) = &self.tx {
-      Event::send_tail(tx, buffer).await?;
+      Event::send_tail(tx, buffer, self.overflow).await?;
     }
 
     Ok(())
   }
 
+  /// 检查文件是否被原地截断：若文件当前的长度小于上一次读取的位置，则认为文件被截断，
+  /// 此时重置读取位置与暂存区，从新的文件开头重新读取，并发送截断事件。
+  ///
+  /// 注意，若截断后新写入的内容已经超过了原来的读取位置，则无法检测出截断。
+  pub async fn check_truncated(&mut self) -> Result<bool> {
+    let Some(file) = self.file.as_mut() else {
+      return Ok(false);
+    };
+
+    if file.metadata().await?.len() >= self.last_position {
+      return Ok(false);
+    }
+
+    self.last_position = 0;
+    self.partial_buffer.clear();
+    file.seek(SeekFrom::Start(0)).await?;
+
+    if let Some(tx) = &self.tx {
+      Event::send_truncated(tx).await?;
+    }
+
+    Ok(true)
+  }
+
   pub fn watcher(&self, poll_interval: Duration) -> Result<Watcher> {
     Watcher::new(&self.raw_path, &self.fd_path, poll_interval)
   }
@@ -190,6 +361,9 @@ pub trait ReaderBase {
 
   /// 获取新的事件，包括新行添加、文件重命名，以及文件删除
   async fn changed(&mut self) -> Option<Vec<Event>>;
+
+  /// 被读取文件的 inode，用于判断同名的文件是否已经被重新创建。数据流没有 inode
+  fn inode(&self) -> Option<u64>;
 }
 
 /// 读取文件的接口定义
@@ -225,6 +399,9 @@ pub async fn read_head_lines(buffer: &mut Vec<u8>, state: &mut State) -> Result<
 
 /// 往尾部方向读取新的内容，将完整的行发送出去
 pub async fn read_tail_lines(buffer: &mut Vec<u8>, state: &mut State) -> Result<()> {
+  // 文件若被截断，则从头开始读取
+  state.check_truncated().await?;
+
   // 向后读取一部分内容，
   // 这里分成三个部分，头部和之前的缓存连在一起，组成完整的行。中间部分是完整的行。
   // 尾部如果包含换行符，则是完整的行，否则需要暂存起来，等待下次读取时拼接。
@@ -356,9 +533,77 @@ pub async fn update_tail_line(
   Ok(())
 }
 
+/// 从文件末尾采样一段内容，根据其中最长的行选择读取的缓冲区大小，使得一次读取通常能包含若干完整的行，
+/// 减少不完整行的拼接。结果不小于 `min_size`，除此之外也不超过 [MAX_LINE_LENGTH]
+pub async fn sample_buffer_size(path: &Path, min_size: u64) -> Result<u64> {
+  let mut file = File::open(path).await?;
+  let len = file.metadata().await?.len();
+  let start = len.saturating_sub(BUFFER_SAMPLE_SIZE);
+  file.seek(SeekFrom::Start(start)).await?;
+
+  let mut buffer = Vec::with_capacity((len - start) as usize);
+  file.read_to_end(&mut buffer).await?;
+
+  // 采样的开头可能是被截断的行，不参与统计
+  let mut lines = buffer.split(|&c| c == b'\n');
+  if start > 0 {
+    lines.next();
+  }
+  let max_line = lines.map(|line| line.len() as u64 + 1).max().unwrap_or(0);
+
+  Ok(
+    (max_line * 4)
+      .next_power_of_two()
+      .min(MAX_LINE_LENGTH as u64)
+      .max(min_size),
+  )
+}
+
+/// 去掉末尾不完整的 UTF-8 字符后的长度。只检查最后一个字符，末尾不是不完整字符时返回原长度
+fn complete_utf8_len(buffer: &[u8]) -> usize {
+  // 从末尾往前找到最后一个字符的首字节（跳过至多 3 个 0b10xxxxxx 的后续字节）
+  let Some(start) = buffer
+    .iter()
+    .rev()
+    .take(4)
+    .position(|&c| c & 0xC0 != 0x80)
+    .map(|pos| buffer.len() - 1 - pos)
+  else {
+    return buffer.len();
+  };
+
+  // 由首字节得出该字符应有的长度，不足则说明它被截断了
+  let expected = match buffer[start] {
+    c if c & 0xE0 == 0xC0 => 2,
+    c if c & 0xF0 == 0xE0 => 3,
+    c if c & 0xF8 == 0xF0 => 4,
+    _ => 1,
+  };
+  match buffer.len() - start < expected {
+    true => start,
+    false => buffer.len(),
+  }
+}
+
 /// 从一个事件接收通道中，尽可能取出多的事件
-pub async fn poll_events(rx: &mut mpsc::Receiver<Event>, buf_size: usize) -> Option<Vec<Event>> {
+pub async fn poll_events(rx: &EventReceiver, buf_size: usize) -> Option<Vec<Event>> {
   let mut buf = Vec::with_capacity(buf_size);
-  let count = rx.recv_many(&mut buf, buf_size).await;
+  let count = rx.lock().await.recv_many(&mut buf, buf_size).await;
   if count == 0 { None } else { Some(buf) }
 }
+
+/// 同时从元数据事件通道与内容事件通道中取出事件，元数据事件优先。
+/// 这样即使内容事件堆积，重命名与删除事件也能被及时处理。
+/// 内容读取结束、通道关闭后，继续等待元数据事件，两个通道都关闭时才返回空
+pub async fn poll_events_with_metadata(
+  meta_rx: &mut mpsc::UnboundedReceiver<Event>,
+  rx: &EventReceiver,
+  buf_size: usize,
+) -> Option<Vec<Event>> {
+  tokio::select! {
+    biased;
+    Some(event) = meta_rx.recv() => Some(vec![event]),
+    Some(events) = poll_events(rx, buf_size) => Some(events),
+    else => None,
+  }
+}
@@ -0,0 +1,142 @@
+//! 从数据流中逐行读取内容，用于标准输入、管道等没有回滚、重命名与删除事件的数据源。
+//! 数据流只能从前往后读取，因此所有的行都会往尾部发送。
+
+use crate::file::{
+  Event, Reader,
+  event::{self, EventReceiver, EventSender},
+  reader::{self, Config, ReaderBase},
+};
+use anyhow::Result;
+use std::path::Path;
+use tokio::{
+  fs::File,
+  io::{AsyncBufReadExt, AsyncRead, BufReader},
+  task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
+
+/// 代表标准输入的路径
+pub const STDIN_PATH: &str = "-";
+
+/// 给定的路径是否代表标准输入
+pub fn is_stdin(path: &Path) -> bool {
+  path.as_os_str() == STDIN_PATH
+}
+
+/// 可以被读取的数据流
+type Stream = Box<dyn AsyncRead + Unpin + Send + Sync>;
+
+/// 从数据流中持续读取新行的读取器
+pub struct StreamReader {
+  config: Config,
+
+  /// 数据流，开始读取后将被移交给异步流程
+  stream: Option<Stream>,
+
+  /// 用于控制读取取消的 token
+  cancel_token: CancellationToken,
+
+  /// 用于收发事件的通道
+  tx: EventSender,
+  rx: EventReceiver,
+
+  /// 异步读取的 join handler
+  jh_reading: Option<JoinHandle<()>>,
+}
+
+impl Reader for StreamReader {
+  /// 路径为 `-` 时读取标准输入，否则将给定的文件（如命名管道）作为数据流读取
+  async fn open(path: &Path, config: Config) -> Result<Self> {
+    let stream: Stream = if is_stdin(path) {
+      Box::new(tokio::io::stdin())
+    } else {
+      Box::new(File::open(path).await?)
+    };
+
+    Ok(Self::from_stream(stream, config))
+  }
+}
+
+impl ReaderBase for StreamReader {
+  async fn start(&mut self) -> Result<()> {
+    if let Some(stream) = self.stream.take() {
+      self.jh_reading = Some(self.spawn_reading(stream));
+    }
+    Ok(())
+  }
+
+  async fn stop(&mut self) -> Result<()> {
+    self.cancel_token.cancel();
+    if let Some(jh) = self.jh_reading.take() {
+      jh.await?;
+    }
+    Ok(())
+  }
+
+  async fn changed(&mut self) -> Option<Vec<Event>> {
+    reader::poll_events(&self.rx, self.config.recv_buffer_size).await
+  }
+
+  fn inode(&self) -> Option<u64> {
+    None
+  }
+}
+
+impl StreamReader {
+  /// 基于任意的数据流创建读取器
+  pub fn from_stream(
+    stream: impl AsyncRead + Unpin + Send + Sync + 'static,
+    config: Config,
+  ) -> Self {
+    // 创建通信通道
+    let (tx, rx) = event::channel(config.channel_size);
+
+    Self {
+      config,
+      stream: Some(Box::new(stream)),
+      cancel_token: CancellationToken::new(),
+      tx,
+      rx,
+      jh_reading: None,
+    }
+  }
+
+  /// 启动新协程，一直读取数据流，直到数据流结束
+  fn spawn_reading(&mut self, stream: Stream) -> JoinHandle<()> {
+    let cancel_token = self.cancel_token.clone();
+    let tx = self.tx.clone();
+    let overflow = self.config.overflow;
+    let mut stream = BufReader::with_capacity(self.config.buffer_size as usize, stream);
+
+    tokio::spawn(async move {
+      let mut buffer = Vec::new();
+
+      loop {
+        buffer.clear();
+
+        let bytes_read = tokio::select! {
+          _ = cancel_token.cancelled() => break,
+          res = stream.read_until(b'\n', &mut buffer) => match res {
+            Ok(n) => n,
+            Err(e) => {
+              crate::eprintln!("Error while reading stream: {e}");
+              break;
+            }
+          },
+        };
+
+        // 数据流已经结束
+        if bytes_read == 0 {
+          break;
+        }
+
+        // 去掉换行符后发送出去。数据流结束前最后不完整的一行，也作为完整的行发送
+        let line = buffer.strip_suffix(b"\n").unwrap_or(&buffer);
+        if let Err(e) = Event::send_tail(&tx, line, overflow).await {
+          crate::eprintln!("Failed to send stream line: {e}");
+          break;
+        }
+      }
+    })
+  }
+}
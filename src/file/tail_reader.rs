@@ -1,13 +1,20 @@
 //! 从尾部读取文件，会打开两个异步流程，一个从尾部往前读取，一个从尾部
 //! 持续监听新行的增加，往尾部发送新行。
+//!
+//! 若配置了 `tail_lines`，则只从末尾往前读取这么多行，不再监听文件的变化。
 
 use crate::file::{
   Event, Reader,
+  event::{self, EventReceiver},
   reader::{self, Config, ReadDirection, ReaderBase, State},
   watcher::ChangedEvent,
 };
 use anyhow::Result;
-use std::{os::fd::AsRawFd, path::Path};
+use std::{
+  os::{fd::AsRawFd, unix::fs::MetadataExt},
+  path::Path,
+  time::{Duration, Instant},
+};
 use tokio::{fs::File, sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
@@ -18,6 +25,9 @@ pub struct TailReader {
   /// 文件对象（保持打开状态，确保 fd 不变更）
   file: File,
 
+  /// 打开时文件的 inode
+  inode: u64,
+
   /// 往头部读取内容的状态
   head_state: State,
 
@@ -27,9 +37,12 @@ pub struct TailReader {
   /// 用于控制读取取消的 token
   cancel_token: CancellationToken,
 
-  /// 用于收发事件的通道
-  tx: mpsc::Sender<Event>,
-  rx: mpsc::Receiver<Event>,
+  /// 用于接收内容事件的通道，发送端由读取状态持有
+  rx: EventReceiver,
+
+  /// 用于收发元数据事件（重命名、删除）的通道，它独立于内容事件，不会因内容堆积而阻塞
+  meta_tx: mpsc::UnboundedSender<Event>,
+  meta_rx: mpsc::UnboundedReceiver<Event>,
 
   /// 向前读取的 join handler
   jh_reading_head: Option<JoinHandle<()>>,
@@ -44,23 +57,35 @@ impl Reader for TailReader {
     // 无论文件如何重命名，我们都能找到它
     let file = File::open(path).await?;
     let fd = file.as_raw_fd();
+    let inode = file.metadata().await?.ino();
 
     // 创建通信通道
-    let (tx, rx) = mpsc::channel::<Event>(config.channel_size);
-
-    // 初始化用于读取的状态
-    let head_state = State::new_tail(path, fd, config.buffer_size, tx.clone()).await?;
-    let tail_state = State::new_tail(path, fd, config.buffer_size, tx.clone()).await?;
+    let (tx, rx) = event::channel(config.channel_size);
+    let (meta_tx, meta_rx) = mpsc::unbounded_channel::<Event>();
+
+    // 初始化用于读取的状态。只读取末尾若干行时，向前读取的状态直接从文件末尾开始
+    let head_state = match config.tail_lines {
+      None => State::new_tail(path, fd, config.buffer_size, tx.clone()).await?,
+      Some(lines) => State::new_end(path, fd, config.buffer_size, tx.clone())
+        .await?
+        .with_line_budget(lines),
+    }
+    .with_overflow(config.overflow);
+    let tail_state = State::new_tail(path, fd, config.buffer_size, tx.clone())
+      .await?
+      .with_overflow(config.overflow);
 
     // 返回文件读取器
     Ok(TailReader {
       config,
       file,
+      inode,
       head_state,
       tail_state,
       cancel_token: CancellationToken::new(),
-      tx,
       rx,
+      meta_tx,
+      meta_rx,
       jh_reading_head: None,
       jh_reading_tail: None,
     })
@@ -69,6 +94,12 @@ impl Reader for TailReader {
 
 impl ReaderBase for TailReader {
   async fn start(&mut self) -> Result<()> {
+    // 只读取末尾若干行时，仅往头部方向读取
+    if self.config.tail_lines.is_some() {
+      self.jh_reading_head = Some(self.spawn_reading_head());
+      return Ok(());
+    }
+
     // 初始化读取尾部一些内容
     self.init_read().await?;
 
@@ -93,7 +124,12 @@ impl ReaderBase for TailReader {
   }
 
   async fn changed(&mut self) -> Option<Vec<Event>> {
-    reader::poll_events(&mut self.rx, self.config.recv_buffer_size).await
+    reader::poll_events_with_metadata(&mut self.meta_rx, &self.rx, self.config.recv_buffer_size)
+      .await
+  }
+
+  fn inode(&self) -> Option<u64> {
+    Some(self.inode)
   }
 }
 
@@ -147,13 +183,21 @@ impl TailReader {
       // 用于读取的缓存
       let mut buffer = vec![0; config.buffer_size as usize];
 
-      // 一直读取，直至到达文件头部
-      while !state.has_reached_head() && !cancel_token.is_cancelled() {
+      // 一直读取，直至到达文件头部，或者读够了所需的行数
+      while !state.has_reached_head() && !state.is_out_of_budget() && !cancel_token.is_cancelled() {
         if let Err(e) = reader::read_head_lines(&mut buffer, &mut state).await {
           crate::eprintln!("Error while reading head lines: {e}");
           break;
         }
       }
+
+      // 到达了文件开头，通知等待前面内容的那些行不必再等
+      if state.has_reached_head()
+        && !cancel_token.is_cancelled()
+        && let Err(e) = state.send_head_end().await
+      {
+        crate::eprintln!("Error while sending head end: {e}");
+      }
     })
   }
 
@@ -168,12 +212,12 @@ impl TailReader {
     // 导出 config
     let config = self.config.clone();
 
-    // 取出事件发送通道，用于发送 metadata 变化事件
-    let tx = self.tx.clone();
+    // 取出元数据事件发送通道，用于发送 metadata 变化事件
+    let meta_tx = self.meta_tx.clone();
 
     // 启动新协程，监控文件变化
     tokio::spawn(async move {
-      // 创建文件系统监视器
+      // 创建文件系统监视器，并开始监控
       let mut watcher = match state.watcher(config.poll_interval) {
         Ok(w) => w,
         Err(e) => {
@@ -181,27 +225,45 @@ impl TailReader {
           return;
         }
       };
+      if let Err(e) = watcher.start() {
+        crate::eprintln!("Failed to start watcher: {e}");
+        return;
+      }
 
       // 用于读取的缓存
       let mut buffer = vec![0; config.buffer_size as usize];
 
+      // 最后一次出现新内容的时间，以及是否已经为这段没有新内容的时间发出过 Idle 事件
+      let mut last_content = Instant::now();
+      let mut idle_sent = false;
+
       // 循环监听
       'watch_loop: loop {
         tokio::select! {
           // 外部的取消信号
           _ = cancel_token.cancelled() => { break 'watch_loop; },
 
+          // 长时间没有新内容，发出一次 Idle 事件，直到出现新内容后才会再次检测
+          _ = Self::wait_idle(config.idle_timeout, last_content), if !idle_sent => {
+            idle_sent = true;
+            if let Err(e) = meta_tx.send(Event::Idle(last_content)) {
+              crate::eprintln!("Failed to send idle event: {e}");
+            }
+          },
+
           // 监控文件变化
           res = watcher.changed() => match res {
             // 文件元数据变更，可能是重命名或者被删除。如果被删除，则结束监听
             Ok(ChangedEvent::Metadata(event)) => {
-              if (event.send(&tx).await) {
+              if event.send(&meta_tx) {
                 break 'watch_loop;
               }
             },
 
             // 文件变更，对于我们从尾部读取的情况来说，就是尾部新增了内容
             Ok(ChangedEvent::Content) => {
+              last_content = Instant::now();
+              idle_sent = false;
               if let Err(e) = reader::read_tail_lines(&mut buffer, &mut state).await {
                 crate::eprintln!("Error while reading tail lines: {e}");
                 break 'watch_loop;
@@ -217,8 +279,19 @@ impl TailReader {
         }
       }
 
+      // 停止监控，文件可能已经被删除，忽略错误
+      let _ = watcher.stop().await;
+
       // 确保向前读取内容的流程也停止
       cancel_token.cancel();
     })
   }
+
+  /// 等待到最后一次出现新内容的时间之后的 timeout 时长，未配置时永远等待
+  async fn wait_idle(timeout: Option<Duration>, last_content: Instant) {
+    match timeout {
+      None => std::future::pending().await,
+      Some(timeout) => tokio::time::sleep_until((last_content + timeout).into()).await,
+    }
+  }
 }
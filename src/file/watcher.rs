@@ -33,11 +33,11 @@ pub enum MetadataEvent {
 impl MetadataEvent {
   /// 将自己转化为 reader::Event 发送出去，
   /// 返回是否中断轮询
-  pub async fn send(self, tx: &mpsc::Sender<Event>) -> bool {
+  pub fn send(self, tx: &mpsc::UnboundedSender<Event>) -> bool {
     match self {
       MetadataEvent::Any => false,
       MetadataEvent::Renamed(new_path) => {
-        if let Err(e) = tx.send(Event::Renamed(new_path)).await {
+        if let Err(e) = tx.send(Event::Renamed(new_path)) {
           crate::eprintln!("Failed to send renamed event: {e}");
           true
         } else {
@@ -45,7 +45,7 @@ impl MetadataEvent {
         }
       }
       MetadataEvent::Removed => {
-        if let Err(e) = tx.send(Event::Removed).await {
+        if let Err(e) = tx.send(Event::Removed) {
           crate::eprintln!("Failed to send removed event: {e}");
         }
         true
@@ -203,7 +203,8 @@ impl Watcher {
                 }
 
                 // 检查路径末尾是否有被删除的标记，有说明文件被删除，发送删除事件并结束轮询
-                if link.ends_with("(deleted)") {
+                // （注意 Path::ends_with 按路径组件比较，这里需要按字符串比较）
+                if link.as_os_str().to_string_lossy().ends_with(" (deleted)") {
                   let _ = tx.send(MetadataEvent::Removed);
                   break;
                 }
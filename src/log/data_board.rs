@@ -1,6 +1,24 @@
+use crate::log::{Label, LogLine};
+use regex::Regex;
 use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use std::sync::Arc;
-use std::{collections::BTreeMap, path::PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+
+/// 过滤条件版本的全局计数器，数据黑板与自定义的过滤条件都从这里分配版本，
+/// 使得不同的条件不会拿到相同的版本。版本 0 保留给不过滤任何日志的条件
+static FILTER_VERSIONS: AtomicUsize = AtomicUsize::new(1);
+
+/// 分配一个新的过滤条件版本
+pub(crate) fn next_filter_version() -> usize {
+  FILTER_VERSIONS.fetch_add(1, Ordering::Relaxed)
+}
 
 /// 从日志中发现的标签集合，用于过滤日志，布尔值代表是否选中
 #[derive(Default)]
@@ -11,11 +29,21 @@ pub struct TagsData {
   /// 用于快速查重的记录
   hashed_tags: HashMap<String, bool>,
 
+  /// 已经不再出现在日志中的标签（如所在的日志文件被淘汰或删除）当时的选择状态，
+  /// 它们再次出现时恢复该状态，而不是默认选中
+  removed_tags: HashMap<String, bool>,
+
+  /// 移除集，不再出现在日志中的标签会添加在这里，供展示它们的界面同步移除
+  removed_keys: HashSet<String>,
+
   /// 标签版本信息
   ver: usize,
 
   /// 标记标签内容是否有变化
   changed: bool,
+
+  /// 被选中的标签数量，随选择状态的变化维护，免去统计时遍历
+  selected: usize,
 }
 
 impl TagsData {
@@ -46,12 +74,44 @@ impl TagsData {
     }
   }
 
-  pub fn insert_new(&mut self, tag: &str) {
-    self.hashed_tags.insert(tag.to_string(), true);
+  /// 被选中的标签数量
+  pub fn selected_count(&self) -> usize {
+    self.selected
+  }
+
+  /// 加入新出现的标签，它默认的选择状态为 `default`，但如果它之前出现过，则恢复当时的选择状态
+  pub fn insert_new(&mut self, tag: &str, default: bool) {
+    let value = self.removed_tags.remove(tag).unwrap_or(default);
+    if self.hashed_tags.insert(tag.to_string(), value) == Some(true) {
+      self.selected -= 1;
+    }
+    self.selected += value as usize;
+    self.removed_keys.remove(tag);
     self.updated_tags.insert(tag.to_string());
     self.changed = true;
   }
 
+  /// 设置标签的选择状态。标签还没有出现时，记住该状态，在它出现时恢复，用于恢复上次会话的选择
+  pub fn remember(&mut self, tag: &str, value: bool) {
+    match self.hashed_tags.contains_key(tag) {
+      true => self.set_value(tag, value),
+      false => {
+        self.removed_tags.insert(tag.to_string(), value);
+      }
+    }
+  }
+
+  /// 移除不再出现在日志中的标签，但记住它的选择状态，以便它再次出现时恢复
+  pub fn remove(&mut self, tag: &str) {
+    if let Some(value) = self.hashed_tags.remove(tag) {
+      self.selected -= value as usize;
+      self.removed_tags.insert(tag.to_string(), value);
+      self.updated_tags.remove(tag);
+      self.removed_keys.insert(tag.to_string());
+      self.changed = true;
+    }
+  }
+
   pub fn get_version(&self) -> usize {
     self.ver
   }
@@ -67,12 +127,204 @@ impl TagsData {
     std::mem::take(&mut self.updated_tags)
   }
 
+  /// 取出上次取出之后被移除、且没有再次出现的标签
+  pub fn take_removed(&mut self) -> HashSet<String> {
+    std::mem::take(&mut self.removed_keys)
+  }
+
   fn set_value(&mut self, tag: &str, value: bool) {
     if let Some(flag) = self.hashed_tags.get_mut(tag) {
       if *flag != value {
         self.changed = true;
         *flag = value;
+        match value {
+          true => self.selected += 1,
+          false => self.selected -= 1,
+        }
+      }
+    }
+  }
+}
+
+/// 一次日志更新中积累的、待写入数据看板的统计信息。
+/// 解析日志时不持有数据看板的锁，处理完一批事件后再一次性写入，缩短界面等待锁的时间
+#[derive(Default)]
+pub struct DataBoardUpdates {
+  /// 本批日志中出现的标签，及其出现的行数
+  tags: HashMap<String, usize>,
+
+  /// 本批日志中出现的主机名（已去重）
+  hosts: HashSet<String>,
+
+  /// 本批中作为单独的行保留下来的无法解析的行数，被拼接或丢弃的行不计入
+  bad_lines: usize,
+
+  /// 本批中被加载或追加的行数，包括无法解析的行
+  new_lines: usize,
+
+  /// 本批中作为单独的行保留下来的行数，包括无法解析的行，被拼接或丢弃的行不计入
+  kept_lines: usize,
+
+  /// 本批中被删除或淘汰的日志的标签，及其行数
+  removed_tags: HashMap<String, usize>,
+
+  /// 本批中被删除或淘汰的行数
+  removed_lines: usize,
+
+  /// 本批中被删除或淘汰的无法解析的行数
+  removed_bad_lines: usize,
+}
+
+impl DataBoardUpdates {
+  /// 记录一行新的日志，收集它的标签与主机名
+  pub fn add_log(&mut self, log: &LogLine) {
+    self.new_lines += 1;
+    self.kept_lines += 1;
+    if let LogLine::Good(log) = log {
+      match self.tags.get_mut(&log.tag) {
+        Some(count) => *count += 1,
+        None => {
+          self.tags.insert(log.tag.clone(), 1);
+        }
       }
+      if !log.hostname.is_empty() && !self.hosts.contains(&log.hostname) {
+        self.hosts.insert(log.hostname.clone());
+      }
+    }
+  }
+
+  /// 记录一行作为单独的行保留下来的无法解析的行
+  pub fn add_bad_line(&mut self) {
+    self.new_lines += 1;
+    self.kept_lines += 1;
+    self.bad_lines += 1;
+  }
+
+  /// 记录一行没有作为单独的行保留的无法解析的行（被拼接到上一条日志中，或者被丢弃），
+  /// 它不计入保留的行数，但仍然使日志内容的版本变化
+  pub fn add_unkept_line(&mut self) {
+    self.new_lines += 1;
+  }
+
+  /// 记录一行被删除或淘汰的日志，扣除它的标签的行数
+  pub fn remove_log(&mut self, log: &LogLine) {
+    self.removed_lines += 1;
+    match log {
+      LogLine::Good(log) => *self.removed_tags.entry(log.tag.clone()).or_default() += 1,
+      LogLine::Bad(_) => self.removed_bad_lines += 1,
+    }
+  }
+
+  /// 是否没有任何需要写入的信息
+  pub fn is_empty(&self) -> bool {
+    self.new_lines == 0 && self.removed_lines == 0
+  }
+}
+
+/// 数据看板在某一时刻的统计数据，在一次加锁中取出，各项之间是一致的
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Stats {
+  /// 已发现的标签数量
+  pub tags: usize,
+
+  /// 被选中的标签数量
+  pub selected_tags: usize,
+
+  /// 各个标签当前已加载的日志行数，与数据看板共享，直到数据看板下一次写入
+  pub tag_counts: Arc<HashMap<String, usize>>,
+
+  /// 当前保留的行数，包括无法解析的行，被拼接、丢弃、删除或淘汰的行不计入
+  pub loaded_lines: usize,
+
+  /// 当前保留的无法解析的行数，被拼接、丢弃、删除或淘汰的行不计入
+  pub bad_lines: usize,
+}
+
+/// 按进程号过滤日志的条件，为闭区间 `[start, end]`，单个进程号即首尾相同的区间
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PidFilter {
+  start: i32,
+  end: i32,
+}
+
+impl PidFilter {
+  /// 只保留给定进程号的日志
+  pub fn exact(pid: i32) -> Self {
+    Self::range(pid, pid)
+  }
+
+  /// 只保留进程号在给定区间内的日志，首尾颠倒时自动交换
+  pub fn range(start: i32, end: i32) -> Self {
+    Self {
+      start: start.min(end),
+      end: start.max(end),
+    }
+  }
+
+  /// 日志是否符合条件。没有进程号的日志（如 rsyslog 自身的日志）的进程号为 0，
+  /// 只有区间包含 0 时才保留它们；无法解析的行没有进程号，总是被过滤
+  pub fn is_matched(&self, log: &LogLine) -> bool {
+    match log {
+      LogLine::Good(log) => (self.start..=self.end).contains(&log.pid),
+      LogLine::Bad(_) => false,
+    }
+  }
+}
+
+impl FromStr for PidFilter {
+  type Err = String;
+
+  /// 解析形如 `1234` 或者 `100-200` 的进程号条件
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let parse = |s: &str| {
+      s.trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|pid| *pid >= 0)
+        .ok_or(format!("invalid pid '{}'", s.trim()))
+    };
+    match s.split_once('-') {
+      None => Ok(Self::exact(parse(s)?)),
+      Some((start, end)) => Ok(Self::range(parse(start)?, parse(end)?)),
+    }
+  }
+}
+
+/// 排除日志的条件。形如 `/REGEX/` 时按正则表达式匹配日志内容，否则按子串匹配
+#[derive(Clone, Debug)]
+pub enum Exclusion {
+  Text(String),
+  Regex(Regex),
+}
+
+impl Exclusion {
+  /// 日志内容是否符合排除条件
+  pub fn is_matched(&self, content: &str) -> bool {
+    match self {
+      Exclusion::Text(text) => content.contains(text.as_str()),
+      Exclusion::Regex(re) => re.is_match(content),
+    }
+  }
+}
+
+impl FromStr for Exclusion {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.strip_prefix('/').and_then(|s| s.strip_suffix('/')) {
+      Some(pattern) if !pattern.is_empty() => Regex::new(pattern)
+        .map(Exclusion::Regex)
+        .map_err(|e| format!("invalid exclusion regex: {e}")),
+      _ => Ok(Exclusion::Text(s.to_string())),
+    }
+  }
+}
+
+impl Display for PidFilter {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self.start == self.end {
+      true => write!(f, "{}", self.start),
+      false => write!(f, "{}-{}", self.start, self.end),
     }
   }
 }
@@ -83,8 +335,59 @@ pub struct DataBoard {
   /// 日志的标签数据
   tags: TagsData,
 
+  /// 日志的主机名数据，和标签一样用于过滤日志
+  hosts: TagsData,
+
+  /// 被隐藏的日志等级
+  hidden_labels: HashSet<Label>,
+
+  /// 是否只保留无法解析的行，用于排查解析不了的日志格式
+  bad_lines_only: bool,
+
+  /// 排除的内容，符合其中任一条件的行被隐藏，条件的写法见 [Exclusion]
+  exclusions: Vec<String>,
+
+  /// 由排除的内容解析出的条件，无法解析的内容被忽略
+  exclusion_matchers: Vec<Exclusion>,
+
+  /// 进程号的过滤条件，为空时不过滤
+  pid_filter: Option<PidFilter>,
+
+  /// 日志等级过滤、是否只保留无法解析的行、排除内容、以及进程号过滤的版本
+  labels_ver: usize,
+
+  /// 上一次取出过滤条件版本时，各项选择的版本之和
+  filter_selections: AtomicUsize,
+
+  /// 为上述选择分配的过滤条件版本。初始时不过滤任何日志，版本为 0
+  filter_version: AtomicUsize,
+
+  /// 各个被锁定的标签，为它分配版本时上述过滤条件的版本，以及分配给它的版本
+  locked_tag_versions: HashMap<String, (usize, usize)>,
+
+  /// 当前保留的无法解析的行数，被删除或淘汰的行会被扣除
+  bad_lines: usize,
+
+  /// 当前保留的行数，包括无法解析的行，被删除或淘汰的行会被扣除
+  loaded_lines: usize,
+
+  /// 各个标签当前已加载的日志行数，被删除或淘汰的行会被扣除，行数归零的标签将被移除
+  tag_counts: Arc<HashMap<String, usize>>,
+
   /// 日志文件所在的根目录
   log_files_root: Arc<PathBuf>,
+
+  /// 已经请求、但还没有完成的日志文件加载数量
+  pending_loads: usize,
+
+  /// 日志标记的版本，每次有日志的标记状态变化时增加
+  marks_ver: usize,
+
+  /// 日志内容的版本，每次有日志行被加载、追加或者淘汰时增加
+  lines_ver: usize,
+
+  /// 长时间没有新内容的被跟踪日志，及其最后一次出现新内容的时间
+  idle_logs: HashMap<PathBuf, Instant>,
 }
 
 impl DataBoard {
@@ -97,10 +400,10 @@ impl DataBoard {
 }
 
 impl DataBoard {
-  /// 记录潜在可能得首次出现的日志标签
-  pub fn update_tag(&mut self, new_tag: &str) {
+  /// 记录潜在可能得首次出现的日志标签，`default` 为它首次出现时是否被选中
+  pub fn update_tag(&mut self, new_tag: &str, default: bool) {
     if !self.tags.contains(new_tag) {
-      self.tags.insert_new(new_tag);
+      self.tags.insert_new(new_tag, default);
     }
   }
 
@@ -114,10 +417,258 @@ impl DataBoard {
     &mut self.tags
   }
 
+  /// 记录潜在可能得首次出现的日志主机名
+  pub fn update_host(&mut self, new_host: &str) {
+    if !self.hosts.contains(new_host) {
+      self.hosts.insert_new(new_host, true);
+    }
+  }
+
+  /// 一次性写入一批日志更新积累的统计信息，新出现的标签默认的选择状态为 `new_tag_default`
+  pub fn apply_updates(&mut self, updates: DataBoardUpdates, new_tag_default: bool) {
+    if updates.new_lines > 0 {
+      self.update_lines();
+    }
+    self.loaded_lines =
+      (self.loaded_lines + updates.kept_lines).saturating_sub(updates.removed_lines);
+    self.bad_lines = (self.bad_lines + updates.bad_lines).saturating_sub(updates.removed_bad_lines);
+    for (tag, count) in &updates.tags {
+      self.update_tag(tag, new_tag_default);
+      *Arc::make_mut(&mut self.tag_counts)
+        .entry(tag.clone())
+        .or_default() += count;
+    }
+    for host in &updates.hosts {
+      self.update_host(host);
+    }
+
+    // 扣除被删除或淘汰的日志行，不再出现在任何日志中的标签被移除，但记住它的选择状态
+    for (tag, count) in &updates.removed_tags {
+      let tag_counts = Arc::make_mut(&mut self.tag_counts);
+      if let Some(loaded) = tag_counts.get_mut(tag) {
+        *loaded = loaded.saturating_sub(*count);
+        if *loaded == 0 {
+          tag_counts.remove(tag);
+          self.tags.remove(tag);
+        }
+      }
+    }
+  }
+
+  /// 取出当前的统计数据，不需要遍历任何集合
+  pub fn snapshot(&self) -> Stats {
+    Stats {
+      tags: self.tags.all().len(),
+      selected_tags: self.tags.selected_count(),
+      tag_counts: self.tag_counts.clone(),
+      loaded_lines: self.loaded_lines,
+      bad_lines: self.bad_lines,
+    }
+  }
+
+  /// 获取所有的日志主机名的容器
+  pub fn get_hosts(&self) -> &TagsData {
+    &self.hosts
+  }
+
+  /// 获取所有的日志主机名的容器，但是可以修改
+  pub fn get_hosts_mut(&mut self) -> &mut TagsData {
+    &mut self.hosts
+  }
+
+  /// 过滤条件的版本，标签或主机名的选择发生变化时，将从全局计数器中分配新的版本
+  pub fn get_filter_version(&self) -> usize {
+    let selections = self.tags.get_version() + self.hosts.get_version() + self.labels_ver;
+    if self.filter_selections.swap(selections, Ordering::Relaxed) != selections {
+      self
+        .filter_version
+        .store(next_filter_version(), Ordering::Relaxed);
+    }
+    self.filter_version.load(Ordering::Relaxed)
+  }
+
+  /// 在上述过滤条件之外，再只保留给定标签的日志时，过滤条件的版本。过滤条件不变时，
+  /// 同一个标签沿用同一个版本，使得锁定到该标签的遍历可以跨帧复用跳转链路
+  pub fn get_locked_tag_filter_version(&mut self, tag: &str) -> usize {
+    let base = self.get_filter_version();
+    match self.locked_tag_versions.get(tag) {
+      Some(&(locked_base, version)) if locked_base == base => version,
+      _ => {
+        let version = next_filter_version();
+        self
+          .locked_tag_versions
+          .insert(tag.to_string(), (base, version));
+        version
+      }
+    }
+  }
+
+  /// 切换某个日志等级的隐藏状态
+  pub fn toggle_label(&mut self, label: Label) {
+    if !self.hidden_labels.remove(&label) {
+      self.hidden_labels.insert(label);
+    }
+    self.labels_ver += 1;
+  }
+
+  /// 获取被隐藏的日志等级
+  pub fn get_hidden_labels(&self) -> &HashSet<Label> {
+    &self.hidden_labels
+  }
+
+  /// 切换是否只保留无法解析的行
+  pub fn toggle_bad_lines_only(&mut self) {
+    self.bad_lines_only = !self.bad_lines_only;
+    self.labels_ver += 1;
+  }
+
+  /// 是否只保留无法解析的行
+  pub fn is_bad_lines_only(&self) -> bool {
+    self.bad_lines_only
+  }
+
+  /// 设置排除的内容，符合其中任一条件的行被隐藏，条件的写法见 [Exclusion]
+  pub fn set_exclusions(&mut self, exclusions: Vec<String>) {
+    if self.exclusions != exclusions {
+      self.exclusion_matchers = exclusions.iter().filter_map(|s| s.parse().ok()).collect();
+      self.exclusions = exclusions;
+      self.labels_ver += 1;
+    }
+  }
+
+  /// 获取排除的内容
+  pub fn get_exclusions(&self) -> &[String] {
+    &self.exclusions
+  }
+
+  /// 设置进程号的过滤条件，为空时不过滤
+  pub fn set_pid_filter(&mut self, pid_filter: Option<PidFilter>) {
+    if self.pid_filter != pid_filter {
+      self.pid_filter = pid_filter;
+      self.labels_ver += 1;
+    }
+  }
+
+  /// 获取进程号的过滤条件
+  pub fn get_pid_filter(&self) -> Option<PidFilter> {
+    self.pid_filter
+  }
+
+  /// 记录保留了一行无法解析的行
+  pub fn add_bad_line(&mut self) {
+    self.bad_lines += 1;
+  }
+
+  /// 当前保留的无法解析的行数
+  pub fn get_bad_lines(&self) -> usize {
+    self.bad_lines
+  }
+
+  /// 记录一次日志标记状态的变化
+  pub fn update_marks(&mut self) {
+    self.marks_ver += 1;
+  }
+
+  /// 日志标记的版本，标记状态发生变化时，该版本会变化
+  pub fn get_marks_version(&self) -> usize {
+    self.marks_ver
+  }
+
+  /// 记录一次日志内容的变化
+  pub fn update_lines(&mut self) {
+    self.lines_ver += 1;
+  }
+
+  /// 日志内容的版本，有日志行被加载、追加或者淘汰时，该版本会变化
+  pub fn get_lines_version(&self) -> usize {
+    self.lines_ver
+  }
+
+  /// 记录被跟踪的日志从何时起没有新内容，为 None 时表示它又有了新内容
+  pub fn set_idle(&mut self, path: &Path, since: Option<Instant>) {
+    match since {
+      None => self.idle_logs.remove(path),
+      Some(since) => self.idle_logs.insert(path.to_path_buf(), since),
+    };
+  }
+
+  /// 没有新内容最久的那份被跟踪日志，及其最后一次出现新内容的时间
+  pub fn get_longest_idle(&self) -> Option<(&PathBuf, Instant)> {
+    self
+      .idle_logs
+      .iter()
+      .min_by_key(|(_, since)| **since)
+      .map(|(path, since)| (path, *since))
+  }
+
+  /// 正在生效的过滤条件数量：有标签或主机名未被选中时，各计为一个过滤条件，
+  /// 每个被隐藏的日志等级、每个排除的内容也计为一个，只保留无法解析的行、过滤进程号时也各计为一个
+  pub fn active_filters(&self) -> usize {
+    let deselected = |data: &TagsData| data.all().values().any(|selected| !selected);
+    deselected(&self.tags) as usize
+      + deselected(&self.hosts) as usize
+      + self.hidden_labels.len()
+      + self.exclusions.len()
+      + self.bad_lines_only as usize
+      + self.pid_filter.is_some() as usize
+  }
+
+  /// 检查日志是否被标签、主机名、日志等级、进程号或者排除的内容过滤掉了。
+  /// 只保留无法解析的行时，除了排除的内容，其他过滤条件不再生效
+  pub fn is_filtered(&self, log: &LogLine) -> bool {
+    if self
+      .exclusion_matchers
+      .iter()
+      .any(|exclusion| exclusion.is_matched(log.get_content()))
+    {
+      return true;
+    }
+
+    if self.bad_lines_only {
+      return !log.is_bad();
+    }
+
+    // 坏行没有进程号，过滤进程号时总是被过滤
+    if let Some(pid) = &self.pid_filter
+      && !pid.is_matched(log)
+    {
+      return true;
+    }
+
+    match log {
+      LogLine::Good(log) => {
+        !self.tags.get(&log.tag)
+          || (!log.hostname.is_empty() && !self.hosts.get(&log.hostname))
+          || self.hidden_labels.contains(&log.label)
+      }
+      LogLine::Bad(_) => false,
+    }
+  }
+
   /// 获取日志所在的根目录
   pub fn get_root_path(&self) -> Arc<PathBuf> {
     self.log_files_root.clone()
   }
+
+  /// 记录一次日志文件加载的开始
+  pub fn begin_loading(&mut self) {
+    self.pending_loads += 1;
+  }
+
+  /// 记录一次日志文件加载的结束
+  pub fn end_loading(&mut self) {
+    self.pending_loads = self.pending_loads.saturating_sub(1);
+  }
+
+  /// 尚未完成的日志文件加载数量
+  pub fn pending_loads(&self) -> usize {
+    self.pending_loads
+  }
+
+  /// 是否有日志文件正在加载
+  pub fn is_loading(&self) -> bool {
+    self.pending_loads > 0
+  }
 }
 
 #[cfg(test)]
@@ -128,9 +679,9 @@ mod tests {
   #[test]
   fn test_tags_data() {
     let mut db = DataBoard::default();
-    db.update_tag("test1");
-    db.update_tag("test2");
-    db.update_tag("test3");
+    db.update_tag("test1", true);
+    db.update_tag("test2", true);
+    db.update_tag("test3", true);
 
     let mut true_tags: HashMap<String, bool> = HashMap::new();
     true_tags.insert("test1".to_string(), true);
@@ -149,4 +700,370 @@ mod tests {
     db.get_tags_mut().toggle("test3");
     assert_eq!(db.get_tags().get("test3"), true);
   }
+
+  #[test]
+  fn test_tags_data_remembers_removed() {
+    let mut db = DataBoard::default();
+    db.update_tag("test1", true);
+    db.update_tag("test2", true);
+    db.get_tags_mut().unset("test1");
+
+    // 移除后不再出现在标签集合中
+    db.get_tags_mut().remove("test1");
+    db.get_tags_mut().remove("test2");
+    assert!(!db.get_tags().contains("test1"));
+    assert!(db.get_tags().all().is_empty());
+
+    // 被移除的标签可以取出一次；在取出前再次出现的标签不算被移除
+    db.update_tag("test3", true);
+    db.get_tags_mut().remove("test3");
+    db.update_tag("test3", true);
+    assert_eq!(
+      db.get_tags_mut().take_removed(),
+      ["test1".to_string(), "test2".to_string()].into()
+    );
+    assert!(db.get_tags_mut().take_removed().is_empty());
+
+    // 再次出现时恢复之前的选择状态，从未出现过的标签仍默认选中
+    db.update_tag("test1", true);
+    db.update_tag("test2", true);
+    db.update_tag("test3", true);
+    assert!(!db.get_tags().get("test1"));
+    assert!(db.get_tags().get("test2"));
+    assert!(db.get_tags().get("test3"));
+
+    // 预先记住的选择状态在标签出现时生效，已经出现的标签立即生效
+    db.get_tags_mut().remember("test3", false);
+    db.get_tags_mut().remember("test4", false);
+    assert!(!db.get_tags().get("test3"));
+    assert!(!db.get_tags().contains("test4"));
+    db.update_tag("test4", true);
+    assert!(!db.get_tags().get("test4"));
+  }
+
+  #[test]
+  fn test_filter_version_allocation() {
+    // 没有任何选择变化时不过滤日志，版本为 0
+    let mut db = DataBoard::default();
+    assert_eq!(db.get_filter_version(), 0);
+
+    // 选择变化后从全局计数器分配版本，不会和自定义条件的版本重复，且再次变化时版本继续递增
+    db.toggle_label(Label::Error);
+    let ver = db.get_filter_version();
+    assert_eq!(db.get_filter_version(), ver);
+    let other = next_filter_version();
+    assert_ne!(ver, other);
+    db.toggle_label(Label::Error);
+    assert!(db.get_filter_version() > other);
+  }
+
+  #[test]
+  fn test_locked_tag_filter_version() {
+    let mut db = DataBoard::default();
+
+    // 每个锁定的标签有自己的版本，过滤条件不变时保持不变
+    let kernel = db.get_locked_tag_filter_version("kernel");
+    let sshd = db.get_locked_tag_filter_version("sshd");
+    assert_ne!(kernel, sshd);
+    assert_ne!(kernel, db.get_filter_version());
+    assert_eq!(db.get_locked_tag_filter_version("kernel"), kernel);
+
+    // 过滤条件变化后，重新分配版本
+    db.toggle_label(Label::Error);
+    assert_ne!(db.get_locked_tag_filter_version("kernel"), kernel);
+  }
+
+  #[test]
+  fn test_hosts_filter() {
+    let log_a = LogLine::new("2026-01-17T10:22:55.642782+08:00 host-a kernel: hello".to_string());
+    let log_b = LogLine::new("2026-01-17T10:22:56.642782+08:00 host-b kernel: world".to_string());
+
+    let mut db = DataBoard::default();
+    for log in [&log_a, &log_b] {
+      if let LogLine::Good(log) = log {
+        db.update_tag(&log.tag, true);
+        db.update_host(&log.hostname);
+      }
+    }
+
+    assert_eq!(db.get_hosts().all().len(), 2);
+    assert!(!db.is_filtered(&log_a));
+    assert!(!db.is_filtered(&log_b));
+
+    // 取消选择某个主机名后，过滤版本变化，且该主机的日志被过滤
+    let ver = db.get_filter_version();
+    db.get_hosts_mut().unset("host-b");
+    db.get_hosts_mut().update_version();
+    assert_ne!(db.get_filter_version(), ver);
+    assert!(!db.is_filtered(&log_a));
+    assert!(db.is_filtered(&log_b));
+
+    // 标签与主机名的过滤条件同时生效
+    db.get_tags_mut().unset("kernel");
+    assert!(db.is_filtered(&log_a));
+  }
+
+  #[test]
+  fn test_exclusions() {
+    let log_a = LogLine::new("2026-01-17T10:22:55.642782+08:00 host kernel: hello".to_string());
+    let log_b = LogLine::new("2026-01-17T10:22:56.642782+08:00 host kernel: world".to_string());
+    let bad = LogLine::new("hello, not a syslog line".to_string());
+
+    let mut db = DataBoard::default();
+    db.update_tag("kernel", true);
+    db.update_host("host");
+
+    // 包含排除内容的行被过滤，无论它能否被解析
+    let ver = db.get_filter_version();
+    db.set_exclusions(vec!["hello".to_string()]);
+    assert_ne!(db.get_filter_version(), ver);
+    assert_eq!(db.active_filters(), 1);
+    assert!(db.is_filtered(&log_a));
+    assert!(!db.is_filtered(&log_b));
+    assert!(db.is_filtered(&bad));
+
+    // 只保留无法解析的行时，排除的内容仍然生效
+    db.toggle_bad_lines_only();
+    assert!(db.is_filtered(&bad));
+
+    // 排除的内容没有变化时，过滤版本不变
+    let ver = db.get_filter_version();
+    db.set_exclusions(vec!["hello".to_string()]);
+    assert_eq!(db.get_filter_version(), ver);
+    db.set_exclusions(vec![]);
+    assert!(!db.is_filtered(&bad));
+
+    // 形如 /REGEX/ 的内容按正则表达式匹配
+    db.toggle_bad_lines_only();
+    db.set_exclusions(vec!["/^w.r/".to_string()]);
+    assert!(!db.is_filtered(&log_a));
+    assert!(db.is_filtered(&log_b));
+    assert!(!db.is_filtered(&bad));
+  }
+
+  #[test]
+  fn test_parse_exclusion() {
+    assert!(matches!("hello".parse(), Ok(Exclusion::Text(s)) if s == "hello"));
+    assert!(matches!("/he.*o/".parse(), Ok(Exclusion::Regex(re)) if re.as_str() == "he.*o"));
+
+    // 只有一个斜杠或者斜杠之间为空时，按子串匹配
+    assert!(matches!("/".parse(), Ok(Exclusion::Text(s)) if s == "/"));
+    assert!(matches!("//".parse(), Ok(Exclusion::Text(s)) if s == "//"));
+    assert!("/[a/".parse::<Exclusion>().is_err());
+  }
+
+  #[test]
+  fn test_pid_filter() {
+    let logs: Vec<_> = [
+      "2026-01-17T10:22:55.642782+08:00 host sshd[42]: accepted",
+      "2026-01-17T10:22:56.642782+08:00 host sshd[150]: closed",
+      "2026-01-17T10:22:57.642782+08:00 host cron[200]: tick",
+      "2026-01-17T10:22:58.642782+08:00 host rsyslogd: restarted",
+      "not a syslog line",
+    ]
+    .into_iter()
+    .map(|s| LogLine::new(s.to_string()))
+    .collect();
+
+    let mut db = DataBoard::default();
+    for tag in ["sshd", "cron", "rsyslogd"] {
+      db.update_tag(tag, true);
+    }
+    db.update_host("host");
+    let kept = |db: &DataBoard| -> Vec<usize> {
+      (0..logs.len())
+        .filter(|i| !db.is_filtered(&logs[*i]))
+        .collect()
+    };
+    assert_eq!(kept(&db), vec![0, 1, 2, 3, 4]);
+
+    // 单个进程号，跨越不同的标签也只保留该进程的日志
+    let ver = db.get_filter_version();
+    db.set_pid_filter(Some("150".parse().unwrap()));
+    assert_ne!(db.get_filter_version(), ver);
+    assert_eq!(db.active_filters(), 1);
+    assert_eq!(kept(&db), vec![1]);
+
+    // 进程号区间，包含首尾
+    db.set_pid_filter(Some(" 42 - 200 ".parse().unwrap()));
+    assert_eq!(kept(&db), vec![0, 1, 2]);
+    db.set_pid_filter(Some("200-100".parse().unwrap()));
+    assert_eq!(kept(&db), vec![1, 2]);
+
+    // 没有进程号的日志，只有请求了 0 时才保留
+    db.set_pid_filter(Some("0".parse().unwrap()));
+    assert_eq!(kept(&db), vec![3]);
+    db.set_pid_filter(Some("0-42".parse().unwrap()));
+    assert_eq!(kept(&db), vec![0, 3]);
+
+    // 与标签过滤一同生效；条件没有变化时，过滤版本不变
+    db.get_tags_mut().unset("sshd");
+    assert_eq!(kept(&db), vec![3]);
+    let ver = db.get_filter_version();
+    db.set_pid_filter(Some(PidFilter::range(0, 42)));
+    assert_eq!(db.get_filter_version(), ver);
+    db.set_pid_filter(None);
+    assert_eq!(kept(&db), vec![2, 3, 4]);
+
+    // 解析与展示
+    assert_eq!(PidFilter::exact(42).to_string(), "42");
+    assert_eq!(
+      "200-100".parse::<PidFilter>().unwrap().to_string(),
+      "100-200"
+    );
+    assert!("".parse::<PidFilter>().is_err());
+    assert!("-5".parse::<PidFilter>().is_err());
+    assert!("12-ab".parse::<PidFilter>().is_err());
+  }
+
+  #[test]
+  fn test_apply_updates() {
+    let logs: Vec<_> = [
+      "2026-01-17T10:22:55.642782+08:00 host-a kernel: hello",
+      "2026-01-17T10:22:56.642782+08:00 host-a sshd[42]: world",
+      "not a syslog line",
+      "2026-01-17T10:22:57.642782+08:00 host-b kernel: again",
+      "2026-01-17T10:22:58.642782+08:00 cron[7]: tick",
+    ]
+    .into_iter()
+    .map(|s| LogLine::new(s.to_string()))
+    .collect();
+
+    // 逐行更新
+    let mut per_line = DataBoard::default();
+    per_line.update_tag("cron", true);
+    per_line.get_tags_mut().unset("cron");
+    per_line.get_tags_mut().remove("cron");
+    for log in &logs {
+      match log {
+        LogLine::Good(log) => {
+          per_line.update_tag(&log.tag, false);
+          if !log.hostname.is_empty() {
+            per_line.update_host(&log.hostname);
+          }
+        }
+        LogLine::Bad(_) => per_line.add_bad_line(),
+      }
+    }
+
+    // 成批更新
+    let mut batched = DataBoard::default();
+    batched.update_tag("cron", true);
+    batched.get_tags_mut().unset("cron");
+    batched.get_tags_mut().remove("cron");
+    let mut updates = DataBoardUpdates::default();
+    assert!(updates.is_empty());
+    for log in &logs {
+      match log {
+        LogLine::Good(_) => updates.add_log(log),
+        LogLine::Bad(_) => updates.add_bad_line(),
+      }
+    }
+    assert!(!updates.is_empty());
+    batched.apply_updates(updates, false);
+
+    assert_eq!(batched.get_tags().all(), per_line.get_tags().all());
+    assert_eq!(batched.get_hosts().all(), per_line.get_hosts().all());
+    assert_eq!(batched.get_bad_lines(), per_line.get_bad_lines());
+    assert_eq!(batched.get_lines_version(), 1);
+
+    // 被移除的标签再次出现时，仍然恢复之前的选择状态
+    assert!(!batched.get_tags().get("cron"));
+    assert!(!batched.get_tags().get("kernel"));
+  }
+
+  #[test]
+  fn test_apply_removals() {
+    let kernel = LogLine::new("2026-01-17T10:22:55.642782+08:00 host kernel: hello".to_string());
+    let cron = LogLine::new("2026-01-17T10:22:58.642782+08:00 host cron[7]: tick".to_string());
+
+    let mut db = DataBoard::default();
+    let mut updates = DataBoardUpdates::default();
+    for log in [&kernel, &kernel, &cron] {
+      updates.add_log(log);
+    }
+    updates.add_bad_line();
+    updates.add_bad_line();
+    updates.add_unkept_line();
+    db.apply_updates(updates, true);
+    assert_eq!(db.get_bad_lines(), 2);
+    assert_eq!(db.snapshot().loaded_lines, 5);
+    db.get_tags_mut().unset("cron");
+
+    // 扣除部分行后，标签仍然存在
+    let mut removals = DataBoardUpdates::default();
+    removals.remove_log(&kernel);
+    assert!(!removals.is_empty());
+    db.apply_updates(removals, true);
+    assert_eq!(db.snapshot().tag_counts.get("kernel"), Some(&1));
+    assert_eq!(db.snapshot().loaded_lines, 4);
+    assert!(db.get_tags().contains("kernel"));
+
+    // 行数归零的标签被移除，但再次出现时恢复之前的选择状态
+    let mut removals = DataBoardUpdates::default();
+    removals.remove_log(&cron);
+    removals.remove_log(&LogLine::new("not a syslog line".to_string()));
+    db.apply_updates(removals, true);
+    assert_eq!(db.snapshot().tag_counts.get("cron"), None);
+    assert!(!db.get_tags().contains("cron"));
+    assert_eq!(db.snapshot().selected_tags, 1);
+    assert_eq!(db.get_bad_lines(), 1);
+    assert_eq!(db.snapshot().loaded_lines, 2);
+
+    let mut updates = DataBoardUpdates::default();
+    updates.add_log(&cron);
+    db.apply_updates(updates, true);
+    assert!(!db.get_tags().get("cron"));
+  }
+
+  #[test]
+  fn test_snapshot() {
+    let mut db = DataBoard::default();
+    assert_eq!(db.snapshot(), Stats::default());
+
+    // 直接记录标签，只影响标签数量与选择数量
+    db.update_tag("kernel", true);
+    db.update_tag("sshd", false);
+    db.update_tag("kernel", false);
+    let stats = db.snapshot();
+    assert_eq!((stats.tags, stats.selected_tags), (2, 1));
+    assert!(stats.tag_counts.is_empty());
+
+    // 成批读取日志行，累计各个标签的行数
+    let mut updates = DataBoardUpdates::default();
+    for s in [
+      "2026-01-17T10:22:55.642782+08:00 host kernel: hello",
+      "2026-01-17T10:22:56.642782+08:00 host kernel: world",
+      "2026-01-17T10:22:57.642782+08:00 host cron[7]: tick",
+      "not a syslog line",
+    ] {
+      let log = LogLine::new(s.to_string());
+      match log.is_bad() {
+        true => updates.add_bad_line(),
+        false => updates.add_log(&log),
+      }
+    }
+    db.apply_updates(updates, true);
+    let stats = db.snapshot();
+    assert_eq!((stats.tags, stats.selected_tags), (3, 2));
+    assert_eq!(stats.tag_counts.get("kernel"), Some(&2));
+    assert_eq!(stats.tag_counts.get("cron"), Some(&1));
+    assert_eq!(stats.tag_counts.get("sshd"), None);
+    assert_eq!((stats.loaded_lines, stats.bad_lines), (4, 1));
+
+    // 选择状态的变化与标签的移除反映在之后的统计中，已经取出的统计数据不变
+    db.get_tags_mut().toggle("sshd");
+    db.get_tags_mut().unset("kernel");
+    db.get_tags_mut().remove("cron");
+    let mut updates = DataBoardUpdates::default();
+    updates.add_log(&LogLine::new(
+      "2026-01-17T10:22:58.642782+08:00 host kernel: again".to_string(),
+    ));
+    db.apply_updates(updates, true);
+    let latest = db.snapshot();
+    assert_eq!((latest.tags, latest.selected_tags), (2, 1));
+    assert_eq!(latest.tag_counts.get("kernel"), Some(&3));
+    assert_eq!(latest.loaded_lines, 5);
+    assert_eq!(stats.tag_counts.get("kernel"), Some(&2));
+  }
 }
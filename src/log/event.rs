@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 /// 日志事件的定义
 pub enum Event {
   /// 普通的定周期事件，代表没有什么特殊的事情发生
@@ -5,4 +7,7 @@ pub enum Event {
 
   /// 代表本日志被删除
   Removed,
+
+  /// 代表本日志长时间没有新内容，携带最后一次出现新内容的时间
+  Idle(Instant),
 }
@@ -0,0 +1,182 @@
+//! 用正则表达式描述的日志行格式，用于读取不符合 syslog 格式的应用日志
+
+use crate::log::{Label, NormalLogLine, log_line};
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime};
+use regex::Regex;
+use std::str::FromStr;
+
+/// 没有时区信息的时间戳依次尝试的格式，按本地时区理解
+const NAIVE_TIMESTAMP_FORMATS: [&str; 3] = [
+  "%Y-%m-%d %H:%M:%S%.f",
+  "%Y-%m-%dT%H:%M:%S%.f",
+  "%Y/%m/%d %H:%M:%S%.f",
+];
+
+/// 日志行的格式模板，是一个带有命名捕获组的正则表达式，如 `\[(?P<level>\w+)\] (?P<timestamp>\S+) (?P<message>.*)`。
+///
+/// 必须包含 `timestamp` 与 `message` 两个捕获组，还可以包含 `host`、`tag`、`pid`、`level`，
+/// 缺少的字段为空。`level` 仅用于判断日志等级，它与内容一同参与关键字匹配。
+#[derive(Clone, Debug)]
+pub struct LineTemplate {
+  re: Regex,
+}
+
+impl LineTemplate {
+  /// 编译格式模板，缺少必须的捕获组时返回错误
+  pub fn new(pattern: &str) -> Result<Self, String> {
+    let re = Regex::new(pattern).map_err(|e| format!("invalid line template: {e}"))?;
+    for name in ["timestamp", "message"] {
+      if !re.capture_names().flatten().any(|n| n == name) {
+        return Err(format!(
+          "line template '{pattern}' has no '(?P<{name}>...)' group"
+        ));
+      }
+    }
+    Ok(Self { re })
+  }
+
+  /// 按模板解析一行日志，模板不匹配、或者时间戳、pid 无法解析时返回 None
+  pub(super) fn parse(&self, line: &str) -> Option<NormalLogLine> {
+    let caps = self.re.captures(line)?;
+    let field = |name: &str| caps.name(name).map_or("", |m| m.as_str());
+
+    let timestamp = parse_timestamp(field("timestamp"))?;
+    let pid = match field("pid") {
+      "" => 0,
+      pid => pid.parse().ok()?,
+    };
+    let message = field("message").to_string();
+    let label = match log_line::label_of(field("level")) {
+      Label::Unknown => log_line::label_with_level(log_line::label_of(&message), field("level")),
+      label => label,
+    };
+
+    Some(NormalLogLine {
+      timestamp,
+      hostname: field("host").to_string(),
+      tag: field("tag").to_string(),
+      pid,
+      message,
+      label,
+      ..Default::default()
+    })
+  }
+}
+
+impl FromStr for LineTemplate {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::new(s)
+  }
+}
+
+/// 解析模板捕获到的时间戳，优先按 RFC3339 解析，否则按本地时区理解常见的格式
+fn parse_timestamp(s: &str) -> Option<DateTime<FixedOffset>> {
+  if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+    return Some(dt);
+  }
+
+  NAIVE_TIMESTAMP_FORMATS
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+    .and_then(|dt| dt.and_local_timezone(Local).single())
+    .map(|dt| dt.fixed_offset())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::log::LogLine;
+
+  #[test]
+  fn test_line_template() {
+    let template: LineTemplate =
+      r"^\[(?P<level>\w+)\] (?P<timestamp>\S+) (?P<tag>\w+)(\[(?P<pid>\d+)\])?: (?P<message>.*)$"
+        .parse()
+        .unwrap();
+
+    // syslog 格式的行仍然由内置的解析器处理
+    let log = LogLine::parse(
+      "2026-01-17T10:22:55.642782+08:00 host kernel: usb 1-1: reset".to_string(),
+      Some(&template),
+    );
+    assert_eq!(log.get_tag(), Some("kernel"));
+
+    // 不符合 syslog 格式的行按模板解析，日志等级来自 level 字段
+    let log = LogLine::parse(
+      "[ERROR] 2026-01-17T10:22:55.642782+08:00 worker[42]: job 7 done".to_string(),
+      Some(&template),
+    );
+    let LogLine::Good(log) = log else {
+      panic!("line should match the template");
+    };
+    assert_eq!(
+      log.timestamp.to_rfc3339(),
+      "2026-01-17T10:22:55.642782+08:00"
+    );
+    assert_eq!(log.hostname, "");
+    assert_eq!(log.tag, "worker");
+    assert_eq!(log.pid, 42);
+    assert_eq!(log.message, "job 7 done");
+    assert_eq!(log.label, Label::Error);
+    assert_eq!(
+      log.raw,
+      "[ERROR] 2026-01-17T10:22:55.642782+08:00 worker[42]: job 7 done"
+    );
+
+    // level 字段没有关键字时，仍然按内容判断日志等级
+    let log = LogLine::parse(
+      "[INFO] 2026-01-17T10:22:55+08:00 worker: connect failed".to_string(),
+      Some(&template),
+    );
+    assert!(matches!(log, LogLine::Good(log) if log.label == Label::Error && log.pid == 0));
+
+    // 内容也没有关键字时，level 字段可以给出 info、debug 等级
+    for (level, label) in [("INFO", Label::Info), ("debug", Label::Debug)] {
+      let log = LogLine::parse(
+        format!("[{level}] 2026-01-17T10:22:55+08:00 worker: job 7 done"),
+        Some(&template),
+      );
+      assert_eq!(log.get_label(), Some(label));
+    }
+
+    // 不匹配模板、或者时间戳无法解析的行仍然是坏行
+    for line in ["worker: job 7 done", "[INFO] yesterday worker: job 7 done"] {
+      assert!(LogLine::parse(line.to_string(), Some(&template)).is_bad());
+    }
+    assert!(
+      LogLine::parse(
+        "[INFO] 2026-01-17T10:22:55+08:00 worker: x".to_string(),
+        None
+      )
+      .is_bad()
+    );
+  }
+
+  #[test]
+  fn test_line_template_naive_timestamp() {
+    let template = LineTemplate::new(
+      r"^(?P<timestamp>\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(\.\d+)?) (?P<message>.*)$",
+    )
+    .unwrap();
+
+    // 没有时区信息的时间戳按本地时区理解
+    let log = LogLine::parse("2026-01-17 10:22:55.5 started".to_string(), Some(&template));
+    let expected = NaiveDateTime::parse_from_str("2026-01-17 10:22:55.5", "%Y-%m-%d %H:%M:%S%.f")
+      .unwrap()
+      .and_local_timezone(Local)
+      .unwrap()
+      .fixed_offset();
+    assert_eq!(log.get_timestamp(), Some(expected));
+    assert_eq!(log.get_content(), "started");
+  }
+
+  #[test]
+  fn test_line_template_errors() {
+    assert!(LineTemplate::new(r"(?P<timestamp>\S+) (?P<message>.*)").is_ok());
+    assert!(LineTemplate::new(r"(?P<timestamp>\S+) (.*)").is_err());
+    assert!(LineTemplate::new(r"(\S+) (?P<message>.*)").is_err());
+    assert!(LineTemplate::new(r"(?P<timestamp>\S+ (?P<message>.*)").is_err());
+  }
+}
@@ -1,9 +1,9 @@
 use super::log_file_content::LogFileContent;
 use crate::file::{
-  Event, HeadReader, TailReader,
+  Event, HeadReader, StreamReader, TailReader, is_stdin,
   reader::{self, Reader, ReaderBase},
 };
-use crate::log::{DataBoard, Event as LogEvent, LogLine};
+use crate::log::{Config, DataBoard, DataBoardUpdates, Event as LogEvent, LineTemplate, LogLine};
 use anyhow::Result;
 use enum_dispatch::enum_dispatch;
 use std::{path::PathBuf, sync::Arc};
@@ -14,6 +14,7 @@ use tokio::sync::Mutex;
 enum AnyReader {
   Head(HeadReader),
   Tail(TailReader),
+  Stream(StreamReader),
 }
 
 /// 维护一份日志文件的内容读取、名称变更与删除，同时提供一些只读查询接口
@@ -26,72 +27,188 @@ pub struct LogFile {
 
   /// 文件内容读取器
   reader: AnyReader,
+
+  /// 已经从读取器中取出、但还没有处理完的事件。
+  /// 处理过程中等待数据看板时可能被取消，这些事件保留到下一次处理
+  pending_events: Vec<Event>,
+
+  /// 已经处理、但还没有写入数据看板的统计信息。
+  /// 等待数据看板时可能被取消，这些信息保留到下一次写入
+  board_updates: DataBoardUpdates,
+
+  /// 已经处理、但还没有交给调用者的日志事件（删除、空闲）。
+  /// 等待数据看板时可能被取消，这些事件保留到下一次返回
+  log_events: Vec<LogEvent>,
+
+  /// 是否将无法解析的行拼接到上一条日志的内容中
+  join_continuation_lines: bool,
+
+  /// 新出现的标签默认是否被选中
+  new_tag_default: bool,
+
+  /// 是否保留无法解析的行
+  keep_bad_lines: bool,
+
+  /// 无法按 syslog 格式解析时使用的格式模板
+  line_template: Option<LineTemplate>,
+
+  /// 从头部读取时，后面的行先于前面的行到来，无法解析的行需要暂存，
+  /// 等到它们所属的日志到来后再拼接（按到来的顺序存放，也即逆序）。
+  /// 读到文件开头时仍然没有所属日志的，按无法解析的行处理
+  head_continuation_lines: Vec<LogLine>,
 }
 
 impl LogFile {
   /// 打开指定文件，并开始异步读取内容，监听它的变化。
   ///
   /// 使用 `latest` 参数指明该文件是否是最新的、正在被系统更新的文件，是我们将持续追踪它的最新内容,
-  /// 否则一次性读完内容后，就会自动结束异步读取流程。
+  /// 否则一次性读完内容后，就会自动结束异步读取流程。若配置了只读取末尾若干行，则无论是否最新，
+  /// 都只从尾部读取这些行。
   ///
   /// `tags` 参数是之前历史上已经查询出来的一些标签记录，在打开新日志时，它可以用于去重。
-  pub async fn open(path: PathBuf, latest: bool) -> Result<LogFile> {
-    let config = reader::Config::default();
-    let mut reader = if latest {
-      AnyReader::Tail(TailReader::open(&path, config).await?)
+  ///
+  /// 若路径为 `-`，则持续读取标准输入的内容。
+  ///
+  /// `config` 参数是本文件所属的系统日志的配置，如日志内容的分块大小、读取的缓冲区大小等。
+  pub async fn open(path: PathBuf, latest: bool, config: &Config) -> Result<LogFile> {
+    // 未指定读取的缓冲区大小时，根据文件中行的长度选择。标准输入无法采样，使用默认大小
+    let default_buffer_size = reader::Config::default().buffer_size;
+    let buffer_size = match config.buffer_size {
+      Some(buffer_size) => buffer_size,
+      None if is_stdin(&path) => default_buffer_size,
+      None => reader::sample_buffer_size(&path, default_buffer_size)
+        .await
+        .unwrap_or(default_buffer_size),
+    };
+
+    let reader_config = reader::Config {
+      buffer_size,
+      poll_interval: config.poll_interval,
+      idle_timeout: config.idle_timeout,
+      tail_lines: config.tail_lines,
+      overflow: config.overflow,
+      // 旧的日志文件不会再被写入，最后一行即使没有换行符也是完整的
+      flush_partial_at_eof: true,
+      ..Default::default()
+    };
+    let mut reader = if is_stdin(&path) {
+      AnyReader::Stream(StreamReader::open(&path, reader_config).await?)
+    } else if latest || config.tail_lines.is_some() {
+      AnyReader::Tail(TailReader::open(&path, reader_config).await?)
     } else {
-      AnyReader::Head(HeadReader::open(&path, config).await?)
+      AnyReader::Head(HeadReader::open(&path, reader_config).await?)
     };
 
     reader.start().await?;
 
     Ok(LogFile {
       path,
-      content: LogFileContent::default(),
+      content: LogFileContent::new(config.chunk_capacity),
       reader,
+      pending_events: Vec::new(),
+      board_updates: DataBoardUpdates::default(),
+      log_events: Vec::new(),
+      join_continuation_lines: config.join_continuation_lines,
+      new_tag_default: config.new_tag_default,
+      keep_bad_lines: config.keep_bad_lines,
+      line_template: config.line_template.clone(),
+      head_continuation_lines: Vec::new(),
     })
   }
 
   /// 处理一次文件内容的变更检查与处理
   ///
   /// # Cancel Safety
-  /// 本函数保证，当 await 被取消时，没有副作用。
+  /// 本函数保证，当 await 被取消时，没有副作用，已经从读取器中取出的事件会在下一次调用时处理，
+  /// 已经处理、但还没有写入数据看板的统计信息会在下一次调用时写入，
+  /// 还没有交给调用者的删除、空闲事件会在下一次调用时返回。
   pub async fn update(&mut self, data_board: Arc<Mutex<DataBoard>>) -> Option<Vec<LogEvent>> {
-    if let Some(events) = self.reader.changed().await {
-      // 处理多个日志底层事件，消化掉内容新增事件，并向数据看板更新可能的新增标签，
-      // 消化掉更名事件，
-      // 如果是删除事件，则直接向调用者透传。
-      let mut result = vec![];
-      for event in events.into_iter() {
-        match event {
-          Event::NewHead(s) => {
-            let new_log = LogLine::new(s);
-            if new_log.is_bad() {
-              continue;
+    // 先取出新的事件，如果上一次处理被取消，则继续处理上一次剩下的事件，
+    // 或者写入上一次剩下的统计信息、返回上一次剩下的日志事件。
+    // 无法读到新的变更，代表本阅读器已经出错，返回空
+    if self.pending_events.is_empty() && self.board_updates.is_empty() && self.log_events.is_empty()
+    {
+      self.pending_events = self.reader.changed().await?;
+    }
+
+    // 处理多个日志底层事件，消化掉内容新增事件，并收集可能的新增标签，
+    // 消化掉更名事件，
+    // 如果是删除事件，则直接向调用者透传。
+    for event in std::mem::take(&mut self.pending_events) {
+      match event {
+        Event::NewHead(s) => {
+          let mut new_log = LogLine::parse_bytes(s, self.line_template.as_ref());
+          if new_log.is_bad() {
+            if self.join_continuation_lines {
+              self.head_continuation_lines.push(new_log);
+            } else if self.keep_bad_lines {
+              self.board_updates.add_bad_line();
+              self.content.push_front(new_log);
+            } else {
+              self.board_updates.add_unkept_line();
             }
-            self.update_data_board(&new_log, &data_board).await;
-            self.content.push_front(new_log);
+            continue;
           }
-          Event::NewTail(s) => {
-            let new_log = LogLine::new(s);
-            if new_log.is_bad() {
-              continue;
+          if let LogLine::Good(log) = &mut new_log {
+            for line in self.head_continuation_lines.drain(..).rev() {
+              self.board_updates.add_unkept_line();
+              log.append_line(line.raw());
             }
-            self.update_data_board(&new_log, &data_board).await;
-            self.content.push_back(new_log);
           }
-          Event::Renamed(new_path) => {
-            self.path = new_path;
+          self.board_updates.add_log(&new_log);
+          self.content.push_front(new_log);
+        }
+        Event::NewTail(s) => {
+          let new_log = LogLine::parse_bytes(s, self.line_template.as_ref());
+          if new_log.is_bad() {
+            if self.join_continuation_lines
+              && let Some(LogLine::Good(log)) = self.content.get_mut(self.content.last_index())
+            {
+              self.board_updates.add_unkept_line();
+              log.append_line(new_log.raw());
+            } else if self.keep_bad_lines {
+              self.board_updates.add_bad_line();
+              self.content.push_back(new_log);
+            } else {
+              self.board_updates.add_unkept_line();
+            }
+            continue;
           }
-          Event::Removed => result.push(LogEvent::Removed),
+          self.board_updates.add_log(&new_log);
+          self.content.push_back(new_log);
+        }
+        Event::Renamed(new_path) => {
+          self.path = new_path;
+        }
+        Event::HeadEnd => {
+          // 文件开头的那些无法解析的行，之前已经没有可以拼接的日志了，按无法解析的行处理
+          for line in self.head_continuation_lines.drain(..) {
+            if self.keep_bad_lines {
+              self.board_updates.add_bad_line();
+              self.content.push_front(line);
+            } else {
+              self.board_updates.add_unkept_line();
+            }
+          }
+        }
+        Event::Removed => self.log_events.push(LogEvent::Removed),
+        Event::Idle(since) => self.log_events.push(LogEvent::Idle(since)),
+        Event::Truncated => {
+          // 截断之前读到的日志仍然是有效的历史，保留它们，新的内容继续追加在后面
+          crate::println!("log file {:?} is truncated", self.path);
         }
       }
-
-      Some(result)
-    } else {
-      // 无法读到新的变更，代表本阅读器已经出错
-      None
     }
+
+    // 解析期间不持有数据看板的锁，最后一次性写入收集到的统计信息。
+    // 在这里被取消时，统计信息保留到下一次调用写入，日志事件保留到下一次调用返回
+    let mut data_board = data_board.lock().await;
+    data_board.apply_updates(
+      std::mem::take(&mut self.board_updates),
+      self.new_tag_default,
+    );
+
+    Some(std::mem::take(&mut self.log_events))
   }
 
   /// 关闭本日志的异步监听流程
@@ -114,11 +231,8 @@ impl LogFile {
     &self.path
   }
 
-  /// 检查给定的新的日志行，将它的某些统计信息，刷新到全局的数据黑板中
-  async fn update_data_board(&mut self, log: &LogLine, data_board: &Mutex<DataBoard>) {
-    let mut data_board = data_board.lock().await;
-    if let LogLine::Good(log) = log {
-      data_board.update_tag(&log.tag);
-    }
+  /// 正在读取的文件的 inode，读取标准输入时没有值
+  pub fn inode(&self) -> Option<u64> {
+    self.reader.inode()
   }
 }
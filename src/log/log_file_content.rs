@@ -1,7 +1,8 @@
 use crate::log::{IterNextNth, LogLine};
+use chrono::{DateTime, FixedOffset};
 
 /// 索引日志内容中的某一行日志，可以和日志内容的迭代器互相转换
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
 pub struct Index {
   chunk_index: usize,
   line_index: usize,
@@ -92,9 +93,19 @@ impl Chunk {
   }
 }
 
+/// 默认的 chunk 大小，也即每一块连续内存中存放的日志行数量
+pub const DEFAULT_CHUNK_CAPACITY: usize = 512;
+
 pub struct LogFileContent {
   chunks: Vec<Chunk>,
   chunk_capacity: usize,
+
+  /// 因为内存限制，已经从头部淘汰掉的 chunk 数量。
+  /// 索引中的 chunk_index 总是包含这部分 chunk，以保证淘汰后，剩余日志行的索引不变
+  evicted_chunks: usize,
+
+  /// 各个 chunk 中日志行数量之和，随插入、淘汰同步更新，避免每次统计时遍历所有 chunk
+  lines: usize,
 }
 
 impl LogFileContent {
@@ -103,22 +114,97 @@ impl LogFileContent {
     Self {
       chunks: Vec::new(),
       chunk_capacity,
+      evicted_chunks: 0,
+      lines: 0,
     }
   }
 
+  /// 给定索引中的 chunk_index，获取对应的 chunk，已经被淘汰的 chunk 返回空
+  fn chunk(&self, chunk_index: usize) -> Option<&Chunk> {
+    self
+      .chunks
+      .get(chunk_index.checked_sub(self.evicted_chunks)?)
+  }
+
+  /// 给定索引中的 chunk_index，获取对应的可变 chunk，已经被淘汰的 chunk 返回空
+  fn chunk_mut(&mut self, chunk_index: usize) -> Option<&mut Chunk> {
+    self
+      .chunks
+      .get_mut(chunk_index.checked_sub(self.evicted_chunks)?)
+  }
+
   /// 文件内容是否为空
   pub fn is_empty(&self) -> bool {
     self.chunks.is_empty()
   }
 
-  /// 在头部插入新日志行
+  /// 日志行数量
+  pub fn len(&self) -> usize {
+    self.lines
+  }
+
+  /// 每个 chunk 最多存放的日志行数量
+  pub fn chunk_capacity(&self) -> usize {
+    self.chunk_capacity
+  }
+
+  /// 目前保留的 chunk 数量
+  pub fn chunk_count(&self) -> usize {
+    self.chunks.len()
+  }
+
+  /// 给定索引，计算它指向的日志行在内容中是第几行（从 0 开始）。
+  /// 若索引越界，则返回日志行数量
+  pub fn position(&self, index: Index) -> usize {
+    match self.chunk(index.chunk_index) {
+      None => self.len(),
+      Some(chunk) => {
+        self.chunks[..index.chunk_index - self.evicted_chunks]
+          .iter()
+          .map(Chunk::len)
+          .sum::<usize>()
+          + index.line_index.min(chunk.len())
+      }
+    }
+  }
+
+  /// 头部是否有日志行已经被淘汰
+  pub fn is_front_evicted(&self) -> bool {
+    self.evicted_chunks > 0
+  }
+
+  /// 从头部开始，整块地淘汰日志行，直到剩余的日志行数量不超过给定值。
+  /// 剩余日志行的索引保持不变，而指向被淘汰日志行的索引将变为无效。
+  ///
+  /// 每一行被淘汰的日志都会交给 `on_evicted`，返回被淘汰的日志行数量
+  pub fn evict_front(&mut self, max_lines: usize, mut on_evicted: impl FnMut(&LogLine)) -> usize {
+    let mut evicted = 0;
+
+    while self.lines > max_lines && !self.chunks.is_empty() {
+      let chunk = self.chunks.remove(0);
+      let chunk_len = chunk.len();
+      chunk.lines.iter().for_each(&mut on_evicted);
+      self.evicted_chunks += 1;
+      self.lines -= chunk_len;
+      evicted += chunk_len;
+    }
+
+    evicted
+  }
+
+  /// 在头部插入新日志行。如果头部已经有日志行被淘汰，则更旧的日志行也不再保留
   pub fn push_front(&mut self, line: LogLine) {
+    if self.is_front_evicted() {
+      return;
+    }
+
     if self.should_extend_front() {
       self.chunks.insert(0, self.new_chunk(true));
     }
 
     if let Some(chunk) = self.chunks.first_mut() {
       chunk.push(line);
+      self.lines += 1;
     }
   }
 
@@ -130,6 +216,7 @@ impl LogFileContent {
 
     if let Some(chunk) = self.chunks.last_mut() {
       chunk.push(line);
+      self.lines += 1;
     }
   }
 
@@ -156,24 +243,26 @@ impl LogFileContent {
 
   /// 获取指向第一条日志的索引
   pub fn first_index(&self) -> Index {
-    Index::zero()
+    Index::new(self.evicted_chunks, 0)
   }
 
   /// 获取指向最后一条日志的索引
   pub fn last_index(&self) -> Index {
-    let chunk_index = self.chunks.len().saturating_sub(1);
-    let line_index = match self.chunks.get(chunk_index) {
+    let line_index = match self.chunks.last() {
       None => 0,
       Some(chunk) => chunk.len().saturating_sub(1),
     };
-    Index::new(chunk_index, line_index)
+    Index::new(
+      self.evicted_chunks + self.chunks.len().saturating_sub(1),
+      line_index,
+    )
   }
 
   /// 将给定索引移动指定的步长。若移动结束时指向了有效的数据，则返回新的索引，
   /// 若移动结束时发现索引越界，则返回剩余需要移动的步长。
   pub fn step_index(&self, mut index: Index, mut n: isize) -> Result<Index, isize> {
     // 获取当前索引的 chunk，如果不存在，则终止处理
-    let mut chunk = self.chunks.get(index.chunk_index).ok_or(n)?;
+    let mut chunk = self.chunk(index.chunk_index).ok_or(n)?;
 
     loop {
       // 检查当前索引指向的条目在正确的数据范围内，如果否，则终止处理
@@ -194,14 +283,14 @@ impl LogFileContent {
         // 若行超出 chunk 下界，则往前迭代 chunk，若没有前面已经没有 chunk，则终止处理
         n = next_line_index + 1;
         index.chunk_index = index.chunk_index.overflowing_sub(1).0;
-        chunk = self.chunks.get(index.chunk_index).ok_or(n)?;
+        chunk = self.chunk(index.chunk_index).ok_or(n)?;
         index.line_index = chunk.len().saturating_sub(1);
       } else if next_line_index >= chunk_size as isize {
         // 若超出 chunk 上届，则往后迭代 chunk，若后边已经没有 chunk，则终止处理
         n = next_line_index - chunk_size as isize;
         index.chunk_index += 1;
         index.line_index = 0;
-        chunk = self.chunks.get(index.chunk_index).ok_or(n)?;
+        chunk = self.chunk(index.chunk_index).ok_or(n)?;
       } else {
         // 移动之后刚好落在本 chunk 内，返回更新后的索引
         index.line_index = next_line_index as usize;
@@ -210,24 +299,93 @@ impl LogFileContent {
     }
   }
 
+  /// 二分查找时间戳与给定时间最接近的日志行，距离相同时取更早的一行。
+  /// 文件内的日志按时间排序，无法解析的行没有时间戳，不参与比较；没有可以比较的日志行时返回空
+  pub fn find_by_timestamp(&self, dt: DateTime<FixedOffset>) -> Option<Index> {
+    // 与第一条时间不早于给定时间的日志、以及它前面最近的一条可比较日志相比，取更接近的一条
+    let lo = self.partition_by_timestamp(dt);
+    let after = self.next_timestamp(lo, self.len());
+    let before = self.prev_timestamp(lo);
+    match (before, after) {
+      (Some((_, before, t0)), Some((_, after, t1))) => match dt - t0 <= t1 - dt {
+        true => Some(before),
+        false => Some(after),
+      },
+      (Some((_, index, _)), None) | (None, Some((_, index, _))) => Some(index),
+      (None, None) => None,
+    }
+  }
+
+  /// 二分查找第一条时间不早于给定时间的日志行，无法解析的行不参与比较；没有这样的日志行时返回空
+  pub fn first_at_or_after(&self, dt: DateTime<FixedOffset>) -> Option<Index> {
+    let lo = self.partition_by_timestamp(dt);
+    self
+      .next_timestamp(lo, self.len())
+      .map(|(_, index, _)| index)
+  }
+
+  /// 找到第一条时间不早于给定时间的日志所在的位置，没有时返回日志行数量。
+  /// 某个位置之后（含）的第一条可比较日志不早于给定时间，是随位置单调的条件，坏行并不破坏这一点
+  fn partition_by_timestamp(&self, dt: DateTime<FixedOffset>) -> usize {
+    let (mut lo, mut hi) = (0, self.len());
+    while lo < hi {
+      let mid = lo + (hi - lo) / 2;
+      match self.next_timestamp(mid, hi) {
+        Some((pos, _, timestamp)) if timestamp < dt => lo = pos + 1,
+        _ => hi = mid,
+      }
+    }
+    lo
+  }
+
+  /// 从位置 `from` 开始，在 `to` 之前找到第一条有时间戳的日志，返回它的位置、索引与时间戳
+  fn next_timestamp(
+    &self,
+    from: usize,
+    to: usize,
+  ) -> Option<(usize, Index, DateTime<FixedOffset>)> {
+    let index = self.step_index(self.first_index(), from as isize).ok()?;
+    self
+      .iter_forward_from(index)
+      .take(to.saturating_sub(from))
+      .enumerate()
+      .find_map(|(i, (index, log))| Some((from + i, index, log.get_timestamp()?)))
+  }
+
+  /// 找到位置 `to` 之前最近的一条有时间戳的日志，返回它的位置、索引与时间戳
+  fn prev_timestamp(&self, to: usize) -> Option<(usize, Index, DateTime<FixedOffset>)> {
+    let last = to.checked_sub(1)?;
+    let index = self.step_index(self.first_index(), last as isize).ok()?;
+    self
+      .iter_backward_from(index)
+      .enumerate()
+      .find_map(|(i, (index, log))| Some((last - i, index, log.get_timestamp()?)))
+  }
+
   /// 给定索引，获取日志行数据
   pub fn get(&self, index: Index) -> Option<&LogLine> {
-    self.chunks.get(index.chunk_index)?.get(index.line_index)
+    self.chunk(index.chunk_index)?.get(index.line_index)
   }
 
-  /// 给定索引，获取可变的日志行数据
-  pub fn get_mut<'a>(&mut self, index: Index) -> Option<&'a mut LogLine> {
+  /// 遍历所有（未被淘汰的）日志行，不保证遍历的顺序
+  pub fn for_each_mut(&mut self, f: impl FnMut(&mut LogLine)) {
     self
       .chunks
-      .get_mut(index.chunk_index)?
-      .get_mut(index.line_index)
+      .iter_mut()
+      .flat_map(|chunk| chunk.lines.iter_mut())
+      .for_each(f);
+  }
+
+  /// 给定索引，获取可变的日志行数据
+  pub fn get_mut<'a>(&mut self, index: Index) -> Option<&'a mut LogLine> {
+    self.chunk_mut(index.chunk_index)?.get_mut(index.line_index)
   }
 }
 
 impl Default for LogFileContent {
   /// 创建默认 chunk 大小的日志内容
   fn default() -> Self {
-    Self::new(512)
+    Self::new(DEFAULT_CHUNK_CAPACITY)
   }
 }
 
@@ -340,4 +498,140 @@ mod tests {
     let mut iter = content.iter_backward_from_tail();
     assert_eq!(iter.next_nth(6), Err(2));
   }
+
+  #[test]
+  fn test_log_file_content_evict_front() {
+    let line = |i: usize| LogLine::new(i.to_string());
+
+    // 每个 chunk 两行：[1, 0] [2, 3] [4, 5] [6]
+    let mut content = LogFileContent::new(2);
+    for i in 2..7 {
+      content.push_back(line(i));
+    }
+    content.push_front(line(1));
+    content.push_front(line(0));
+
+    let index_of_4 = content.step_index(content.first_index(), 4).unwrap();
+    assert_eq!(content.get(index_of_4), Some(&line(4)));
+
+    // 只能整块淘汰，剩余不超过 4 行
+    let mut evicted = vec![];
+    assert_eq!(content.evict_front(4, |line| evicted.push(line.clone())), 4);
+    evicted.sort_by_key(|line| line.raw().to_string());
+    assert_eq!(evicted, (0..4).map(line).collect::<Vec<_>>());
+    assert_eq!(content.len(), 3);
+    assert!(content.is_front_evicted());
+
+    // 剩余日志行的索引不变，被淘汰日志行的索引失效
+    assert_eq!(content.get(index_of_4), Some(&line(4)));
+    assert_eq!(content.get(Index::zero()), None);
+    assert_eq!(content.position(index_of_4), 0);
+
+    // 迭代只覆盖保留下来的日志行
+    let lines: Vec<LogLine> = content
+      .iter_forward_from_head()
+      .map(|(_, line)| line.clone())
+      .collect();
+    assert_eq!(lines, vec![line(4), line(5), line(6)]);
+
+    let lines: Vec<LogLine> = content
+      .iter_backward_from_tail()
+      .map(|(_, line)| line.clone())
+      .collect();
+    assert_eq!(lines, vec![line(6), line(5), line(4)]);
+
+    let mut iter = content.iter_backward_from_tail();
+    assert_eq!(iter.next_nth(4), Err(1));
+
+    // 头部已经淘汰后，更旧的日志行不再保留，而尾部仍然可以继续追加
+    content.push_front(line(3));
+    content.push_back(line(7));
+    assert_eq!(content.get(content.first_index()), Some(&line(4)));
+    assert_eq!(content.get(content.last_index()), Some(&line(7)));
+    assert_eq!(content.len(), 4);
+  }
+
+  #[test]
+  fn test_log_file_content_len() {
+    let line = |i: usize| LogLine::new(i.to_string());
+    let count = |content: &LogFileContent| content.iter_forward_from_head().count();
+
+    // 行数随头尾插入、整块淘汰同步更新，且总是与迭代得到的行数一致
+    let mut content = LogFileContent::new(3);
+    assert!(content.is_empty());
+    assert_eq!(content.len(), 0);
+    for i in 0..20 {
+      match i % 3 {
+        0 => content.push_front(line(i)),
+        _ => content.push_back(line(i)),
+      }
+      assert_eq!(content.len(), count(&content));
+    }
+    assert_eq!(content.len(), 20);
+
+    content.evict_front(10, |_| {});
+    assert_eq!(content.len(), count(&content));
+    assert!(content.len() <= 10);
+
+    content.push_front(line(20));
+    content.push_back(line(21));
+    assert_eq!(content.len(), count(&content));
+  }
+
+  #[test]
+  fn test_find_by_timestamp() {
+    let at =
+      |s: &str| DateTime::parse_from_rfc3339(&format!("2026-01-17T{s}.000000+08:00")).unwrap();
+    let line = |s: &str| LogLine::new(format!("2026-01-17T{s}.000000+08:00 host kernel: {s}"));
+    let bad = || LogLine::new("not a syslog line".to_string());
+    let find = |content: &LogFileContent, s: &str| {
+      content
+        .find_by_timestamp(at(s))
+        .and_then(|index| content.get(index))
+        .map(|log| log.get_content().to_string())
+    };
+
+    // 没有可以比较的日志行
+    let mut content = LogFileContent::new(2);
+    assert_eq!(find(&content, "10:00:00"), None);
+    content.push_back(bad());
+    assert_eq!(find(&content, "10:00:00"), None);
+
+    // 每个 chunk 两行，从头部与尾部分别插入，中间夹杂着坏行：
+    // [bad, 10:00:00] [10:00:10, bad] [bad, bad] [10:00:20, 10:00:20] [10:00:40, bad]
+    let mut content = LogFileContent::new(2);
+    content.push_front(line("10:00:00"));
+    content.push_front(bad());
+    for log in [
+      line("10:00:10"),
+      bad(),
+      bad(),
+      bad(),
+      line("10:00:20"),
+      line("10:00:20"),
+      line("10:00:40"),
+      bad(),
+    ] {
+      content.push_back(log);
+    }
+    let find = |s: &str| find(&content, s);
+
+    // 精确匹配，相同时间戳的多行日志取第一行
+    assert_eq!(find("10:00:00"), Some("10:00:00".to_string()));
+    assert_eq!(find("10:00:10"), Some("10:00:10".to_string()));
+    assert_eq!(find("10:00:40"), Some("10:00:40".to_string()));
+    let index = content.find_by_timestamp(at("10:00:20")).unwrap();
+    assert_eq!(content.position(index), 6);
+
+    // 没有精确匹配时，取最接近的一行，距离相同时取更早的一行
+    assert_eq!(find("10:00:03"), Some("10:00:00".to_string()));
+    assert_eq!(find("10:00:05"), Some("10:00:00".to_string()));
+    assert_eq!(find("10:00:07"), Some("10:00:10".to_string()));
+    assert_eq!(find("10:00:16"), Some("10:00:20".to_string()));
+    assert_eq!(find("10:00:31"), Some("10:00:40".to_string()));
+
+    // 超出两端时，取最早或者最新的一行
+    assert_eq!(find("09:00:00"), Some("10:00:00".to_string()));
+    assert_eq!(find("11:00:00"), Some("10:00:40".to_string()));
+  }
 }
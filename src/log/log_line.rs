@@ -1,20 +1,36 @@
 //! 描述一条、也即一行的系统日志，并维护相关操作状态
 
+use crate::log::LineTemplate;
 use crate::log::LogLine::{Bad, Good};
 use aho_corasick::{AhoCorasick, MatchKind};
-use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDateTime};
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDateTime, SecondsFormat};
 use lazy_static::lazy_static;
+use serde::{Serialize, Serializer, ser::SerializeStruct};
 use std::cmp::Ordering;
 
 /// 日志内容标签
-#[derive(PartialEq, Debug, Clone, Default)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Label {
   #[default]
   Unknown,
+  Debug,
+  Info,
   Warn,
   Error,
 }
 
+impl Label {
+  /// 所有的日志等级
+  pub const ALL: [Label; 5] = [
+    Label::Error,
+    Label::Warn,
+    Label::Info,
+    Label::Debug,
+    Label::Unknown,
+  ];
+}
+
 /// 日志遍历的方向，主要用于描述 LogLink 的方向
 #[derive(Clone, Copy)]
 pub enum LogDirection {
@@ -39,18 +55,31 @@ pub struct LogLink {
   pub skip: usize,
 }
 
-/// 来自 syslog 的日志行
-#[derive(Debug, Clone, Default)]
+/// 来自 syslog 的日志行。
+/// 序列化时只输出日志本身的内容，不包含标记状态与跳转链接这些浏览时的状态
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct NormalLogLine {
   /// 日志的产生时间
+  #[serde(serialize_with = "serialize_rfc3339")]
   pub timestamp: DateTime<FixedOffset>,
 
+  /// 产生日志的主机名
+  pub hostname: String,
+
   /// 日志的标签
   pub tag: String,
 
   /// 产生的进程 PID，如果是 rsyslog 自己的日志，这个值为 0
   pub pid: i32,
 
+  /// 由行首的 PRI（如 `<13>`）解析出的 facility，日志没有 PRI 时为空
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub facility: Option<u8>,
+
+  /// 由行首的 PRI 解析出的 severity，日志没有 PRI 时为空
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub severity: Option<u8>,
+
   /// 内容
   pub message: String,
 
@@ -58,20 +87,42 @@ pub struct NormalLogLine {
   pub label: Label,
 
   /// 标记该日志是否被 marked，用于 viewer 快速定位
+  #[serde(skip)]
   pub marked: bool,
 
   /// 正向迭代的跳转链接
+  #[serde(skip)]
   pub forward_link: LogLink,
 
   /// 逆向迭代的跳转链接
+  #[serde(skip)]
   pub backward_link: LogLink,
+
+  /// 原始的日志行文本，用于原样复制或导出。
+  /// 它直接复用了构造时传入的字符串，不会额外拷贝，但会让每行日志的内存占用大约翻倍
+  pub raw: String,
+}
+
+impl NormalLogLine {
+  /// 将一行续行（如调用栈中的一行）以换行拼接到本日志的内容，以及原始文本中。
+  /// 续行行尾 CRLF 换行残留的 `\r` 只保留在原始文本中
+  pub fn append_line(&mut self, line: &str) {
+    self.message.push('\n');
+    self.message.push_str(strip_cr(line));
+    self.raw.push('\n');
+    self.raw.push_str(line);
+  }
 }
 
+/// 比较时忽略跳转链接以及原始文本，它们不属于日志的内容
 impl PartialEq for NormalLogLine {
   fn eq(&self, other: &Self) -> bool {
     self.timestamp == other.timestamp
+      && self.hostname == other.hostname
       && self.tag == other.tag
       && self.pid == other.pid
+      && self.facility == other.facility
+      && self.severity == other.severity
       && self.message == other.message
       && self.label == other.label
       && self.marked == other.marked
@@ -81,13 +132,46 @@ impl PartialEq for NormalLogLine {
 /// 无法解析的日志行
 #[derive(PartialEq, Debug, Clone, Default)]
 pub struct BrokenLogLine {
-  /// 内容
+  /// 内容，原始内容不是合法的 UTF-8 时，非法的字节被替换为 U+FFFD，仅用于展示
   pub content: String,
 
+  /// 原始内容不是合法的 UTF-8 时，保存原始的字节，以便导出时原样还原
+  pub bytes: Option<Vec<u8>>,
+
   /// 标记该日志是否被 marked，用于 viewer 快速定位
   pub marked: bool,
 }
 
+impl BrokenLogLine {
+  /// 用于展示的内容，去掉了 CRLF 换行残留在行尾的 `\r`
+  pub fn display_content(&self) -> &str {
+    strip_cr(&self.content)
+  }
+}
+
+/// 以 CRLF 换行的文件按 `\n` 分行后，去掉行尾残留的一个 `\r`
+fn strip_cr(line: &str) -> &str {
+  line.strip_suffix('\r').unwrap_or(line)
+}
+
+/// 无法解析的日志行序列化为 `{ "raw": ..., "parsed": false }`
+impl Serialize for BrokenLogLine {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("BrokenLogLine", 2)?;
+    s.serialize_field("raw", &self.content)?;
+    s.serialize_field("parsed", &false)?;
+    s.end()
+  }
+}
+
+/// 时间戳总是序列化为 RFC3339 格式，与 `LogLine::to_syslog_string` 保持一致
+fn serialize_rfc3339<S: Serializer>(
+  timestamp: &DateTime<FixedOffset>,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  serializer.serialize_str(&timestamp.to_rfc3339_opts(SecondsFormat::Micros, false))
+}
+
 /// 记录当前的时间
 struct NowDate {
   now: DateTime<Local>,
@@ -106,6 +190,28 @@ lazy_static! {
   static ref NOW_DATE: NowDate = NowDate::new();
 }
 
+/// 各个 syslog facility 的名称，按 RFC 5424 中的取值排列
+const FACILITY_NAMES: [&str; 24] = [
+  "kern", "user", "mail", "daemon", "auth", "syslog", "lpr", "news", "uucp", "cron", "authpriv",
+  "ftp", "ntp", "security", "console", "clock", "local0", "local1", "local2", "local3", "local4",
+  "local5", "local6", "local7",
+];
+
+/// 各个 syslog severity 的名称，按 RFC 5424 中的取值排列
+const SEVERITY_NAMES: [&str; 8] = [
+  "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+/// syslog facility 的名称，如 `kern`、`mail`，无法识别的取值返回 None
+pub fn facility_name(facility: u8) -> Option<&'static str> {
+  FACILITY_NAMES.get(facility as usize).copied()
+}
+
+/// syslog severity 的名称，如 `err`、`info`，无法识别的取值返回 None
+pub fn severity_name(severity: u8) -> Option<&'static str> {
+  SEVERITY_NAMES.get(severity as usize).copied()
+}
+
 /// 关键字匹配器
 fn make_keywords_matcher(keywords: &[&str]) -> AhoCorasick {
   AhoCorasick::builder()
@@ -121,8 +227,41 @@ lazy_static! {
   static ref WRAN_KEYWORDS_MATCHER: AhoCorasick = make_keywords_matcher(&["warn"]);
 }
 
+/// 匹配文本中是否有关键字，并按重要程度的优先级，给出日志等级
+pub(super) fn label_of(text: &str) -> Label {
+  if ERROR_KEYWORDS_MATCHER.is_match(text) {
+    Label::Error
+  } else if WRAN_KEYWORDS_MATCHER.is_match(text) {
+    Label::Warn
+  } else {
+    Label::Unknown
+  }
+}
+
+/// 内容中没有关键字时，按 PRI 中的 severity 给出等级：emerg 至 err 为 error，warning 为 warn，
+/// info、debug 分别为 info、debug，notice 仍然未知
+fn label_with_severity(label: Label, severity: Option<u8>) -> Label {
+  match (label, severity) {
+    (Label::Unknown, Some(0..=3)) => Label::Error,
+    (Label::Unknown, Some(4)) => Label::Warn,
+    (Label::Unknown, Some(6)) => Label::Info,
+    (Label::Unknown, Some(7)) => Label::Debug,
+    (label, _) => label,
+  }
+}
+
+/// 内容中没有关键字时，按格式模板中的等级字段给出 info、debug 等级
+pub(super) fn label_with_level(label: Label, level: &str) -> Label {
+  match (label, level.to_lowercase().as_str()) {
+    (Label::Unknown, "info" | "information") => Label::Info,
+    (Label::Unknown, "debug" | "trace") => Label::Debug,
+    (label, _) => label,
+  }
+}
+
 /// 日志行
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize)]
+#[serde(untagged)]
 pub enum LogLine {
   Good(NormalLogLine),
   Bad(BrokenLogLine),
@@ -130,13 +269,31 @@ pub enum LogLine {
 
 impl LogLine {
   pub fn new(line: String) -> LogLine {
-    let bytes = line.as_bytes();
+    Self::parse(line, None)
+  }
+
+  /// 解析一行日志。内置的 syslog 解析器都无法解析时，若给出了格式模板，则再按模板解析。
+  /// 以 CRLF 换行的文件按 `\n` 分行后，行尾残留的一个 `\r` 不参与解析，只保留在原始文本中
+  pub fn parse(line: String, template: Option<&LineTemplate>) -> LogLine {
+    let text = strip_cr(&line);
+
+    // 行首可能带有 PRI，如直接从网络收到的、或者配置了输出 PRI 的日志
+    let (pri, bytes) = Self::try_parse_pri(text.as_bytes());
 
     // 尝试解析不同时间戳格式的系统日志行
     if let Some((timestamp, seeker)) = Self::try_parse_any_timestamp(bytes)
       && let Some(log) = Self::try_parse_rest(timestamp, seeker)
     {
-      LogLine::Good(log)
+      let severity = pri.map(|pri| pri & 0x07);
+      LogLine::Good(NormalLogLine {
+        facility: pri.map(|pri| pri >> 3),
+        severity,
+        label: label_with_severity(log.label, severity),
+        raw: line,
+        ..log
+      })
+    } else if let Some(log) = template.and_then(|template| template.parse(text)) {
+      LogLine::Good(NormalLogLine { raw: line, ..log })
     } else {
       LogLine::Bad(BrokenLogLine {
         content: line,
@@ -145,6 +302,44 @@ impl LogLine {
     }
   }
 
+  /// 从原始字节创建日志行。不是合法 UTF-8 的内容按有损转换后解析，
+  /// 若无法解析为日志，则在坏行中保留原始的字节
+  pub fn from_bytes(bytes: Vec<u8>) -> LogLine {
+    Self::parse_bytes(bytes, None)
+  }
+
+  /// 同 [LogLine::from_bytes]，但无法按 syslog 解析时，再按给出的格式模板解析
+  pub fn parse_bytes(bytes: Vec<u8>, template: Option<&LineTemplate>) -> LogLine {
+    let bytes = match String::from_utf8(bytes) {
+      Ok(line) => return LogLine::parse(line, template),
+      Err(e) => e.into_bytes(),
+    };
+
+    match LogLine::parse(String::from_utf8_lossy(&bytes).into_owned(), template) {
+      LogLine::Bad(log) => LogLine::Bad(BrokenLogLine {
+        bytes: Some(bytes),
+        ..log
+      }),
+      log => log,
+    }
+  }
+
+  /// 解析行首形如 `<13>` 的 PRI，返回它的值以及剩余的内容。没有合法的 PRI 时，原样返回内容
+  fn try_parse_pri(bytes: &[u8]) -> (Option<u8>, &[u8]) {
+    let mut seeker = BytesSeeker::new(bytes);
+    let pri = seeker
+      .next_is(b'<')
+      .and_then(|_| seeker.find_next(b'>'))
+      .filter(|digits| digits.len() <= 3 && digits.iter().all(u8::is_ascii_digit))
+      .and_then(|digits| std::str::from_utf8(digits).ok()?.parse::<u8>().ok())
+      .filter(|pri| *pri < 192);
+
+    match pri {
+      Some(pri) => (Some(pri), seeker.rest_of_all()),
+      None => (None, bytes),
+    }
+  }
+
   fn try_parse_any_timestamp(bytes: &'_ [u8]) -> Option<(DateTime<FixedOffset>, BytesSeeker<'_>)> {
     Self::try_parse_modern_timestamp(&bytes).or(Self::try_parse_traditional_timestamp(&bytes))
   }
@@ -202,9 +397,10 @@ impl LogLine {
     // 按照这样的格式解析：
     // {timestamp} {hostname} {tag}[{pid}]: {message..}
     // 其中，timestamp 已经被解析，另外，rsyslog 自己的日志，没有 pid 的部分。
-    // 跳过 hostname
+    // 取出 hostname
     seeker.next_is(b' ')?;
-    seeker.find_next(b' ')?;
+    let hostname = seeker.find_next(b' ')?;
+    let hostname = String::from_utf8_lossy(hostname).to_string();
 
     // 找到 tag 与 pid 部分。由于 pid 不一定存在，因此我们只能直接找到 : 前的所有
     let bytes_tag_and_ip = seeker.find_next(b':')?;
@@ -228,17 +424,12 @@ impl LogLine {
     let tag = String::from_utf8_lossy(&tag).to_string();
 
     // 匹配消息中是否有关键字，并按重要程度的优先级，进行设置
-    let label = if ERROR_KEYWORDS_MATCHER.is_match(&message) {
-      Label::Error
-    } else if WRAN_KEYWORDS_MATCHER.is_match(&message) {
-      Label::Warn
-    } else {
-      Label::Unknown
-    };
+    let label = label_of(&message);
 
     // 返回结果
     Some(NormalLogLine {
       timestamp,
+      hostname,
       tag,
       pid,
       message,
@@ -341,6 +532,14 @@ impl LogLine {
     }
   }
 
+  /// 设置本条日志的标记状态
+  pub fn set_mark(&mut self, marked: bool) {
+    match self {
+      Good(log) => log.marked = marked,
+      Bad(log) => log.marked = marked,
+    }
+  }
+
   /// 获取本日志是否被标记
   pub fn is_marked(&self) -> bool {
     match self {
@@ -379,13 +578,95 @@ impl LogLine {
     }
   }
 
+  /// 获取产生日志的进程 PID，日志没有 PID（如 rsyslog 自己的日志）时为空
+  pub fn get_pid(&self) -> Option<i32> {
+    match self {
+      Good(log) if log.pid != 0 => Some(log.pid),
+      _ => None,
+    }
+  }
+
+  /// 获取日志内容的等级标签，无法解析的行没有标签
+  pub fn get_label(&self) -> Option<Label> {
+    match self {
+      Good(log) => Some(log.label),
+      Bad(_) => None,
+    }
+  }
+
+  /// 获取日志的 syslog facility，日志没有 PRI 时为空
+  pub fn get_facility(&self) -> Option<u8> {
+    match self {
+      Good(log) => log.facility,
+      Bad(_) => None,
+    }
+  }
+
+  /// 获取日志的 syslog severity，日志没有 PRI 时为空
+  pub fn get_severity(&self) -> Option<u8> {
+    match self {
+      Good(log) => log.severity,
+      Bad(_) => None,
+    }
+  }
+
   /// 获取日志内容
   pub fn get_content(&self) -> &str {
     match self {
       Good(log) => &log.message,
+      Bad(log) => log.display_content(),
+    }
+  }
+
+  /// 获取日志行的原始文本
+  pub fn raw(&self) -> &str {
+    match self {
+      Good(log) => &log.raw,
       Bad(log) => &log.content,
     }
   }
+
+  /// 将日志重新组装为 syslog 格式的字符串：`{timestamp} {hostname} {tag}[{pid}]: {message}`，
+  /// 其中时间戳总是使用 RFC3339 格式，pid 为 0 时省略 `[{pid}]` 部分。坏行原样返回
+  pub fn to_syslog_string(&self) -> String {
+    match self {
+      Good(log) => {
+        let mut s = match (log.facility, log.severity) {
+          (Some(facility), Some(severity)) => format!("<{}>", facility << 3 | severity),
+          _ => String::new(),
+        };
+        s.push_str(&log.timestamp.to_rfc3339_opts(SecondsFormat::Micros, false));
+        if !log.hostname.is_empty() {
+          s.push(' ');
+          s.push_str(&log.hostname);
+        }
+        s.push(' ');
+        s.push_str(&log.tag);
+        if log.pid != 0 {
+          s.push_str(&format!("[{}]", log.pid));
+        }
+        s.push_str(": ");
+        s.push_str(&log.message);
+        s
+      }
+      Bad(log) => log.content.clone(),
+    }
+  }
+
+  /// 与 `to_syslog_string` 相同，但坏行会原样返回读取时的原始字节，即使它们不是合法的 UTF-8
+  pub fn to_syslog_bytes(&self) -> Vec<u8> {
+    match self {
+      Bad(BrokenLogLine {
+        bytes: Some(bytes), ..
+      }) => bytes.clone(),
+      _ => self.to_syslog_string().into_bytes(),
+    }
+  }
+
+  /// 将日志序列化为一行 JSON，便于其他工具处理
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).expect("log line is always serializable")
+  }
 }
 
 #[cfg(test)]
@@ -418,6 +699,31 @@ mod tests {
     assert_eq!(log.message, content);
   }
 
+  #[test]
+  fn test_accessors() {
+    let log =
+      LogLine::new("2026-01-17T10:22:55.642782+08:00 host sshd[42]: connect failed".to_string());
+    assert_eq!(
+      log.get_timestamp(),
+      Some(DateTime::parse_from_rfc3339("2026-01-17T10:22:55.642782+08:00").unwrap())
+    );
+    assert_eq!(log.get_tag(), Some("sshd"));
+    assert_eq!(log.get_pid(), Some(42));
+    assert_eq!(log.get_label(), Some(Label::Error));
+
+    // 没有 PID 的日志
+    let log = LogLine::new("2026-01-17T10:22:55.642782+08:00 host kernel: usb reset".to_string());
+    assert_eq!(log.get_pid(), None);
+    assert_eq!(log.get_label(), Some(Label::Unknown));
+
+    // 无法解析的行没有这些字段
+    let log = LogLine::new("not a syslog line".to_string());
+    assert_eq!(log.get_timestamp(), None);
+    assert_eq!(log.get_tag(), None);
+    assert_eq!(log.get_pid(), None);
+    assert_eq!(log.get_label(), None);
+  }
+
   #[test]
   fn test_parse_traditional() {
     let timestamp = "Jan 15 22:41:02";
@@ -439,4 +745,206 @@ mod tests {
     assert_eq!(log.pid, pid);
     assert_eq!(log.message, content);
   }
+
+  #[test]
+  fn test_label_from_severity() {
+    let label = |line: &str| LogLine::new(line.to_string()).get_label();
+
+    // severity 为 0 至 3 时是 error，4 是 warn，6、7 分别是 info、debug，内容中的关键字优先
+    let rest = "2026-01-17T10:22:55.642782+08:00 host kernel[1]:";
+    assert_eq!(label(&format!("<8>{rest} hello")), Some(Label::Error));
+    assert_eq!(label(&format!("<11>{rest} hello")), Some(Label::Error));
+    assert_eq!(label(&format!("<12>{rest} hello")), Some(Label::Warn));
+    assert_eq!(label(&format!("<14>{rest} hello")), Some(Label::Info));
+    assert_eq!(label(&format!("<15>{rest} hello")), Some(Label::Debug));
+    assert_eq!(
+      label(&format!("<15>{rest} load failed")),
+      Some(Label::Error)
+    );
+    assert_eq!(
+      label(&format!("<11>{rest} low memory warning")),
+      Some(Label::Warn)
+    );
+    assert_eq!(label(&format!("<13>{rest} hello")), Some(Label::Unknown));
+    assert_eq!(label(&format!("{rest} hello")), Some(Label::Unknown));
+  }
+
+  #[test]
+  fn test_parse_pri() {
+    let parse = |pri: &str| {
+      let log = LogLine::new(format!(
+        "{pri}2026-01-17T10:22:55.642782+08:00 host kernel[1]: hello"
+      ));
+      assert!(!log.is_bad());
+      (log.get_facility(), log.get_severity())
+    };
+
+    // PRI = facility * 8 + severity
+    assert_eq!(parse("<0>"), (Some(0), Some(0)));
+    assert_eq!(parse("<13>"), (Some(1), Some(5)));
+    assert_eq!(parse("<22>"), (Some(2), Some(6)));
+    assert_eq!(parse("<38>"), (Some(4), Some(6)));
+    assert_eq!(parse("<86>"), (Some(10), Some(6)));
+    assert_eq!(parse("<191>"), (Some(23), Some(7)));
+    assert_eq!(parse(""), (None, None));
+
+    // 传统时间戳格式同样可以带有 PRI
+    let log = LogLine::new("<30>Jan 15 22:41:02 host systemd[1]: started".to_string());
+    assert_eq!((log.get_facility(), log.get_severity()), (Some(3), Some(6)));
+    assert_eq!(log.get_tag(), Some("systemd"));
+
+    // 非法的 PRI 导致整行无法解析
+    assert!(
+      LogLine::new("<192>2026-01-17T10:22:55.642782+08:00 host kernel: x".to_string()).is_bad()
+    );
+    assert!(
+      LogLine::new("<1a>2026-01-17T10:22:55.642782+08:00 host kernel: x".to_string()).is_bad()
+    );
+
+    // 带 PRI 的日志可以完整还原
+    let line = "<13>2026-01-17T10:22:55.642782+08:00 host kernel[1]: hello";
+    assert_eq!(LogLine::new(line.to_string()).to_syslog_string(), line);
+  }
+
+  #[test]
+  fn test_facility_severity_names() {
+    assert_eq!(facility_name(0), Some("kern"));
+    assert_eq!(facility_name(2), Some("mail"));
+    assert_eq!(facility_name(3), Some("daemon"));
+    assert_eq!(facility_name(4), Some("auth"));
+    assert_eq!(facility_name(16), Some("local0"));
+    assert_eq!(facility_name(23), Some("local7"));
+    assert_eq!(facility_name(24), None);
+
+    assert_eq!(severity_name(0), Some("emerg"));
+    assert_eq!(severity_name(3), Some("err"));
+    assert_eq!(severity_name(6), Some("info"));
+    assert_eq!(severity_name(7), Some("debug"));
+    assert_eq!(severity_name(8), None);
+  }
+
+  #[test]
+  fn test_raw() {
+    let line = "Jan 15 22:41:02 yarten-Dell-G16-7630   gnome-shell[3203]: keep   spacing ";
+    assert_eq!(LogLine::new(line.to_string()).raw(), line);
+
+    let line = "2026-01-17T10:22:55.642782+08:00 host tag[1]: message";
+    let log = LogLine::new(line.to_string());
+    assert!(!log.is_bad());
+    assert_eq!(log.raw(), line);
+
+    let line = "this is not a syslog line";
+    assert_eq!(LogLine::new(line.to_string()).raw(), line);
+  }
+
+  #[test]
+  fn test_to_syslog_string() {
+    // 现代时间戳格式的日志，可以完全还原
+    let line = "2026-01-17T10:22:55.642782+08:00 yarten-Dell-G16-7630 gnome-shell[3208]: Can't update stage views actor <unnamed>[<MetaSurfaceActorX11>:0x572a8221c360].";
+    assert_eq!(LogLine::new(line.to_string()).to_syslog_string(), line);
+
+    // 没有 pid 的日志
+    let line = "2026-01-17T10:22:55.000000+08:00 yarten-Dell-G16-7630 rsyslogd: [origin software=\"rsyslogd\"] start";
+    assert_eq!(LogLine::new(line.to_string()).to_syslog_string(), line);
+
+    // 传统时间戳格式的日志，会转换为 RFC3339 格式，但重新解析后内容不变
+    let log = LogLine::new(
+      "Jan 15 22:41:02 yarten-Dell-G16-7630 gnome-shell[3203]: hello: world".to_string(),
+    );
+    let s = log.to_syslog_string();
+    assert!(s.ends_with(" yarten-Dell-G16-7630 gnome-shell[3203]: hello: world"));
+    assert_eq!(LogLine::new(s), log);
+
+    // 坏行原样返回
+    let line = "this is not a syslog line";
+    assert_eq!(LogLine::new(line.to_string()).to_syslog_string(), line);
+  }
+
+  #[test]
+  fn test_invalid_utf8() {
+    // 无法解析的坏行，导出时还原原始的字节，展示时使用有损转换后的内容
+    let bytes = b"\xff\xfebinary\x00 data \xc3".to_vec();
+    let log = LogLine::from_bytes(bytes.clone());
+    assert!(log.is_bad());
+    assert_eq!(
+      log.get_content(),
+      "\u{FFFD}\u{FFFD}binary\u{0} data \u{FFFD}"
+    );
+    assert_eq!(log.to_syslog_bytes(), bytes);
+
+    // 合法的 UTF-8 与直接从字符串创建的日志行相同
+    let line = "2026-01-17T10:22:55.642782+08:00 host tag[1]: message";
+    let log = LogLine::from_bytes(line.as_bytes().to_vec());
+    assert_eq!(log, LogLine::new(line.to_string()));
+    assert_eq!(log.to_syslog_bytes(), line.as_bytes());
+
+    // 可以解析的日志行，非法的字节被替换
+    let log =
+      LogLine::from_bytes(b"2026-01-17T10:22:55.642782+08:00 host tag[1]: bad \xff".to_vec());
+    assert!(!log.is_bad());
+    assert_eq!(log.get_content(), "bad \u{FFFD}");
+  }
+
+  #[test]
+  fn test_crlf_line_endings() {
+    let lf = [
+      "2026-01-17T10:22:55.642782+08:00 host sshd[42]: connect failed",
+      "Jan 15 22:41:02 host kernel: usb reset",
+      "not a syslog line",
+    ];
+
+    // 行尾的 `\r` 不参与解析，展示的内容与 LF 换行的行相同，原始文本则保持原样
+    for line in lf {
+      let crlf = format!("{line}\r");
+      for log in [
+        LogLine::new(crlf.clone()),
+        LogLine::from_bytes(crlf.clone().into_bytes()),
+      ] {
+        let expected = LogLine::new(line.to_string());
+        assert_eq!(log.is_bad(), expected.is_bad());
+        assert_eq!(log.get_content(), expected.get_content());
+        assert_eq!(log.raw(), crlf);
+      }
+    }
+    let log = LogLine::new(format!("{}\r", lf[0]));
+    assert_eq!(log, LogLine::new(lf[0].to_string()));
+    assert_eq!(log.get_content(), "connect failed");
+    assert_eq!(log.to_syslog_string(), lf[0]);
+
+    // 续行的 `\r` 同样只保留在原始文本中
+    let LogLine::Good(mut log) = log else {
+      unreachable!()
+    };
+    log.append_line("  at main()\r");
+    assert_eq!(log.message, "connect failed\n  at main()");
+    assert_eq!(log.raw, format!("{}\r\n  at main()\r", lf[0]));
+
+    // 非法 UTF-8 的坏行原样保留读取时的字节，展示时同样去掉行尾的 `\r`。
+    // 只去掉行尾的一个，其它位置的 `\r` 与空白保持原样
+    let log = LogLine::from_bytes(b"binary \xff\r".to_vec());
+    assert_eq!(log.get_content(), "binary \u{FFFD}");
+    assert_eq!(log.to_syslog_bytes(), b"binary \xff\r");
+    let log = LogLine::new("2026-01-17T10:22:55.642782+08:00 host tag: a\rb \r\r".to_string());
+    assert_eq!(log.get_content(), "a\rb \r");
+    let log = LogLine::new("2026-01-17T10:22:55.642782+08:00 host tag: padded  ".to_string());
+    assert_eq!(log.get_content(), "padded  ");
+  }
+
+  #[test]
+  fn test_to_json() {
+    let log = LogLine::new(
+      "2026-01-17T10:22:55.642782+08:00 host gnome-shell[3208]: Failed to \"load\"".to_string(),
+    );
+    assert_eq!(
+      log.to_json(),
+      r#"{"timestamp":"2026-01-17T10:22:55.642782+08:00","hostname":"host","tag":"gnome-shell","pid":3208,"message":"Failed to \"load\"","label":"error","raw":"2026-01-17T10:22:55.642782+08:00 host gnome-shell[3208]: Failed to \"load\""}"#
+    );
+
+    let log = LogLine::new("not a syslog line".to_string());
+    assert!(log.is_bad());
+    assert_eq!(
+      log.to_json(),
+      r#"{"raw":"not a syslog line","parsed":false}"#
+    );
+  }
 }
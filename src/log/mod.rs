@@ -1,14 +1,21 @@
 mod data_board;
 mod event;
 mod iterator;
+mod line_template;
 mod log_file;
 mod log_file_content;
 mod log_line;
 mod rotated_log;
+mod rotation;
 
-pub use data_board::{DataBoard, TagsData};
+pub use data_board::{DataBoard, DataBoardUpdates, Exclusion, PidFilter, Stats, TagsData};
 pub use event::Event;
 pub use iterator::IterNextNth;
+pub use line_template::LineTemplate;
 pub use log_file::LogFile;
-pub use log_line::{BrokenLogLine, Label, LogDirection, LogLine, LogLink, NormalLogLine};
-pub use rotated_log::{Config, Index, RotatedLog};
+pub use log_line::{
+  BrokenLogLine, Label, LogDirection, LogLine, LogLink, NormalLogLine, facility_name, severity_name,
+};
+pub(crate) use rotated_log::position_fraction;
+pub use rotated_log::{Config, Index, LogFilter, RotatedLog};
+pub use rotation::RotationNaming;
@@ -1,14 +1,30 @@
-use crate::log::{
-  DataBoard, Event, IterNextNth, LogDirection, LogFile, LogLine, LogLink, data_board::TagsData,
-  log_file_content::Index as LogFileIndex,
+use crate::{
+  file::{is_stdin, reader::Overflow},
+  log::{
+    DataBoard, DataBoardUpdates, Event, IterNextNth, LineTemplate, LogDirection, LogFile, LogLine,
+    LogLink,
+    data_board::next_filter_version,
+    log_file_content::{DEFAULT_CHUNK_CAPACITY, Index as LogFileIndex},
+    rotation::{Recency, RotationNaming},
+  },
+};
+use chrono::{DateTime, FixedOffset};
+use std::{
+  collections::VecDeque,
+  fs,
+  os::unix::fs::MetadataExt,
+  path::{Path, PathBuf},
+  rc::Rc,
+  sync::Arc,
+  time::Duration,
 };
-use std::{collections::VecDeque, fs, path::PathBuf, sync::Arc};
 use tokio::sync::Mutex;
 
 /// 索引某一个系统日志中的某一行
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Index {
-  /// `RotatedLog` 中的日志文件数组的索引
+  /// `RotatedLog` 中日志文件的编号。文件的编号在加载时分配，此后不再变化，
+  /// 因此加载更旧的文件、淘汰或者删除其他文件后，指向剩余日志行的索引保持不变
   file_index: usize,
 
   /// 指向了某一份 `LogFile` 后的其中一行的索引
@@ -29,20 +45,159 @@ impl Index {
 }
 
 /// 日志文件的配置
+#[derive(Clone)]
 pub struct Config {
   possible_max_rotated_count: usize,
+
+  /// 最多在内存中保留的日志行数量，超出时从最旧的日志开始淘汰，为空则不限制
+  max_lines: Option<usize>,
+
+  /// 日志内容每一块连续内存中存放的日志行数量
+  pub(super) chunk_capacity: usize,
+
+  /// 是否在启动时并发地预加载所有滚动的旧日志，而不是等到需要时再逐份加载
+  eager_preload: bool,
+
+  /// 是否将无法解析的行（如调用栈）拼接到上一条日志的内容中，而不是丢弃它们
+  pub(super) join_continuation_lines: bool,
+
+  /// 滚动日志文件的命名方式
+  rotation_naming: RotationNaming,
+
+  /// 检查日志文件变化的间隔
+  pub(super) poll_interval: Duration,
+
+  /// 最新的日志持续多久没有新内容后，认为它已经停止更新，为空时不检测
+  pub(super) idle_timeout: Option<Duration>,
+
+  /// 新出现的标签默认是否被选中，也即是否展示它们的日志
+  pub(super) new_tag_default: bool,
+
+  /// 只读取最新一份日志末尾的这么多行，之后不再读取，为空则正常跟踪
+  pub(super) tail_lines: Option<usize>,
+
+  /// 读取日志文件的缓冲区大小，为空时在打开文件时根据行的长度自动选择
+  pub(super) buffer_size: Option<u64>,
+
+  /// 是否保留无法解析的行（没有被拼接到上一条日志中时），而不是丢弃它们
+  pub(super) keep_bad_lines: bool,
+
+  /// 无法按 syslog 格式解析的行，再按该模板解析，为空时这些行都是坏行
+  pub(super) line_template: Option<LineTemplate>,
+
+  /// 读取的内容来不及处理时的策略
+  pub(super) overflow: Overflow,
 }
 
 impl Config {
   pub fn default() -> Self {
     Self {
       possible_max_rotated_count: 5,
+      max_lines: None,
+      chunk_capacity: DEFAULT_CHUNK_CAPACITY,
+      eager_preload: false,
+      join_continuation_lines: false,
+      rotation_naming: RotationNaming::default(),
+      poll_interval: Duration::from_millis(100),
+      idle_timeout: Some(Duration::from_secs(300)),
+      new_tag_default: true,
+      tail_lines: None,
+      buffer_size: None,
+      keep_bad_lines: false,
+      line_template: None,
+      overflow: Overflow::default(),
     }
   }
+
+  /// 限制最多在内存中保留的日志行数量
+  pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+    self.max_lines = Some(max_lines);
+    self
+  }
+
+  /// 设置日志内容每一块连续内存中存放的日志行数量。日志行很短时可以调大，很长时可以调小
+  pub fn with_chunk_capacity(mut self, chunk_capacity: usize) -> Self {
+    self.chunk_capacity = chunk_capacity.max(1);
+    self
+  }
+
+  /// 启动时并发地预加载所有滚动的旧日志（最多 `possible_max_rotated_count` 份），
+  /// 适用于打开后马上就要浏览最早内容的场景
+  pub fn with_eager_preload(mut self) -> Self {
+    self.eager_preload = true;
+    self
+  }
+
+  /// 将紧跟在一条日志之后、无法解析的行（如 Java、Python 的调用栈，或内核的转储信息），
+  /// 以换行拼接到这条日志的内容中。这会改变日志的行数，因此默认关闭
+  pub fn with_join_continuation_lines(mut self) -> Self {
+    self.join_continuation_lines = true;
+    self
+  }
+
+  /// 设置滚动日志文件的命名方式，如 logrotate 的 dateext 会产生 x.log-20250101 这样的文件
+  pub fn with_rotation_naming(mut self, rotation_naming: RotationNaming) -> Self {
+    self.rotation_naming = rotation_naming;
+    self
+  }
+
+  /// 设置检查日志文件变化的间隔。调大可以降低空闲时的 CPU 占用，但新日志的出现会更迟
+  pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+    self.poll_interval = poll_interval;
+    self
+  }
+
+  /// 设置最新的日志持续多久没有新内容后，在状态栏中提醒它已经停止更新，为空时不提醒
+  pub fn with_idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+    self.idle_timeout = idle_timeout;
+    self
+  }
+
+  /// 设置新出现的标签默认是否被选中。设为 false 时，新标签的日志在被手动选中之前都是隐藏的，
+  /// 适用于只关注少数几个标签的场景
+  pub fn with_new_tag_default(mut self, new_tag_default: bool) -> Self {
+    self.new_tag_default = new_tag_default;
+    self
+  }
+
+  /// 只读取最新一份日志末尾的若干行，之后既不跟踪新内容，也不加载更旧的滚动日志，
+  /// 适用于事后分析的场景
+  pub fn with_tail_lines(mut self, lines: usize) -> Self {
+    self.tail_lines = Some(lines);
+    self
+  }
+
+  /// 指定读取日志文件的缓冲区大小（字节），而不是自动选择。
+  /// 行很长时调大可以减少不完整行的拼接，但每份文件都会占用更多内存
+  pub fn with_buffer_size(mut self, buffer_size: u64) -> Self {
+    self.buffer_size = Some(buffer_size.max(1));
+    self
+  }
+
+  /// 保留无法解析的行，而不是丢弃它们，以便排查解析不了的日志格式。
+  /// 若同时开启了拼接，能拼接到上一条日志中的行仍然会被拼接
+  pub fn with_keep_bad_lines(mut self) -> Self {
+    self.keep_bad_lines = true;
+    self
+  }
+
+  /// 设置日志行的格式模板，用于读取不符合 syslog 格式的应用日志。
+  /// 内置的 syslog 解析器仍然优先，模板也无法匹配的行依旧是坏行
+  pub fn with_line_template(mut self, line_template: LineTemplate) -> Self {
+    self.line_template = Some(line_template);
+    self
+  }
+
+  /// 设置读取的内容来不及处理时的策略。默认暂停读取，日志写入很快时，
+  /// 可以选择丢弃部分内容，以免读取远远落后于文件
+  pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+    self.overflow = overflow;
+    self
+  }
 }
 
 /// 维护一组由 syslog 滚动的系统日志，
-/// 这些日志是按需加载的，但总是从最新的一份开始
+/// 这些日志默认是按需加载的（也可以配置为预加载），但总是从最新的一份开始
 pub struct RotatedLog {
   /// 日志文件路径，不带被回滚时的后缀别名，一般指向最新的、正在被更新的日志
   path: PathBuf,
@@ -52,44 +207,121 @@ pub struct RotatedLog {
   /// 也即，在数组中这些日志这么排序：\[x.log.2, x.log.1, x.log]
   log_files: VecDeque<LogFile>,
 
+  /// 与 `log_files` 一一对应的文件编号，从旧到新递增（按回绕运算），删除中间的文件后可能不连续
+  file_ids: VecDeque<usize>,
+
+  /// 下一份插入头部、尾部的文件将要分配的编号
+  next_front_id: usize,
+  next_back_id: usize,
+
   /// 期望加载上一个日志
   want_older_log: bool,
+
+  /// 期望一次性预加载所有的旧日志，完成后清除
+  want_preload: bool,
+
+  /// 本系统日志的配置
+  config: Config,
+
+  /// 因为内存限制，已经从头部淘汰掉的日志文件数量
+  evicted_files: usize,
+
+  /// 预先建立跳转链路的进度，过滤条件变化后从头开始
+  links_warming: LinksWarming,
+
+  /// 被删除或淘汰的日志行的统计信息，等待从数据看板中扣除
+  board_removals: DataBoardUpdates,
+}
+
+/// 为某个版本的过滤条件预先建立跳转链路的进度
+#[derive(Default, Copy, Clone)]
+struct LinksWarming {
+  /// 过滤条件的版本
+  version: usize,
+
+  /// 正向、逆向遍历下一批开始的位置，为空表示该方向已经遍历完
+  forward: Option<Index>,
+  backward: Option<Index>,
 }
 
 impl RotatedLog {
   /// 创建新的一组系统日志文件维护实例，给定的 `path` 参数是未带回滚后缀的路径，
   /// 本类会自动在相同目录下，扫描它的被滚动的其他日志。
+  ///
+  /// 若 `path` 为 `-`，则本实例只读取标准输入，不存在任何滚动的日志。
   pub fn new(path: PathBuf, config: Config) -> Self {
     Self {
       path,
       log_files: VecDeque::with_capacity(config.possible_max_rotated_count),
+      file_ids: VecDeque::with_capacity(config.possible_max_rotated_count),
+      next_front_id: usize::MAX,
+      next_back_id: 0,
       want_older_log: false,
+      want_preload: config.eager_preload && config.tail_lines.is_none(),
+      config,
+      evicted_files: 0,
+      links_warming: LinksWarming::default(),
+      board_removals: DataBoardUpdates::default(),
     }
   }
 
+  /// 日志文件路径，不带被回滚时的后缀别名
+  pub fn path(&self) -> &PathBuf {
+    &self.path
+  }
+
+  /// 已经加载的日志文件，从旧到新排列
+  pub fn log_files(&self) -> impl Iterator<Item = &LogFile> {
+    self.log_files.iter()
+  }
+
+  /// 遍历所有已经加载的日志行，不考虑过滤，也不保证遍历的顺序
+  pub fn for_each_line_mut(&mut self, mut f: impl FnMut(&mut LogLine)) {
+    self
+      .log_files
+      .iter_mut()
+      .for_each(|file| file.data_mut().for_each_mut(&mut f));
+  }
+
   /// 标记期望获得更旧一点的日志
   pub fn set_want_older_log(&mut self) {
     self.want_older_log = true;
   }
 
+  /// 是否已经标记了期望获得更旧一点的日志
+  pub fn wants_older_log(&self) -> bool {
+    self.want_older_log
+  }
+
   /// 一个轮询周期内，在检查各个日志文件内容变更前，加载新的日志文件、
   /// 或者按照需求，加载耿旧的日志文件。
   ///
   /// 返回是否需要加入内容变更轮询，如果本系统日志还没有加载任何文件，则不参与轮询。
   ///
   /// 加载日志文件过程中有很多 await 点，它们并不能保证取消安全。
-  pub async fn prepare(&mut self) -> bool {
+  /// 每次打开文件前后，都会在数据看板中记录加载状态，以便界面展示加载进度。
+  pub async fn prepare(&mut self, data_board: &Arc<Mutex<DataBoard>>) -> bool {
     // 加载最新的日志。如果已经加载，则无事发生
-    let _ = self.maybe_load_latest_log().await;
+    let _ = self.maybe_load_latest_log(data_board).await;
+
+    // 根据配置，预加载所有的旧日志
+    let _ = self.maybe_preload_older_logs(data_board).await;
 
-    // 根据需求，加载旧一点的一份日志
-    let _ = self.maybe_load_older_log().await;
+    // 根据需求，加载旧一点的一份日志。该需求在提出时已经被记为一次加载，无论是否真的打开了文件，
+    // 都在处理后结束它
+    if self.want_older_log {
+      let _ = self.maybe_load_older_log(data_board).await;
+      data_board.lock().await.end_loading();
+    }
 
     !self.log_files.is_empty()
   }
 
   /// 处理日志内容的变更、文件的滚动与删除
   pub async fn update(&mut self, data_board: Arc<Mutex<DataBoard>>) {
+    // 最新的一份日志文件当前的行数，用于检查它是否有了新内容
+    let latest_len = self.log_files.back().map(|log_file| log_file.data().len());
+
     // select 所有日志文件的事件
     let async_fns: Vec<_> = self
       .log_files
@@ -99,30 +331,183 @@ impl RotatedLog {
 
     // 处理其中一个，其余取消处理
     let (events, index, _) = futures::future::select_all(async_fns).await;
+    let len = self.len();
+
+    // 只有最新的一份日志文件在被跟踪，它有了新内容时，不再处于空闲状态
+    let is_latest = index + 1 == self.log_files.len();
+    if is_latest && self.log_files.back().map(|log_file| log_file.data().len()) != latest_len {
+      data_board.lock().await.set_idle(&self.path, None);
+    }
 
-    // 处理该日志可能的删除事件
+    // 处理该日志可能的删除事件，以及最新日志的空闲事件
     if let Some(events) = events {
       for event in events {
-        if let Event::Removed = event
-          && let Some(mut log_file) = self.log_files.remove(index)
-        {
-          let _ = log_file.close().await;
+        match event {
+          Event::Removed => {
+            if let Some(mut log_file) = self.remove_log_file(index) {
+              let _ = log_file.close().await;
+            }
+          }
+          Event::Idle(since) if is_latest => {
+            data_board.lock().await.set_idle(&self.path, Some(since));
+          }
+          _ => {}
         }
       }
     }
+
+    // 内容可能增加了，检查是否超出了内存限制
+    self.evict().await;
+
+    // 删除或淘汰了日志行，同样是日志内容的变化，同时从统计信息中扣除它们
+    if self.len() != len || !self.board_removals.is_empty() {
+      let mut data_board = data_board.lock().await;
+      if self.len() != len {
+        data_board.update_lines();
+      }
+      data_board.apply_updates(
+        std::mem::take(&mut self.board_removals),
+        self.config.new_tag_default,
+      );
+    }
+  }
+
+  /// 若已加载的日志行数量超出限制，则从最旧的日志开始淘汰：先整份地淘汰更旧的日志文件，
+  /// 再整块地淘汰剩余最旧的那份文件头部的日志行。最新的一份日志文件总是保留
+  async fn evict(&mut self) {
+    let Some(max_lines) = self.config.max_lines else {
+      return;
+    };
+
+    let mut over = self.len().saturating_sub(max_lines);
+    if over == 0 {
+      return;
+    }
+
+    // 淘汰整份的旧日志文件
+    while self.log_files.len() > 1
+      && let Some(oldest) = self.log_files.front()
+      && oldest.data().len() <= over
+    {
+      over -= oldest.data().len();
+      self.evict_oldest_file().await;
+    }
+
+    // 淘汰剩余最旧的那份文件头部的部分日志行，整块淘汰后文件可能变空，同样将它淘汰
+    if over > 0
+      && let Some(oldest) = self.log_files.front_mut()
+    {
+      let max_lines = oldest.data().len() - over;
+      let board_removals = &mut self.board_removals;
+      oldest
+        .data_mut()
+        .evict_front(max_lines, |log| board_removals.remove_log(log));
+
+      if oldest.data().is_empty() && self.log_files.len() > 1 {
+        self.evict_oldest_file().await;
+      }
+    }
+  }
+
+  /// 关闭并淘汰最旧的那份日志文件
+  async fn evict_oldest_file(&mut self) {
+    if let Some(mut log_file) = self.remove_log_file(0) {
+      crate::println!("evict log file {:?}", log_file.path());
+      let _ = log_file.close().await;
+      self.evicted_files += 1;
+    }
+  }
+
+  /// 是否已经有日志行因为内存限制被淘汰，此时不再加载更旧的日志
+  fn is_evicted(&self) -> bool {
+    self.evicted_files > 0
+      || self
+        .log_files
+        .front()
+        .is_some_and(|log_file| log_file.data().is_front_evicted())
+  }
+
+  /// 在头部插入一份更旧的日志文件，为它分配新的编号
+  fn push_older_log_file(&mut self, log_file: LogFile) {
+    self.log_files.push_front(log_file);
+    self.file_ids.push_front(self.next_front_id);
+    self.next_front_id = self.next_front_id.wrapping_sub(1);
+  }
+
+  /// 在尾部插入一份更新的日志文件，为它分配新的编号
+  fn push_newer_log_file(&mut self, log_file: LogFile) {
+    self.log_files.push_back(log_file);
+    self.file_ids.push_back(self.next_back_id);
+    self.next_back_id = self.next_back_id.wrapping_add(1);
+  }
+
+  /// 移除给定位置的日志文件，其余文件的编号不变，它的日志行等待从统计信息中扣除
+  fn remove_log_file(&mut self, pos: usize) -> Option<LogFile> {
+    self.file_ids.remove(pos);
+    let log_file = self.log_files.remove(pos)?;
+    for (_, log) in log_file.data().iter_forward_from_head() {
+      self.board_removals.remove_log(log);
+    }
+    Some(log_file)
+  }
+
+  /// 给定索引中的 file_index，找到对应的文件在 log_files 中的位置，已经被淘汰、删除的文件返回空
+  fn file_position(&self, file_index: usize) -> Option<usize> {
+    // 编号按回绕运算从旧到新递增，以最旧的文件为基准，转换为单调递增的偏移量
+    let front = *self.file_ids.front()?;
+    let offset = file_index.wrapping_sub(front);
+
+    // 没有从中间删除过文件时，编号是连续的，偏移量就是位置
+    if self.file_ids.get(offset) == Some(&file_index) {
+      return Some(offset);
+    }
+    self
+      .file_ids
+      .binary_search_by_key(&offset, |id| id.wrapping_sub(front))
+      .ok()
+  }
+
+  /// 给定索引中的 file_index，找到相邻的更新（或者更旧）的那份文件的编号
+  fn adjacent_file_index(&self, file_index: usize, newer: bool) -> Option<usize> {
+    let pos = self.file_position(file_index)?;
+    let pos = match newer {
+      true => pos + 1,
+      false => pos.checked_sub(1)?,
+    };
+    self.file_ids.get(pos).copied()
+  }
+
+  /// 给定索引中的 file_index，获取对应的日志文件，已经被淘汰、删除的文件返回空
+  fn log_file(&self, file_index: usize) -> Option<&LogFile> {
+    self.log_files.get(self.file_position(file_index)?)
+  }
+
+  /// 给定索引中的 file_index，获取对应的可变日志文件，已经被淘汰、删除的文件返回空
+  fn log_file_mut(&mut self, file_index: usize) -> Option<&mut LogFile> {
+    let pos = self.file_position(file_index)?;
+    self.log_files.get_mut(pos)
   }
 
   /// 若当前还未加载最新的日志文件，也即系统正在更新的那一份（如 x.log），则尝试加载它。
   /// 如果根本不存在正在更新的日志，而我们的日志文件一份都没有加载，那就找到最新的一份滚动日志文件，进行加载
-  async fn maybe_load_latest_log(&mut self) -> Option<()> {
+  async fn maybe_load_latest_log(&mut self, data_board: &Arc<Mutex<DataBoard>>) -> Option<()> {
+    // 只读取末尾若干行时，加载一份之后就不再变化
+    if self.config.tail_lines.is_some() && !self.log_files.is_empty() {
+      return None;
+    }
+
     // 目前已经加载的最新日志文件的路径
     let loaded_latest_path = match self.log_files.back() {
       None => &PathBuf::new(),
       Some(log_file) => log_file.path(),
     };
 
+    // 已经加载的文件虽然路径相同，但可能已经被删除，并被重新创建了一份同名的新文件，
+    // 此时需要加载这份新文件，旧的文件会在收到删除事件时移除
+    let loaded_latest_is_recreated = self.is_loaded_latest_recreated();
+
     // 快速路径判断：如果最新的路径等于系统正在更新的路径，则结束处理
-    if loaded_latest_path == &self.path {
+    if loaded_latest_path == &self.path && !loaded_latest_is_recreated {
       return None;
     }
 
@@ -130,117 +515,181 @@ impl RotatedLog {
     let latest_path = self.find_latest_log_path()?;
 
     // 如果最新的这一份文件已经被加载，则结束处理
-    if loaded_latest_path == &latest_path {
+    if loaded_latest_path == &latest_path && !loaded_latest_is_recreated {
       return None;
     }
 
     // 加载最新的文件
-    self
-      .log_files
-      .push_back(self.open_log_file(latest_path).await?);
+    let log_file = self.open_log_file(latest_path, data_board).await?;
+    self.push_newer_log_file(log_file);
 
     None
   }
 
+  /// 检查已经加载的最新日志文件，是否和它的路径现在指向的文件不是同一个（通过 inode 判断）
+  fn is_loaded_latest_recreated(&self) -> bool {
+    let Some(log_file) = self.log_files.back() else {
+      return false;
+    };
+    let Some(inode) = log_file.inode() else {
+      return false;
+    };
+
+    match fs::metadata(log_file.path()) {
+      Ok(metadata) => metadata.ino() != inode,
+      Err(_) => false,
+    }
+  }
+
   /// 找到本系统日志最新的那一份
   fn find_latest_log_path(&self) -> Option<PathBuf> {
-    // 最新路径的记录
-    let mut latest_path: Option<PathBuf> = None;
+    // 标准输入没有滚动的文件，它自己就是最新的一份
+    if is_stdin(&self.path) {
+      return Some(self.path.clone());
+    }
 
-    // 找到字典序最小的路径，即为最新的文件
+    // 找到按滚动后缀解析出的最新的文件
     // （x.log, x.log.1, x.log.2 中，x.log 比 x.log.1 新，x.log.1 比 x.log.2 新）
-    self.visit_log_paths(|path: PathBuf| {
-      Self::update_with_latest_path(&mut latest_path, path);
-    });
-
-    // 返回可能找到的最新文件路径
-    latest_path
+    self
+      .log_paths()
+      .into_iter()
+      .min_by_key(|(recency, _)| *recency)
+      .map(|(_, path)| path)
   }
 
   /// 如果有需要，尝试加载更老一点的日志，这份日志仅比目前已经加载的日志再老一点
-  async fn maybe_load_older_log(&mut self) -> Option<()> {
+  async fn maybe_load_older_log(&mut self, data_board: &Arc<Mutex<DataBoard>>) -> Option<()> {
     // 判断是否有设置想要加载一份老日志的标志
     if !self.want_older_log {
       return None;
     }
     self.want_older_log = false;
 
+    // 已经有日志被淘汰时，再加载更旧的日志会导致内容不连续。只读取末尾若干行时，也不加载更旧的日志
+    if self.is_evicted() || self.config.tail_lines.is_some() {
+      return None;
+    }
+
     // 找到目录下，稍微旧一点的一份日志
     let older_path = self.find_older_log_path()?;
 
     // 加载这一份日志文件
-    self
-      .log_files
-      .push_front(self.open_log_file(older_path).await?);
+    let log_file = self.open_log_file(older_path, data_board).await?;
+    self.push_older_log_file(log_file);
 
     None
   }
 
-  fn find_older_log_path(&self) -> Option<PathBuf> {
-    // 找出目前已经加载的最老文件。如果找不到，则不往后处理
-    let loaded_oldest_path = self.log_files.front()?.path();
+  /// 若配置了预加载，则在最新的日志加载之后，并发地加载所有更旧的日志
+  async fn maybe_preload_older_logs(&mut self, data_board: &Arc<Mutex<DataBoard>>) -> Option<()> {
+    if !self.want_preload {
+      return None;
+    }
 
-    // 记录下一个旧一点的路径
-    let mut next_older_path: Option<PathBuf> = None;
+    // 以已经加载的最老文件为起点，如果一份都还没有加载，则下次再处理
+    let loaded_oldest_path = self.log_files.front()?.path().clone();
+    self.want_preload = false;
 
-    // 找到比已经加载的最老文件更老，但又在这些更老的文件中最新的那一个
-    self.visit_log_paths(|path: PathBuf| {
-      if &path > loaded_oldest_path {
-        Self::update_with_latest_path(&mut next_older_path, path);
-      }
-    });
+    if self.is_evicted() {
+      return None;
+    }
+
+    // 找到所有更旧的日志，按从新到旧排序
+    let loaded_oldest = self.recency_of(&loaded_oldest_path)?;
+    let mut older_paths: Vec<_> = self
+      .log_paths()
+      .into_iter()
+      .filter(|(recency, _)| *recency > loaded_oldest)
+      .collect();
+    older_paths.sort_by_key(|(recency, _)| *recency);
+    let mut older_paths: Vec<PathBuf> = older_paths.into_iter().map(|(_, path)| path).collect();
+    older_paths.truncate(
+      self
+        .config
+        .possible_max_rotated_count
+        .saturating_sub(self.log_files.len()),
+    );
+
+    // 并发地打开它们
+    let log_files = futures::future::join_all(
+      older_paths
+        .into_iter()
+        .map(|path| self.open_log_file(path, data_board)),
+    )
+    .await;
+
+    // 从新到旧依次插入头部，若有某一份加载失败，则不再插入更旧的，以保证内容连续
+    for log_file in log_files {
+      self.push_older_log_file(log_file?);
+    }
 
-    next_older_path
+    None
   }
 
-  /// 遍历属于本系统日志的那些具体的文件，也即 x.log, x.log.1, x.log.2 等
-  fn visit_log_paths(&self, mut func: impl FnMut(PathBuf)) -> Option<()> {
-    // 日志的名称
-    let log_name = self.path.file_name()?.to_str()?;
+  fn find_older_log_path(&self) -> Option<PathBuf> {
+    // 找出目前已经加载的最老文件。如果找不到，或者无法判断它的新旧，则不往后处理
+    let loaded_oldest = self.recency_of(self.log_files.front()?.path())?;
 
-    // 遍历本系统日志目录下的所有文件
-    for entry in fs::read_dir(self.path.parent()?).ok()? {
-      let entry = entry.ok()?;
+    // 找到比已经加载的最老文件更老，但又在这些更老的文件中最新的那一个
+    self
+      .log_paths()
+      .into_iter()
+      .filter(|(recency, _)| *recency > loaded_oldest)
+      .min_by_key(|(recency, _)| *recency)
+      .map(|(_, path)| path)
+  }
+
+  /// 找出属于本系统日志的那些具体的文件，也即 x.log, x.log.1, x.log.2 等，以及它们的新旧程度。
+  /// 无法识别滚动后缀的文件（如 x.log.bak）不属于本系统日志
+  fn log_paths(&self) -> Vec<(Recency, PathBuf)> {
+    let mut paths = vec![];
+    let Some(dir) = self.path.parent().and_then(|dir| fs::read_dir(dir).ok()) else {
+      return paths;
+    };
 
-      // 跳过文件的情况（很少命中这种情况）
-      if !entry.file_type().ok()?.is_file() {
+    // 遍历本系统日志目录下的所有文件
+    for entry in dir.flatten() {
+      // 跳过非文件的情况（很少命中这种情况）
+      if !entry.file_type().is_ok_and(|t| t.is_file()) {
         continue;
       }
 
-      // 找到有本系统日志名称前缀的文件，它们就是和本系统日志相关的文件，接着处理它们
-      if entry.file_name().to_str()?.starts_with(&log_name) {
-        func(entry.path());
+      // 解析出滚动后缀的文件，就是和本系统日志相关的文件
+      let path = entry.path();
+      if let Some(recency) = self.recency_of(&path) {
+        paths.push((recency, path));
       }
     }
 
-    Some(())
+    paths
   }
 
-  /// 比较记录中的最新路径，与一个新的路径，如果新的路径比记录中的路径更加新，
-  /// 则拿它来更新到记录中。
-  fn update_with_latest_path(curr_latest_path: &mut Option<PathBuf>, new_path: PathBuf) {
-    match curr_latest_path {
-      None => {
-        *curr_latest_path = Some(new_path);
-      }
-      Some(curr_latest_path) => {
-        if *curr_latest_path > new_path {
-          *curr_latest_path = new_path;
-        }
-      }
-    }
+  /// 解析属于本系统日志的某个文件的新旧程度
+  fn recency_of(&self, path: &Path) -> Option<Recency> {
+    let log_name = self.path.file_name()?.to_str()?;
+    Recency::parse(
+      log_name,
+      path.file_name()?.to_str()?,
+      &self.config.rotation_naming,
+    )
   }
 
-  /// 打开指定路径的日志文件
-  async fn open_log_file(&self, path: PathBuf) -> Option<LogFile> {
-    crate::println!("load log file {:?}", path);
-
+  /// 打开指定路径的日志文件，打开期间在数据看板中记录为正在加载
+  async fn open_log_file(
+    &self,
+    path: PathBuf,
+    data_board: &Arc<Mutex<DataBoard>>,
+  ) -> Option<LogFile> {
     // 如果要求被加载的日志文件名称等于系统日志最新的那份文件名称，
     // 则我们认为我们在打开一份正在被实时更新的日志文件
     let is_rolling_log = &path == &self.path;
 
     // 打开这一份日志文件
-    match LogFile::open(path, is_rolling_log).await {
+    data_board.lock().await.begin_loading();
+    let result = LogFile::open(path, is_rolling_log, &self.config).await;
+    data_board.lock().await.end_loading();
+
+    match result {
       Ok(log_file) => Some(log_file),
       Err(e) => {
         crate::eprintln!("failed to load log file: {}", e);
@@ -253,16 +702,20 @@ impl RotatedLog {
 impl RotatedLog {
   /// 获取指向第一条日志的索引
   pub fn first_index(&self) -> Index {
+    let line_index = match self.log_files.front() {
+      None => LogFileIndex::zero(),
+      Some(log_file) => log_file.data().first_index(),
+    };
     Index {
-      file_index: 0,
-      line_index: LogFileIndex::zero(),
+      file_index: self.file_ids.front().copied().unwrap_or(self.next_back_id),
+      line_index,
     }
   }
 
   /// 获取指向最后一条日志的索引
   pub fn last_index(&self) -> Index {
-    let file_index = self.log_files.len().saturating_sub(1);
-    let line_index = match self.log_files.get(file_index) {
+    let file_index = self.file_ids.back().copied().unwrap_or(self.next_back_id);
+    let line_index = match self.log_file(file_index) {
       None => LogFileIndex::zero(),
       Some(log_file) => log_file.data().last_index(),
     };
@@ -276,7 +729,7 @@ impl RotatedLog {
   /// 若移动结束时发现索引越界，则返回剩余需要移动的步长。
   pub fn step_index(&self, mut index: Index, mut n: isize) -> Result<Index, isize> {
     // 获取当前索引的 log_file，如果不存在，则终止处理
-    let mut log_file = self.log_files.get(index.file_index).ok_or(n)?;
+    let mut log_file = self.log_file(index.file_index).ok_or(n)?;
 
     // 是向前迭代、还是向后迭代
     let is_forward = n >= 0;
@@ -303,14 +756,12 @@ impl RotatedLog {
           n = m;
 
           // 往下或往上找文件，取决于步长的符号
-          index.file_index = if is_forward {
-            index.file_index + 1
-          } else {
-            index.file_index.overflowing_sub(1).0
-          };
+          index.file_index = self
+            .adjacent_file_index(index.file_index, is_forward)
+            .ok_or(n)?;
 
           // 找到新文件索引指向的文件数据，如果没找到，返回以剩余步长为信息的错误
-          log_file = self.log_files.get(index.file_index).ok_or(n)?;
+          log_file = self.log_file(index.file_index).ok_or(n)?;
 
           // 如果是下翻文件，则从该新文件的头开始新的搜素。反之，从尾部开始搜索。
           index.line_index = if is_forward {
@@ -323,11 +774,115 @@ impl RotatedLog {
     }
   }
 
+  /// 已经加载的所有文件中的日志行数量
+  pub fn len(&self) -> usize {
+    self
+      .log_files
+      .iter()
+      .map(|log_file| log_file.data().len())
+      .sum()
+  }
+
+  /// 是否还没有加载任何日志行
+  pub fn is_empty(&self) -> bool {
+    self
+      .log_files
+      .iter()
+      .all(|log_file| log_file.data().is_empty())
+  }
+
+  /// 给定索引，计算它指向的日志行在已加载的所有文件中是第几行（从 0 开始）。
+  /// 若索引越界，则返回日志行数量
+  pub fn position(&self, index: Index) -> usize {
+    match self.file_position(index.file_index) {
+      None => self.len(),
+      Some(pos) => {
+        let log_file = &self.log_files[pos];
+        self
+          .log_files
+          .iter()
+          .take(pos)
+          .map(|log_file| log_file.data().len())
+          .sum::<usize>()
+          + log_file.data().position(index.line_index)
+      }
+    }
+  }
+
+  /// 给定索引，计算它指向的日志行处于已加载内容中的相对位置，范围为 \[0, 1]。
+  /// 由于更旧的日志是按需加载的，这个值只反映已加载的内容，而不是完整的日志
+  pub fn fraction(&self, index: Index) -> f64 {
+    position_fraction(self.position(index), self.len())
+  }
+
+  /// 给定已加载内容中的相对位置（范围为 \[0, 1]），找到处于该位置的日志行的索引，是 fraction 的逆运算。
+  /// 同样只反映已加载的内容
+  pub fn index_at_fraction(&self, fraction: f64) -> Index {
+    let steps = (fraction.clamp(0.0, 1.0) * self.len().saturating_sub(1) as f64).round();
+    self
+      .step_index(self.first_index(), steps as isize)
+      .unwrap_or_else(|_| self.last_index())
+  }
+
+  /// 在已加载的各个文件中二分查找时间戳与给定时间最接近的日志行，返回它的索引与时间戳，
+  /// 距离相同时取更早的一行。不考虑过滤条件
+  pub fn find_by_timestamp(
+    &self,
+    dt: DateTime<FixedOffset>,
+  ) -> Option<(Index, DateTime<FixedOffset>)> {
+    let mut nearest: Option<(Index, DateTime<FixedOffset>)> = None;
+    for (file_index, log_file) in self.file_ids.iter().zip(self.log_files.iter()) {
+      let content = log_file.data();
+      let Some(line_index) = content.find_by_timestamp(dt) else {
+        continue;
+      };
+      let Some(timestamp) = content.get(line_index).and_then(LogLine::get_timestamp) else {
+        continue;
+      };
+
+      // 文件从旧到新排列，只有严格更近时才替换，保证距离相同时取更早的一行
+      if nearest.is_none_or(|(_, nearest)| (timestamp - dt).abs() < (nearest - dt).abs()) {
+        nearest = Some((Index::new(*file_index, line_index), timestamp));
+      }
+    }
+    nearest
+  }
+
+  /// 在已加载的各个文件中二分查找第一条时间不早于给定时间的日志行，不考虑过滤条件
+  pub fn first_at_or_after(&self, dt: DateTime<FixedOffset>) -> Option<Index> {
+    self
+      .file_ids
+      .iter()
+      .zip(self.log_files.iter())
+      .find_map(|(file_index, log_file)| {
+        let line_index = log_file.data().first_at_or_after(dt)?;
+        Some(Index::new(*file_index, line_index))
+      })
+  }
+
+  /// 找到最后一条早于给定时间的日志行，不存在时取第一行，不考虑过滤条件。
+  /// 从它出发正向遍历，不会遗漏任何不早于给定时间的日志行，以及夹在它们之间的、无法解析的行
+  pub fn last_before(&self, dt: DateTime<FixedOffset>) -> Index {
+    let Some(mut index) = self.first_at_or_after(dt) else {
+      return self.last_index();
+    };
+    while let Ok(prev) = self.step_index(index, -1) {
+      index = prev;
+      if self
+        .get(index)
+        .and_then(LogLine::get_timestamp)
+        .is_some_and(|timestamp| timestamp < dt)
+      {
+        break;
+      }
+    }
+    index
+  }
+
   /// 给定索引，获取日志行数据
   pub fn get(&self, index: Index) -> Option<&LogLine> {
     self
-      .log_files
-      .get(index.file_index)?
+      .log_file(index.file_index)?
       .data()
       .get(index.line_index)
   }
@@ -335,26 +890,78 @@ impl RotatedLog {
   /// 给定索引，获取日志行数据
   pub fn get_mut<'a>(&mut self, index: Index) -> Option<&'a mut LogLine> {
     self
-      .log_files
-      .get_mut(index.file_index)?
+      .log_file_mut(index.file_index)?
       .data_mut()
       .get_mut(index.line_index)
   }
 }
 
+/// 将日志行位置映射为它在总行数中的相对位置，范围为 \[0, 1]
+pub(crate) fn position_fraction(position: usize, len: usize) -> f64 {
+  if len <= 1 {
+    0.0
+  } else {
+    (position as f64 / (len - 1) as f64).clamp(0.0, 1.0)
+  }
+}
+
 // 定义迭代器以及获取接口
 crate::define_all_iterators!(RotatedLog, Index);
 
 type ItemMut<'a> = (Index, &'a mut LogLine);
 
-/// 带有标签过滤功能的迭代器，会在遍历过程中，建立缓存的快速跳转链路
+/// 日志的过滤条件，由保留日志的判断条件，以及该条件的版本组成。
+/// 跳转链路是按版本缓存在日志行上的，因此每个条件都有自己的版本，复用同一个条件（的克隆）才能复用缓存；
+/// 日志行上默认的链接版本为 0，它只用于不过滤任何日志的条件。
+#[derive(Clone)]
+pub struct LogFilter<'b> {
+  /// 条件的版本
+  version: usize,
+
+  /// 判断日志是否保留
+  predicate: Rc<dyn Fn(&LogLine) -> bool + 'b>,
+}
+
+impl<'b> LogFilter<'b> {
+  /// 创建新的过滤条件，并为它分配一个新的版本
+  pub fn new(predicate: impl Fn(&LogLine) -> bool + 'b) -> Self {
+    Self {
+      version: next_filter_version(),
+      predicate: Rc::new(predicate),
+    }
+  }
+}
+
+impl<'b> LogFilter<'b> {
+  /// 在数据黑板的过滤条件之外，只保留给定标签的日志，版本由数据黑板为该标签分配
+  pub fn locked_to_tag(data_board: &'b mut DataBoard, tag: &'b str) -> Self {
+    let version = data_board.get_locked_tag_filter_version(tag);
+    let data_board: &DataBoard = data_board;
+    Self {
+      version,
+      predicate: Rc::new(move |log| !data_board.is_filtered(log) && log.get_tag() == Some(tag)),
+    }
+  }
+}
+
+/// 按数据黑板中的标签、主机名选择进行过滤
+impl<'b> From<&'b DataBoard> for LogFilter<'b> {
+  fn from(data_board: &'b DataBoard) -> Self {
+    Self {
+      version: data_board.get_filter_version(),
+      predicate: Rc::new(|log| !data_board.is_filtered(log)),
+    }
+  }
+}
+
+/// 带有过滤功能的迭代器，会在遍历过程中，建立缓存的快速跳转链路
 pub struct FilteredIter<'a, 'b, I>
 where
   I: Iterator<Item = ItemMut<'a>> + IterNextNth<Item = ItemMut<'a>>,
 {
   data: &'a mut RotatedLog,
   iter: I,
-  tags: &'b TagsData,
+  filter: LogFilter<'b>,
   index: Index,
   link: LogLink,
 
@@ -367,6 +974,12 @@ where
 
   /// 确保迭代器走到尽头后，不再进入处理循环，而是直接结束
   end_is_reached: bool,
+
+  /// 还允许访问的日志行数量，为空时不限制。用完后迭代器提前结束
+  steps_left: Option<usize>,
+
+  /// 最近一次访问的日志行，无论它是否被过滤
+  last_visited: Option<Index>,
 }
 
 /// 用于特化取出指定索引开始遍历的迭代器（可能是正向，也可能是逆向）
@@ -407,30 +1020,45 @@ where
   I: Iterator<Item = ItemMut<'a>> + IterNextNth<Item = ItemMut<'a>>,
   Self: IterFrom<'a, Iter = I>,
 {
-  fn new(data: &'a mut RotatedLog, tags: &'b TagsData, index: Index) -> Self {
+  fn new(data: &'a mut RotatedLog, filter: LogFilter<'b>, index: Index) -> Self {
     let iter = Self::data_iter_from(data, index);
     Self {
       data,
       iter,
-      tags,
+      filter,
       index,
       link: LogLink::default(),
       chain_begin_is_known_as_invalid: false,
       end_is_reached: false,
+      steps_left: None,
+      last_visited: None,
     }
   }
 
+  /// 限制最多访问这么多行日志（包括被过滤掉的），用于分批遍历
+  pub fn with_step_limit(mut self, steps: usize) -> Self {
+    self.steps_left = Some(steps);
+    self
+  }
+
+  /// 迭代器是否因为访问行数的限制而提前结束，而不是走到了尽头
+  pub fn is_step_limit_reached(&self) -> bool {
+    self.steps_left == Some(0)
+  }
+
+  /// 最近一次访问的日志行，无论它是否被过滤，可以从这里继续下一批遍历
+  pub fn last_visited(&self) -> Option<Index> {
+    self.last_visited
+  }
+
   /// 检查给定的 link 是否有效
   fn is_link_valid(&self, link: LogLink) -> bool {
-    self.tags.get_version() == link.ver
+    self.filter.version == link.ver
   }
 
   /// 检查指定日志是否被过滤
   fn is_filtered(&self, log: &LogLine) -> bool {
-    match log.get_tag() {
-      None => false,
-      Some(tag) => !self.tags.get(tag),
-    }
+    !(self.filter.predicate)(log)
   }
 
   /// 从之前记录的（也即是上一次有效记录的第一跳无效记录）的日志行，
@@ -470,7 +1098,7 @@ where
       log.set_link(
         self.direction(),
         LogLink {
-          ver: self.tags.get_version(),
+          ver: self.filter.version,
           skip: farest_skip,
         },
       );
@@ -519,6 +1147,15 @@ where
         0
       };
 
+      // 访问行数用完时提前结束。此前无效日志行的 link 保持无效，不影响之后的遍历
+      if let Some(steps_left) = self.steps_left.as_mut() {
+        if *steps_left == 0 {
+          self.end_is_reached = true;
+          return None;
+        }
+        *steps_left -= 1;
+      }
+
       // 取出下一跳的日志
       let curr_item = self.iter.next_nth(skip).ok();
 
@@ -532,6 +1169,7 @@ where
 
       // 解包内容
       let (index, log) = curr_item?;
+      self.last_visited = Some(index);
 
       // 若上一个元素的 link 是有效的，那么之后的 link 更新处理都从新元素开始
       if self.is_link_valid(self.link) {
@@ -561,7 +1199,7 @@ where
         // 可能会出现在随机开始的第一次遍历里，从这一次跳转到正确的快速路径上后，应该一路命中的都是正确的日志行
         skip_sum = 0;
       } else {
-        // 本条日志 link 无效（当 tags 发生变更时，会让所有 link 无效），则继续下一次遍历寻找。
+        // 本条日志 link 无效（当 tags 或 hosts 发生变更时，会让所有 link 无效），则继续下一次遍历寻找。
         // 由于此前的步骤，现在记录的 link 一定是无效的，换句话说，下一个处理循环中，只会向前走一步
         skip_sum += 1;
       }
@@ -570,49 +1208,92 @@ where
 }
 
 impl RotatedLog {
-  /// 获取从指定索引出发的、带有标签过滤功能的正向迭代器
+  /// 获取从指定索引出发的、带有过滤功能的正向迭代器
   pub fn filtered_iter_forward_from<'a, 'b>(
     &'a mut self,
-    tags: &'b TagsData,
+    filter: impl Into<LogFilter<'b>>,
     index: Index,
   ) -> FilteredIter<'a, 'b, ForwardIterMut<'a>>
   where
     'b: 'a,
   {
-    FilteredIter::new(self, tags, index)
+    FilteredIter::new(self, filter.into(), index)
   }
 
-  /// 获取从指定索引出发的、带有标签过滤功能的逆向迭代器
+  /// 获取从指定索引出发的、带有过滤功能的逆向迭代器
   pub fn filtered_iter_backward_from<'a, 'b>(
     &'a mut self,
-    tags: &'b TagsData,
+    filter: impl Into<LogFilter<'b>>,
     index: Index,
   ) -> FilteredIter<'a, 'b, BackwardIterMut<'a>>
   where
     'b: 'a,
   {
-    FilteredIter::new(self, tags, index)
+    FilteredIter::new(self, filter.into(), index)
   }
 
-  /// 获取从头部出发的、带有标签过滤功能的正向迭代器
+  /// 获取从头部出发的、带有过滤功能的正向迭代器
   pub fn filtered_iter_forward_from_head<'a, 'b>(
     &'a mut self,
-    tags: &'b TagsData,
+    filter: impl Into<LogFilter<'b>>,
   ) -> FilteredIter<'a, 'b, ForwardIterMut<'a>>
   where
     'b: 'a,
   {
-    self.filtered_iter_forward_from(tags, self.first_index())
+    self.filtered_iter_forward_from(filter, self.first_index())
+  }
+
+  /// 按数据黑板中的过滤条件，分批地预先遍历所有日志，建立正向与逆向的跳转链路，
+  /// 使得过滤条件变化后的第一次滚动不必逐行检查。每次调用最多访问 `steps` 行日志，
+  /// 返回是否已经遍历完；过滤条件再次变化时，之前的进度作废，从头开始
+  pub fn warm_links(&mut self, data_board: &DataBoard, steps: usize) -> bool {
+    // 版本 0 的条件不过滤任何日志，默认的链接就是有效的
+    let version = data_board.get_filter_version();
+    if version == 0 {
+      return true;
+    }
+
+    if self.links_warming.version != version {
+      self.links_warming = LinksWarming {
+        version,
+        forward: Some(self.first_index()),
+        backward: Some(self.last_index()),
+      };
+    }
+
+    let mut warming = self.links_warming;
+    if let Some(index) = warming.forward {
+      let mut iter = self
+        .filtered_iter_forward_from(data_board, index)
+        .with_step_limit(steps);
+      iter.by_ref().for_each(drop);
+      warming.forward = iter
+        .is_step_limit_reached()
+        .then(|| iter.last_visited())
+        .flatten();
+    } else if let Some(index) = warming.backward {
+      let mut iter = self
+        .filtered_iter_backward_from(data_board, index)
+        .with_step_limit(steps);
+      iter.by_ref().for_each(drop);
+      warming.backward = iter
+        .is_step_limit_reached()
+        .then(|| iter.last_visited())
+        .flatten();
+    }
+    self.links_warming = warming;
+
+    warming.forward.is_none() && warming.backward.is_none()
   }
 
-  /// 获取从尾部出发的、带有标签过滤功能的逆向迭代器
+  /// 获取从尾部出发的、带有过滤功能的逆向迭代器
   pub fn filtered_iter_backward_from_tail<'a, 'b>(
     &'a mut self,
-    tags: &'b TagsData,
+    filter: impl Into<LogFilter<'b>>,
   ) -> FilteredIter<'a, 'b, BackwardIterMut<'a>>
   where
     'b: 'a,
   {
-    self.filtered_iter_backward_from(tags, self.last_index())
+    self.filtered_iter_backward_from(filter, self.last_index())
   }
 }
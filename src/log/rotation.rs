@@ -0,0 +1,202 @@
+//! 解析被滚动的日志文件名称的后缀，用于判断同一个系统日志的多份文件之间的新旧
+
+use chrono::{NaiveDate, NaiveDateTime};
+use std::{cmp::Reverse, str::FromStr};
+
+/// 滚动日志文件的命名方式，决定了如何从文件名后缀中解析出文件的新旧
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub enum RotationNaming {
+  /// 同时识别数字后缀与常见格式的日期后缀
+  #[default]
+  Auto,
+
+  /// 只识别数字后缀，如 x.log.1
+  Numeric,
+
+  /// 只识别给定 chrono 格式的日期后缀，如 logrotate dateext 默认的 `-%Y%m%d`
+  DateExt(String),
+}
+
+impl RotationNaming {
+  /// logrotate dateext 默认的日期后缀格式
+  pub const DEFAULT_DATE_FORMAT: &'static str = "-%Y%m%d";
+
+  /// 使用 logrotate dateext 默认日期格式的命名方式
+  pub fn date_ext() -> Self {
+    RotationNaming::DateExt(Self::DEFAULT_DATE_FORMAT.to_string())
+  }
+}
+
+impl FromStr for RotationNaming {
+  type Err = String;
+
+  /// 解析 `auto`、`numeric`、`dateext`，或者带自定义格式的 `dateext:FORMAT`
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "auto" => Ok(RotationNaming::Auto),
+      "numeric" => Ok(RotationNaming::Numeric),
+      "dateext" => Ok(RotationNaming::date_ext()),
+      _ => match s.strip_prefix("dateext:") {
+        Some(format) if !format.is_empty() => Ok(RotationNaming::DateExt(format.to_string())),
+        _ => Err(format!(
+          "unknown rotation naming '{s}', expected 'auto', 'numeric', 'dateext' or 'dateext:FORMAT'"
+        )),
+      },
+    }
+  }
+}
+
+/// 日志文件的新旧程度，比较时越小代表越新。
+///
+/// 不同的滚动方式混用时，数字后缀的文件总被认为比日期后缀的文件更新。
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Recency {
+  /// 没有滚动后缀，即正在被更新的那一份，如 x.log
+  Latest,
+
+  /// 数字后缀，如 x.log.1，数字越小越新
+  Numbered(u64),
+
+  /// 日期后缀，如 x.log-20250101，日期越晚越新
+  Dated(Reverse<NaiveDateTime>),
+}
+
+impl Recency {
+  /// 按给定的命名方式，解析文件名相对于日志名称的滚动后缀。
+  /// 不属于该日志、或者无法识别后缀的文件返回 None
+  pub fn parse(log_name: &str, file_name: &str, naming: &RotationNaming) -> Option<Self> {
+    let suffix = file_name.strip_prefix(log_name)?;
+    if suffix.is_empty() {
+      return Some(Recency::Latest);
+    }
+
+    match naming {
+      RotationNaming::Auto => Self::parse_auto(suffix),
+      RotationNaming::Numeric => {
+        Self::parse_number(suffix.strip_prefix('.')?).map(Recency::Numbered)
+      }
+      RotationNaming::DateExt(format) => {
+        Self::parse_date_with(suffix, format).map(|date| Recency::Dated(Reverse(date)))
+      }
+    }
+  }
+
+  /// 自动识别数字后缀或者日期后缀
+  fn parse_auto(suffix: &str) -> Option<Self> {
+    if let Some(number) = suffix.strip_prefix('.') {
+      return Self::parse_number(number).map(Recency::Numbered);
+    }
+
+    if let Some(date) = suffix.strip_prefix('-') {
+      return Self::parse_date(date).map(|date| Recency::Dated(Reverse(date)));
+    }
+
+    None
+  }
+
+  /// 解析纯数字的后缀
+  fn parse_number(s: &str) -> Option<u64> {
+    if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+      return None;
+    }
+    s.parse().ok()
+  }
+
+  /// 按给定的格式解析日期后缀，格式中可以只有日期
+  fn parse_date_with(s: &str, format: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(s, format).ok().or_else(|| {
+      NaiveDate::parse_from_str(s, format)
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+    })
+  }
+
+  /// 解析日期后缀，支持 logrotate 常用的 %Y%m%d、%Y%m%d%H 与 %Y-%m-%d 格式
+  fn parse_date(s: &str) -> Option<NaiveDateTime> {
+    if let Ok(date) = NaiveDateTime::parse_from_str(&format!("{s}00"), "%Y%m%d%H%M") {
+      return Some(date);
+    }
+
+    ["%Y%m%d", "%Y-%m-%d"]
+      .iter()
+      .find_map(|fmt| NaiveDate::parse_from_str(s, fmt).ok())
+      .and_then(|date| date.and_hms_opt(0, 0, 0))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_recency() {
+    let parse = |file_name| Recency::parse("x.log", file_name, &RotationNaming::Auto);
+
+    assert_eq!(parse("x.log"), Some(Recency::Latest));
+    assert_eq!(parse("x.log.1"), Some(Recency::Numbered(1)));
+    assert_eq!(parse("x.log.bak"), None);
+    assert_eq!(parse("x.log.1.gz"), None);
+    assert_eq!(parse("x.logger"), None);
+    assert_eq!(parse("y.log"), None);
+    assert!(matches!(parse("x.log-20250101"), Some(Recency::Dated(_))));
+    assert!(matches!(parse("x.log-2025010112"), Some(Recency::Dated(_))));
+    assert!(matches!(parse("x.log-2025-01-01"), Some(Recency::Dated(_))));
+    assert_eq!(parse("x.log-2025"), None);
+  }
+
+  #[test]
+  fn test_recency_order() {
+    let mut names = vec![
+      "x.log-20241231",
+      "x.log.10",
+      "x.log-20250101",
+      "x.log.2",
+      "x.log",
+      "x.log-2025010112",
+      "x.log.1",
+    ];
+    names.sort_by_key(|name| Recency::parse("x.log", name, &RotationNaming::Auto).unwrap());
+
+    // 数字后缀按数值而不是字符串排序，日期后缀按日期从晚到早排序
+    assert_eq!(
+      names,
+      vec![
+        "x.log",
+        "x.log.1",
+        "x.log.2",
+        "x.log.10",
+        "x.log-2025010112",
+        "x.log-20250101",
+        "x.log-20241231",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_rotation_naming() {
+    let numeric = RotationNaming::Numeric;
+    assert_eq!(
+      Recency::parse("x.log", "x.log.3", &numeric),
+      Some(Recency::Numbered(3))
+    );
+    assert_eq!(Recency::parse("x.log", "x.log-20250101", &numeric), None);
+
+    // dateext 不识别数字后缀，日期越晚越新
+    let date_ext: RotationNaming = "dateext".parse().unwrap();
+    assert_eq!(Recency::parse("x.log", "x.log.3", &date_ext), None);
+    assert!(
+      Recency::parse("x.log", "x.log-20250102", &date_ext).unwrap()
+        < Recency::parse("x.log", "x.log-20250101", &date_ext).unwrap()
+    );
+
+    // 自定义日期格式
+    let custom: RotationNaming = "dateext:_%Y%m%d-%H%M".parse().unwrap();
+    assert!(
+      Recency::parse("x.log", "x.log_20250101-1230", &custom).unwrap()
+        < Recency::parse("x.log", "x.log_20250101-0930", &custom).unwrap()
+    );
+    assert_eq!(Recency::parse("x.log", "x.log-20250101", &custom), None);
+
+    assert!("unknown".parse::<RotationNaming>().is_err());
+  }
+}
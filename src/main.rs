@@ -1,35 +1,235 @@
-use clap::Parser;
+use clap::{CommandFactory, Parser, error::ErrorKind};
 use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
 use rs_syslog_viewer::{
-  app::{Config, Viewer},
-  log::Config as LogConfig,
+  app::{
+    ColorScheme, Config, DisplayTimezone, HighlightRule, KeyAction, KeyBinding, KeyMap, Viewer,
+    controller::log_controller::TimestampFormat, page::log_page,
+  },
+  file::{is_stdin, reader::Overflow},
+  log::{Config as LogConfig, LineTemplate, RotationNaming},
+  ui::{ColorCapability, KeyEventEx, ScrollStyle, state_machine::Config as SmConfig},
 };
 use std::collections::BTreeSet;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// syslog viewer configured by command line arguments
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-  /// logs' root
-  root: PathBuf,
+  /// logs' root, or '-' to read logs from stdin
+  #[arg(required_unless_present = "files")]
+  root: Option<PathBuf>,
 
   /// logs' names (without postfix)
   names: Vec<String>,
+
+  /// full path of a log file to open, which may live anywhere and have any name (repeatable)
+  #[arg(long = "file", value_name = "PATH")]
+  files: Vec<PathBuf>,
+
+  /// watch the root for new log files matching this glob, e.g. '*.log'
+  #[arg(long)]
+  watch: Option<String>,
+
+  /// show a clock and live stats on the right of the status bar
+  #[arg(long)]
+  stats: bool,
+
+  /// capture mouse events to scroll and click logs (disables the terminal's own text selection)
+  #[arg(long)]
+  mouse: bool,
+
+  /// color scheme, 'dark' or 'light'
+  #[arg(long, default_value = "dark")]
+  theme: ColorScheme,
+
+  /// terminal color capability, 'truecolor', 'ansi16' or 'mono' (defaults to 'mono' if NO_COLOR is set)
+  #[arg(long)]
+  colors: Option<ColorCapability>,
+
+  /// highlight contents matching REGEX with COLOR, e.g. 'req-[0-9]+=lightred' (repeatable)
+  #[arg(long = "highlight", value_name = "REGEX=COLOR")]
+  highlight_rules: Vec<HighlightRule>,
+
+  /// timezone to display timestamps and match timestamp searches in, 'local', 'utc' or an offset like '+08:00'
+  #[arg(long, default_value = "local", allow_hyphen_values = true)]
+  tz: DisplayTimezone,
+
+  /// custom chrono format of timestamps, e.g. '%Y/%m/%d %I:%M:%S %p'; it is shown first and joins the timestamp style cycle
+  #[arg(long, allow_hyphen_values = true)]
+  timestamp_format: Option<TimestampFormat>,
+
+  /// show the newest logs on top, like 'journalctl -r'
+  #[arg(long)]
+  reverse: bool,
+
+  /// milliseconds between checks for key presses and log changes; larger values use less CPU
+  #[arg(long, value_name = "MS", default_value_t = 100)]
+  poll_interval: u64,
+
+  /// milliseconds to batch new lines for while following, so bursts of logs redraw at most once per interval; 0 disables it
+  #[arg(long, value_name = "MS", default_value_t = 0)]
+  follow_batch: u64,
+
+  /// hide logs of newly discovered tags until they are selected in the tags filter
+  #[arg(long)]
+  hide_new_tags: bool,
+
+  /// seconds without new lines before the followed log is reported as idle in the status bar; 0 disables it
+  #[arg(long, value_name = "SECS", default_value_t = 300)]
+  idle_timeout: u64,
+
+  /// load only the last N lines of each log and stop, without following new lines or loading rotated logs
+  #[arg(long, value_name = "N")]
+  tail: Option<usize>,
+
+  /// give each tag its own color, derived from its name
+  #[arg(long)]
+  tag_colors: bool,
+
+  /// cut each displayed line at this many columns with a trailing '…'; search and export still use full lines
+  #[arg(long, value_name = "COLS")]
+  max_width: Option<usize>,
+
+  /// symbol shown before marked lines; non-ASCII symbols fall back to '*' with '--colors mono'
+  #[arg(long, value_name = "GLYPH", default_value = "🚩")]
+  marker: String,
+
+  /// quit immediately on the quit key (or 'esc' with no sub-page open) instead of asking y/n
+  #[arg(long)]
+  no_confirm_quit: bool,
+
+  /// key to quit the program or leave a sub-page
+  #[arg(long, value_name = "CHAR", default_value_t = 'q')]
+  quit_key: char,
+
+  /// bind ACTION to KEY, e.g. 'toggle-mark=x' or 'follow=ctrl-f'; KEY is a character with an optional 'ctrl-' or 'alt-' prefix, or 'enter', 'tab', 'space' (repeatable)
+  #[arg(long = "bind", value_name = "ACTION=KEY")]
+  bindings: Vec<KeyBinding>,
+
+  /// how marked lines stand out, 'italic', 'bold' or 'band' (a background color)
+  #[arg(long, default_value = "italic")]
+  mark_style: log_page::MarkStyle,
+
+  /// keep the horizontal scroll when jumping between search matches instead of scrolling the match into view
+  #[arg(long)]
+  keep_scroll_on_search: bool,
+
+  /// keep this many lines of the previous page when paging through logs
+  #[arg(long, value_name = "LINES")]
+  page_overlap: Option<usize>,
+
+  /// columns to scroll the log view sideways on 'ctrl left' / 'ctrl right'
+  #[arg(long, value_name = "COLUMNS", default_value_t = 20)]
+  horizontal_jump: usize,
+
+  /// how the cursor scrolls in the log view: 'edge' keeps it 5 lines from the top and bottom, 'edge:N' keeps N lines (at most 20% of the view height plus one), 'centered' keeps it in the middle, 'free' lets it reach the edges
+  #[arg(long, value_name = "STYLE", default_value = "edge")]
+  scroll_style: ScrollStyle,
+
+  /// keep lines that cannot be parsed instead of dropping them, to debug unrecognized formats
+  #[arg(long)]
+  keep_bad_lines: bool,
+
+  /// regex with named groups to parse non-syslog lines, e.g. '\[(?P<level>\w+)\] (?P<timestamp>\S+) (?P<message>.*)'; groups 'timestamp' and 'message' are required, 'host', 'tag', 'pid' and 'level' are optional
+  #[arg(long, value_name = "REGEX")]
+  template: Option<LineTemplate>,
+
+  /// save tag selections, searches, styles and the cursor of the browsed logs to a session file under $XDG_STATE_HOME (or ~/.local/state) on exit, and restore them on startup
+  #[arg(long)]
+  session: bool,
+
+  /// naming of rotated log files, 'auto', 'numeric', 'dateext' or 'dateext:FORMAT' (e.g. 'dateext:-%Y%m%d')
+  #[arg(long, default_value = "auto")]
+  rotation: RotationNaming,
+
+  /// what to do when lines arrive faster than they can be handled, 'block', 'drop-newest' or 'drop-oldest'
+  #[arg(long, default_value = "block")]
+  overflow: Overflow,
 }
 
 fn main() -> Result<()> {
   let args = Args::parse();
+  if args.root.as_deref().is_some_and(is_stdin) && !args.files.is_empty() {
+    Args::command()
+      .error(
+        ErrorKind::ArgumentConflict,
+        "reading logs from stdin ('-') cannot be combined with '--file'",
+      )
+      .exit();
+  }
+  let quit_key = KeyBinding {
+    action: KeyAction::Quit,
+    key: KeyEvent::simple(KeyCode::Char(args.quit_key)),
+  };
+  let key_map = KeyMap::default()
+    .with_bindings(std::iter::once(&quit_key).chain(&args.bindings))
+    .unwrap_or_else(|e| Args::command().error(ErrorKind::ArgumentConflict, e).exit());
+  let poll_interval = Duration::from_millis(args.poll_interval.max(1));
+  let idle_timeout = (args.idle_timeout > 0).then(|| Duration::from_secs(args.idle_timeout));
+  let log_config = || {
+    let config = LogConfig::default()
+      .with_rotation_naming(args.rotation.clone())
+      .with_poll_interval(poll_interval)
+      .with_idle_timeout(idle_timeout)
+      .with_new_tag_default(!args.hide_new_tags)
+      .with_overflow(args.overflow);
+    let config = match args.keep_bad_lines {
+      true => config.with_keep_bad_lines(),
+      false => config,
+    };
+    let config = match &args.template {
+      Some(template) => config.with_line_template(template.clone()),
+      None => config,
+    };
+    match args.tail {
+      Some(lines) => config.with_tail_lines(lines),
+      None => config,
+    }
+  };
 
   Viewer::run(Config {
-    logs_root: args.root,
+    logs_root: args.root.clone().unwrap_or_default(),
     logs_configs: args
       .names
-      .into_iter()
+      .iter()
+      .cloned()
       .collect::<BTreeSet<String>>()
       .into_iter()
-      .map(|s| (s, LogConfig::default()))
+      .map(|s| (s, log_config()))
+      .collect(),
+    logs_files: args
+      .files
+      .iter()
+      .map(|path| (path.clone(), log_config()))
       .collect(),
+    log_config: log_config(),
+    logs_watch_pattern: args.watch,
+    mouse_capture: args.mouse,
+    show_stats: args.stats,
+    color_scheme: args.theme,
+    color_capability: args.colors.unwrap_or_else(ColorCapability::detect),
+    highlight_rules: args.highlight_rules,
+    display_tz: args.tz,
+    timestamp_format: args.timestamp_format,
+    reverse_order: args.reverse,
+    log_page_config: log_page::Config::default()
+      .with_tag_colors(args.tag_colors)
+      .with_max_line_width(args.max_width)
+      .with_marker(args.marker)
+      .with_mark_style(args.mark_style),
+    follow: args.tail.is_none(),
+    follow_batch_interval: Duration::from_millis(args.follow_batch),
+    scroll_to_content_match: !args.keep_scroll_on_search,
+    page_overlap: args.page_overlap,
+    horizontal_scroll_jump: args.horizontal_jump,
+    scroll_style: args.scroll_style,
+    confirm_quit: !args.no_confirm_quit,
+    persist_session: args.session,
+    key_map,
+    sm_config: SmConfig::default().with_poll_interval(poll_interval),
     ..Default::default()
   })
 }
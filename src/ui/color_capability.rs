@@ -0,0 +1,206 @@
+use ratatui::{
+  style::{Color, Modifier, Style},
+  text::Line,
+};
+use std::str::FromStr;
+
+/// 终端的颜色能力。能力有限的终端上，部分样式（颜色、斜体、下划线）可能难以辨认，
+/// 需要在渲染前降级
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ColorCapability {
+  /// 支持任意颜色，样式保持不变
+  #[default]
+  TrueColor,
+
+  /// 仅支持 16 种基本颜色，其余颜色映射为最接近的基本颜色，且不使用斜体
+  Ansi16,
+
+  /// 不支持颜色，仅通过加粗与反色进行强调
+  Mono,
+}
+
+/// 16 种基本颜色及其近似的 RGB 值
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+  (Color::Black, (0, 0, 0)),
+  (Color::Red, (128, 0, 0)),
+  (Color::Green, (0, 128, 0)),
+  (Color::Yellow, (128, 128, 0)),
+  (Color::Blue, (0, 0, 128)),
+  (Color::Magenta, (128, 0, 128)),
+  (Color::Cyan, (0, 128, 128)),
+  (Color::Gray, (192, 192, 192)),
+  (Color::DarkGray, (128, 128, 128)),
+  (Color::LightRed, (255, 0, 0)),
+  (Color::LightGreen, (0, 255, 0)),
+  (Color::LightYellow, (255, 255, 0)),
+  (Color::LightBlue, (0, 0, 255)),
+  (Color::LightMagenta, (255, 0, 255)),
+  (Color::LightCyan, (0, 255, 255)),
+  (Color::White, (255, 255, 255)),
+];
+
+impl ColorCapability {
+  /// 按环境变量推断终端的颜色能力：设置了非空的 `NO_COLOR` 时不使用颜色
+  pub fn detect() -> Self {
+    match std::env::var_os("NO_COLOR") {
+      Some(v) if !v.is_empty() => ColorCapability::Mono,
+      _ => ColorCapability::TrueColor,
+    }
+  }
+
+  /// 将样式降级为本能力能够展示的样式
+  pub fn downgrade(&self, style: Style) -> Style {
+    match self {
+      ColorCapability::TrueColor => style,
+      ColorCapability::Ansi16 => Style {
+        fg: style.fg.map(Self::to_ansi16),
+        bg: style.bg.map(Self::to_ansi16),
+        underline_color: style.underline_color.map(Self::to_ansi16),
+        add_modifier: style.add_modifier - Modifier::ITALIC,
+        ..style
+      },
+      ColorCapability::Mono => {
+        // 有背景色的内容（如光标所在行）改为反色展示，其余只保留加粗与反色
+        let mut add_modifier = style.add_modifier & (Modifier::BOLD | Modifier::REVERSED);
+        if style.bg.is_some_and(|bg| bg != Color::Reset) {
+          add_modifier |= Modifier::REVERSED;
+        }
+
+        Style {
+          fg: None,
+          bg: None,
+          underline_color: None,
+          add_modifier,
+          ..style
+        }
+      }
+    }
+  }
+
+  /// 降级一行中所有的样式
+  pub fn downgrade_line(&self, line: &mut Line) {
+    if *self == ColorCapability::TrueColor {
+      return;
+    }
+
+    line.style = self.downgrade(line.style);
+    for span in line.spans.iter_mut() {
+      span.style = self.downgrade(span.style);
+    }
+  }
+
+  /// 将任意颜色映射为最接近的基本颜色
+  fn to_ansi16(color: Color) -> Color {
+    let rgb = match color {
+      Color::Rgb(r, g, b) => (r, g, b),
+      Color::Indexed(i) if i < 16 => return ANSI16[i as usize].0,
+      Color::Indexed(i) if i < 232 => {
+        // 6x6x6 的颜色立方体
+        let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+        let i = i - 16;
+        (level(i / 36), level(i / 6 % 6), level(i % 6))
+      }
+      Color::Indexed(i) => {
+        // 24 级灰度
+        let v = 8 + (i - 232) * 10;
+        (v, v, v)
+      }
+      _ => return color,
+    };
+
+    let distance = |(r, g, b): (u8, u8, u8)| {
+      let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+      d(r, rgb.0) + d(g, rgb.1) + d(b, rgb.2)
+    };
+    ANSI16
+      .iter()
+      .min_by_key(|(_, v)| distance(*v))
+      .map(|(color, _)| *color)
+      .unwrap()
+  }
+}
+
+impl FromStr for ColorCapability {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "truecolor" => Ok(ColorCapability::TrueColor),
+      "ansi16" => Ok(ColorCapability::Ansi16),
+      "mono" => Ok(ColorCapability::Mono),
+      _ => Err(format!(
+        "unknown color capability '{s}', expected 'truecolor', 'ansi16' or 'mono'"
+      )),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ratatui::style::Stylize;
+
+  #[test]
+  fn test_true_color() {
+    let style = Style::default().fg(Color::Rgb(1, 2, 3)).italic();
+    assert_eq!(ColorCapability::TrueColor.downgrade(style), style);
+  }
+
+  #[test]
+  fn test_ansi16() {
+    let cap = ColorCapability::Ansi16;
+
+    // 基本颜色保持不变，其他颜色映射到最接近的基本颜色
+    let url = Style::default().blue().underlined();
+    assert_eq!(cap.downgrade(url), url);
+    assert_eq!(
+      cap
+        .downgrade(Style::default().fg(Color::Rgb(250, 10, 10)))
+        .fg,
+      Some(Color::LightRed)
+    );
+    assert_eq!(
+      cap.downgrade(Style::default().bg(Color::Indexed(4))).bg,
+      Some(Color::Blue)
+    );
+    assert_eq!(
+      cap.downgrade(Style::default().fg(Color::Indexed(231))).fg,
+      Some(Color::White)
+    );
+    assert_eq!(
+      cap.downgrade(Style::default().fg(Color::Indexed(232))).fg,
+      Some(Color::Black)
+    );
+
+    // 不使用斜体
+    let marked = cap.downgrade(Style::default().italic().bold());
+    assert_eq!(marked.add_modifier, Modifier::BOLD);
+  }
+
+  #[test]
+  fn test_mono() {
+    let cap = ColorCapability::Mono;
+
+    // 颜色与下划线被去掉
+    let url = cap.downgrade(Style::default().blue().underlined());
+    assert_eq!(url, Style::default());
+
+    // 搜索高亮仍然反色，加粗保留
+    let matched = cap.downgrade(Style::default().magenta().reversed());
+    assert_eq!(matched, Style::default().reversed());
+    let title = cap.downgrade(Style::default().white().bold().italic());
+    assert_eq!(title, Style::default().bold());
+
+    // 光标所在行的背景色变为反色
+    let cursor = cap.downgrade(Style::default().bg(Color::White));
+    assert_eq!(cursor, Style::default().reversed());
+    let reset = cap.downgrade(Style::default().bg(Color::Reset));
+    assert_eq!(reset, Style::default());
+
+    let mut line = Line::from(vec!["a".cyan(), "b".bold().yellow()]).bg(Color::White);
+    cap.downgrade_line(&mut line);
+    assert_eq!(line.style, Style::default().reversed());
+    assert_eq!(line.spans[0].style, Style::default());
+    assert_eq!(line.spans[1].style, Style::default().bold());
+  }
+}
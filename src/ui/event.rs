@@ -6,6 +6,9 @@ pub enum Event {
   /// 退出事件
   Quit,
 
-  /// 有某个事件发生
+  /// 有按键被当前状态响应
+  Key,
+
+  /// 有某个其他事件发生，如鼠标操作、终端大小变化
   Some,
 }
@@ -74,6 +74,13 @@ impl KeyEventEx for KeyEvent {
       (KeyCode::Char(c1), KeyCode::Char(c2))
         if c1.is_ascii_alphabetic() && c2.is_ascii_alphabetic() && c1.eq_ignore_ascii_case(&c2) =>
       {
+        // 若本按键明确要求了 shift（也即大写字母），那么另一个按键也需要是大写的
+        if self.modifiers.contains(KeyModifiers::SHIFT)
+          && !other.modifiers.contains(KeyModifiers::SHIFT)
+        {
+          return false;
+        }
+
         // 如果字母键相同，那么在去掉了 shift 的基础上，对比额外的修饰键是否相同
         let mut m1 = self.modifiers;
         m1.remove(KeyModifiers::SHIFT);
@@ -97,3 +104,22 @@ impl KeyEventEx for KeyEvent {
     self.modifiers == other.modifiers
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_same_as_uppercase() {
+    let g = KeyEvent::simple(KeyCode::Char('g'));
+    let upper_g = KeyEvent::shift('G');
+    let pressed_upper_g = KeyEvent::platform_consistent(KeyEvent::simple(KeyCode::Char('G')));
+
+    // 小写字母的按键忽略大小写
+    assert!(g.same_as(&pressed_upper_g));
+
+    // 大写字母的按键只响应大写字母
+    assert!(upper_g.same_as(&pressed_upper_g));
+    assert!(!upper_g.same_as(&g));
+  }
+}
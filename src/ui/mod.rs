@@ -1,3 +1,4 @@
+mod color_capability;
 mod event;
 mod key_event_ex;
 pub mod pager;
@@ -5,9 +6,12 @@ pub mod state_machine;
 mod status_bar;
 pub mod view_port;
 
+pub use color_capability::ColorCapability;
 pub use event::Event;
 pub use key_event_ex::KeyEventEx;
 pub use pager::{DemoPage, Page, PageState, Pager};
 pub use state_machine::{State, StateMachine};
 pub use status_bar::StatusBar;
-pub use view_port::{CursorEx, CursorExpectation, ViewPort, ViewPortEx, ViewPortRenderEx};
+pub use view_port::{
+  CursorEx, CursorExpectation, ScrollStyle, ViewPort, ViewPortEx, ViewPortRenderEx,
+};
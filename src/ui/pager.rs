@@ -1,4 +1,4 @@
-use crate::ui::{StatusBar, status_bar};
+use crate::ui::{ColorCapability, StatusBar, status_bar};
 use ratatui::{
   Frame,
   buffer::Buffer,
@@ -13,9 +13,16 @@ use std::{
 };
 
 /// 一帧页面渲染里，该页面的状态数据
+#[derive(Copy, Clone)]
 pub struct PageState {
   /// 焦点页面
   pub focus: bool,
+
+  /// 光标所在行的背景色
+  pub cursor_bg: Color,
+
+  /// 终端的颜色能力，页面渲染的内容需要按它降级样式
+  pub color_capability: ColorCapability,
 }
 
 /// 适用于本页面管理器的渲染接口
@@ -71,7 +78,7 @@ impl Page for DemoPage {
   }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub struct PageTheme {
   borders: Borders,
   border_type: BorderType,
@@ -81,6 +88,15 @@ pub struct PageTheme {
 }
 
 impl PageTheme {
+  /// 将边框与标题改为给定的颜色
+  fn with_fg(self, fg: Color) -> Self {
+    Self {
+      border_style: self.border_style.fg(fg),
+      title_style: self.title_style.fg(fg),
+      ..self
+    }
+  }
+
   pub fn full() -> Self {
     Self {
       borders: Borders::TOP | Borders::BOTTOM,
@@ -102,7 +118,7 @@ impl PageTheme {
   }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub struct Theme {
   /// 页面的背景色
   bg: Color,
@@ -118,6 +134,9 @@ pub struct Theme {
 
   /// 半屏页面实际占据的百分比
   half_page_constraint: Constraint,
+
+  /// 光标所在行的背景色
+  cursor_bg: Color,
 }
 
 impl Default for Theme {
@@ -128,6 +147,46 @@ impl Default for Theme {
       half_page: PageTheme::half(),
       status_bar: status_bar::Theme::default(),
       half_page_constraint: Constraint::Percentage(25),
+      cursor_bg: Color::White,
+    }
+  }
+}
+
+impl Theme {
+  /// 适用于浅色背景的主题
+  pub fn light() -> Self {
+    Self {
+      bg: Color::White,
+      full_page: PageTheme::full().with_fg(Color::Black),
+      half_page: PageTheme::half().with_fg(Color::Black),
+      status_bar: status_bar::Theme::light(),
+      cursor_bg: Color::Gray,
+      ..Self::default()
+    }
+  }
+
+  /// 光标所在行的背景色
+  pub fn cursor_bg(&self) -> Color {
+    self.cursor_bg
+  }
+
+  /// 按终端的颜色能力降级页面框与状态栏的样式。光标所在行的背景色由各页面渲染时一同降级
+  fn downgrade(self, capability: ColorCapability) -> Self {
+    let page = |theme: PageTheme| PageTheme {
+      border_style: capability.downgrade(theme.border_style),
+      title_style: capability.downgrade(theme.title_style),
+      ..theme
+    };
+
+    Self {
+      bg: capability
+        .downgrade(Style::new().fg(self.bg))
+        .fg
+        .unwrap_or(Color::Reset),
+      full_page: page(self.full_page),
+      half_page: page(self.half_page),
+      status_bar: self.status_bar.downgrade(capability),
+      ..self
     }
   }
 }
@@ -170,6 +229,9 @@ pub struct Pager {
   /// 风格
   theme: Theme,
 
+  /// 终端的颜色能力
+  color_capability: ColorCapability,
+
   /// 子页面的打开栈。
   /// 新打开的页面总是从 front 插入，如果有些页面在底部、而又被打开一次，将会被调整到最前面。
   /// 左边、右边、全屏总是只记录一个页面，也即在左边连续打开两个页面时，后打开的页面将顶掉前一个的记录，
@@ -194,6 +256,7 @@ impl Pager {
       pages: HashMap::new(),
       status_bar: StatusBar::new(theme.status_bar),
       theme,
+      color_capability: ColorCapability::default(),
       pages_stack: VecDeque::new(),
       focused_page_index: None,
     }
@@ -208,6 +271,14 @@ impl Pager {
     self.root_page = Box::new(page);
     self
   }
+
+  /// 设置终端的颜色能力，所有页面与状态栏都将按它降级样式
+  pub fn with_color_capability(mut self, capability: ColorCapability) -> Self {
+    self.theme = self.theme.downgrade(capability);
+    self.status_bar = StatusBar::new(self.theme.status_bar);
+    self.color_capability = capability;
+    self
+  }
 }
 
 impl Pager {
@@ -256,6 +327,48 @@ impl Pager {
     self.toggle_page(PageMode::Full(index));
   }
 
+  /// 指定的子页面是否正在最顶部全屏展示
+  pub fn is_full(&self, index: usize) -> bool {
+    matches!(self.pages_stack.front(), Some(PageMode::Full(top_index)) if *top_index == index)
+  }
+
+  /// 指定的子页面当前是否被渲染出来：它在最顶部全屏展示，或者是左右两边最近打开的页面
+  pub fn is_visible(&self, index: usize) -> bool {
+    match self.pages_stack.front() {
+      Some(PageMode::Full(top_index)) => *top_index == index,
+      _ => {
+        let (left, right) = self.side_pages();
+        left == Some(index) || right == Some(index)
+      }
+    }
+  }
+
+  /// 左右两边各自最近打开的子页面，第0个代表左边页面，第1个代表右边页面。
+  /// 仅在最顶部不是全屏页面时有意义
+  fn side_pages(&self) -> (Option<usize>, Option<usize>) {
+    let mut pattern: (Option<usize>, Option<usize>) = (None, None);
+
+    for page_mode in self.pages_stack.iter() {
+      match page_mode {
+        PageMode::Left(index) => {
+          if pattern.0.is_none() {
+            pattern.0 = Some(*index);
+          }
+        }
+        PageMode::Right(index) => {
+          if pattern.1.is_none() {
+            pattern.1 = Some(*index);
+          }
+        }
+        PageMode::Full(_) => {
+          unreachable!()
+        }
+      }
+    }
+
+    pattern
+  }
+
   /// 关闭指定的子页面，返回是否关闭成功
   pub fn close(&mut self, index: usize) -> bool {
     for i in 0..self.pages_stack.len() {
@@ -363,7 +476,11 @@ impl Pager {
   /// 4. 如果没有打开的子页面，则全部空间用于渲染根页面。
   fn render_main(&self, area: Rect, buf: &mut Buffer) {
     // 构建页面状态数据
-    let mut state = PageState { focus: false };
+    let mut state = PageState {
+      focus: false,
+      cursor_bg: self.theme.cursor_bg,
+      color_capability: self.color_capability,
+    };
 
     // 置顶打开了一个全屏子页面，全部空间用于渲染它
     if let Some(PageMode::Full(index)) = self.pages_stack.front() {
@@ -373,25 +490,7 @@ impl Pager {
     }
 
     // 寻找子页面的打开模式，用双元素元组表示，第0个代表左边页面，第1个代表右边页面
-    let mut pattern: (Option<usize>, Option<usize>) = (None, None);
-
-    for page_mode in self.pages_stack.iter() {
-      match page_mode {
-        PageMode::Left(index) => {
-          if pattern.0.is_none() {
-            pattern.0 = Some(*index);
-          }
-        }
-        PageMode::Right(index) => {
-          if pattern.1.is_none() {
-            pattern.1 = Some(*index);
-          }
-        }
-        PageMode::Full(_) => {
-          unreachable!()
-        }
-      }
-    }
+    let pattern = self.side_pages();
 
     // 根据不同的子页面打开模式，进行不同的渲染
     match &pattern {
@@ -1,5 +1,5 @@
 use crate::ui::{Event as UiEvent, KeyEventEx, Pager};
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 use std::{collections::HashMap, time::Duration};
 
 /// 在某个状态下，识别到指定按键事件后，执行的动作。不会引起状态切换
@@ -12,6 +12,9 @@ type GotoAction = Box<dyn FnMut(&mut Pager) -> bool>;
 /// 输入栏内容变更时的回调函数
 type InputHandler = Box<dyn FnMut(&str)>;
 
+/// 在某个状态下，收到鼠标事件后执行的动作。不会引起状态切换
+type MouseAction = Box<dyn FnMut(&mut Pager, MouseEvent)>;
+
 /// 定义状态响应某个键盘事件后，发生的动作、或下一个转移的目标状态
 struct Transition {
   /// 响应的事件
@@ -62,6 +65,9 @@ pub struct State {
 
   /// 需要手动调用的动作
   manual_actions: Vec<Action>,
+
+  /// 响应鼠标事件的动作
+  mouse_actions: Vec<MouseAction>,
 }
 
 impl State {
@@ -76,6 +82,7 @@ impl State {
       enter_actions: Vec::new(),
       leave_actions: Vec::new(),
       manual_actions: Vec::new(),
+      mouse_actions: Vec::new(),
     }
   }
 
@@ -148,6 +155,15 @@ impl State {
     self.manual_actions.push(Box::new(act));
     self
   }
+
+  /// 设置响应鼠标事件的动作
+  pub fn mouse_action<F>(mut self, act: F) -> Self
+  where
+    F: FnMut(&mut Pager, MouseEvent) + 'static,
+  {
+    self.mouse_actions.push(Box::new(act));
+    self
+  }
 }
 
 impl State {
@@ -183,7 +199,7 @@ impl State {
 
     // 优先处理输入的相关的事件
     if self.handle_input(pager, event).is_some() {
-      return SmEvent::Some(UiEvent::Some);
+      return SmEvent::Some(UiEvent::Key);
     }
 
     // 从前往后逐一对比事件响应条件，命中第一个时进行处理
@@ -192,7 +208,7 @@ impl State {
         return if (t.act)(pager) {
           SmEvent::Jump(t.next_state)
         } else {
-          SmEvent::Some(UiEvent::Some)
+          SmEvent::Some(UiEvent::Key)
         };
       }
     }
@@ -210,9 +226,13 @@ impl State {
     // 取出输入模式下的回调处理函数。如果没有配置输入模式，则终止处理
     let handler = &mut self.input_mode.as_mut()?.handler;
 
-    // 响应输入相关的按键输入，记录内容是否变更
+    // 响应输入相关的按键输入，记录内容是否变更。带 ctrl 或 alt 的字符按键留给状态的动作处理
     let changed = match event.code {
-      KeyCode::Char(to_insert) if !event.modifiers.contains(KeyModifiers::CONTROL) => {
+      KeyCode::Char(to_insert)
+        if !event
+          .modifiers
+          .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+      {
         pager.status().enter_char(to_insert);
         true
       }
@@ -251,6 +271,14 @@ impl Default for Config {
   }
 }
 
+impl Config {
+  /// 设置等待键盘事件的时间，也即没有事件时，主循环检查一次数据变化的间隔
+  pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+    self.poll_interval = poll_interval;
+    self
+  }
+}
+
 /// 处理 UI 的键盘事件，管理多个状态，并执行它们的转移与响应
 pub struct StateMachine {
   /// 使用整数索引的所有状态量
@@ -322,7 +350,18 @@ impl StateMachine {
           };
         }
 
-        // 非键盘事件，全部忽略，程序继续运行
+        // 处理鼠标事件（仅在开启鼠标捕获时才会收到），程序继续运行
+        Ok(Event::Mouse(event)) => {
+          for act in self.get_current_state().mouse_actions.iter_mut() {
+            act(pager, event);
+          }
+          return UiEvent::Some;
+        }
+
+        // 终端大小变化，需要重新渲染，程序继续运行
+        Ok(Event::Resize(..)) => return UiEvent::Some,
+
+        // 其他事件，全部忽略，程序继续运行
         Ok(_) => {}
 
         // 读取事件出错，记录，程序继续运行
@@ -347,14 +386,15 @@ impl StateMachine {
     }
   }
 
-  fn manage_once(&mut self, pager: &mut Pager, event: KeyEvent) -> UiEvent {
+  /// 响应一次键盘事件，进行状态流转
+  pub(crate) fn manage_once(&mut self, pager: &mut Pager, event: KeyEvent) -> UiEvent {
     let event = KeyEvent::platform_consistent(event);
     match self.get_current_state().react(pager, event) {
       SmEvent::Some(event) => event,
       SmEvent::Jump(next_state_index) => {
         self.leave_current(pager);
         self.enter(pager, next_state_index);
-        UiEvent::Some
+        UiEvent::Key
       }
     }
   }
@@ -1,9 +1,10 @@
+use crate::ui::ColorCapability;
 use itertools::Itertools;
 use ratatui::{
   buffer::Buffer,
-  layout::Rect,
+  layout::{Constraint, Layout, Rect},
   style::{Color, Style, Stylize},
-  text::{Span, Text},
+  text::{Line, Span, Text},
   widgets::Widget,
 };
 use std::borrow::Cow;
@@ -17,7 +18,7 @@ enum Mode {
   Input,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub struct Theme {
   pub bg: Color,
   pub prefix: Style,
@@ -40,6 +41,35 @@ impl Default for Theme {
   }
 }
 
+impl Theme {
+  /// 按终端的颜色能力降级主题中的样式
+  pub fn downgrade(self, capability: ColorCapability) -> Self {
+    Self {
+      bg: capability
+        .downgrade(Style::new().fg(self.bg))
+        .fg
+        .unwrap_or(Color::Reset),
+      prefix: capability.downgrade(self.prefix),
+      info: capability.downgrade(self.info),
+      error: capability.downgrade(self.error),
+      prompt: capability.downgrade(self.prompt),
+      input: capability.downgrade(self.input),
+    }
+  }
+
+  /// 适用于浅色背景的主题，状态栏本身使用深色背景以示区分
+  pub fn light() -> Self {
+    Self {
+      bg: Color::Black,
+      prefix: Style::new().white().bold(),
+      info: Style::new().green().bold(),
+      error: Style::new().red().bold(),
+      prompt: Style::new().gray().bold(),
+      input: Style::new().white(),
+    }
+  }
+}
+
 /// 渲染界面最底部的状态栏，展示提示或报错消息，有时候它也会成为输入框。
 pub struct StatusBar {
   /// 展示的模式
@@ -61,6 +91,21 @@ pub struct StatusBar {
   /// 光标的渲染位置（相对于可输入范围的相对位置）
   cursor_index: usize,
 
+  /// 展示在状态栏右侧的常驻信息，不受模式的影响
+  indicator: String,
+
+  /// 展示在状态栏最右侧的统计信息，如时钟、日志数量等
+  stats: String,
+
+  /// 展示在右侧的提醒，如被跟踪的日志长时间没有新内容
+  notice: String,
+
+  /// 是否有日志正在加载，是则在右侧展示一个旋转的加载提示
+  loading: bool,
+
+  /// 加载提示当前的帧
+  spinner_frame: usize,
+
   /// 本状态栏的主题
   theme: Theme,
 }
@@ -74,6 +119,11 @@ impl StatusBar {
       input: String::new(),
       input_index: 0,
       cursor_index: 0,
+      indicator: String::new(),
+      stats: String::new(),
+      notice: String::new(),
+      loading: false,
+      spinner_frame: 0,
       theme,
     }
   }
@@ -104,6 +154,46 @@ impl StatusBar {
     self.reset_error();
   }
 
+  pub fn set_indicator<T>(&mut self, indicator: T)
+  where
+    T: Into<String>,
+  {
+    self.indicator = indicator.into();
+  }
+
+  /// 设置展示在最右侧的统计信息，为空时不展示
+  pub fn set_stats<T>(&mut self, stats: T)
+  where
+    T: Into<String>,
+  {
+    self.stats = stats.into();
+  }
+
+  pub fn stats(&self) -> &str {
+    &self.stats
+  }
+
+  /// 设置展示在右侧的提醒，为空时不展示
+  pub fn set_notice<T>(&mut self, notice: T)
+  where
+    T: Into<String>,
+  {
+    self.notice = notice.into();
+  }
+
+  pub fn notice(&self) -> &str {
+    &self.notice
+  }
+
+  /// 设置是否有日志正在加载
+  pub fn set_loading(&mut self, loading: bool) {
+    self.loading = loading;
+  }
+
+  pub fn is_loading(&self) -> bool {
+    self.loading
+  }
+
   pub fn reset_input(&mut self, input: String) {
     self.input = input;
     self.input_index = self.input.chars().count();
@@ -217,10 +307,19 @@ impl StatusBar {
 const INFO_PREFIX: &str = " # ";
 const ERROR_PREFIX: &str = " ! ";
 const INPUT_PREFIX: &str = " $ ";
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+const LOADING: &str = "loading";
 
 impl StatusBar {
   /// 渲染状态栏，返回光标位置，由外层调用者渲染
   pub fn render(&mut self, area: Rect, buf: &mut Buffer) -> Option<usize> {
+    // 将状态栏分为左侧的消息或输入区，以及右侧的常驻信息区
+    let horizontal = Layout::horizontal([
+      Constraint::Fill(1),
+      Constraint::Length(self.right_width() as u16),
+    ]);
+    let [left, right] = area.layout(&horizontal);
+
     let mut text = Text::default().bg(self.theme.bg);
     let mut cursor_position = None;
 
@@ -238,7 +337,7 @@ impl StatusBar {
           text.push_span(Span::styled(&self.message, self.theme.prompt));
 
           // 供输入内容展示的最大宽度，如果输入超过这个宽度，我们需要省略内容
-          let max_width = area.width as isize
+          let max_width = left.width as isize
             - 1
             - INPUT_PREFIX.len() as isize
             - self.message.chars().count() as isize;
@@ -258,7 +357,29 @@ impl StatusBar {
       }
     }
 
-    text.render(area, buf);
+    text.render(left, buf);
+
+    let mut line = Line::default().bg(self.theme.bg);
+    if self.loading {
+      self.spinner_frame = (self.spinner_frame + 1) % SPINNER.len();
+      line.push_span(Span::styled(
+        format!(" {} {} ", SPINNER[self.spinner_frame], LOADING),
+        self.theme.prompt,
+      ));
+    }
+    if !self.notice.is_empty() {
+      line.push_span(Span::styled(format!(" {} ", self.notice), self.theme.error));
+    }
+    if !self.indicator.is_empty() {
+      line.push_span(Span::styled(
+        format!(" {} ", self.indicator),
+        self.theme.prompt,
+      ));
+    }
+    if !self.stats.is_empty() {
+      line.push_span(Span::styled(format!(" {} ", self.stats), self.theme.info));
+    }
+    line.right_aligned().render(right, buf);
 
     match cursor_position {
       None => None,
@@ -269,6 +390,19 @@ impl StatusBar {
     }
   }
 
+  /// 右侧常驻信息与统计信息所占的宽度
+  fn right_width(&self) -> usize {
+    let width = |s: &str| {
+      if s.is_empty() {
+        0
+      } else {
+        s.chars().count() + 2
+      }
+    };
+    let loading = if self.loading { LOADING.len() + 4 } else { 0 };
+    loading + width(&self.notice) + width(&self.indicator) + width(&self.stats)
+  }
+
   /// 当输入框的宽度不够时，根据光标的位置，对内容进行选择性缩略
   fn omit_some_input_by_cursor(&'_ self, max_width: usize) -> (Cow<'_, str>, usize) {
     // 获取输入的字符数量
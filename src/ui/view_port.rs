@@ -1,12 +1,12 @@
-use crate::log::LogDirection;
+use crate::{log::LogDirection, ui::PageState};
 use ratatui::{
   buffer::Buffer,
   layout::Rect,
   prelude::*,
   symbols,
-  widgets::{Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+  widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
 };
-use std::collections::VecDeque;
+use std::{collections::VecDeque, str::FromStr};
 
 /// 描述本帧内的控制
 #[derive(Default, Copy, Clone)]
@@ -26,6 +26,62 @@ pub enum Control {
 
   /// 往下翻页
   PageDown,
+
+  /// 保持光标在展示区中的行不变，将内容滚动这么多行，向上为负，向下为正。用于半页翻动以及带重叠的翻页
+  ScrollBy(isize),
+
+  /// 跳转到最旧的数据。和 Follow 一样，它会一直保持，使得更旧的数据加载后，光标也能继续跳转到最旧处
+  GoTop,
+
+  /// 跳转到最新的数据，但不跟踪
+  GoBottom,
+}
+
+/// 描述光标在展示区中的滚动风格
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScrollStyle {
+  /// 光标离上下边界最少保持这么多行。该间距最多为展示区高度的 20% 再加一行，
+  /// 展示区较矮时会按此缩减，以免光标几乎无法在展示区内移动
+  EdgeSpacing(usize),
+
+  /// 在数据允许的情况下，光标总是保持在展示区中间
+  Centered,
+
+  /// 光标可以自由移动到展示区的任意位置
+  Free,
+}
+
+impl Default for ScrollStyle {
+  fn default() -> Self {
+    ScrollStyle::EdgeSpacing(5)
+  }
+}
+
+impl ScrollStyle {
+  /// 按展示区高度缩减后，光标实际离上下边界的最少行数
+  fn edge_spacing(spacing: usize, height: usize) -> usize {
+    ((height as f64 * 0.2 + 1.0) as usize).min(spacing)
+  }
+}
+
+impl FromStr for ScrollStyle {
+  type Err = String;
+
+  /// 解析 `edge`、`edge:N`、`centered` 或 `free`，其中 `edge` 保持默认的间距
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.split_once(':') {
+      None if s == "edge" => Ok(ScrollStyle::default()),
+      None if s == "centered" => Ok(ScrollStyle::Centered),
+      None if s == "free" => Ok(ScrollStyle::Free),
+      Some(("edge", spacing)) => spacing
+        .parse()
+        .map(ScrollStyle::EdgeSpacing)
+        .map_err(|_| format!("invalid edge spacing '{spacing}', expected a number of lines")),
+      _ => Err(format!(
+        "unknown scroll style '{s}', expected 'edge', 'edge:N', 'centered' or 'free'"
+      )),
+    }
+  }
 }
 
 #[derive(Default, Copy, Clone)]
@@ -34,7 +90,6 @@ struct VerticalScrollState {
   position: usize,
 }
 
-#[derive(Default)]
 pub struct ViewPort {
   /// 展示区的高度，也即能够展示的日志行数量
   height: usize,
@@ -57,9 +112,55 @@ pub struct ViewPort {
   /// 如果位置设置为 None，则没有横向滚动能力。
   horizontal_scroll_position: Option<usize>,
 
+  /// 横向快速跳转时，一次移动的步长
+  horizontal_scroll_jump: usize,
+
+  /// 光标的滚动风格
+  scroll_style: ScrollStyle,
+
   /// 纵向滚动条的状态，其中的内容数量以及滚动条位置需要外部设置。
   /// 如果条目数量为零，则不会展示纵向滚动条，但仍然具备纵向滚动能力。
   vertical_scroll_state: VerticalScrollState,
+
+  /// 上一次渲染内容的区域，用于将鼠标点击的位置映射到展示区的行上
+  area: Rect,
+
+  /// 展示区高度是否发生了变化。高度变化时光标位置会被钳制，它指向的不一定还是原来的数据
+  resized: bool,
+
+  /// 高度变化前光标所在的行。钳制后的光标不一定指向原来的数据，保持不动时按该行取出上一帧光标指向的数据
+  cursor_before_resize: Option<usize>,
+
+  /// 跟踪最新数据时，光标是否置顶。数据倒序展示时，最新的数据在顶部
+  follow_at_top: bool,
+
+  /// 整页翻动时，保留上一页的这么多行作为上下文。为空时沿用光标置顶、置底的翻页方式
+  page_overlap: Option<usize>,
+
+  /// 上一次渲染时的列数。多列展示时，数据按先列后行的顺序排列，展示区的高度即所有列能容纳的数据数量
+  columns: usize,
+}
+
+impl Default for ViewPort {
+  fn default() -> Self {
+    Self {
+      height: 0,
+      cursor: 0,
+      cursor_index: 0,
+      data_count: 0,
+      control: Control::default(),
+      horizontal_scroll_position: None,
+      horizontal_scroll_jump: 20,
+      scroll_style: ScrollStyle::default(),
+      vertical_scroll_state: VerticalScrollState::default(),
+      area: Rect::default(),
+      resized: false,
+      cursor_before_resize: None,
+      follow_at_top: false,
+      page_overlap: None,
+      columns: 1,
+    }
+  }
 }
 
 /// 扩展 view port 数据的能力，假设了 view port 数据是由 Key 和 Value 组成的元组
@@ -83,6 +184,12 @@ pub enum CursorExpectation {
   None,
   MoreUp,
   MoreDown,
+
+  /// 期望光标指向全部数据中的第一条
+  Top,
+
+  /// 期望光标指向全部数据中的最后一条
+  Bottom,
 }
 
 /// 扩展了 [ViewPort] 对具体数据的操作能力，以及响应控制的能力。
@@ -106,24 +213,27 @@ pub trait ViewPortEx {
   /// 一般返回的是上一帧的旧数据
   fn apply(&mut self) -> Option<(&Self::Item, CursorExpectation)> {
     let control = self.control();
+    let cursor_before_resize = self.ui_mut().cursor_before_resize.take();
 
-    // 重置控制量
-    match control {
-      Control::Follow => {}
-      _ => *self.control_mut() = Control::Idle,
+    // 重置控制量，持续生效的控制除外
+    if !self.ui().is_sticky() {
+      *self.control_mut() = Control::Idle;
     }
 
     // 响应控制
     match control {
-      // 光标保持不动，返回光标指向的数据
+      // 光标保持不动，返回光标指向的数据。高度变化过时，返回变化前光标指向的数据，使其在页面重新打开、缩放时保持不动
       Control::Idle => self
         .data()
-        .get(self.ui().cursor)
+        .get(cursor_before_resize.unwrap_or(self.ui().cursor))
         .map(|v| (v, CursorExpectation::None)),
 
       // 将光标拉到最顶部，跟踪最新的数据。由于本类记录的数据是落后的，并不知道最新是什么数据，因此这里返回 None
       Control::Follow => {
-        self.ui_mut().set_cursor_at_bottom();
+        match self.ui().follow_at_top {
+          true => self.ui_mut().set_cursor_at_top(),
+          false => self.ui_mut().set_cursor_at_bottom(),
+        };
         None
       }
 
@@ -170,6 +280,27 @@ pub trait ViewPortEx {
         self.ui_mut().set_cursor_at_top();
         self.data().back().map(|v| (v, cursor_expectation))
       }
+
+      // 滚动内容，返回滚动后光标所在行应当展示的数据。目标数据超出展示区时，退而取展示区边缘的数据，
+      // 并移动光标的行，使内容滚动的行数保持不变。展示区外的数据由外部从这条数据开始填充，不需要额外的期望
+      Control::ScrollBy(lines) => {
+        let ui = self.ui();
+        let (index, cursor) = ViewPort::scroll_target(ui.cursor, ui.data_count, ui.height, lines);
+        self.ui_mut().cursor = cursor;
+        self.data().get(index).map(|v| (v, CursorExpectation::None))
+      }
+
+      // 跳转到顶部，光标置顶。展示区内的数据不一定包含最旧的数据，因此由外部根据期望重新定位
+      Control::GoTop => {
+        self.ui_mut().set_cursor_at_top();
+        self.data().front().map(|v| (v, CursorExpectation::Top))
+      }
+
+      // 跳转到底部，光标置底，同上
+      Control::GoBottom => {
+        self.ui_mut().set_cursor_at_bottom();
+        self.data().back().map(|v| (v, CursorExpectation::Bottom))
+      }
     }
   }
 
@@ -205,9 +336,11 @@ pub trait ViewPortRenderEx: ViewPortEx {
     &mut self,
     mut area: Rect,
     buf: &mut Buffer,
-    focus: bool,
+    state: &PageState,
     f: impl Fn(&Self::Item) -> Line,
   ) {
+    let capability = state.color_capability;
+
     // 组装渲染条目
     let mut items: Vec<Line> = self.data().iter().map(|i| f(i)).collect();
 
@@ -219,19 +352,10 @@ pub trait ViewPortRenderEx: ViewPortEx {
     let mut horizontal_scroll_position = self.ui().horizontal_scroll_position;
 
     if let Some(pos) = horizontal_scroll_position.as_mut() {
-      // 计算当前行最大宽度
-      let width = max_items_width;
-
-      // 可滚动的范围
-      let area_width = area.width as usize;
-      let scroll_range = if width > area_width {
-        width - area_width
-      } else {
-        0
-      };
-
-      // 确保滚动条位置在可滚动范围内
-      *pos = scroll_range.saturating_sub(1).min(*pos);
+      // 计算可滚动的范围，并确保滚动条位置在可滚动范围内
+      let scroll_range;
+      (scroll_range, *pos) =
+        ViewPort::clamp_horizontal_scroll(*pos, max_items_width, area.width as usize);
 
       // 仅当内容宽度大于可渲染范围时，渲染滚动条。滚动条将重叠在底部边框上。
       if scroll_range > 0 {
@@ -243,10 +367,10 @@ pub trait ViewPortRenderEx: ViewPortEx {
             begin: "<",
             end: ">",
           })
-          .thumb_style(Style::default().yellow())
-          .track_style(Style::default().yellow())
-          .begin_style(Color::Red)
-          .end_style(Color::Red)
+          .thumb_style(capability.downgrade(Style::default().yellow()))
+          .track_style(capability.downgrade(Style::default().yellow()))
+          .begin_style(capability.downgrade(Color::Red.into()))
+          .end_style(capability.downgrade(Color::Red.into()))
           .render(
             area.outer(Margin {
               vertical: 1,
@@ -285,7 +409,9 @@ pub trait ViewPortRenderEx: ViewPortEx {
 
     // -----------------------------------------------------------
     // 高亮光标指向的数据
-    if focus && let Some(line) = items.get_mut(self.ui().cursor) {
+    if state.focus
+      && let Some(line) = items.get_mut(self.ui().cursor)
+    {
       // 若本行的宽度小于可视区的宽度，我们需要在其后方补充空白格，否则高亮区域没法横穿整个行，看起来会比较奇怪。
       // 本来用 List 渲染可以自动解决这个问题，但它不支持 scrollbar ，因此我们只能手动实现下。
       let line_width = line.width();
@@ -294,9 +420,14 @@ pub trait ViewPortRenderEx: ViewPortEx {
         line.push_span(Span::raw(" ".repeat(width - line_width)));
       }
 
-      line.style = line.style.bg(Color::White);
+      line.style = line.style.bg(state.cursor_bg);
     }
 
+    // 按终端的颜色能力降级所有的样式
+    items
+      .iter_mut()
+      .for_each(|line| capability.downgrade_line(line));
+
     // -----------------------------------------------------------
     // 渲染展示区内容
     let mut content = Paragraph::new(items);
@@ -316,6 +447,118 @@ pub trait ViewPortRenderEx: ViewPortEx {
     // 由于现在访问得到的 controller 数据都是基于之前的事实计算的，
     // 因此，我们只能在渲染的最后，再给 controller 更新最新的窗口大小
     ui.set_height(area.height as usize);
+    ui.area = area;
+  }
+
+  /// 渲染展示区，并将给定的数据固定展示在最顶部，用分隔线与下方可滚动的内容隔开。
+  /// 区域过小、容纳不下固定的数据时，则只渲染展示区
+  fn render_with_pinned(
+    &mut self,
+    area: Rect,
+    buf: &mut Buffer,
+    state: &PageState,
+    pinned: Option<&Self::Item>,
+    f: impl Fn(&Self::Item) -> Line,
+  ) {
+    let (Some(item), Some((pinned_area, divider_area, area))) =
+      (pinned, ViewPort::reserve_pinned_rows(area))
+    else {
+      return self.render(area, buf, state, f);
+    };
+
+    // 固定的数据和展示区一同横向滚动
+    let mut line = f(item);
+    state.color_capability.downgrade_line(&mut line);
+    let mut content = Paragraph::new(line);
+    if let Some(pos) = self.ui().horizontal_scroll_position {
+      content = content.scroll((0, pos as u16));
+    }
+    content.render(pinned_area, buf);
+
+    Block::new()
+      .borders(Borders::TOP)
+      .border_style(
+        state
+          .color_capability
+          .downgrade(Style::default().dark_gray()),
+      )
+      .render(divider_area, buf);
+
+    self.render(area, buf, state, f);
+  }
+
+  /// 按先列后行的顺序，将展示区渲染为多列，每列至少 `column_width` 宽。
+  /// 区域只容纳得下一列时，按单列渲染
+  fn render_columns(
+    &mut self,
+    area: Rect,
+    buf: &mut Buffer,
+    state: &PageState,
+    column_width: usize,
+    f: impl Fn(&Self::Item) -> Line,
+  ) {
+    // 总是为纵向滚动条预留一列，使得列数不随滚动条的出现与否而变化
+    let mut content = area;
+    content.width = area.width.saturating_sub(1);
+
+    let columns = ViewPort::columns_within(content.width as usize, column_width);
+    self.ui_mut().columns = columns;
+    if columns < 2 {
+      return self.render(area, buf, state, f);
+    }
+
+    let capability = state.color_capability;
+    let rows = area.height as usize;
+    let width = content.width as usize / columns;
+
+    // -----------------------------------------------------------
+    // 调整并渲染纵向滚动条的位置，可滚动的范围按所有列能容纳的数据数量计算
+    let mut vertical_scroll_state = self.ui().vertical_scroll_state;
+    let scroll_range = vertical_scroll_state
+      .items_count
+      .saturating_sub(rows * columns);
+    vertical_scroll_state.position = vertical_scroll_state
+      .position
+      .min(scroll_range.saturating_sub(1));
+    if scroll_range > 0 {
+      let mut state = ScrollbarState::new(scroll_range).position(vertical_scroll_state.position);
+      Scrollbar::new(ScrollbarOrientation::VerticalRight).render(area, buf, &mut state);
+    }
+
+    // -----------------------------------------------------------
+    // 逐条渲染到各自所在的格子中，高亮光标指向的数据
+    let cursor = self.ui().cursor;
+    for (index, item) in self.data().iter().enumerate() {
+      let (column, row) = ViewPort::column_cell(index, rows);
+      if column >= columns {
+        break;
+      }
+
+      let mut line = f(item);
+      if state.focus && index == cursor {
+        let line_width = line.width();
+        if line_width < width {
+          line.push_span(Span::raw(" ".repeat(width - line_width)));
+        }
+        line.style = line.style.bg(state.cursor_bg);
+      }
+      capability.downgrade_line(&mut line);
+
+      let cell = Rect::new(
+        content.x + (column * width) as u16,
+        content.y + row as u16,
+        width as u16,
+        1,
+      );
+      line.render(cell, buf);
+    }
+
+    // -----------------------------------------------------------
+    // 更新 UI 数据
+    let ui = self.ui_mut();
+    ui.vertical_scroll_state = vertical_scroll_state;
+    ui.set_height(rows * columns);
+    ui.area = content;
   }
 }
 
@@ -325,6 +568,33 @@ impl ViewPort {
     self.horizontal_scroll_position = Some(0);
   }
 
+  /// 本帧是否有需要处理的控制，或者展示区高度发生了变化。持续生效的跟踪、跳转到最旧数据的控制不计入，
+  /// 它们只在数据变化时才会改变展示的内容
+  pub fn is_changing(&self) -> bool {
+    self.resized || !(matches!(self.control, Control::Idle) || self.is_sticky())
+  }
+
+  /// 当前的控制是否持续生效：跟踪最新数据，以及跳转到最旧的数据，使更旧的数据加载后仍然停留在最旧处。
+  /// 跟踪时光标置顶的情况下，最旧的数据在底部
+  fn is_sticky(&self) -> bool {
+    match self.control {
+      Control::Follow => true,
+      Control::GoTop => !self.follow_at_top,
+      Control::GoBottom => self.follow_at_top,
+      _ => false,
+    }
+  }
+
+  /// 是否正在跟踪最新的数据
+  pub fn is_following(&self) -> bool {
+    matches!(self.control, Control::Follow)
+  }
+
+  /// 设置跟踪最新数据时，光标是否置顶
+  pub fn set_follow_at_top(&mut self, follow_at_top: bool) {
+    self.follow_at_top = follow_at_top;
+  }
+
   /// 手动设置纵向滚动条条目上限以及展示区首条数据的位置
   pub fn update_vertical_scroll_state(&mut self, items_count: usize, top_item_index: usize) {
     self.vertical_scroll_state.items_count = items_count;
@@ -335,16 +605,80 @@ impl ViewPort {
   /// 不用担心会不会超出内容范围，在渲染时会钳制
   pub fn want_scroll_horizontally(&mut self, steps: isize) {
     if let Some(pos) = self.horizontal_scroll_position.as_mut() {
-      *pos = (*pos as isize + steps).max(0) as usize;
+      *pos = pos.saturating_add_signed(steps);
+    }
+  }
+
+  /// 将横向滚动条直接移动到指定位置，超出内容范围的部分在渲染时钳制
+  pub fn scroll_horizontally_to(&mut self, pos: usize) {
+    if let Some(curr_pos) = self.horizontal_scroll_position.as_mut() {
+      *curr_pos = pos;
     }
   }
 
+  /// 移动横向滚动条，使得内容中指定列的位置可见，并在其左侧预留少量空间
+  pub fn scroll_horizontally_to_column(&mut self, column: usize) {
+    self.scroll_horizontally_to(Self::column_scroll_offset(column));
+  }
+
+  /// 将横向滚动条移动到最左侧
+  pub fn scroll_horizontal_home(&mut self) {
+    self.scroll_horizontally_to(0);
+  }
+
+  /// 将横向滚动条移动到最右侧，使最宽的内容末尾可见
+  pub fn scroll_horizontal_end(&mut self) {
+    self.scroll_horizontally_to(usize::MAX);
+  }
+
+  /// 按快速跳转的步长移动横向滚动条，向左为负，向右为正
+  pub fn want_jump_horizontally(&mut self, jumps: isize) {
+    self.want_scroll_horizontally(jumps.saturating_mul(self.horizontal_scroll_jump as isize));
+  }
+
+  /// 设置横向快速跳转的步长
+  pub fn set_horizontal_scroll_jump(&mut self, step: usize) -> &mut Self {
+    self.horizontal_scroll_jump = step.max(1);
+    self
+  }
+
+  /// 设置光标的滚动风格
+  pub fn set_scroll_style(&mut self, style: ScrollStyle) -> &mut Self {
+    self.scroll_style = style;
+    self
+  }
+
+  /// 获取光标在展示区中的位置
+  pub fn cursor(&self) -> usize {
+    self.cursor
+  }
+
+  /// 展示区的高度
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  /// 获取光标的滚动风格
+  pub fn scroll_style(&self) -> ScrollStyle {
+    self.scroll_style
+  }
+
   /// 设置展示区高度，同时钳制光标位置，防止越界
   pub fn set_height(&mut self, height: usize) -> &mut Self {
+    if self.height != height {
+      self.resized = true;
+      self.cursor_before_resize.get_or_insert(self.cursor);
+    }
     self.height = height;
     self.set_cursor(self.cursor)
   }
 
+  /// 取出展示区高度自上次取出以来是否发生过变化，取出者自行负责保持光标指向的数据
+  pub fn take_resized(&mut self) -> bool {
+    self.cursor_before_resize = None;
+    std::mem::take(&mut self.resized)
+  }
+
   /// 总是跟踪到最新的日志（退出导航模式）
   pub fn want_follow(&mut self) {
     self.control = Control::Follow;
@@ -362,16 +696,156 @@ impl ViewPort {
 
   /// 往上翻页
   pub fn want_page_up(&mut self) {
-    self.control = Control::PageUp;
+    self.control = match self.page_overlap {
+      None => Control::PageUp,
+      Some(overlap) => Control::ScrollBy(-(self.page_lines(overlap) as isize)),
+    };
   }
 
   /// 往下翻页
   pub fn want_page_down(&mut self) {
-    self.control = Control::PageDown;
+    self.control = match self.page_overlap {
+      None => Control::PageDown,
+      Some(overlap) => Control::ScrollBy(self.page_lines(overlap) as isize),
+    };
+  }
+
+  /// 往上翻半页，光标在展示区中的行保持不变
+  pub fn want_half_page_up(&mut self) {
+    self.control = Control::ScrollBy(-((self.height / 2).max(1) as isize));
+  }
+
+  /// 往下翻半页，光标在展示区中的行保持不变
+  pub fn want_half_page_down(&mut self) {
+    self.control = Control::ScrollBy((self.height / 2).max(1) as isize);
+  }
+
+  /// 设置整页翻动时保留的上下文行数，为空时光标在翻页后置顶或置底
+  pub fn set_page_overlap(&mut self, overlap: Option<usize>) -> &mut Self {
+    self.page_overlap = overlap;
+    self
+  }
+
+  /// 将光标移动到鼠标点击的行上，返回点击的位置是否落在数据上
+  pub fn want_click(&mut self, column: u16, row: u16) -> bool {
+    match self.row_at(column, row) {
+      None => false,
+      Some(row) => {
+        self.want_move_cursor(row as isize - self.cursor as isize);
+        true
+      }
+    }
+  }
+
+  /// 跳转到最旧的数据
+  pub fn want_go_top(&mut self) {
+    self.control = Control::GoTop;
+  }
+
+  /// 跳转到最新的数据
+  pub fn want_go_bottom(&mut self) {
+    self.control = Control::GoBottom;
   }
 }
 
 impl ViewPort {
+  /// 定位到某一列时，其左侧预留的宽度
+  const COLUMN_LEFT_MARGIN: usize = 10;
+
+  /// 保留 `overlap` 行上下文时，整页翻动的行数，至少为一行
+  fn page_lines(&self, overlap: usize) -> usize {
+    self.height.saturating_sub(overlap).max(1)
+  }
+
+  /// 计算将内容滚动 `lines` 行（向上为负）后，光标所在行应当展示的数据在展示区数据中的索引，
+  /// 以及光标新的行，返回（数据索引，光标行）。
+  ///
+  /// 目标数据仍在展示区内时，光标的行不变；否则取最靠近目标的边缘数据，并将光标移到它滚动后所在的行
+  fn scroll_target(
+    cursor: usize,
+    data_count: usize,
+    height: usize,
+    lines: isize,
+  ) -> (usize, usize) {
+    let last = data_count.saturating_sub(1);
+    let max_row = height.saturating_sub(1);
+    let target = cursor as isize + lines;
+    if target < 0 {
+      (0, (cursor + target.unsigned_abs()).min(max_row))
+    } else if target as usize > last {
+      (last, cursor.saturating_sub(target as usize - last))
+    } else {
+      (target as usize, cursor)
+    }
+  }
+
+  /// 计算使指定列可见的横向滚动条位置
+  fn column_scroll_offset(column: usize) -> usize {
+    column.saturating_sub(Self::COLUMN_LEFT_MARGIN)
+  }
+
+  /// 给定横向滚动条位置、内容宽度以及展示区宽度，返回（可滚动范围，钳制后的滚动条位置）
+  fn clamp_horizontal_scroll(
+    pos: usize,
+    content_width: usize,
+    area_width: usize,
+  ) -> (usize, usize) {
+    let scroll_range = content_width.saturating_sub(area_width);
+    (scroll_range, scroll_range.saturating_sub(1).min(pos))
+  }
+
+  /// 在给定区域的顶部，为固定展示的数据预留一行，并在其下预留一行分隔线，
+  /// 返回（固定数据区域，分隔线区域，剩余的可滚动区域）。
+  /// 若剩余的区域不足一行，则不预留
+  fn reserve_pinned_rows(area: Rect) -> Option<(Rect, Rect, Rect)> {
+    if area.height < 3 {
+      return None;
+    }
+
+    let row = |y: u16, height: u16| Rect::new(area.x, y, area.width, height);
+    Some((
+      row(area.y, 1),
+      row(area.y + 1, 1),
+      row(area.y + 2, area.height - 2),
+    ))
+  }
+
+  /// 在给定的宽度内，每列至少 `column_width` 宽时，能够容纳的列数，至少为一列
+  fn columns_within(width: usize, column_width: usize) -> usize {
+    (width / column_width.max(1)).max(1)
+  }
+
+  /// 多列展示时数据按先列后行的顺序排列，返回展示区中第 `index` 条数据所在的（列，行）
+  fn column_cell(index: usize, rows: usize) -> (usize, usize) {
+    let rows = rows.max(1);
+    (index / rows, index % rows)
+  }
+
+  /// 多列展示时，（列，行）处的数据在展示区中的索引，是 [ViewPort::column_cell] 的逆运算
+  fn column_cell_index(column: usize, row: usize, rows: usize) -> usize {
+    column * rows.max(1) + row
+  }
+
+  /// 将终端中的坐标映射为展示区中数据的索引（单列展示时即为行），坐标不在展示区内、或者该处没有数据时返回 None
+  fn row_at(&self, column: u16, row: u16) -> Option<usize> {
+    if !self.area.contains(Position::new(column, row)) {
+      return None;
+    }
+
+    let width = (self.area.width as usize / self.columns).max(1);
+    let column = (column - self.area.x) as usize / width;
+    let index = Self::column_cell_index(
+      column,
+      (row - self.area.y) as usize,
+      self.area.height as usize,
+    );
+    if column < self.columns && index < self.data_count {
+      Some(index)
+    } else {
+      None
+    }
+  }
+
   /// 直接设置光标位置，需要钳制它，防止越界
   fn set_cursor(&mut self, cursor: usize) -> &mut Self {
     self.cursor = cursor.clamp(
@@ -418,22 +892,33 @@ impl ViewPort {
       return;
     }
 
-    // 光标离上下边界最少这么多行
-    let min_spacing = ((self.height as f64 * 0.2 + 1.0) as usize).min(5);
-
-    // 将光标限制在中间这个范围内
-    self.cursor = match (
-      self.ideal_count_up() >= min_spacing,
-      self.ideal_count_down() >= min_spacing,
-    ) {
-      // 光标离上边界过近，离下边界较远，那么将其向下调整
-      (false, true) => min_spacing,
+    // 按滚动风格，调整光标的理想位置
+    self.cursor = match self.scroll_style {
+      ScrollStyle::EdgeSpacing(spacing) => {
+        // 光标离上下边界最少这么多行
+        let min_spacing = ScrollStyle::edge_spacing(spacing, self.height);
+
+        // 将光标限制在中间这个范围内
+        match (
+          self.ideal_count_up() >= min_spacing,
+          self.ideal_count_down() >= min_spacing,
+        ) {
+          // 光标离上边界过近，离下边界较远，那么将其向下调整
+          (false, true) => min_spacing,
+
+          // 光标离下边界过近，离上边界较远，那么将其向上调整
+          (true, false) => self.height - min_spacing,
+
+          // 光标处于中间，或者上下空间都不足，不移动光标
+          _ => self.cursor,
+        }
+      }
 
-      // 光标离下边界过近，离上边界较远，那么将其向上调整
-      (true, false) => self.height - min_spacing,
+      // 光标固定在中间，若某一端数据不足，会在后文对齐到真正的位置上
+      ScrollStyle::Centered => self.height / 2,
 
-      // 光标处于中间，或者上下空间都不足，不移动光标
-      _ => self.cursor,
+      // 光标保持不动
+      ScrollStyle::Free => self.cursor,
     };
 
     // 按现在光标的理想位置，开始取数据。可能某一端的数据其实没有那么多，我们将在后文从另外一端补充
@@ -585,3 +1070,360 @@ macro_rules! view_port {
     }
   };
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_scroll_target() {
+    // 半页翻动，目标数据在展示区内时，光标的行保持不变
+    assert_eq!(ViewPort::scroll_target(15, 20, 20, -10), (5, 15));
+    assert_eq!(ViewPort::scroll_target(5, 20, 20, 10), (15, 5));
+
+    // 目标数据在展示区之外时，取边缘的数据，并将光标移到它滚动后所在的行，使内容滚动的行数不变
+    assert_eq!(ViewPort::scroll_target(3, 20, 20, -10), (0, 10));
+    assert_eq!(ViewPort::scroll_target(16, 20, 20, 10), (19, 9));
+
+    // 保留 2 行上下文的整页翻动，原来顶部的 2 行移到底部，原来底部的 2 行移到顶部
+    assert_eq!(ViewPort::scroll_target(10, 20, 20, -18), (0, 18));
+    assert_eq!(ViewPort::scroll_target(10, 20, 20, 18), (19, 1));
+
+    // 数据不足一屏时，光标不会越过展示区
+    assert_eq!(ViewPort::scroll_target(2, 5, 20, -30), (0, 19));
+    assert_eq!(ViewPort::scroll_target(2, 5, 20, 30), (4, 0));
+  }
+
+  #[test]
+  fn test_page_lines() {
+    let mut ui = ViewPort::default();
+    ui.set_height(20);
+
+    // 未配置重叠时，沿用光标置顶、置底的翻页
+    ui.want_page_up();
+    assert!(matches!(ui.control, Control::PageUp));
+
+    // 配置重叠后，按保留上下文的行数滚动
+    ui.set_page_overlap(Some(2));
+    ui.want_page_up();
+    assert!(matches!(ui.control, Control::ScrollBy(-18)));
+    ui.want_page_down();
+    assert!(matches!(ui.control, Control::ScrollBy(18)));
+
+    // 重叠不小于高度时，至少翻动一行
+    ui.set_page_overlap(Some(30));
+    ui.want_page_down();
+    assert!(matches!(ui.control, Control::ScrollBy(1)));
+
+    // 半页翻动
+    ui.want_half_page_up();
+    assert!(matches!(ui.control, Control::ScrollBy(-10)));
+    ui.want_half_page_down();
+    assert!(matches!(ui.control, Control::ScrollBy(10)));
+  }
+
+  #[test]
+  fn test_clamp_horizontal_scroll() {
+    // 内容比展示区窄时，无法滚动
+    assert_eq!(ViewPort::clamp_horizontal_scroll(5, 30, 80), (0, 0));
+
+    // 正常范围内的位置保持不变
+    assert_eq!(ViewPort::clamp_horizontal_scroll(5, 100, 80), (20, 5));
+
+    // 跳转到超出内容宽度的位置时，钳制到最右侧
+    assert_eq!(
+      ViewPort::clamp_horizontal_scroll(usize::MAX, 100, 80),
+      (20, 19)
+    );
+    assert_eq!(ViewPort::clamp_horizontal_scroll(45, 100, 80), (20, 19));
+  }
+
+  #[test]
+  fn test_scroll_horizontally_to_column() {
+    let mut ui = ViewPort::default();
+    ui.enable_horizontal_scroll();
+
+    // 靠近行首的列，直接回到最左侧
+    ui.scroll_horizontally_to_column(4);
+    assert_eq!(ui.horizontal_scroll_position, Some(0));
+
+    // 其余的列，在左侧预留少量空间
+    ui.scroll_horizontally_to_column(30);
+    assert_eq!(
+      ui.horizontal_scroll_position,
+      Some(30 - ViewPort::COLUMN_LEFT_MARGIN)
+    );
+  }
+
+  /// 按给定的上下方数据数量，模拟填充展示区，返回光标最终的位置
+  fn fill_with(ui: &mut ViewPort, cursor: usize, mut up: usize, mut down: usize) -> usize {
+    ui.cursor = cursor;
+    ui.fill(|dir| {
+      let remain = match dir {
+        LogDirection::Forward => &mut down,
+        LogDirection::Backward => &mut up,
+      };
+      if *remain == 0 {
+        return false;
+      }
+      *remain -= 1;
+      true
+    });
+    ui.cursor
+  }
+
+  #[test]
+  fn test_fill_with_edge_spacing() {
+    let mut ui = ViewPort::default();
+    ui.set_height(20);
+
+    // 默认风格下，光标离边界至少 5 行
+    assert_eq!(fill_with(&mut ui, 0, 100, 100), 5);
+    assert_eq!(fill_with(&mut ui, 19, 100, 100), 15);
+    assert_eq!(fill_with(&mut ui, 8, 100, 100), 8);
+
+    // 数据不足时，光标对齐到真实的位置（下方的数据包含光标所在行）
+    assert_eq!(fill_with(&mut ui, 0, 2, 100), 2);
+    assert_eq!(fill_with(&mut ui, 19, 100, 2), 18);
+
+    // 自定义间距，且展示区较矮时按高度缩减
+    ui.set_scroll_style(ScrollStyle::EdgeSpacing(2));
+    assert_eq!(fill_with(&mut ui, 0, 100, 100), 2);
+    ui.set_scroll_style(ScrollStyle::EdgeSpacing(10));
+    ui.set_height(10);
+    assert_eq!(fill_with(&mut ui, 0, 100, 100), 3);
+    assert_eq!(fill_with(&mut ui, 9, 100, 100), 7);
+  }
+
+  #[test]
+  fn test_edge_spacing_cap() {
+    // 间距最多为高度的 20% 再加一行
+    assert_eq!(ScrollStyle::edge_spacing(5, 40), 5);
+    assert_eq!(ScrollStyle::edge_spacing(10, 40), 9);
+    assert_eq!(ScrollStyle::edge_spacing(10, 20), 5);
+    assert_eq!(ScrollStyle::edge_spacing(10, 4), 1);
+    assert_eq!(ScrollStyle::edge_spacing(10, 0), 1);
+  }
+
+  #[test]
+  fn test_parse_scroll_style() {
+    assert_eq!("edge".parse(), Ok(ScrollStyle::EdgeSpacing(5)));
+    assert_eq!("edge:2".parse(), Ok(ScrollStyle::EdgeSpacing(2)));
+    assert_eq!("centered".parse(), Ok(ScrollStyle::Centered));
+    assert_eq!("free".parse(), Ok(ScrollStyle::Free));
+    assert!("edge:x".parse::<ScrollStyle>().is_err());
+    assert!("middle".parse::<ScrollStyle>().is_err());
+  }
+
+  #[test]
+  fn test_fill_centered() {
+    let mut ui = ViewPort::default();
+    ui.set_height(20).set_scroll_style(ScrollStyle::Centered);
+
+    assert_eq!(fill_with(&mut ui, 0, 100, 100), 10);
+    assert_eq!(fill_with(&mut ui, 19, 100, 100), 10);
+
+    // 数据不足时，无法保持在中间
+    assert_eq!(fill_with(&mut ui, 0, 3, 100), 3);
+    assert_eq!(fill_with(&mut ui, 19, 100, 0), 19);
+  }
+
+  #[test]
+  fn test_fill_free() {
+    let mut ui = ViewPort::default();
+    ui.set_height(20).set_scroll_style(ScrollStyle::Free);
+
+    assert_eq!(fill_with(&mut ui, 0, 100, 100), 0);
+    assert_eq!(fill_with(&mut ui, 19, 100, 100), 19);
+    assert_eq!(fill_with(&mut ui, 7, 100, 100), 7);
+    assert_eq!(fill_with(&mut ui, 7, 3, 100), 3);
+  }
+
+  #[test]
+  fn test_scroll_horizontal_jump() {
+    let mut ui = ViewPort::default();
+
+    // 未启用横向滚动时，所有操作无效
+    ui.scroll_horizontal_end();
+    assert_eq!(ui.horizontal_scroll_position, None);
+
+    ui.enable_horizontal_scroll();
+    ui.set_horizontal_scroll_jump(8);
+    ui.want_jump_horizontally(2);
+    assert_eq!(ui.horizontal_scroll_position, Some(16));
+    ui.want_jump_horizontally(-3);
+    assert_eq!(ui.horizontal_scroll_position, Some(0));
+
+    ui.scroll_horizontal_end();
+    assert_eq!(ui.horizontal_scroll_position, Some(usize::MAX));
+    ui.want_scroll_horizontally(1);
+    assert_eq!(ui.horizontal_scroll_position, Some(usize::MAX));
+    ui.scroll_horizontal_home();
+    assert_eq!(ui.horizontal_scroll_position, Some(0));
+  }
+
+  /// 测试用的展示区，每一行的数据即为其序号
+  #[derive(Default)]
+  struct TestViewPort {
+    ui: ViewPort,
+    data: VecDeque<usize>,
+  }
+
+  impl ViewPortEx for TestViewPort {
+    type Item = usize;
+
+    fn ui_mut(&mut self) -> &mut ViewPort {
+      &mut self.ui
+    }
+
+    fn ui(&self) -> &ViewPort {
+      &self.ui
+    }
+
+    fn data_mut(&mut self) -> &mut VecDeque<usize> {
+      &mut self.data
+    }
+
+    fn data(&self) -> &VecDeque<usize> {
+      &self.data
+    }
+
+    fn control_mut(&mut self) -> &mut Control {
+      &mut self.ui.control
+    }
+
+    fn control(&self) -> Control {
+      self.ui.control
+    }
+  }
+
+  /// 构造一个填充了 [first, first + height) 数据的展示区
+  fn filled_view_port(height: usize, first: usize) -> TestViewPort {
+    let mut view_port = TestViewPort::default();
+    view_port.ui.set_height(height);
+    let mut next = first;
+    let mut prev = first;
+    view_port.do_fill(|dir| match dir {
+      LogDirection::Forward => {
+        next += 1;
+        Some(next - 1)
+      }
+      LogDirection::Backward if prev > 0 => {
+        prev -= 1;
+        Some(prev)
+      }
+      LogDirection::Backward => None,
+    });
+    view_port
+  }
+
+  #[test]
+  fn test_reserve_pinned_rows() {
+    let (pinned, divider, rest) = ViewPort::reserve_pinned_rows(Rect::new(2, 5, 30, 10)).unwrap();
+    assert_eq!(pinned, Rect::new(2, 5, 30, 1));
+    assert_eq!(divider, Rect::new(2, 6, 30, 1));
+    assert_eq!(rest, Rect::new(2, 7, 30, 8));
+
+    // 至少保留一行可滚动的区域
+    let (_, _, rest) = ViewPort::reserve_pinned_rows(Rect::new(0, 0, 30, 3)).unwrap();
+    assert_eq!(rest.height, 1);
+    assert!(ViewPort::reserve_pinned_rows(Rect::new(0, 0, 30, 2)).is_none());
+    assert!(ViewPort::reserve_pinned_rows(Rect::new(0, 0, 30, 0)).is_none());
+  }
+
+  #[test]
+  fn test_click_row_to_cursor() {
+    let mut view_port = filled_view_port(10, 100);
+    view_port.ui.area = Rect::new(5, 3, 40, 10);
+    view_port.ui.cursor = 4;
+
+    // 点击展示区内的行，光标移动到该行
+    let (row7, row0) = (view_port.data[7], view_port.data[0]);
+    assert!(view_port.ui.want_click(10, 3 + 7));
+    let (item, _) = view_port.apply().unwrap();
+    assert_eq!(*item, row7);
+    assert_eq!(view_port.ui.cursor, 7);
+
+    assert!(view_port.ui.want_click(5, 3));
+    let (item, _) = view_port.apply().unwrap();
+    assert_eq!(*item, row0);
+
+    // 展示区之外的点击被忽略
+    assert!(!view_port.ui.want_click(4, 5));
+    assert!(!view_port.ui.want_click(10, 2));
+    assert!(!view_port.ui.want_click(10, 13));
+    assert!(matches!(view_port.ui.control, Control::Idle));
+
+    // 没有数据的行也被忽略
+    view_port.ui.data_count = 6;
+    assert!(!view_port.ui.want_click(10, 3 + 6));
+    assert!(view_port.ui.want_click(10, 3 + 5));
+  }
+
+  #[test]
+  fn test_column_layout() {
+    // 每列至少 20 宽时，区域能容纳的列数，至少为一列
+    assert_eq!(ViewPort::columns_within(65, 20), 3);
+    assert_eq!(ViewPort::columns_within(19, 20), 1);
+    assert_eq!(ViewPort::columns_within(0, 0), 1);
+
+    // 先列后行：每列 10 行时，第 0~9 条在第一列，第 10 条回到第二列的顶部
+    assert_eq!(ViewPort::column_cell(0, 10), (0, 0));
+    assert_eq!(ViewPort::column_cell(9, 10), (0, 9));
+    assert_eq!(ViewPort::column_cell(10, 10), (1, 0));
+    assert_eq!(ViewPort::column_cell(27, 10), (2, 7));
+    for index in 0..30 {
+      let (column, row) = ViewPort::column_cell(index, 10);
+      assert_eq!(ViewPort::column_cell_index(column, row, 10), index);
+    }
+
+    // 三列展示时，点击的位置按所在的列映射到数据上
+    let mut view_port = filled_view_port(30, 100);
+    view_port.ui.area = Rect::new(5, 3, 60, 10);
+    view_port.ui.columns = 3;
+    view_port.ui.cursor = 0;
+
+    let item = view_port.data[27];
+    assert!(view_port.ui.want_click(5 + 45, 3 + 7));
+    assert_eq!(*view_port.apply().unwrap().0, item);
+    assert_eq!(view_port.ui.cursor, 27);
+
+    // 没有数据的格子被忽略
+    view_port.ui.data_count = 12;
+    assert!(!view_port.ui.want_click(5 + 25, 3 + 2));
+    assert!(view_port.ui.want_click(5 + 25, 3 + 1));
+  }
+
+  #[test]
+  fn test_go_top_and_bottom() {
+    let mut view_port = filled_view_port(10, 100);
+
+    // 跳到顶部时，光标置顶，并期望外部定位到最旧的数据，该控制会一直保持
+    let front = *view_port.data.front().unwrap();
+    let back = *view_port.data.back().unwrap();
+
+    view_port.ui.want_go_top();
+    let (item, expectation) = view_port.apply().unwrap();
+    assert_eq!(*item, front);
+    assert!(matches!(expectation, CursorExpectation::Top));
+    assert_eq!(view_port.ui.cursor, 0);
+    assert!(matches!(view_port.ui.control, Control::GoTop));
+
+    // 跳到底部时，光标置底，且不会进入跟踪状态
+    view_port.ui.want_go_bottom();
+    let (item, expectation) = view_port.apply().unwrap();
+    assert_eq!(*item, back);
+    assert!(matches!(expectation, CursorExpectation::Bottom));
+    assert_eq!(view_port.ui.cursor, 9);
+    assert!(matches!(view_port.ui.control, Control::Idle));
+
+    // 跟踪时光标置顶（倒序展示）的情况下，最旧的数据在底部，跳到底部的控制会一直保持
+    view_port.ui.set_follow_at_top(true);
+    view_port.ui.want_go_bottom();
+    view_port.apply();
+    assert!(matches!(view_port.ui.control, Control::GoBottom));
+    view_port.ui.want_go_top();
+    view_port.apply();
+    assert!(matches!(view_port.ui.control, Control::Idle));
+  }
+}
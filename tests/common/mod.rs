@@ -1,10 +1,47 @@
+//! 各个集成测试共用的工具，每个测试只用到其中一部分
+#![allow(dead_code)]
+
 use rs_syslog_viewer::log::LogLine;
 use std::collections::BTreeSet;
 use std::fs;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
 
+/// 测试用的临时目录，名称中带有进程号，创建时清空之前残留的内容，离开作用域时自动删除，
+/// 即使测试中途失败也不会留下文件
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+  pub fn new(name: &str) -> Self {
+    let root = std::env::temp_dir().join(format!("rs_syslog_viewer_{name}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    Self(root)
+  }
+}
+
+impl Deref for TempDir {
+  type Target = Path;
+
+  fn deref(&self) -> &Path {
+    &self.0
+  }
+}
+
+impl AsRef<Path> for TempDir {
+  fn as_ref(&self) -> &Path {
+    &self.0
+  }
+}
+
+impl Drop for TempDir {
+  fn drop(&mut self) {
+    let _ = fs::remove_dir_all(&self.0);
+  }
+}
+
 pub fn get_test_root() -> PathBuf {
   Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data")
 }
@@ -0,0 +1,1223 @@
+use rs_syslog_viewer::app::{
+  Clipboard, Command, Controller, LogHub,
+  controller::{LogController, TimeLinkController, log_controller::Error},
+};
+use rs_syslog_viewer::log::{Config, Label, LogLine};
+use rs_syslog_viewer::ui::ViewPortEx;
+use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use tokio::time::{Duration, Instant};
+
+mod common;
+
+/// 反复运行控制器，直到后台没有正在加载的日志文件，且日志内容与展示区都稳定了一段时间。
+/// 稳定的时长超过读取日志的轮询间隔，使得刚写入文件的内容也能被读到
+async fn run_for_a_while(log_hub: &mut LogHub, ctrl: &mut LogController) {
+  const FRAME: Duration = Duration::from_millis(20);
+  const SETTLED: Duration = Duration::from_millis(300);
+  const TIMEOUT: Duration = Duration::from_secs(5);
+
+  let start = Instant::now();
+  let mut last_version = None;
+  let mut settled_since = Instant::now();
+  while start.elapsed() < TIMEOUT {
+    tokio::time::sleep(FRAME).await;
+    // 在运行控制器之前检查加载状态，控制器可能在本帧中提出新的加载需求
+    let mut data = log_hub.data().await;
+    let loading = data.data_board().is_loading();
+    ctrl.run_once(&mut data);
+
+    let version = data.data_board().get_lines_version();
+    let settled = !loading && !ctrl.is_dirty() && last_version.replace(version) == Some(version);
+    if !settled {
+      settled_since = Instant::now();
+    } else if settled_since.elapsed() >= SETTLED {
+      return;
+    }
+  }
+}
+
+/// 打开日志目录下给定名称的日志
+fn open_log(root: impl Into<PathBuf>, name: &str, config: Config) -> LogHub {
+  LogHub::open(
+    root.into(),
+    [(name.to_string(), config)].into(),
+    Config::default(),
+  )
+}
+
+/// 打开日志目录下给定名称的日志，返回运行到日志加载完毕的控制器，其展示区的高度为 10
+async fn open_controller(
+  root: impl Into<PathBuf>,
+  name: &str,
+  config: Config,
+) -> (LogHub, LogController) {
+  open_controller_with(root, name, config, |_| {}).await
+}
+
+/// 同 `open_controller`，`setup` 在控制器首次运行之前配置它
+async fn open_controller_with(
+  root: impl Into<PathBuf>,
+  name: &str,
+  config: Config,
+  setup: impl FnOnce(&mut LogController),
+) -> (LogHub, LogController) {
+  let mut log_hub = open_log(root, name, config);
+  let mut ctrl = LogController::default();
+  ctrl.view_mut().ui_mut().set_height(10);
+  setup(&mut ctrl);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  (log_hub, ctrl)
+}
+
+#[tokio::test]
+async fn test_log_controller_go_top_and_bottom() {
+  let root = common::get_test_root();
+  let true_content: Vec<LogLine> = common::read_all_files_as_lines(&root, "test").unwrap();
+
+  let (mut log_hub, mut ctrl) = open_controller(root, "test", Config::default()).await;
+
+  // 跳转到顶部时，会一直加载更旧的滚动日志，直到光标指向最旧的一条日志
+  ctrl.view_mut().ui_mut().want_go_top();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+
+  let (_, log, _) = &ctrl.view().data()[ctrl.view().ui().cursor()];
+  assert_eq!(log, &true_content[0]);
+
+  // 跳转到底部时，光标指向最新的一条日志
+  ctrl.view_mut().ui_mut().want_go_bottom();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+
+  let (_, log, _) = &ctrl.view().data()[ctrl.view().ui().cursor()];
+  assert_eq!(log, true_content.last().unwrap());
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_keeps_cursor_on_resize() {
+  let root = common::get_test_root();
+
+  let (mut log_hub, mut ctrl) = open_controller_with(root, "test", Config::default(), |ctrl| {
+    ctrl.view_mut().ui_mut().set_height(20);
+  })
+  .await;
+
+  // 离开跟踪模式，光标停留在展示区的中下部
+  ctrl.view_mut().ui_mut().want_move_cursor(-3);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+
+  let cursor = ctrl.view().ui().cursor();
+  assert!(cursor >= 5);
+  let (_, anchor, _) = &ctrl.view().data()[cursor];
+  let anchor = anchor.clone();
+
+  // 缩小展示区，光标会被钳制，但仍指向原来的日志
+  ctrl.view_mut().ui_mut().set_height(5);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+
+  let (_, log, _) = &ctrl.view().data()[ctrl.view().ui().cursor()];
+  assert_eq!(log, &anchor);
+
+  // 恢复展示区大小，光标仍指向原来的日志
+  ctrl.view_mut().ui_mut().set_height(20);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+
+  let (_, log, _) = &ctrl.view().data()[ctrl.view().ui().cursor()];
+  assert_eq!(log, &anchor);
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_collapse_duplicates() {
+  let root = common::get_test_root();
+
+  let (mut log_hub, mut ctrl) = open_controller(root, "dups", Config::default()).await;
+  assert_eq!(ctrl.view().data().len(), 7);
+
+  // 折叠后，连续的相同标签与内容的日志只占一行，并记录被折叠的数量。标签不同的日志不会被折叠
+  ctrl.toggle_collapse_duplicates();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+
+  let rows: Vec<(String, usize, usize)> = ctrl
+    .view()
+    .data()
+    .iter()
+    .map(|(_, log, props)| {
+      (
+        log.get_content().to_string(),
+        props.duplicates,
+        props.ordinal,
+      )
+    })
+    .collect();
+  assert_eq!(
+    rows,
+    vec![
+      ("usb 1-1: new high-speed USB device".to_string(), 0, 1),
+      ("usb 1-1: device descriptor read error".to_string(), 2, 2),
+      ("usb 1-1: device descriptor read error".to_string(), 0, 5),
+      ("usb 1-1: reset".to_string(), 1, 6),
+    ]
+  );
+
+  // 折叠的行指向最早的一条日志
+  let (_, log, _) = &ctrl.view().data()[1];
+  assert_eq!(
+    log.get_timestamp().unwrap().to_rfc3339(),
+    "2026-03-01T09:00:01.000001+08:00"
+  );
+
+  ctrl.toggle_collapse_duplicates();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(ctrl.view().data().len(), 7);
+
+  // 隐藏 error 等级后，各行的序号仍然是日志的行号
+  ctrl.toggle_label(Label::Error);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  let ordinals: Vec<usize> = ctrl
+    .view()
+    .data()
+    .iter()
+    .map(|(.., props)| props.ordinal)
+    .collect();
+  assert_eq!(ordinals, vec![1, 6, 7]);
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_scroll_past_collapsed_duplicates() {
+  let root = common::get_test_root();
+
+  let (mut log_hub, mut ctrl) = open_controller_with(root, "dups", Config::default(), |ctrl| {
+    ctrl.view_mut().ui_mut().set_height(2);
+    ctrl.toggle_collapse_duplicates();
+    ctrl.view_mut().ui_mut().want_go_top();
+  })
+  .await;
+
+  let cursor_row = |ctrl: &LogController| {
+    let (_, log, props) = &ctrl.view().data()[ctrl.view().ui().cursor()];
+    (log.get_timestamp().unwrap().to_rfc3339(), props.duplicates)
+  };
+  assert_eq!(
+    cursor_row(&ctrl),
+    ("2026-03-01T09:00:00.000001+08:00".to_string(), 0)
+  );
+
+  // 光标移到展示区底部折叠的一行上，再往下移动时，跨过整段重复日志，而不是停在这一行
+  let expected = [
+    ("2026-03-01T09:00:01.000001+08:00", 2),
+    ("2026-03-01T09:00:04.000001+08:00", 0),
+    ("2026-03-01T09:00:05.000001+08:00", 1),
+    ("2026-03-01T09:00:05.000001+08:00", 1),
+  ];
+  for (timestamp, duplicates) in expected {
+    ctrl.view_mut().ui_mut().want_move_cursor(1);
+    run_for_a_while(&mut log_hub, &mut ctrl).await;
+    assert_eq!(cursor_row(&ctrl), (timestamp.to_string(), duplicates));
+    assert_eq!(ctrl.view().ui().cursor(), 1);
+  }
+
+  // 往上移动同样跨过整段重复日志，光标指向这一段中最早的一条
+  for (timestamp, duplicates) in [
+    ("2026-03-01T09:00:04.000001+08:00", 0),
+    ("2026-03-01T09:00:01.000001+08:00", 2),
+  ] {
+    ctrl.view_mut().ui_mut().want_move_cursor(-1);
+    run_for_a_while(&mut log_hub, &mut ctrl).await;
+    assert_eq!(cursor_row(&ctrl), (timestamp.to_string(), duplicates));
+  }
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_mark_all_matching() {
+  let root = common::get_test_root();
+
+  let (mut log_hub, mut ctrl) = open_controller(root, "dups", Config::default()).await;
+
+  let marked = |ctrl: &LogController| -> Vec<bool> {
+    ctrl
+      .view()
+      .data()
+      .iter()
+      .map(|(_, log, _)| log.is_marked())
+      .collect()
+  };
+
+  // 所有符合内容搜索的日志都被标记，且展示区立即反映标记结果
+  ctrl.search_content(Some("read error".to_string()));
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  ctrl.mark_all_matching(true);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(
+    marked(&ctrl),
+    vec![false, true, true, true, true, false, false]
+  );
+
+  // 取消标记只作用于符合搜索的日志
+  ctrl.search_content(Some("reset".to_string()));
+  ctrl.mark_all_matching(true);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  ctrl.search_content(Some("descriptor".to_string()));
+  ctrl.mark_all_matching(false);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(
+    marked(&ctrl),
+    vec![false, false, false, false, false, true, true]
+  );
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_mark_all_matching_stacked_terms() {
+  let root = common::get_test_root();
+
+  let (mut log_hub, mut ctrl) = open_controller(root, "dups", Config::default()).await;
+
+  let marked = |ctrl: &LogController| -> Vec<bool> {
+    ctrl
+      .view()
+      .data()
+      .iter()
+      .map(|(_, log, _)| log.is_marked())
+      .collect()
+  };
+
+  // 只有叠加的搜索条件、输入为空时，按所有的搜索条件标记
+  for term in ["high-speed", "reset"] {
+    ctrl.search_content(Some(term.to_string()));
+    ctrl.add_search_term();
+  }
+  ctrl.toggle_search_combine();
+  ctrl.mark_all_matching(true);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(ctrl.search_needles(), vec!["high-speed", "reset"]);
+  assert_eq!(
+    marked(&ctrl),
+    vec![true, false, false, false, false, true, true]
+  );
+
+  // 要求同时满足时，没有日志符合，标记不变
+  ctrl.toggle_search_combine();
+  ctrl.mark_all_matching(false);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(
+    marked(&ctrl),
+    vec![true, false, false, false, false, true, true]
+  );
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_locked_tags() {
+  let root = common::get_test_root();
+
+  let mut log_hub = open_log(root, "test", Config::default());
+
+  // 两个独立的展示区，分别锁定到不同的标签
+  let mut left = LogController::default();
+  let mut right = LogController::default();
+  left.lock_tag(Some("gnome-shell".to_string()));
+  right.lock_tag(Some("systemd".to_string()));
+
+  for ctrl in [&mut left, &mut right] {
+    ctrl.view_mut().ui_mut().set_height(10);
+  }
+  for _ in 0..10 {
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let mut data = log_hub.data().await;
+    left.run_once(&mut data);
+    right.run_once(&mut data);
+  }
+
+  let tags = |ctrl: &LogController| -> Vec<String> {
+    ctrl
+      .view()
+      .data()
+      .iter()
+      .map(|(_, log, _)| log.get_tag().unwrap_or_default().to_string())
+      .collect()
+  };
+
+  // 各自只展示锁定的标签，两者没有交集
+  let left_tags = tags(&left);
+  let right_tags = tags(&right);
+  assert_eq!(left_tags.len(), 10);
+  assert_eq!(right_tags.len(), 5);
+  assert!(left_tags.iter().all(|tag| tag == "gnome-shell"));
+  assert!(right_tags.iter().all(|tag| tag == "systemd"));
+
+  // 锁定只作用于控制器自身的遍历，不影响其他使用者
+  let mut data = log_hub.data().await;
+  assert!(
+    data
+      .iter_forward_from_head()
+      .any(|(_, log)| log.get_tag() == Some("CRON"))
+  );
+  drop(data);
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_time_link_nearest_by_time() {
+  // 两个交错的日志流：alpha 每分钟一条，beta 比 alpha 晚 36 秒
+  let root = common::TempDir::new("link");
+
+  let lines: Vec<String> = (0..10)
+    .flat_map(|i| {
+      [
+        format!("2026-03-01T09:{i:02}:00.000000+08:00 host alpha: a{i}"),
+        format!("2026-03-01T09:{i:02}:36.000000+08:00 host beta: b{i}"),
+      ]
+    })
+    .collect();
+  std::fs::write(root.join("time.log"), lines.join("\n") + "\n").unwrap();
+
+  let mut log_hub = open_log(root.to_path_buf(), "time", Config::default());
+
+  let primary = Rc::new(RefCell::new(LogController::default()));
+  let secondary = Rc::new(RefCell::new(LogController::default()));
+  let mut link = TimeLinkController::new(primary.clone(), secondary.clone());
+  primary.borrow_mut().lock_tag(Some("alpha".to_string()));
+  secondary.borrow_mut().lock_tag(Some("beta".to_string()));
+  for ctrl in [&primary, &secondary] {
+    ctrl.borrow_mut().view_mut().ui_mut().set_height(20);
+  }
+  link.toggle();
+
+  let run = async |log_hub: &mut LogHub, link: &mut TimeLinkController| {
+    for _ in 0..10 {
+      tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+      let mut data = log_hub.data().await;
+      primary.borrow_mut().run_once(&mut data);
+      link.run_once(&mut data);
+      secondary.borrow_mut().run_once(&mut data);
+    }
+  };
+  let cursor_content = |ctrl: &Rc<RefCell<LogController>>| {
+    ctrl
+      .borrow()
+      .cursor_log()
+      .unwrap()
+      .get_content()
+      .to_string()
+  };
+
+  // 跟随最新日志时，alpha 的 09:09:00 最接近 beta 的 09:08:36，而不是最新的 09:09:36
+  run(&mut log_hub, &mut link).await;
+  assert_eq!(cursor_content(&primary), "a9");
+  assert_eq!(cursor_content(&secondary), "b8");
+
+  // 光标移动到 alpha 的 09:05:00，最接近的是 beta 的 09:04:36，而不是 09:05:36
+  primary.borrow_mut().view_mut().ui_mut().want_go_top();
+  run(&mut log_hub, &mut link).await;
+  primary.borrow_mut().view_mut().ui_mut().want_move_cursor(5);
+  run(&mut log_hub, &mut link).await;
+  assert_eq!(cursor_content(&primary), "a5");
+  assert_eq!(cursor_content(&secondary), "b4");
+
+  // 关闭联动后，副展示区不再跟随
+  link.toggle();
+  primary.borrow_mut().view_mut().ui_mut().want_move_cursor(3);
+  run(&mut log_hub, &mut link).await;
+  assert_eq!(cursor_content(&primary), "a8");
+  assert_eq!(cursor_content(&secondary), "b4");
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_reverse_order() {
+  let root = common::get_test_root();
+  let true_content: Vec<LogLine> = common::read_all_files_as_lines(&root, "test").unwrap();
+
+  let (mut log_hub, mut ctrl) = open_controller_with(root, "test", Config::default(), |ctrl| {
+    ctrl.set_reverse_order(true);
+  })
+  .await;
+
+  let contents = |ctrl: &LogController| -> Vec<LogLine> {
+    ctrl
+      .view()
+      .data()
+      .iter()
+      .map(|(_, log, _)| log.clone())
+      .collect()
+  };
+
+  // 跟踪最新日志时，最新的日志在顶部，光标也在顶部，自上而下时间戳递减
+  let logs = contents(&ctrl);
+  assert_eq!(logs.len(), 10);
+  assert_eq!(ctrl.view().ui().cursor(), 0);
+  assert_eq!(&logs[0], true_content.last().unwrap());
+  let expected: Vec<LogLine> = true_content.iter().rev().take(10).cloned().collect();
+  assert_eq!(logs, expected);
+  assert!(
+    logs
+      .windows(2)
+      .all(|w| w[0].get_timestamp() >= w[1].get_timestamp())
+  );
+
+  // 跳转到底部时，光标指向最旧的一条日志
+  ctrl.view_mut().ui_mut().want_go_bottom();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+
+  let logs = contents(&ctrl);
+  assert_eq!(logs.last(), Some(&true_content[0]));
+  assert_eq!(&logs[ctrl.view().ui().cursor()], &true_content[0]);
+
+  // 光标向上移动，指向的是更新的日志
+  ctrl.view_mut().ui_mut().want_move_cursor(-1);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  let logs = contents(&ctrl);
+  assert_eq!(&logs[ctrl.view().ui().cursor()], &true_content[1]);
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_dirty_flag() {
+  let root = common::get_test_root();
+
+  let (mut log_hub, mut ctrl) = open_controller(root, "test", Config::default()).await;
+
+  let mut run_once = async |ctrl: &mut LogController| {
+    let mut data = log_hub.data().await;
+    ctrl.run_once(&mut data);
+    ctrl.is_dirty()
+  };
+
+  // 日志加载完成后，没有控制、也没有新数据时，展示的内容不会变化
+  assert!(!run_once(&mut ctrl).await);
+
+  // 有控制时变化，控制处理完后又恢复
+  ctrl.view_mut().ui_mut().want_move_cursor(-1);
+  assert!(run_once(&mut ctrl).await);
+  assert!(!run_once(&mut ctrl).await);
+
+  // 过滤条件变化时，同样会变化
+  ctrl.toggle_label(Label::Error);
+  assert!(run_once(&mut ctrl).await);
+  assert!(!run_once(&mut ctrl).await);
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_reuses_unchanged_rows() {
+  let root = common::get_test_root();
+
+  let (mut log_hub, mut ctrl) = open_controller(root, "test", Config::default()).await;
+
+  // 各行日志内容所在的内存地址，地址不变说明这一行没有被重新拷贝
+  let rows = |ctrl: &LogController| -> Vec<(String, usize)> {
+    ctrl
+      .view()
+      .data()
+      .iter()
+      .map(|(_, log, _)| {
+        (
+          log.get_content().to_string(),
+          log.get_content().as_ptr() as usize,
+        )
+      })
+      .collect()
+  };
+  let before = rows(&ctrl);
+  assert_eq!(before.len(), 10);
+
+  // 内容没有变化时，每一帧都复用上一帧的日志
+  {
+    let mut data = log_hub.data().await;
+    ctrl.run_once(&mut data);
+  }
+  assert_eq!(rows(&ctrl), before);
+
+  // 展示区滚动后，仍在展示区中的日志同样被复用
+  ctrl.view_mut().ui_mut().want_go_top();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  let top = rows(&ctrl);
+  ctrl.view_mut().ui_mut().want_move_cursor(20);
+  {
+    let mut data = log_hub.data().await;
+    ctrl.run_once(&mut data);
+  }
+  let scrolled = rows(&ctrl);
+  assert_ne!(scrolled, top);
+  let kept = scrolled.iter().filter(|row| top.contains(row)).count();
+  assert!(kept > 0);
+  assert_eq!(
+    kept,
+    scrolled
+      .iter()
+      .filter(|(content, _)| top.iter().any(|(c, _)| c == content))
+      .count()
+  );
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_caches_rich_spans() {
+  let root = common::get_test_root();
+
+  let (mut log_hub, mut ctrl) = open_controller(root, "test", Config::default()).await;
+
+  // 各行缓存的高亮结果都对应当前的搜索内容
+  let cached_for = |ctrl: &LogController, search: &str| {
+    ctrl.view().data().iter().all(|(_, log, props)| {
+      props
+        .rich
+        .as_ref()
+        .is_some_and(|rich| rich.is_valid_for(log.get_content(), &[search]))
+    })
+  };
+  assert!(cached_for(&ctrl, ""));
+
+  // 没有变化时，高亮结果跨帧复用
+  let before: Vec<_> = ctrl
+    .view()
+    .data()
+    .iter()
+    .map(|(.., p)| p.rich.clone())
+    .collect();
+  {
+    let mut data = log_hub.data().await;
+    ctrl.run_once(&mut data);
+  }
+  let after: Vec<_> = ctrl
+    .view()
+    .data()
+    .iter()
+    .map(|(.., p)| p.rich.clone())
+    .collect();
+  assert_eq!(before, after);
+
+  // 搜索内容变化后，高亮结果重新计算
+  ctrl.search_content_incrementally("a".to_string());
+  {
+    let mut data = log_hub.data().await;
+    ctrl.run_once(&mut data);
+  }
+  assert!(cached_for(&ctrl, "a"));
+  assert!(!cached_for(&ctrl, ""));
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_search_time_window() {
+  let root = common::get_test_root();
+
+  let (mut log_hub, mut ctrl) = open_controller_with(root, "dups", Config::default(), |ctrl| {
+    ctrl.style_mut().display_tz = "+08:00".parse().unwrap();
+  })
+  .await;
+
+  let cursor_time = |ctrl: &LogController| -> String {
+    let (_, log, _) = &ctrl.view().data()[ctrl.view().ui().cursor()];
+    log.get_timestamp().unwrap().to_rfc3339()
+  };
+
+  // 没有时间戳搜索指令时，不能限定时间窗口
+  assert!(!ctrl.toggle_search_time_window());
+
+  // 时间戳搜索结束后，窗口依旧保留
+  ctrl.set_search_timestamp(Some(
+    "2026.03.01 09:00:01 ~ 2026.03.01 09:00:03".to_string(),
+  ));
+  assert!(ctrl.toggle_search_time_window());
+  ctrl.set_search_timestamp(None);
+  assert_eq!(
+    ctrl.get_search_time_window(),
+    Some("2026.03.01 09:00:01 ~ 2026.03.01 09:00:03")
+  );
+
+  // 窗口外的 09:00:04 同样匹配内容，但被跳过
+  ctrl.view_mut().ui_mut().do_not_follow();
+  ctrl.search_content(Some("read error".to_string()));
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(cursor_time(&ctrl), "2026-03-01T09:00:03.000001+08:00");
+
+  ctrl.prev_content_search();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  ctrl.prev_content_search();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(cursor_time(&ctrl), "2026-03-01T09:00:01.000001+08:00");
+
+  // 到达窗口边界后，不再继续查找
+  ctrl.prev_content_search();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(cursor_time(&ctrl), "2026-03-01T09:00:01.000001+08:00");
+
+  // 取消限定后，窗口外的日志可以被找到
+  assert!(!ctrl.toggle_search_time_window());
+  ctrl.search_content(Some("reset".to_string()));
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(cursor_time(&ctrl), "2026-03-01T09:00:05.000001+08:00");
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_search_time_window_across_days() {
+  let root = common::TempDir::new("time_window_days");
+  let lines = [
+    "2026-03-01T10:40:00.000000+08:00 host alpha: read error",
+    "2026-03-02T10:00:00.000000+08:00 host alpha: read error",
+    "2026-03-02T10:40:00.000000+08:00 host alpha: ok",
+    "2026-03-02T10:50:00.000000+08:00 host alpha: read error",
+  ];
+  std::fs::write(root.join("days.log"), lines.join("\n") + "\n").unwrap();
+
+  let (mut log_hub, mut ctrl) =
+    open_controller_with(root.to_path_buf(), "days", Config::default(), |ctrl| {
+      ctrl.style_mut().display_tz = "+08:00".parse().unwrap();
+    })
+    .await;
+
+  let cursor_time = |ctrl: &LogController| -> String {
+    let (_, log, _) = &ctrl.view().data()[ctrl.view().ui().cursor()];
+    log.get_timestamp().unwrap().to_rfc3339()
+  };
+
+  // 时间戳搜索的各项条件分别比较，`> 2026.03.01 10:30` 每天都匹配一段时间
+  ctrl.set_search_timestamp(Some("> 2026.03.01 10:30".to_string()));
+  assert!(ctrl.toggle_search_time_window());
+  ctrl.set_search_timestamp(None);
+  ctrl.view_mut().ui_mut().do_not_follow();
+  ctrl.search_content(Some("read error".to_string()));
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(cursor_time(&ctrl), "2026-03-02T10:50:00+08:00");
+
+  // 向上离开第二天的窗口后，跳过窗口外的 10:00，仍能找到第一天窗口内的日志
+  ctrl.prev_content_search();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert!(ctrl.take_error().is_none());
+  assert_eq!(cursor_time(&ctrl), "2026-03-01T10:40:00+08:00");
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_search_time_window_stops_at_boundary() {
+  let root = common::TempDir::new("time_window_boundary");
+  let lines = [
+    "2026-03-01T09:00:01.000000+08:00 host alpha: read error",
+    "2026-03-01T09:00:05.000000+08:00 host alpha: ok",
+    "2026-03-01T09:00:02.000000+08:00 host alpha: read error",
+  ];
+  std::fs::write(root.join("bound.log"), lines.join("\n") + "\n").unwrap();
+
+  let (mut log_hub, mut ctrl) =
+    open_controller_with(root.to_path_buf(), "bound", Config::default(), |ctrl| {
+      ctrl.style_mut().display_tz = "+08:00".parse().unwrap();
+    })
+    .await;
+
+  let cursor_time = |ctrl: &LogController| -> String {
+    let (_, log, _) = &ctrl.view().data()[ctrl.view().ui().cursor()];
+    log.get_timestamp().unwrap().to_rfc3339()
+  };
+
+  // 连续的时间窗口，查找越过窗口的结束后便停止，不再理会之后乱序的日志
+  ctrl.set_search_timestamp(Some(
+    "2026.03.01 09:00:01 ~ 2026.03.01 09:00:03".to_string(),
+  ));
+  assert!(ctrl.toggle_search_time_window());
+  ctrl.set_search_timestamp(None);
+  ctrl.view_mut().ui_mut().do_not_follow();
+  ctrl.search_content(Some("read error".to_string()));
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(cursor_time(&ctrl), "2026-03-01T09:00:02+08:00");
+  ctrl.prev_content_search();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(cursor_time(&ctrl), "2026-03-01T09:00:01+08:00");
+
+  ctrl.next_content_search();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert!(matches!(
+    ctrl.take_error(),
+    Some(Error::NextContentSearchNotFound)
+  ));
+  assert_eq!(cursor_time(&ctrl), "2026-03-01T09:00:01+08:00");
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_tag_change() {
+  let root = common::get_test_root();
+
+  let (mut log_hub, mut ctrl) = open_controller(root, "dups", Config::default()).await;
+
+  let cursor_log = |ctrl: &LogController| -> (String, String) {
+    let log = ctrl.cursor_log().unwrap();
+    (
+      log.get_tag().unwrap().to_string(),
+      log.get_timestamp().unwrap().to_rfc3339(),
+    )
+  };
+
+  // 从顶部的 kernel 日志出发，跳过所有 kernel 日志，停在第一条 systemd 日志上
+  ctrl.view_mut().ui_mut().want_go_top();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  ctrl.view_mut().ui_mut().do_not_follow();
+  ctrl.next_tag_change();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(
+    cursor_log(&ctrl),
+    (
+      "systemd".to_string(),
+      "2026-03-01T09:00:04.000001+08:00".to_string()
+    )
+  );
+
+  // 再往后，停在紧接着的 kernel 日志上
+  ctrl.next_tag_change();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(
+    cursor_log(&ctrl),
+    (
+      "kernel".to_string(),
+      "2026-03-01T09:00:05.000001+08:00".to_string()
+    )
+  );
+
+  // 之后没有标签变化了，光标不动并报错
+  ctrl.next_tag_change();
+  let mut data = log_hub.data().await;
+  ctrl.run_once(&mut data);
+  drop(data);
+  assert!(matches!(
+    ctrl.take_error(),
+    Some(Error::NextTagChangeNotFound)
+  ));
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(
+    cursor_log(&ctrl).1,
+    "2026-03-01T09:00:05.000001+08:00".to_string()
+  );
+
+  // 向前跳转，回到 systemd 日志
+  ctrl.prev_tag_change();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(cursor_log(&ctrl).0, "systemd".to_string());
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_bad_lines_only() {
+  let root = common::TempDir::new("bad");
+
+  let lines = [
+    "2026-03-01T09:00:00.000000+08:00 host kernel: good 1",
+    "Mar 1 09:00:01 unparsed 1",
+    "2026-03-01T09:00:02.000000+08:00 host kernel: good 2",
+    "2026/03/01 09:00:03 unparsed 2",
+    "2026-03-01T09:00:04.000000+08:00 host kernel: good 3",
+  ];
+  std::fs::write(root.join("bad.log"), lines.join("\n") + "\n").unwrap();
+
+  let (mut log_hub, mut ctrl) = open_controller(
+    root.to_path_buf(),
+    "bad",
+    Config::default().with_keep_bad_lines(),
+  )
+  .await;
+
+  let contents = |ctrl: &LogController| -> Vec<(bool, String)> {
+    ctrl
+      .view()
+      .data()
+      .iter()
+      .map(|(_, log, _)| (log.is_bad(), log.raw().to_string()))
+      .collect()
+  };
+
+  // 无法解析的行被保留与计数，默认和其他日志一同展示
+  assert_eq!(ctrl.bad_lines(), 2);
+  assert_eq!(contents(&ctrl).len(), 5);
+
+  // 只展示无法解析的行
+  ctrl.toggle_bad_lines_only();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert!(ctrl.is_bad_lines_only());
+  assert_eq!(
+    contents(&ctrl),
+    vec![
+      (true, "Mar 1 09:00:01 unparsed 1".to_string()),
+      (true, "2026/03/01 09:00:03 unparsed 2".to_string()),
+    ]
+  );
+
+  // 恢复展示所有日志
+  ctrl.toggle_bad_lines_only();
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(contents(&ctrl).len(), 5);
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_export_by_command() {
+  let root = common::get_test_root();
+  let out_dir = common::TempDir::new("export");
+
+  let mut log_hub = open_log(root, "test", Config::default());
+  let mut ctrl = LogController::default();
+  ctrl.view_mut().ui_mut().set_height(10);
+
+  // 命令面板中的命令被翻译为展示区的控制：锁定标签后，只导出该标签的日志
+  let out = out_dir.join("systemd.log");
+  for command in [
+    "tag systemd".to_string(),
+    format!("export {}", out.display()),
+  ] {
+    command.parse::<Command>().unwrap().apply(&mut ctrl);
+  }
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert!(ctrl.take_error().is_none());
+
+  let exported = std::fs::read_to_string(&out).unwrap();
+  let lines: Vec<LogLine> = exported
+    .lines()
+    .map(|line| LogLine::new(line.to_string()))
+    .collect();
+  assert!(!lines.is_empty());
+  assert!(lines.iter().all(|log| log.get_tag() == Some("systemd")));
+
+  // 无法写入时报错
+  let command: Command = format!("export {}", out_dir.join("missing/x.log").display())
+    .parse()
+    .unwrap();
+  command.apply(&mut ctrl);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert!(matches!(ctrl.take_error(), Some(Error::ExportFailed(_))));
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_follow_batching() {
+  let root = common::TempDir::new("batch");
+
+  let line = |i: usize| {
+    format!(
+      "2026-03-01T09:00:{:02}.000000+08:00 host kernel: line {}",
+      i, i
+    )
+  };
+  let path = root.join("burst.log");
+  std::fs::write(
+    &path,
+    (0..5).map(line).collect::<Vec<_>>().join("\n") + "\n",
+  )
+  .unwrap();
+
+  let (mut log_hub, mut ctrl) =
+    open_controller(root.to_path_buf(), "burst", Config::default()).await;
+
+  let last_content = |ctrl: &LogController| {
+    ctrl
+      .view()
+      .data()
+      .back()
+      .map(|(_, log, _)| log.get_content().to_string())
+  };
+  assert_eq!(last_content(&ctrl).as_deref(), Some("line 4"));
+
+  // 间隔内快速追加的多行新日志，至多触发一次填充
+  let interval = std::time::Duration::from_secs(3);
+  ctrl.set_follow_batch_interval(interval);
+  {
+    let mut data = log_hub.data().await;
+    ctrl.run_once(&mut data);
+  }
+  let started = std::time::Instant::now();
+
+  let mut file = std::fs::OpenOptions::new()
+    .append(true)
+    .open(&path)
+    .unwrap();
+  let mut fills = 0;
+  for i in 5..10 {
+    use std::io::Write;
+    writeln!(file, "{}", line(i)).unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let mut data = log_hub.data().await;
+    ctrl.run_once(&mut data);
+    fills += ctrl.is_dirty() as usize;
+  }
+  assert!(started.elapsed() < interval);
+  assert!(fills <= 1);
+  assert_eq!(last_content(&ctrl).as_deref(), Some("line 4"));
+
+  // 间隔结束后，最新的日志总会被展示出来
+  tokio::time::sleep(interval.saturating_sub(started.elapsed())).await;
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(last_content(&ctrl).as_deref(), Some("line 9"));
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_keeps_cursor_when_older_file_loads() {
+  let root = common::TempDir::new("older");
+
+  let line = |i: usize| {
+    format!(
+      "2026-03-01T09:00:{:02}.000000+08:00 host kernel: line {}",
+      i, i
+    )
+  };
+  let write = |name: &str, range: std::ops::Range<usize>| {
+    let lines: Vec<String> = range.map(line).collect();
+    std::fs::write(root.join(name), lines.join("\n") + "\n").unwrap();
+  };
+  write("x.log.1", 0..20);
+  write("x.log", 20..25);
+
+  let mut log_hub = open_log(root.to_path_buf(), "x", Config::default());
+  tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+
+  // 光标停留在最新文件中的某一行，展示区顶到头后会触发加载更旧的文件
+  let mut ctrl = LogController::default();
+  ctrl.view_mut().ui_mut().set_height(10);
+  ctrl.view_mut().ui_mut().do_not_follow();
+  {
+    let mut data = log_hub.data().await;
+    assert_eq!(data.total_lines(), 5);
+    ctrl.run_once(&mut data);
+  }
+  ctrl.view_mut().ui_mut().want_move_cursor(-2);
+  {
+    let mut data = log_hub.data().await;
+    ctrl.run_once(&mut data);
+  }
+  let cursor = ctrl.cursor_log().map(|log| log.get_content().to_string());
+  assert_eq!(cursor.as_deref(), Some("line 22"));
+
+  // 更旧的文件插入到头部后，光标仍然指向同一行日志
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(log_hub.data().await.total_lines(), 25);
+  assert_eq!(
+    ctrl.cursor_log().map(|log| log.get_content().to_string()),
+    cursor
+  );
+
+  log_hub.close().await;
+}
+
+/// 测试用的剪贴板，给出预设的内容
+struct MockClipboard(Result<String, String>);
+
+impl Clipboard for MockClipboard {
+  fn read_text(&mut self) -> Result<String, String> {
+    self.0.clone()
+  }
+}
+
+#[tokio::test]
+async fn test_log_controller_locate_clipboard_time() {
+  let root = common::TempDir::new("clipboard");
+
+  let lines: Vec<String> = (0..10)
+    .map(|i| format!("2026-03-01T09:{i:02}:00.000000+08:00 host alpha: a{i}"))
+    .collect();
+  std::fs::write(root.join("time.log"), lines.join("\n") + "\n").unwrap();
+
+  let (mut log_hub, mut ctrl) =
+    open_controller_with(root.to_path_buf(), "time", Config::default(), |ctrl| {
+      ctrl.view_mut().ui_mut().set_height(20);
+    })
+    .await;
+
+  let cursor_content = |ctrl: &LogController| ctrl.cursor_log().unwrap().get_content().to_string();
+
+  // RFC3339 的时间戳按其自带的时区理解，跳转到最接近的日志
+  ctrl.locate_clipboard_time(MockClipboard(Ok("2026-03-01T01:04:50+00:00\n".to_string())));
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert!(ctrl.take_error().is_none());
+  assert_eq!(cursor_content(&ctrl), "a5");
+
+  // 时间戳搜索中的时间点格式按展示时区理解
+  ctrl.style_mut().display_tz = "+08:00".parse().unwrap();
+  ctrl.locate_clipboard_time(MockClipboard(Ok("2026.03.01 09:02".to_string())));
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert!(ctrl.take_error().is_none());
+  assert_eq!(cursor_content(&ctrl), "a2");
+
+  // 内容无法解析、或者剪贴板无法读取时报错，光标不动
+  for text in [
+    Ok("not a timestamp".to_string()),
+    Err("no clipboard".to_string()),
+  ] {
+    ctrl.locate_clipboard_time(MockClipboard(text));
+    run_for_a_while(&mut log_hub, &mut ctrl).await;
+    assert!(matches!(
+      ctrl.take_error(),
+      Some(Error::ClipboardTimestampError(_))
+    ));
+    assert_eq!(cursor_content(&ctrl), "a2");
+  }
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_exclusions() {
+  let root = common::TempDir::new("exclude");
+
+  let lines: Vec<String> = (0..6)
+    .map(|i| {
+      let tag = if i % 2 == 0 { "kernel" } else { "sshd" };
+      let content = if i % 3 == 0 {
+        "health check ok"
+      } else {
+        "request"
+      };
+      format!("2026-03-01T09:00:0{i}.000000+08:00 host {tag}: {content} {i}")
+    })
+    .collect();
+  std::fs::write(root.join("exclude.log"), lines.join("\n") + "\n").unwrap();
+
+  let (mut log_hub, mut ctrl) =
+    open_controller(root.to_path_buf(), "exclude", Config::default()).await;
+
+  let contents = |ctrl: &LogController| -> Vec<String> {
+    ctrl
+      .view()
+      .data()
+      .iter()
+      .map(|(_, log, _)| log.get_content().to_string())
+      .collect()
+  };
+  assert_eq!(contents(&ctrl).len(), 6);
+
+  // 包含排除内容的行从展示区以及日志的遍历中消失
+  "exclude health check"
+    .parse::<Command>()
+    .unwrap()
+    .apply(&mut ctrl);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(ctrl.exclusions(), ["health check"]);
+  assert_eq!(
+    contents(&ctrl),
+    vec!["request 1", "request 2", "request 4", "request 5"]
+  );
+  {
+    let mut data = log_hub.data().await;
+    assert_eq!(data.iter_forward_from_head().count(), 4);
+    assert_eq!(data.data_board().active_filters(), 1);
+  }
+
+  // 与标签过滤一同生效
+  ctrl.lock_tag(Some("kernel".to_string()));
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(contents(&ctrl), vec!["request 2", "request 4"]);
+  ctrl.lock_tag(None);
+
+  // 取消排除后恢复展示
+  ":exclude".parse::<Command>().unwrap().apply(&mut ctrl);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert!(ctrl.exclusions().is_empty());
+  assert_eq!(contents(&ctrl).len(), 6);
+  {
+    let mut data = log_hub.data().await;
+    assert_eq!(data.iter_forward_from_head().count(), 6);
+    assert_eq!(data.data_board().active_filters(), 0);
+  }
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_goto_time() {
+  let root = common::TempDir::new("goto");
+
+  let lines: Vec<String> = (0..6)
+    .map(|i| format!("2026-03-01T0{i}:00:00.000000+08:00 host app: line {i}"))
+    .collect();
+  std::fs::write(root.join("goto.log"), lines.join("\n") + "\n").unwrap();
+
+  let (mut log_hub, mut ctrl) =
+    open_controller(root.to_path_buf(), "goto", Config::default()).await;
+  ctrl.style_mut().display_tz = "+08:00".parse().unwrap();
+  let cursor_content = |ctrl: &LogController| ctrl.cursor_log().unwrap().get_content().to_string();
+  assert_eq!(cursor_content(&ctrl), "line 5");
+
+  // 只有时间时，按已加载日志的日期理解，而不是今天
+  "goto 02:10".parse::<Command>().unwrap().apply(&mut ctrl);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(cursor_content(&ctrl), "line 2");
+
+  "goto 2026-03-01 04:00"
+    .parse::<Command>()
+    .unwrap()
+    .apply(&mut ctrl);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(cursor_content(&ctrl), "line 4");
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_controller_pid_filter() {
+  let root = common::TempDir::new("pid");
+
+  let lines = [
+    "2026-03-01T09:00:00.000000+08:00 host sshd[42]: accepted 0",
+    "2026-03-01T09:00:01.000000+08:00 host sshd[150]: closed 1",
+    "2026-03-01T09:00:02.000000+08:00 host cron[42]: tick 2",
+    "2026-03-01T09:00:03.000000+08:00 host rsyslogd: restarted 3",
+  ];
+  std::fs::write(root.join("pid.log"), lines.join("\n") + "\n").unwrap();
+
+  let (mut log_hub, mut ctrl) = open_controller(root.to_path_buf(), "pid", Config::default()).await;
+
+  let contents = |ctrl: &LogController| -> Vec<String> {
+    ctrl
+      .view()
+      .data()
+      .iter()
+      .map(|(_, log, _)| log.get_content().to_string())
+      .collect()
+  };
+  assert_eq!(contents(&ctrl).len(), 4);
+
+  // 同一进程号跨越不同的标签
+  ":pid 42".parse::<Command>().unwrap().apply(&mut ctrl);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(
+    ctrl.pid_filter().map(|pid| pid.to_string()),
+    Some("42".into())
+  );
+  assert_eq!(contents(&ctrl), vec!["accepted 0", "tick 2"]);
+  {
+    let mut data = log_hub.data().await;
+    assert_eq!(data.iter_forward_from_head().count(), 2);
+    assert_eq!(data.data_board().active_filters(), 1);
+  }
+
+  // 没有进程号的日志只在请求了 0 时展示
+  ":pid 0-100".parse::<Command>().unwrap().apply(&mut ctrl);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(contents(&ctrl), vec!["accepted 0", "tick 2", "restarted 3"]);
+
+  // 取消过滤后恢复展示
+  ":pid".parse::<Command>().unwrap().apply(&mut ctrl);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  assert_eq!(ctrl.pid_filter(), None);
+  assert_eq!(contents(&ctrl).len(), 4);
+
+  log_hub.close().await;
+}
@@ -1,4 +1,4 @@
-use rs_syslog_viewer::log::{DataBoard, Event, LogFile, LogLine};
+use rs_syslog_viewer::log::{Config, DataBoard, Event, LogFile, LogLine};
 use std::collections::{BTreeSet, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -17,7 +17,7 @@ async fn test_log_file() {
   let true_tags: BTreeSet<String> = common::all_tags(&true_content);
 
   let data_board = Arc::new(Mutex::new(DataBoard::default()));
-  let mut log_file = LogFile::open(log_path, false)
+  let mut log_file = LogFile::open(log_path, false, &Config::default())
     .await
     .expect("Could not open log file");
 
@@ -46,3 +46,101 @@ async fn test_log_file() {
   assert_eq!(&reversed_content, &true_reversed_content);
   assert_eq!(&tags, &true_tags);
 }
+
+#[tokio::test]
+async fn test_log_file_join_continuation_lines() {
+  let log_path = common::get_test_root().join("multiline.log");
+  let lines = common::read_file_as_lines(&log_path);
+
+  // 第二条日志跨越了两行
+  let mut true_content: Vec<LogLine> = lines.iter().cloned().map(LogLine::new).collect();
+  let continuation = true_content.remove(2);
+  assert!(continuation.is_bad());
+  if let LogLine::Good(log) = &mut true_content[1] {
+    log.append_line(continuation.raw());
+  }
+
+  // 从头部读取（旧日志）、从尾部读取（最新的日志）两种方式都要正确拼接
+  for latest in [false, true] {
+    let data_board = Arc::new(Mutex::new(DataBoard::default()));
+    let mut log_file = LogFile::open(
+      log_path.clone(),
+      latest,
+      &Config::default().with_join_continuation_lines(),
+    )
+    .await
+    .expect("Could not open log file");
+
+    loop {
+      tokio::select! {
+        _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)) => {
+          break;
+        },
+        _ = log_file.update(data_board.clone()) => {}
+      }
+    }
+
+    let content: Vec<LogLine> = common::collect_lines(log_file.data().iter_forward_from_head());
+    assert_eq!(&content, &true_content);
+    assert_eq!(
+      content[1].get_content(),
+      "Traceback (most recent call last):\n  File \"app.py\", line 3, in <module>"
+    );
+    assert_eq!(content[1].raw(), lines[1..3].join("\n"));
+
+    // 被拼接的行不计为无法解析的行
+    assert_eq!(data_board.lock().await.get_bad_lines(), 0);
+
+    log_file.close().await.unwrap();
+  }
+}
+
+#[tokio::test]
+async fn test_log_file_leading_continuation_lines() {
+  // 文件开头的两行无法解析，它们之前没有可以拼接的日志
+  let root = common::TempDir::new("leading_continuation");
+  let log_path = root.join("leading.log");
+  let lines = [
+    "  at main.rs:1",
+    "  at main.rs:2",
+    "2026-02-15T12:00:00.000001+08:00 host app[100]: starting",
+    "  at main.rs:3",
+  ];
+  std::fs::write(&log_path, lines.join("\n") + "\n").unwrap();
+
+  // 从尾部读取（最新的日志）时，读到文件开头后，按是否保留无法解析的行处理它们
+  for keep_bad_lines in [false, true] {
+    let mut config = Config::default().with_join_continuation_lines();
+    if keep_bad_lines {
+      config = config.with_keep_bad_lines();
+    }
+    let data_board = Arc::new(Mutex::new(DataBoard::default()));
+    let mut log_file = LogFile::open(log_path.clone(), true, &config)
+      .await
+      .expect("Could not open log file");
+
+    loop {
+      tokio::select! {
+        _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)) => {
+          break;
+        },
+        _ = log_file.update(data_board.clone()) => {}
+      }
+    }
+
+    let content: Vec<String> = common::collect_lines(log_file.data().iter_forward_from_head())
+      .iter()
+      .map(|log| log.raw().to_string())
+      .collect();
+    let joined = lines[2..].join("\n");
+    if keep_bad_lines {
+      assert_eq!(content, vec![lines[0], lines[1], joined.as_str()]);
+      assert_eq!(data_board.lock().await.get_bad_lines(), 2);
+    } else {
+      assert_eq!(content, vec![joined.as_str()]);
+      assert_eq!(data_board.lock().await.get_bad_lines(), 0);
+    }
+
+    log_file.close().await.unwrap();
+  }
+}
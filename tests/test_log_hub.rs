@@ -1,3 +1,4 @@
+use chrono::{DateTime, Duration, FixedOffset};
 use rs_syslog_viewer::app::LogHub;
 use rs_syslog_viewer::log::{Config, LogLine};
 
@@ -25,6 +26,7 @@ async fn test_log_hub() {
       .iter()
       .map(|name| (name.to_string(), Config::default()))
       .collect(),
+    Config::default(),
   );
 
   for i in 0..5 {
@@ -53,3 +55,495 @@ async fn test_log_hub() {
   let content: Vec<LogLine> = common::collect_mut_lines(data.iter_forward_from_head());
   assert_eq!(&content, &true_content);
 }
+
+#[tokio::test]
+async fn test_log_hub_watch_new_logs() {
+  // 在一个空的临时目录中打开，并监控新的日志
+  let root = common::TempDir::new("watch");
+
+  let mut log_hub = LogHub::open(root.to_path_buf(), Default::default(), Config::default());
+  log_hub.watch_new_logs("*.log").unwrap();
+
+  {
+    let mut data = log_hub.data().await;
+    assert_eq!(data.iter_forward_from_head().count(), 0);
+  }
+
+  // 创建新日志，以及不符合模式的文件
+  let lines = common::read_file_as_lines(&common::get_test_log());
+  let lines = &lines[..10];
+  std::fs::write(root.join("new.log"), lines.join("\n") + "\n").unwrap();
+  std::fs::write(root.join("ignored.txt"), lines.join("\n") + "\n").unwrap();
+
+  for _ in 0..5 {
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let _ = log_hub.data().await;
+  }
+
+  let mut data = log_hub.data().await;
+  let content: Vec<LogLine> = common::collect_mut_lines(data.iter_forward_from_head());
+  let true_content: Vec<LogLine> = lines.iter().cloned().map(LogLine::new).collect();
+  assert_eq!(&content, &true_content);
+
+  drop(data);
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_hub_watch_new_logs_with_config() {
+  // 新发现的日志沿用打开时给出的配置，这里保留了无法解析的行
+  let root = common::TempDir::new("watch_config");
+
+  let mut log_hub = LogHub::open(
+    root.to_path_buf(),
+    Default::default(),
+    Config::default().with_keep_bad_lines(),
+  );
+  log_hub.watch_new_logs("*.log").unwrap();
+  let _ = log_hub.data().await;
+
+  let mut lines = common::read_file_as_lines(&common::get_test_log())[..3].to_vec();
+  lines.push("not a syslog line".to_string());
+  std::fs::write(root.join("new.log"), lines.join("\n") + "\n").unwrap();
+
+  for _ in 0..5 {
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let _ = log_hub.data().await;
+  }
+
+  let mut data = log_hub.data().await;
+  let content: Vec<LogLine> = common::collect_mut_lines(data.iter_forward_from_head());
+  let true_content: Vec<LogLine> = lines.into_iter().map(LogLine::new).collect();
+  assert_eq!(&content, &true_content);
+  assert!(content[3].is_bad());
+
+  drop(data);
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_hub_recreated_log() {
+  let root = common::TempDir::new("recreate");
+
+  let lines = common::read_file_as_lines(&common::get_test_log());
+  let path = root.join("x.log");
+  std::fs::write(&path, lines[..3].join("\n") + "\n").unwrap();
+
+  let mut log_hub = LogHub::open(
+    root.to_path_buf(),
+    [("x".to_string(), Config::default())].into_iter().collect(),
+    Config::default(),
+  );
+  for _ in 0..5 {
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let _ = log_hub.data().await;
+  }
+
+  // 删除后重新创建同名文件，应该从新文件的头部重新加载
+  std::fs::remove_file(&path).unwrap();
+  std::fs::write(&path, lines[3..5].join("\n") + "\n").unwrap();
+
+  for _ in 0..10 {
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let _ = log_hub.data().await;
+  }
+
+  let mut data = log_hub.data().await;
+  let content: Vec<LogLine> = common::collect_mut_lines(data.iter_forward_from_head());
+  let true_content: Vec<LogLine> = lines[3..5].iter().cloned().map(LogLine::new).collect();
+  assert_eq!(&content, &true_content);
+
+  drop(data);
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_hub_open_files() {
+  let root = common::TempDir::new("files");
+  std::fs::create_dir_all(root.join("nginx")).unwrap();
+  std::fs::create_dir_all(root.join("app")).unwrap();
+
+  // 位于不同目录、不以 .log 结尾的日志，其中一份还有被滚动的旧文件
+  let lines = common::read_file_as_lines(&common::get_test_log());
+  let write = |path: &str, lines: &[String]| {
+    std::fs::write(root.join(path), lines.join("\n") + "\n").unwrap();
+  };
+  write("app/foo.out.1", &lines[..5]);
+  write("app/foo.out", &lines[5..10]);
+  write("nginx/access.log", &lines[10..15]);
+  write("nginx/other.log", &lines[15..20]);
+
+  let mut log_hub = LogHub::open_files(
+    vec![
+      (root.join("nginx/access.log"), Config::default()),
+      (root.join("app/foo.out"), Config::default()),
+    ],
+    Config::default(),
+  );
+
+  for _ in 0..5 {
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let mut data = log_hub.data().await;
+    let first_index = data.first_index();
+    data.try_load_older_logs(&first_index);
+  }
+
+  // 公共父目录作为根目录，各个日志只在自己所在的目录下查找被滚动的旧文件
+  let mut data = log_hub.data().await;
+  assert_eq!(data.data_board().get_root_path().as_path(), &*root);
+  let content: Vec<LogLine> = common::collect_mut_lines(data.iter_forward_from_head());
+  let true_content: Vec<LogLine> = lines[..15].iter().cloned().map(LogLine::new).collect();
+  assert_eq!(&content, &true_content);
+
+  drop(data);
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_hub_locate_time() {
+  let root = common::TempDir::new("locate");
+
+  // 三份日志的时间交错，间隔不均匀，其中一份夹杂着无法解析的行，且比其他日志更早结束
+  let start = DateTime::parse_from_rfc3339("2026-01-17T10:00:00+08:00").unwrap();
+  let line = |tag: &str, ms: i64| {
+    let t = start + Duration::milliseconds(ms);
+    format!(
+      "{} host {tag}: at {ms}",
+      t.to_rfc3339_opts(chrono::SecondsFormat::Micros, false)
+    )
+  };
+  let write = |name: &str, lines: Vec<String>| {
+    std::fs::write(root.join(name), lines.join("\n") + "\n").unwrap();
+  };
+  write("a.log", (0..40).map(|i| line("a", i * 300)).collect());
+  write("b.log", (0..25).map(|i| line("b", i * 500 + 100)).collect());
+  write(
+    "c.log",
+    (0..10)
+      .flat_map(|i| [line("c", i * 700 + 250), "not a syslog line".to_string()])
+      .collect(),
+  );
+
+  let mut log_hub = LogHub::open_files(
+    ["a.log", "b.log", "c.log"]
+      .iter()
+      .map(|name| (root.join(name), Config::default()))
+      .collect(),
+    Config::default(),
+  );
+  for _ in 0..5 {
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let mut data = log_hub.data().await;
+    let first_index = data.first_index();
+    data.try_load_older_logs(&first_index);
+  }
+
+  let mut data = log_hub.data().await;
+  let all: Vec<LogLine> = common::collect_mut_lines(data.iter_forward_from_head());
+  let timestamps: Vec<DateTime<FixedOffset>> =
+    all.iter().filter_map(LogLine::get_timestamp).collect();
+  assert_eq!(timestamps.len(), 40 + 25 + 10);
+
+  // 逐行比较得到的真值，距离相同时取更早的一条
+  let nearest = |dt: DateTime<FixedOffset>| {
+    *timestamps
+      .iter()
+      .min_by_key(|t| ((**t - dt).abs(), **t))
+      .unwrap()
+  };
+
+  for ms in [
+    -1000, 0, 100, 140, 200, 260, 4321, 6999, 7150, 11700, 11999, 12150, 99999,
+  ] {
+    let dt = start + Duration::milliseconds(ms);
+    let index = data.locate_time(dt);
+    let log = data.get(index.clone()).unwrap().clone();
+    assert_eq!(log.get_timestamp(), Some(nearest(dt)), "locate {ms}ms");
+
+    // 得到的是完整的索引，从它出发正向、逆向遍历都能得到正确的日志
+    let pos = all.iter().position(|l| l == &log).unwrap();
+    let (forward, backward) = data.iter_at(index);
+    let forward: Vec<LogLine> = common::collect_mut_lines(forward);
+    let backward: Vec<LogLine> = common::collect_mut_lines(backward);
+    assert_eq!(&forward, &all[pos..], "forward from {ms}ms");
+    let true_backward: Vec<LogLine> = all[..=pos].iter().rev().cloned().collect();
+    assert_eq!(&backward, &true_backward, "backward from {ms}ms");
+  }
+
+  drop(data);
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_hub_new_tags_hidden() {
+  let root = common::get_test_root();
+  let mut log_hub = LogHub::open(
+    root.to_path_buf(),
+    [(
+      "test".to_string(),
+      Config::default().with_new_tag_default(false),
+    )]
+    .into(),
+    Config::default(),
+  );
+  tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
+
+  let true_content = common::read_file_as_lines(&root.join("test.log"))
+    .into_iter()
+    .map(LogLine::new)
+    .collect::<Vec<LogLine>>();
+  let tag = true_content[0].get_tag().unwrap().to_string();
+
+  // 新发现的标签默认不选中，它们的日志都被过滤
+  let mut data = log_hub.data().await;
+  assert!(!data.data_board().get_tags().all().is_empty());
+  assert!(data.data_board().get_tags().all().values().all(|v| !v));
+  data.data_board().get_tags_mut().update_version();
+  assert_eq!(data.iter_forward_from_head().count(), 0);
+
+  // 手动选中某个标签后，只展示它的日志
+  data.data_board().get_tags_mut().set(&tag);
+  data.data_board().get_tags_mut().update_version();
+  let content: Vec<LogLine> = common::collect_mut_lines(data.iter_forward_from_head());
+  let true_content: Vec<LogLine> = true_content
+    .into_iter()
+    .filter(|log| log.get_tag() == Some(tag.as_str()))
+    .collect();
+  assert!(!content.is_empty());
+  assert_eq!(&content, &true_content);
+}
+
+#[tokio::test]
+async fn test_log_hub_loading_state() {
+  let root = common::get_test_root();
+  let mut log_hub = LogHub::open(
+    root,
+    [("test".to_string(), Config::default())].into(),
+    Config::default(),
+  );
+
+  // 等待最新的日志加载完成，此时没有正在进行的加载
+  tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
+  let total_lines = {
+    let mut data = log_hub.data().await;
+    assert!(!data.data_board().is_loading());
+
+    // 请求加载更旧的日志后，在它被后台流程处理之前，都处于加载状态
+    let first_index = data.first_index();
+    data.try_load_older_logs(&first_index);
+    assert_eq!(data.data_board().pending_loads(), 1);
+
+    // 重复的请求不会被重复计数
+    data.try_load_older_logs(&first_index);
+    assert_eq!(data.data_board().pending_loads(), 1);
+    data.total_lines()
+  };
+
+  // 后台流程打开旧日志后，加载状态被清除
+  tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
+  let mut data = log_hub.data().await;
+  assert!(!data.data_board().is_loading());
+  assert!(data.total_lines() > total_lines);
+}
+
+#[tokio::test]
+async fn test_log_hub_marked_logs() {
+  let root = common::get_test_root();
+  let mut log_hub = LogHub::open(
+    root,
+    [("test".to_string(), Config::default())].into(),
+    Config::default(),
+  );
+  tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
+
+  let mut data = log_hub.data().await;
+  assert!(data.marked_logs().is_empty());
+
+  // 每隔几行标记一条日志
+  let mut true_marks = vec![];
+  for (i, (_, log)) in data.iter_forward_from_head().enumerate() {
+    if i % 7 == 0 {
+      log.toggle_mark();
+      true_marks.push(log.clone());
+    }
+  }
+  assert!(true_marks.len() > 1);
+
+  // 按从旧到新的顺序收集被标记的日志，且索引指向它们本身
+  let marks = data.marked_logs();
+  let lines: Vec<LogLine> = marks.iter().map(|(_, log)| log.clone()).collect();
+  assert_eq!(&lines, &true_marks);
+  for (index, log) in marks {
+    assert_eq!(data.get(index).unwrap(), &log);
+  }
+
+  // 被过滤掉的标记日志不会被收集
+  let tag = true_marks[0].get_tag().unwrap().to_string();
+  data.data_board().get_tags_mut().unset(&tag);
+  data.data_board().get_tags_mut().update_version();
+  let lines: Vec<LogLine> = data.marked_logs().into_iter().map(|(_, log)| log).collect();
+  let true_lines: Vec<LogLine> = true_marks
+    .into_iter()
+    .filter(|log| log.get_tag() != Some(tag.as_str()))
+    .collect();
+  assert_eq!(&lines, &true_lines);
+}
+
+#[tokio::test]
+async fn test_log_hub_clear_all_marks() {
+  let root = common::get_test_root();
+  let mut log_hub = LogHub::open(
+    root,
+    [("test".to_string(), Config::default())].into(),
+    Config::default(),
+  );
+  tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
+
+  let mut data = log_hub.data().await;
+  let mut marked = 0;
+  for (i, (_, log)) in data.iter_forward_from_head().enumerate() {
+    if i % 3 == 0 {
+      log.toggle_mark();
+      marked += 1;
+    }
+  }
+
+  // 过滤掉部分标记日志后清除，被过滤的日志的标记也会被清除
+  let tag = data.marked_logs()[0].1.get_tag().unwrap().to_string();
+  data.data_board().get_tags_mut().unset(&tag);
+  data.data_board().get_tags_mut().update_version();
+
+  let version = data.data_board().get_marks_version();
+  assert_eq!(data.clear_all_marks(), marked);
+  assert_ne!(data.data_board().get_marks_version(), version);
+
+  data.data_board().get_tags_mut().set(&tag);
+  data.data_board().get_tags_mut().update_version();
+  assert!(
+    data
+      .iter_forward_from_head()
+      .all(|(_, log)| !log.is_marked())
+  );
+  assert_eq!(data.clear_all_marks(), 0);
+}
+
+#[tokio::test]
+async fn test_log_hub_total_lines() {
+  let root = common::TempDir::new("total");
+
+  // 两份日志，其中一份被轮转为两个文件，每个 chunk 只存放少量的日志行
+  let line = |tag: &str, i: usize| {
+    format!(
+      "2026-01-17T10:{:02}:{:02}.000000+08:00 host {tag}: line {i}",
+      i / 60,
+      i % 60
+    )
+  };
+  let write = |name: &str, tag: &str, range: std::ops::Range<usize>| {
+    let lines: Vec<String> = range.map(|i| line(tag, i)).collect();
+    std::fs::write(root.join(name), lines.join("\n") + "\n").unwrap();
+  };
+  write("x.log.1", "x", 0..37);
+  write("x.log", "x", 37..100);
+  write("y.log", "y", 5..78);
+
+  let config = || Config::default().with_chunk_capacity(8);
+  let mut log_hub = LogHub::open(
+    root.to_path_buf(),
+    [("x".to_string(), config()), ("y".to_string(), config())]
+      .into_iter()
+      .collect(),
+    Config::default(),
+  );
+  for _ in 0..5 {
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let mut data = log_hub.data().await;
+    let first_index = data.first_index();
+    data.try_load_older_logs(&first_index);
+  }
+
+  // 已加载的行数与遍历得到的行数一致
+  let mut data = log_hub.data().await;
+  let count = data.iter_forward_from_head().count();
+  assert_eq!(count, 100 + 73);
+  assert_eq!(data.total_lines(), count);
+
+  drop(data);
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_log_hub_remap_after_older_load() {
+  let root = common::TempDir::new("remap");
+
+  // a 只有一份文件；b 的旧文件与 a 的前半段交错，最新的文件晚于 a 的大部分日志
+  let start = DateTime::parse_from_rfc3339("2026-01-17T10:00:00+08:00").unwrap();
+  let line = |tag: &str, secs: i64| {
+    let t = start + Duration::seconds(secs);
+    format!(
+      "{} host {tag}: at {secs}",
+      t.to_rfc3339_opts(chrono::SecondsFormat::Micros, false)
+    )
+  };
+  let write = |name: &str, lines: Vec<String>| {
+    std::fs::write(root.join(name), lines.join("\n") + "\n").unwrap();
+  };
+  write("a.log", (0..20).map(|i| line("a", i * 2)).collect());
+  write("b.log.1", (0..10).map(|i| line("b", i * 2 + 1)).collect());
+  write("b.log", (10..20).map(|i| line("b", i * 2 + 1)).collect());
+
+  let mut log_hub = LogHub::open(
+    root.to_path_buf(),
+    ["a", "b"]
+      .iter()
+      .map(|name| (name.to_string(), Config::default()))
+      .collect(),
+    Config::default(),
+  );
+  tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+  // 只加载了 b 最新的文件时，记下指向 a 中某一行的索引
+  let cached = {
+    let mut data = log_hub.data().await;
+    assert_eq!(data.total_lines(), 30);
+    data
+      .iter_forward_from_head()
+      .find(|(_, log)| log.get_content() == "at 4")
+      .map(|(index, _)| index)
+      .unwrap()
+  };
+
+  // 后台加载 b 的旧文件，其中晚于该行的日志也要能从该索引出发遍历到
+  {
+    let mut data = log_hub.data().await;
+    let first_index = data.first_index();
+    data.try_load_older_logs(&first_index);
+  }
+  for _ in 0..5 {
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let _ = log_hub.data().await;
+  }
+
+  let mut data = log_hub.data().await;
+  let all: Vec<LogLine> = common::collect_mut_lines(data.iter_forward_from_head());
+  assert_eq!(all.len(), 40);
+  let pos = all.iter().position(|l| l.get_content() == "at 4").unwrap();
+
+  let index = data.remap(cached);
+  assert_eq!(
+    data
+      .get(index.clone())
+      .map(|log| log.get_content().to_string())
+      .as_deref(),
+    Some("at 4")
+  );
+  let (forward, backward) = data.iter_at(index);
+  let forward: Vec<LogLine> = common::collect_mut_lines(forward);
+  let backward: Vec<LogLine> = common::collect_mut_lines(backward);
+  assert_eq!(&forward, &all[pos..]);
+  let true_backward: Vec<LogLine> = all[..=pos].iter().rev().cloned().collect();
+  assert_eq!(&backward, &true_backward);
+
+  drop(data);
+  log_hub.close().await;
+}
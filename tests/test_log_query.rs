@@ -0,0 +1,51 @@
+use rs_syslog_viewer::app::LogQuery;
+use rs_syslog_viewer::log::{Label, LogLine};
+
+mod common;
+
+fn is_error(log: &LogLine) -> bool {
+  matches!(log, LogLine::Good(log) if log.label == Label::Error)
+}
+
+#[tokio::test]
+async fn test_log_query_count_errors() {
+  let names = ["test", "user"];
+  let root = common::get_test_root();
+
+  // 真值
+  let true_content: Vec<LogLine> = names
+    .iter()
+    .filter_map(|name| common::read_all_files_as_lines(&root, name))
+    .flatten()
+    .collect();
+  let true_count = true_content.iter().filter(|log| is_error(log)).count();
+  assert!(true_count > 0);
+
+  // 统计所有的错误日志
+  let mut query = LogQuery::open(root.clone(), names).with_predicate(is_error);
+  query.wait_loaded().await;
+  assert_eq!(query.run().await.count(), true_count);
+
+  // 只统计某个标签的错误日志
+  let tag = true_content
+    .iter()
+    .filter(|log| is_error(log))
+    .find_map(|log| log.get_tag())
+    .unwrap()
+    .to_string();
+  let true_tag_count = true_content
+    .iter()
+    .filter(|log| is_error(log) && log.get_tag() == Some(tag.as_str()))
+    .count();
+
+  let mut query = LogQuery::open(root, names)
+    .with_tags([tag.clone()])
+    .with_predicate(is_error);
+  query.wait_loaded().await;
+
+  let logs: Vec<LogLine> = query.run().await.collect();
+  assert_eq!(logs.len(), true_tag_count);
+  assert!(logs.iter().all(|log| log.get_tag() == Some(tag.as_str())));
+
+  query.close().await;
+}
@@ -1,6 +1,11 @@
-use rs_syslog_viewer::file::{Event, HeadReader, Reader, TailReader, reader::Config};
+use rs_syslog_viewer::file::{
+  Event, HeadReader, Reader, StreamReader, TailReader,
+  reader::{self, Config, Overflow, ReaderBase},
+};
+use rs_syslog_viewer::log::LogLine;
 use std::collections::LinkedList;
 use std::path::Path;
+use tokio::io::AsyncWriteExt;
 
 mod common;
 
@@ -8,7 +13,14 @@ async fn read_file<R>(path: &Path, true_content: &Vec<String>)
 where
   R: Reader,
 {
-  let mut reader = R::open(path, Config::default())
+  read_file_with_config::<R>(path, Config::default(), true_content).await;
+}
+
+async fn read_file_with_config<R>(path: &Path, config: Config, true_content: &Vec<String>)
+where
+  R: Reader,
+{
+  let mut reader = R::open(path, config)
     .await
     .expect("Failed to create reader");
 
@@ -23,15 +35,20 @@ where
       Some(events) = reader.changed() => for event in events {
         match event {
           Event::NewHead(s) => {
+            let s = String::from_utf8(s).unwrap();
             println!("Head changed {:?}", s);
             content.push_front(s);
           }
           Event::NewTail(s) => {
+            let s = String::from_utf8(s).unwrap();
             println!("Tail changed {:?}", s);
             content.push_back(s);
           }
           Event::Renamed(_) => {}
           Event::Removed => {}
+          Event::Truncated => {}
+          Event::Idle(_) => {}
+          Event::HeadEnd => {}
         }
       }
     }
@@ -53,3 +70,463 @@ async fn test_readers() {
   println!("Test head reader ...");
   read_file::<HeadReader>(&log_path, &true_content).await;
 }
+
+#[tokio::test]
+async fn test_head_reader_rotated_files() {
+  // 被滚动的旧日志不会再更新，正向读取到文件结尾后，内容应该完整且有序
+  let root = common::get_test_root();
+  for name in ["test.log.1", "test.log.2", "user.log.1"] {
+    let log_path = root.join(name);
+    let true_content = common::read_file_as_lines(&log_path);
+    read_file::<HeadReader>(&log_path, &true_content).await;
+  }
+}
+
+#[tokio::test]
+async fn test_head_reader_watches_rename() {
+  let root = common::TempDir::new("head");
+
+  let log_path = root.join("x.log.1");
+  std::fs::copy(common::get_test_log(), &log_path).unwrap();
+  let true_content = common::read_file_as_lines(&log_path);
+
+  let mut reader = HeadReader::open(&log_path, Config::default())
+    .await
+    .expect("Failed to create reader");
+  reader.start().await.expect("Failed to start reader");
+
+  // 读取完成后，再重命名文件，读取器仍然能感知到
+  let mut content = vec![];
+  let mut renamed = None;
+  for i in 0..10 {
+    if i == 5 {
+      std::fs::rename(&log_path, root.join("x.log.2")).unwrap();
+    }
+    tokio::select! {
+      _ = tokio::time::sleep(tokio::time::Duration::from_millis(200)) => {},
+      Some(events) = reader.changed() => for event in events {
+        match event {
+          Event::NewTail(s) => content.push(String::from_utf8(s).unwrap()),
+          Event::Renamed(path) => renamed = Some(path),
+          _ => {}
+        }
+      }
+    }
+  }
+
+  reader.stop().await.expect("Failed to stop reader");
+
+  assert_eq!(&content, &true_content);
+  assert_eq!(renamed, Some(root.join("x.log.2")));
+}
+
+#[tokio::test]
+async fn test_head_reader_flushes_last_line() {
+  let root = common::TempDir::new("eof");
+
+  // 最后一行没有换行符
+  let log_path = root.join("x.log.1");
+  let mut true_content = common::read_file_as_lines(&common::get_test_log());
+  true_content.truncate(5);
+  std::fs::write(&log_path, true_content.join("\n")).unwrap();
+
+  // 默认等待最后一行写完，不发送它
+  read_file::<HeadReader>(&log_path, &true_content[..4].to_vec()).await;
+
+  // 开启后，读到文件结尾时发送最后一行，缓冲区小于一行时也一样
+  for buffer_size in [4096, 7] {
+    let config = Config {
+      buffer_size,
+      flush_partial_at_eof: true,
+      ..Config::default()
+    };
+    read_file_with_config::<HeadReader>(&log_path, config, &true_content).await;
+  }
+
+  // 以换行符结尾的文件不会多出空行
+  std::fs::write(&log_path, true_content.join("\n") + "\n").unwrap();
+  let config = Config {
+    flush_partial_at_eof: true,
+    ..Config::default()
+  };
+  read_file_with_config::<HeadReader>(&log_path, config, &true_content).await;
+}
+
+/// 打开一份临时的日志文件副本，内容事件通道只能容纳一个事件
+async fn open_saturated_reader(name: &str, overflow: Overflow) -> (HeadReader, common::TempDir) {
+  let root = common::TempDir::new(name);
+
+  let log_path = root.join("x.log.1");
+  std::fs::copy(common::get_test_log(), &log_path).unwrap();
+
+  let config = Config {
+    channel_size: 1,
+    overflow,
+    ..Config::default()
+  };
+  let mut reader = HeadReader::open(&log_path, config)
+    .await
+    .expect("Failed to create reader");
+  reader.start().await.expect("Failed to start reader");
+
+  (reader, root)
+}
+
+/// 取出读取器中的事件，直到一段时间内没有新的事件
+async fn drain_lines(reader: &mut HeadReader) -> Vec<String> {
+  let mut content = vec![];
+  loop {
+    tokio::select! {
+      _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)) => break,
+      Some(events) = reader.changed() => for event in events {
+        if let Event::NewTail(s) = event {
+          content.push(String::from_utf8(s).unwrap());
+        }
+      }
+    }
+  }
+  content
+}
+
+/// 收集尾部读取器在一段时间内的所有事件，按文件中的顺序排列内容。截断事件记为 <truncated>，空闲事件记为 <idle>
+async fn collect(reader: &mut TailReader) -> Vec<String> {
+  let mut content = LinkedList::new();
+  loop {
+    tokio::select! {
+      _ = tokio::time::sleep(tokio::time::Duration::from_millis(800)) => break,
+      Some(events) = reader.changed() => for event in events {
+        match event {
+          Event::NewHead(s) => content.push_front(String::from_utf8(s).unwrap()),
+          Event::NewTail(s) => content.push_back(String::from_utf8(s).unwrap()),
+          Event::Truncated => content.push_back("<truncated>".to_string()),
+          Event::Idle(_) => content.push_back("<idle>".to_string()),
+          Event::HeadEnd => {}
+          e => panic!("unexpected event {:?}", e),
+        }
+      }
+    }
+  }
+  content.into_iter().collect()
+}
+
+#[tokio::test]
+async fn test_reader_metadata_with_saturated_channel() {
+  let (mut reader, root) = open_saturated_reader("saturated", Overflow::Block).await;
+
+  // 不消费任何内容，内容通道被占满，此时重命名文件
+  tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+  std::fs::rename(root.join("x.log.1"), root.join("x.log.2")).unwrap();
+  tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+
+  // 重命名事件优先于堆积的内容被取出
+  let events = reader.changed().await.unwrap();
+  assert!(matches!(&events[..], [Event::Renamed(path)] if path == &root.join("x.log.2")));
+
+  // 阻塞策略下，内容不会丢失
+  let content = drain_lines(&mut reader).await;
+  assert_eq!(content, common::read_file_as_lines(&common::get_test_log()));
+
+  reader.stop().await.expect("Failed to stop reader");
+}
+
+#[tokio::test]
+async fn test_reader_drop_newest_on_overflow() {
+  let (mut reader, _root) = open_saturated_reader("overflow", Overflow::DropNewest).await;
+
+  // 读取流程不被阻塞，通道满时的新内容被丢弃
+  tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+  let content = drain_lines(&mut reader).await;
+  let true_content = common::read_file_as_lines(&common::get_test_log());
+  assert!(!content.is_empty() && content.len() < true_content.len());
+  assert_eq!(content[0], true_content[0]);
+
+  reader.stop().await.expect("Failed to stop reader");
+}
+
+#[tokio::test]
+async fn test_reader_drop_oldest_on_overflow() {
+  let (mut reader, _root) = open_saturated_reader("overflow_oldest", Overflow::DropOldest).await;
+
+  // 读取流程不被阻塞，通道满时旧的内容被丢弃，最新的内容总能送达
+  tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+  let content = drain_lines(&mut reader).await;
+  let true_content = common::read_file_as_lines(&common::get_test_log());
+  assert!(!content.is_empty() && content.len() < true_content.len());
+  assert_eq!(content.last(), true_content.last());
+
+  reader.stop().await.expect("Failed to stop reader");
+}
+
+#[tokio::test]
+async fn test_stream_reader() {
+  let true_content = common::read_file_as_lines(&common::get_test_log());
+
+  // 通过内存管道模拟标准输入，分多次写入，最后一行不带换行符
+  let (mut writer, stream) = tokio::io::duplex(64);
+  let mut reader = StreamReader::from_stream(stream, Config::default());
+  reader.start().await.expect("Failed to start reader");
+
+  let input = true_content.join("\n");
+  tokio::spawn(async move {
+    for chunk in input.as_bytes().chunks(100) {
+      writer.write_all(chunk).await.unwrap();
+    }
+  });
+
+  let mut content = vec![];
+  loop {
+    tokio::select! {
+      _ = tokio::time::sleep(tokio::time::Duration::from_millis(1000)) => {
+        break;
+      },
+      Some(events) = reader.changed() => for event in events {
+        match event {
+          Event::NewTail(s) => content.push(String::from_utf8(s).unwrap()),
+          e => panic!("unexpected event {:?}", e),
+        }
+      }
+    }
+  }
+
+  reader.stop().await.expect("Failed to stop reader");
+
+  assert_eq!(&content, &true_content);
+}
+
+#[tokio::test]
+async fn test_tail_reader_truncated() {
+  let lines = common::read_file_as_lines(&common::get_test_log());
+  let dir = common::TempDir::new("truncated");
+  let path = dir.join("x.log");
+  std::fs::write(&path, lines[..5].join("\n") + "\n").unwrap();
+
+  let mut reader = TailReader::open(&path, Config::default())
+    .await
+    .expect("Failed to create reader");
+  reader.start().await.expect("Failed to start reader");
+
+  assert_eq!(collect(&mut reader).await, lines[..5]);
+
+  // 原地截断文件，并写入更短的新内容
+  std::fs::write(&path, "first\nsecond\n").unwrap();
+  assert_eq!(
+    collect(&mut reader).await,
+    vec!["<truncated>", "first", "second"]
+  );
+
+  reader.stop().await.expect("Failed to stop reader");
+}
+
+#[tokio::test]
+async fn test_tail_reader_no_reemit_after_rename() {
+  let root = common::TempDir::new("tail");
+
+  let lines = common::read_file_as_lines(&common::get_test_log());
+  let log_path = root.join("x.log");
+  std::fs::write(&log_path, lines[..5].join("\n") + "\n").unwrap();
+
+  let mut reader = TailReader::open(&log_path, Config::default())
+    .await
+    .expect("Failed to create reader");
+  reader.start().await.expect("Failed to start reader");
+
+  // 收集一段时间内的所有事件
+  let mut content = LinkedList::new();
+  let mut renamed = None;
+  let renamed_path = root.join("x.log.1");
+  for i in 0..10 {
+    // 读取完成后，重命名文件，并在重命名后的文件中追加新行
+    if i == 3 {
+      std::fs::rename(&log_path, &renamed_path).unwrap();
+    }
+    if i == 6 {
+      let mut file = tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(&renamed_path)
+        .await
+        .unwrap();
+      file
+        .write_all((lines[5..8].join("\n") + "\n").as_bytes())
+        .await
+        .unwrap();
+    }
+    tokio::select! {
+      _ = tokio::time::sleep(tokio::time::Duration::from_millis(200)) => {},
+      Some(events) = reader.changed() => for event in events {
+        match event {
+          Event::NewHead(s) => content.push_front(String::from_utf8(s).unwrap()),
+          Event::NewTail(s) => content.push_back(String::from_utf8(s).unwrap()),
+          Event::Renamed(path) => renamed = Some(path),
+          _ => {}
+        }
+      }
+    }
+  }
+
+  reader.stop().await.expect("Failed to stop reader");
+
+  // 重命名前已经读取的行不会被重复发送
+  assert_eq!(content.into_iter().collect::<Vec<_>>(), lines[..8]);
+  assert_eq!(renamed, Some(renamed_path));
+}
+
+#[tokio::test]
+async fn test_tail_reader_idle() {
+  let dir = common::TempDir::new("idle");
+  let path = dir.join("x.log");
+  std::fs::write(&path, "first\n").unwrap();
+
+  let config = Config {
+    idle_timeout: Some(std::time::Duration::from_millis(300)),
+    ..Default::default()
+  };
+  let mut reader = TailReader::open(&path, config)
+    .await
+    .expect("Failed to create reader");
+  reader.start().await.expect("Failed to start reader");
+
+  // 没有新内容写入时，超时后只会报告一次空闲
+  assert_eq!(collect(&mut reader).await, vec!["first", "<idle>"]);
+  assert!(collect(&mut reader).await.is_empty());
+
+  // 写入新内容后重新计时，之后再次报告空闲
+  let mut file = tokio::fs::OpenOptions::new()
+    .append(true)
+    .open(&path)
+    .await
+    .unwrap();
+  file.write_all(b"second\n").await.unwrap();
+  file.flush().await.unwrap();
+  assert_eq!(collect(&mut reader).await, vec!["second", "<idle>"]);
+
+  reader.stop().await.expect("Failed to stop reader");
+}
+
+#[tokio::test]
+async fn test_readers_long_lines() {
+  let dir = common::TempDir::new("long_lines");
+  let path = dir.join("x.log");
+  let true_content: Vec<String> = (0..20)
+    .map(|i| format!("{i:02} {}", "x".repeat(1997)))
+    .collect();
+  std::fs::write(&path, true_content.join("\n") + "\n").unwrap();
+
+  // 自动选择的缓冲区足以容纳多个完整的行
+  let sampled = reader::sample_buffer_size(&path, 4096).await.unwrap();
+  assert!(sampled >= 4 * 2000);
+
+  // 无论缓冲区比行短还是比行长，都能完整地读出每一行
+  for buffer_size in [256, 4096, sampled] {
+    let config = || Config {
+      buffer_size,
+      ..Default::default()
+    };
+    read_file_with_config::<TailReader>(&path, config(), &true_content).await;
+    read_file_with_config::<HeadReader>(&path, config(), &true_content).await;
+  }
+}
+
+#[tokio::test]
+async fn test_readers_multibyte_across_buffers() {
+  let dir = common::TempDir::new("multibyte");
+  let path = dir.join("x.log");
+  let true_content: Vec<String> = vec![
+    "内核日志：设备已连接".to_string(),
+    "emoji 🚀🚀 and accents éè".to_string(),
+    "混合 mixed 文字 🎉".to_string(),
+  ];
+  std::fs::write(&path, true_content.join("\n") + "\n").unwrap();
+
+  // 缓冲区的长度与字符的字节数互质，多字节字符必然会跨越缓冲区的边界
+  for buffer_size in [5, 7] {
+    let config = || Config {
+      buffer_size,
+      ..Default::default()
+    };
+    read_file_with_config::<TailReader>(&path, config(), &true_content).await;
+    read_file_with_config::<HeadReader>(&path, config(), &true_content).await;
+  }
+}
+
+#[tokio::test]
+async fn test_readers_truncate_long_line_at_char_boundary() {
+  let dir = common::TempDir::new("truncated_line");
+  let path = dir.join("x.log");
+  std::fs::write(&path, "你".repeat(reader::MAX_LINE_LENGTH / 2) + "\nend\n").unwrap();
+
+  // 过长的行被截断时，不会留下半个字符
+  let true_content = vec!["你".repeat(reader::MAX_LINE_LENGTH / 3), "end".to_string()];
+  read_file_with_config::<TailReader>(&path, Config::default(), &true_content).await;
+  read_file_with_config::<HeadReader>(&path, Config::default(), &true_content).await;
+}
+
+#[tokio::test]
+async fn test_tail_reader_tail_lines() {
+  let dir = common::TempDir::new("tail_lines");
+  let path = dir.join("x.log");
+  let content: String = (0..20).map(|i| format!("line {i:02}\n")).collect();
+  std::fs::write(&path, content).unwrap();
+
+  // 缓冲区很小，需要往前读取多次才能读够行数
+  let config = Config {
+    buffer_size: 16,
+    idle_timeout: Some(std::time::Duration::from_millis(300)),
+    tail_lines: Some(5),
+    ..Default::default()
+  };
+  let mut reader = TailReader::open(&path, config)
+    .await
+    .expect("Failed to create reader");
+  reader.start().await.expect("Failed to start reader");
+
+  // 从末尾往前恰好读取 5 行
+  assert_eq!(
+    collect(&mut reader).await,
+    vec!["line 15", "line 16", "line 17", "line 18", "line 19"]
+  );
+
+  // 之后追加的内容不会被读取，也不会报告空闲
+  let mut file = tokio::fs::OpenOptions::new()
+    .append(true)
+    .open(&path)
+    .await
+    .unwrap();
+  file.write_all(b"line 20\n").await.unwrap();
+  file.flush().await.unwrap();
+  assert!(collect(&mut reader).await.is_empty());
+
+  reader.stop().await.expect("Failed to stop reader");
+}
+
+#[tokio::test]
+async fn test_reader_invalid_utf8_round_trip() {
+  let dir = common::TempDir::new("binary");
+  let path = dir.join("x.log");
+  let lines: Vec<&[u8]> = vec![
+    b"\x7fELF\x02\x01\x01\x00\xff\xfe",
+    b"2026-01-17T10:22:55.642782+08:00 host tag[1]: message",
+    b"\xc3\x28 broken \xa0\xa1",
+  ];
+  std::fs::write(&path, [lines.join(&b'\n'), b"\n".to_vec()].concat()).unwrap();
+
+  let mut reader = HeadReader::open(&path, Config::default())
+    .await
+    .expect("Failed to create reader");
+  reader.start().await.expect("Failed to start reader");
+
+  let mut exported = vec![];
+  loop {
+    tokio::select! {
+      _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)) => break,
+      Some(events) = reader.changed() => for event in events {
+        if let Event::NewTail(s) = event {
+          exported.push(LogLine::from_bytes(s).to_syslog_bytes());
+        }
+      }
+    }
+  }
+
+  reader.stop().await.expect("Failed to stop reader");
+
+  assert_eq!(exported, lines);
+}
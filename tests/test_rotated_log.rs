@@ -1,5 +1,7 @@
 use itertools::Itertools;
-use rs_syslog_viewer::log::{Config, DataBoard, Index, IterNextNth, LogLine, RotatedLog};
+use rs_syslog_viewer::log::{
+  Config, DataBoard, Index, IterNextNth, Label, LogFilter, LogLine, RotatedLog, RotationNaming,
+};
 use std::collections::{BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -35,7 +37,7 @@ async fn test_rotated_log() {
   let start = Instant::now();
 
   while start.elapsed() < Duration::from_secs(2) {
-    assert!(log.prepare().await);
+    assert!(log.prepare(&data_board).await);
 
     tokio::select! {
       _ = tokio::time::sleep(Duration::from_millis(300)) => {
@@ -106,7 +108,7 @@ async fn test_rotated_log() {
 
       // 更新数据黑板中的标记记录
       let mut data_board = data_board.lock().await;
-      let mut tags = data_board.get_tags_mut();
+      let tags = data_board.get_tags_mut();
 
       let all_tags: HashSet<String> = tags.all().keys().cloned().collect();
       for tag in all_tags {
@@ -137,13 +139,560 @@ async fn test_rotated_log() {
       // 分析遍历结果是否正确，每次遍历都至少进行两次，第一次没有缓存、之后都有缓存
       for _ in 0..3 {
         let filtered_content: Vec<LogLine> =
-          common::collect_mut_lines(log.filtered_iter_forward_from_head(&tags));
+          common::collect_mut_lines(log.filtered_iter_forward_from_head(&*data_board));
         assert_eq!(filtered_content, true_filtered_content);
 
         let filtered_content: Vec<LogLine> =
-          common::collect_mut_lines(log.filtered_iter_backward_from_tail(&tags));
+          common::collect_mut_lines(log.filtered_iter_backward_from_tail(&*data_board));
         assert_eq!(filtered_content, true_reversed_filtered_content);
       }
     }
   }
 }
+
+#[tokio::test]
+async fn test_rotated_log_label_filter() {
+  let log_path = common::get_test_log();
+  let true_content: Vec<LogLine> =
+    common::read_all_files_as_lines(&common::get_test_root(), "test").unwrap();
+
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut log = RotatedLog::new(log_path.clone(), Config::default().with_eager_preload());
+
+  let start = Instant::now();
+  while start.elapsed() < Duration::from_secs(1) {
+    assert!(log.prepare(&data_board).await);
+
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(300)) => {},
+      _ = log.update(data_board.clone()) => {}
+    }
+  }
+
+  // 按日志等级过滤，每个条件都有自己的版本，即使条件的内容和之前的相同
+  for label in [Label::Error, Label::Warn, Label::Error] {
+    let is_kept = move |l: &LogLine| matches!(l, LogLine::Good(l) if l.label == label);
+    let filter = LogFilter::new(is_kept);
+    let true_filtered_content: Vec<LogLine> = true_content
+      .iter()
+      .filter(|l| is_kept(l))
+      .cloned()
+      .collect();
+    let true_reversed_filtered_content: Vec<LogLine> =
+      true_filtered_content.iter().rev().cloned().collect();
+    assert!(!true_filtered_content.is_empty());
+
+    // 第一次遍历没有缓存，之后都有缓存
+    for _ in 0..3 {
+      let content: Vec<LogLine> =
+        common::collect_mut_lines(log.filtered_iter_forward_from_head(filter.clone()));
+      assert_eq!(content, true_filtered_content);
+
+      let content: Vec<LogLine> =
+        common::collect_mut_lines(log.filtered_iter_backward_from_tail(filter.clone()));
+      assert_eq!(content, true_reversed_filtered_content);
+    }
+  }
+
+  // 切换回标签过滤后，缓存的链接失效，所有的日志都可见
+  let mut data_board = data_board.lock().await;
+  let content: Vec<LogLine> =
+    common::collect_mut_lines(log.filtered_iter_forward_from_head(&*data_board));
+  assert_eq!(content, true_content);
+
+  // 在数据黑板中隐藏某个日志等级，恰好过滤掉该等级的日志
+  data_board.toggle_label(Label::Unknown);
+  let true_filtered_content: Vec<LogLine> = true_content
+    .iter()
+    .filter(|l| !matches!(l, LogLine::Good(l) if l.label == Label::Unknown))
+    .cloned()
+    .collect();
+  for _ in 0..2 {
+    let content: Vec<LogLine> =
+      common::collect_mut_lines(log.filtered_iter_forward_from_head(&*data_board));
+    assert_eq!(content, true_filtered_content);
+  }
+
+  data_board.toggle_label(Label::Unknown);
+  let content: Vec<LogLine> =
+    common::collect_mut_lines(log.filtered_iter_backward_from_tail(&*data_board));
+  let true_reversed_content: Vec<LogLine> = true_content.iter().rev().cloned().collect();
+  assert_eq!(content, true_reversed_content);
+}
+
+#[tokio::test]
+async fn test_rotated_log_hide_info_logs() {
+  // 由 PRI 的 severity 给出 info、debug 等级的日志，和其他等级的日志交错
+  let root = common::TempDir::new("hide_info");
+  let lines: Vec<String> = ["<14>", "<15>", "", "<14>", "<11>", "<14>", "<15>", ""]
+    .iter()
+    .enumerate()
+    .map(|(i, pri)| format!("{pri}2026-02-15T12:00:0{i}.000000+08:00 host app[1]: line {i}"))
+    .collect();
+  std::fs::write(root.join("x.log"), lines.join("\n") + "\n").unwrap();
+  let true_content: Vec<LogLine> = lines.into_iter().map(LogLine::new).collect();
+
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut log = RotatedLog::new(root.join("x.log"), Config::default());
+  let start = Instant::now();
+  while start.elapsed() < Duration::from_millis(500) {
+    assert!(log.prepare(&data_board).await);
+
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(100)) => {},
+      _ = log.update(data_board.clone()) => {}
+    }
+  }
+
+  // 隐藏 info 等级后，恰好过滤掉 info 等级的日志，正向、逆向遍历的结果一致
+  let mut data_board = data_board.lock().await;
+  data_board.toggle_label(Label::Info);
+  let true_filtered_content: Vec<LogLine> = true_content
+    .iter()
+    .filter(|l| l.get_label() != Some(Label::Info))
+    .cloned()
+    .collect();
+  assert_eq!(true_filtered_content.len(), true_content.len() - 3);
+
+  let content: Vec<LogLine> =
+    common::collect_mut_lines(log.filtered_iter_forward_from_head(&*data_board));
+  assert_eq!(content, true_filtered_content);
+  let content: Vec<LogLine> =
+    common::collect_mut_lines(log.filtered_iter_backward_from_tail(&*data_board));
+  let true_reversed_content: Vec<LogLine> = true_filtered_content.into_iter().rev().collect();
+  assert_eq!(content, true_reversed_content);
+
+  // 再隐藏 debug 等级，只剩下其他等级的日志，severity 为 3 的是 error 等级
+  data_board.toggle_label(Label::Debug);
+  let content: Vec<LogLine> =
+    common::collect_mut_lines(log.filtered_iter_forward_from_head(&*data_board));
+  assert_eq!(
+    content.iter().map(|l| l.get_label()).collect::<Vec<_>>(),
+    vec![
+      Some(Label::Unknown),
+      Some(Label::Error),
+      Some(Label::Unknown)
+    ]
+  );
+}
+
+#[tokio::test]
+async fn test_rotated_log_warm_links() {
+  let log_path = common::get_test_log();
+  let true_content: Vec<LogLine> =
+    common::read_all_files_as_lines(&common::get_test_root(), "test").unwrap();
+
+  // 分别加载一份预先建立跳转链路的日志，以及一份不建立的日志
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut warm = RotatedLog::new(log_path.clone(), Config::default().with_eager_preload());
+  let mut cold = RotatedLog::new(log_path.clone(), Config::default().with_eager_preload());
+  let start = Instant::now();
+  while start.elapsed() < Duration::from_secs(1) {
+    assert!(warm.prepare(&data_board).await);
+    assert!(cold.prepare(&data_board).await);
+
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(300)) => {},
+      _ = warm.update(data_board.clone()) => {}
+    }
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(300)) => {},
+      _ = cold.update(data_board.clone()) => {}
+    }
+  }
+
+  let mut data_board = data_board.lock().await;
+
+  // 不过滤任何日志时，不需要建立跳转链路
+  assert!(warm.warm_links(&data_board, 10));
+
+  for label in [Label::Unknown, Label::Warn] {
+    data_board.toggle_label(label);
+    let hidden = data_board.get_hidden_labels().clone();
+    let true_filtered_content: Vec<LogLine> = true_content
+      .iter()
+      .filter(|l| !matches!(l, LogLine::Good(l) if hidden.contains(&l.label)))
+      .cloned()
+      .collect();
+    let true_reversed_filtered_content: Vec<LogLine> =
+      true_filtered_content.iter().rev().cloned().collect();
+
+    // 每批只访问少量的日志，需要多批才能遍历完
+    let mut batches = 1;
+    while !warm.warm_links(&data_board, 16) {
+      batches += 1;
+    }
+    assert!(batches > 2);
+    assert!(warm.warm_links(&data_board, 16));
+
+    // 预先建立了跳转链路的迭代结果，与没有建立的相同
+    for log in [&mut warm, &mut cold] {
+      let content: Vec<LogLine> =
+        common::collect_mut_lines(log.filtered_iter_forward_from_head(&*data_board));
+      assert_eq!(content, true_filtered_content);
+      let content: Vec<LogLine> =
+        common::collect_mut_lines(log.filtered_iter_backward_from_tail(&*data_board));
+      assert_eq!(content, true_reversed_filtered_content);
+    }
+  }
+}
+
+#[tokio::test]
+async fn test_rotated_log_fraction() {
+  let log_path = common::get_test_log();
+  let true_content: Vec<LogLine> =
+    common::read_all_files_as_lines(&common::get_test_root(), "test").unwrap();
+
+  // 未加载任何内容时，总是处于开头
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut log = RotatedLog::new(log_path.clone(), Config::default());
+  assert_eq!(log.fraction(Index::zero()), 0.0);
+
+  // 加载所有的日志文件
+  let start = Instant::now();
+  while start.elapsed() < Duration::from_secs(2) {
+    assert!(log.prepare(&data_board).await);
+
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(300)) => {
+        log.set_want_older_log();
+      },
+      _ = log.update(data_board.clone()) => {}
+    }
+  }
+
+  let len = true_content.len();
+  assert_eq!(log.len(), len);
+  assert_eq!(log.fraction(log.first_index()), 0.0);
+  assert_eq!(log.fraction(log.last_index()), 1.0);
+
+  // 跨越多个日志文件时，相对位置按行数线性增长
+  for n in [1, len / 3, len / 2, len - 2] {
+    let index = log.step_index(log.first_index(), n as isize).ok().unwrap();
+    assert_eq!(log.position(index), n);
+    assert!((log.fraction(index) - n as f64 / (len - 1) as f64).abs() < 1e-9);
+  }
+
+  // 按相对位置找回索引，是上述过程的逆运算；越界的相对位置被限制在首尾
+  assert!(log.index_at_fraction(0.0) == log.first_index());
+  assert!(log.index_at_fraction(1.0) == log.last_index());
+  assert!(log.index_at_fraction(-0.5) == log.first_index());
+  assert!(log.index_at_fraction(1.5) == log.last_index());
+  for n in [1, len / 3, len / 2, len - 2] {
+    let index = log.index_at_fraction(n as f64 / (len - 1) as f64);
+    assert_eq!(log.position(index), n);
+    assert_eq!(log.get(index), Some(&true_content[n]));
+  }
+}
+
+#[tokio::test]
+async fn test_rotated_log_max_lines() {
+  let log_path = common::get_test_log();
+  let true_content: Vec<LogLine> =
+    common::read_all_files_as_lines(&common::get_test_root(), "test").unwrap();
+
+  // 限制内存中的日志行数量，并不断要求加载更旧的日志
+  let max_lines = 200;
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut log = RotatedLog::new(
+    log_path.clone(),
+    Config::default().with_max_lines(max_lines),
+  );
+
+  let start = Instant::now();
+  while start.elapsed() < Duration::from_secs(2) {
+    assert!(log.prepare(&data_board).await);
+
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(300)) => {
+        log.set_want_older_log();
+      },
+      _ = log.update(data_board.clone()) => {}
+    }
+  }
+
+  // 保留下来的总是最新的那部分连续日志
+  let len = log.len();
+  assert!(0 < len && len <= max_lines);
+  let true_content = &true_content[true_content.len() - len..];
+
+  let content: Vec<LogLine> = common::collect_lines(log.iter_forward_from_head());
+  assert_eq!(&content, true_content);
+
+  let reversed_content: Vec<LogLine> = common::collect_lines(log.iter_backward_from_tail());
+  let true_reversed_content: Vec<LogLine> = true_content.iter().rev().cloned().collect();
+  assert_eq!(&reversed_content, &true_reversed_content);
+
+  // 带过滤的迭代器也不会访问到被淘汰的日志行
+  let data_board = data_board.lock().await;
+  let content: Vec<LogLine> =
+    common::collect_mut_lines(log.filtered_iter_forward_from_head(&*data_board));
+  assert_eq!(&content, true_content);
+
+  // 被淘汰的日志行从统计中扣除，只剩下保留下来的日志的标签
+  let tags: BTreeSet<String> = data_board.get_tags().all().keys().cloned().collect();
+  assert_eq!(tags, common::all_tags(&true_content.to_vec()));
+  let good_lines = true_content.iter().filter(|log| !log.is_bad()).count();
+  assert_eq!(
+    data_board.snapshot().tag_counts.values().sum::<usize>(),
+    good_lines
+  );
+
+  assert_eq!(log.position(log.first_index()), 0);
+  assert!(log.step_index(log.first_index(), -1).is_err());
+}
+
+#[tokio::test]
+async fn test_rotated_log_chunk_capacity() {
+  let log_path = common::get_test_log();
+  let chunk_capacity = 16;
+
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut log = RotatedLog::new(
+    log_path.clone(),
+    Config::default().with_chunk_capacity(chunk_capacity),
+  );
+
+  let start = Instant::now();
+  while start.elapsed() < Duration::from_secs(1) {
+    assert!(log.prepare(&data_board).await);
+
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(300)) => {},
+      _ = log.update(data_board.clone()) => {}
+    }
+  }
+
+  // 从头部与尾部两个方向插入时，各自最多只有一个 chunk 没有填满
+  let log_file = log.log_files().next().unwrap();
+  let len = log_file.data().len();
+  let chunk_count = log_file.data().chunk_count();
+  assert_eq!(len, common::read_file_as_lines(&log_path).len());
+  assert_eq!(log_file.data().chunk_capacity(), chunk_capacity);
+  assert!(len.div_ceil(chunk_capacity) <= chunk_count);
+  assert!(chunk_count <= len.div_ceil(chunk_capacity) + 1);
+}
+
+#[tokio::test]
+async fn test_rotated_log_eager_preload() {
+  let log_path = common::get_test_log();
+  let true_content: Vec<LogLine> =
+    common::read_all_files_as_lines(&common::get_test_root(), "test").unwrap();
+
+  // 按需加载：不断要求加载更旧的日志
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut lazy_log = RotatedLog::new(log_path.clone(), Config::default());
+
+  let start = Instant::now();
+  while start.elapsed() < Duration::from_secs(2) {
+    assert!(lazy_log.prepare(&data_board).await);
+
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(300)) => {
+        lazy_log.set_want_older_log();
+      },
+      _ = lazy_log.update(data_board.clone()) => {}
+    }
+  }
+
+  // 预加载：不需要额外的要求
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut eager_log = RotatedLog::new(log_path.clone(), Config::default().with_eager_preload());
+
+  let start = Instant::now();
+  while start.elapsed() < Duration::from_secs(1) {
+    assert!(eager_log.prepare(&data_board).await);
+
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(300)) => {},
+      _ = eager_log.update(data_board.clone()) => {}
+    }
+  }
+
+  let lazy_content: Vec<LogLine> = common::collect_lines(lazy_log.iter_forward_from_head());
+  let eager_content: Vec<LogLine> = common::collect_lines(eager_log.iter_forward_from_head());
+  assert_eq!(&eager_content, &true_content);
+  assert_eq!(&eager_content, &lazy_content);
+}
+
+#[tokio::test]
+async fn test_rotated_log_hosts_filter() {
+  let log_path = common::get_test_root().join("hosts.log");
+  let true_content: Vec<LogLine> = common::read_file_as_lines(&log_path)
+    .into_iter()
+    .map(LogLine::new)
+    .collect();
+
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut log = RotatedLog::new(log_path.clone(), Config::default());
+
+  let start = Instant::now();
+  while start.elapsed() < Duration::from_secs(1) {
+    assert!(log.prepare(&data_board).await);
+
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(300)) => {},
+      _ = log.update(data_board.clone()) => {}
+    }
+  }
+
+  // 从日志中提取出所有的主机名
+  let mut data_board = data_board.lock().await;
+  let hosts: BTreeSet<String> = data_board.get_hosts().all().keys().cloned().collect();
+  assert_eq!(
+    hosts,
+    BTreeSet::from(["host-a".to_string(), "host-b".to_string()])
+  );
+
+  // 取消选择某个主机后，它的日志被过滤
+  data_board.get_hosts_mut().unset("host-b");
+  data_board.get_hosts_mut().update_version();
+
+  let true_filtered_content: Vec<LogLine> = true_content
+    .iter()
+    .filter(|l| matches!(l, LogLine::Good(l) if l.hostname == "host-a"))
+    .cloned()
+    .collect();
+  for _ in 0..2 {
+    let content: Vec<LogLine> =
+      common::collect_mut_lines(log.filtered_iter_forward_from_head(&*data_board));
+    assert_eq!(content, true_filtered_content);
+  }
+
+  // 重新选中后，所有的日志都可见
+  data_board.get_hosts_mut().set("host-b");
+  data_board.get_hosts_mut().update_version();
+
+  let content: Vec<LogLine> =
+    common::collect_mut_lines(log.filtered_iter_backward_from_tail(&*data_board));
+  let true_reversed_content: Vec<LogLine> = true_content.iter().rev().cloned().collect();
+  assert_eq!(content, true_reversed_content);
+}
+
+/// 在临时目录中，按从旧到新的顺序，将测试日志切分写入给定名称的文件中，返回日志的真值
+fn write_rotated_files(dir_name: &str, names: &[&str]) -> (common::TempDir, Vec<LogLine>) {
+  let root = common::TempDir::new(dir_name);
+
+  let lines = common::read_file_as_lines(&common::get_test_log());
+  let chunk_size = lines.len().div_ceil(names.len());
+  for (name, chunk) in names.iter().zip(lines.chunks(chunk_size)) {
+    std::fs::write(root.join(name), chunk.join("\n") + "\n").unwrap();
+  }
+
+  // 不属于该日志的文件会被忽略
+  std::fs::write(root.join("x.log.bak"), "garbage\n").unwrap();
+
+  (root, lines.into_iter().map(LogLine::new).collect())
+}
+
+/// 不断要求加载更旧的日志，直到所有日志加载完成
+async fn load_all_rotated(log_path: PathBuf, config: Config) -> Vec<LogLine> {
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut log = RotatedLog::new(log_path, config);
+
+  let start = Instant::now();
+  while start.elapsed() < Duration::from_secs(3) {
+    assert!(log.prepare(&data_board).await);
+
+    tokio::select! {
+      _ = tokio::time::sleep(Duration::from_millis(100)) => {
+        log.set_want_older_log();
+      },
+      _ = log.update(data_board.clone()) => {}
+    }
+  }
+
+  common::collect_lines(log.iter_forward_from_head())
+}
+
+#[tokio::test]
+async fn test_rotated_log_numeric_suffix_order() {
+  // x.log.10 比 x.log.9 更旧，尽管按字符串比较时它更小
+  let mut names: Vec<String> = (1..=10).rev().map(|n| format!("x.log.{n}")).collect();
+  names.push("x.log".to_string());
+  let names: Vec<&str> = names.iter().map(String::as_str).collect();
+  let (root, true_content) = write_rotated_files("numeric", &names);
+
+  let content = load_all_rotated(root.join("x.log"), Config::default()).await;
+  assert_eq!(&content, &true_content);
+}
+
+#[tokio::test]
+async fn test_rotated_log_date_suffix_order() {
+  let names = [
+    "x.log-20241231",
+    "x.log-20250101",
+    "x.log-20250102",
+    "x.log",
+  ];
+  let (root, true_content) = write_rotated_files("dateext", &names);
+
+  let content = load_all_rotated(root.join("x.log"), Config::default()).await;
+  assert_eq!(&content, &true_content);
+}
+
+#[tokio::test]
+async fn test_rotated_log_dateext_naming() {
+  // 按 dateext 命名时，同目录下数字后缀的文件不属于该日志
+  let names = [
+    "x.log-20241231",
+    "x.log-20250101",
+    "x.log-20250102",
+    "x.log",
+  ];
+  let (root, true_content) = write_rotated_files("dateext_naming", &names);
+  std::fs::write(root.join("x.log.1"), "Jan  1 00:00:00 host tag: ignored\n").unwrap();
+
+  let config = Config::default().with_rotation_naming(RotationNaming::date_ext());
+  let content = load_all_rotated(root.join("x.log"), config).await;
+  assert_eq!(&content, &true_content);
+}
+
+#[tokio::test]
+async fn test_rotated_log_stable_index() {
+  let names = ["x.log.2", "x.log.1", "x.log"];
+  let (root, true_content) = write_rotated_files("stable_index", &names);
+  let latest_len = true_content.len() - 2 * true_content.len().div_ceil(3);
+
+  // 先只加载最新的一份文件，记下指向其中一行的索引
+  let data_board = Arc::new(Mutex::new(DataBoard::default()));
+  let mut log = RotatedLog::new(root.join("x.log"), Config::default());
+  let run = async |log: &mut RotatedLog, want_older_log: bool| {
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_millis(500) {
+      assert!(log.prepare(&data_board).await);
+      if want_older_log {
+        log.set_want_older_log();
+      }
+      tokio::select! {
+        _ = tokio::time::sleep(Duration::from_millis(100)) => {},
+        _ = log.update(data_board.clone()) => {}
+      }
+    }
+  };
+  run(&mut log, false).await;
+  assert_eq!(log.len(), latest_len);
+  let index = log.step_index(log.last_index(), -1).unwrap();
+  let line = log.get(index).cloned();
+  assert_eq!(line.as_ref(), true_content.iter().rev().nth(1));
+
+  // 加载更旧的文件后，索引仍然指向同一行
+  run(&mut log, true).await;
+  assert_eq!(log.len(), true_content.len());
+  assert_eq!(log.get(index).cloned(), line);
+
+  // 删除中间的文件后，索引仍然指向同一行，且可以跨过被删除的文件遍历
+  std::fs::remove_file(root.join("x.log.1")).unwrap();
+  run(&mut log, false).await;
+  assert!(log.len() < true_content.len());
+  assert_eq!(log.get(index).cloned(), line);
+  assert_eq!(
+    log.position(index),
+    log.len() - 2,
+    "index should keep its position"
+  );
+  let content = common::collect_lines(log.iter_forward_from_head());
+  assert_eq!(content.len(), log.len());
+  assert_eq!(content.first(), true_content.first());
+  assert_eq!(content.last(), true_content.last());
+}
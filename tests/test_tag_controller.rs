@@ -0,0 +1,231 @@
+use rs_syslog_viewer::app::{
+  Controller, LogHub,
+  controller::{TagController, TagKind, TagMatchMode},
+};
+use rs_syslog_viewer::log::Config;
+use rs_syslog_viewer::ui::ViewPortEx;
+use std::path::PathBuf;
+
+mod common;
+
+/// 在临时目录中写入一份带有给定标签的日志，返回其所在目录
+fn write_tags_log(name: &str, tags: &[&str]) -> common::TempDir {
+  let root = common::TempDir::new(name);
+
+  let content: String = tags
+    .iter()
+    .enumerate()
+    .map(|(i, tag)| format!("2026-02-15T12:00:0{i}.000000+08:00 host {tag}[1]: line {i}\n"))
+    .collect();
+  std::fs::write(root.join("tags.log"), content).unwrap();
+  root
+}
+
+/// 打开日志，并反复运行控制器，使得后台有机会加载日志
+async fn open_tags_log(root: PathBuf, ctrl: &mut TagController) -> LogHub {
+  open_tags_log_with(root, Config::default(), ctrl).await
+}
+
+/// 按给定的配置打开日志，并反复运行控制器，使得后台有机会加载日志
+async fn open_tags_log_with(root: PathBuf, config: Config, ctrl: &mut TagController) -> LogHub {
+  let mut log_hub = LogHub::open(
+    root,
+    [("tags".to_string(), config)].into(),
+    Config::default(),
+  );
+  ctrl.view_mut().ui_mut().set_height(10);
+  run_for_a_while(&mut log_hub, ctrl).await;
+  log_hub
+}
+
+/// 反复运行控制器，使得后台有机会读取日志的变化
+async fn run_for_a_while(log_hub: &mut LogHub, ctrl: &mut TagController) {
+  for _ in 0..5 {
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let mut data = log_hub.data().await;
+    ctrl.run_once(&mut data);
+  }
+}
+
+/// 运行一帧，返回展示区中与搜索匹配的标签
+async fn matched_tags(log_hub: &mut LogHub, ctrl: &mut TagController) -> Vec<String> {
+  let mut data = log_hub.data().await;
+  ctrl.run_once(&mut data);
+  ctrl
+    .view_mut()
+    .data()
+    .iter()
+    .map(|(k, _)| k.clone())
+    .collect()
+}
+
+#[tokio::test]
+async fn test_tag_search_case_insensitive() {
+  let root = write_tags_log(
+    "tag_case",
+    &["systemd-journald", "kernel", "Systemd", "cron"],
+  );
+  let mut ctrl = TagController::new(TagKind::Tag);
+  let mut log_hub = open_tags_log(root.to_path_buf(), &mut ctrl).await;
+
+  // 大写的搜索可以匹配小写的标签，反之亦然
+  ctrl.search("SYSTEMD".to_string());
+  assert_eq!(
+    matched_tags(&mut log_hub, &mut ctrl).await,
+    vec!["Systemd", "systemd-journald"]
+  );
+
+  // 搜索变长、变短时的增量匹配，同样不区分大小写
+  ctrl.search("SYSTEMD-J".to_string());
+  assert_eq!(
+    matched_tags(&mut log_hub, &mut ctrl).await,
+    vec!["systemd-journald"]
+  );
+  ctrl.search("sYs".to_string());
+  assert_eq!(
+    matched_tags(&mut log_hub, &mut ctrl).await,
+    vec!["Systemd", "systemd-journald"]
+  );
+
+  // 只有大小写变化的搜索，结果不变
+  ctrl.search("SYS".to_string());
+  assert_eq!(
+    matched_tags(&mut log_hub, &mut ctrl).await,
+    vec!["Systemd", "systemd-journald"]
+  );
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_tag_search_fuzzy() {
+  let root = write_tags_log("tag_fuzzy", &["systemd-journald", "kernel", "sshd", "cron"]);
+  let mut ctrl = TagController::new(TagKind::Tag);
+  let mut log_hub = open_tags_log(root.to_path_buf(), &mut ctrl).await;
+
+  // 子串匹配下没有标签包含 sjd
+  ctrl.search("sjd".to_string());
+  assert!(matched_tags(&mut log_hub, &mut ctrl).await.is_empty());
+
+  // 切换到模糊匹配后，搜索不变也会重新匹配
+  assert_eq!(ctrl.toggle_match_mode(), TagMatchMode::Fuzzy);
+  assert_eq!(
+    matched_tags(&mut log_hub, &mut ctrl).await,
+    vec!["systemd-journald"]
+  );
+
+  // 搜索变短时同样重新匹配
+  ctrl.search("sd".to_string());
+  assert_eq!(
+    matched_tags(&mut log_hub, &mut ctrl).await,
+    vec!["sshd", "systemd-journald"]
+  );
+
+  // 切换回子串匹配
+  ctrl.set_match_mode(TagMatchMode::Substring);
+  assert!(matched_tags(&mut log_hub, &mut ctrl).await.is_empty());
+
+  log_hub.close().await;
+}
+
+/// 运行一帧，返回光标所在的标签
+async fn cursor_tag(log_hub: &mut LogHub, ctrl: &mut TagController) -> String {
+  let mut data = log_hub.data().await;
+  ctrl.run_once(&mut data);
+  let view = ctrl.view_mut();
+  view.data()[view.ui().cursor()].0.clone()
+}
+
+#[tokio::test]
+async fn test_tag_cursor_kept_near_on_search() {
+  let root = write_tags_log("tag_cursor", &["apple", "banana", "cherry", "date"]);
+  let mut ctrl = TagController::new(TagKind::Tag);
+  let mut log_hub = open_tags_log(root.to_path_buf(), &mut ctrl).await;
+
+  ctrl.view_mut().ui_mut().want_move_cursor(2);
+  assert_eq!(cursor_tag(&mut log_hub, &mut ctrl).await, "cherry");
+
+  // 光标所在的标签被搜索过滤掉后，光标落在其后相邻的标签上，而不是回到顶部
+  ctrl.search("a".to_string());
+  assert_eq!(cursor_tag(&mut log_hub, &mut ctrl).await, "date");
+
+  // 其后没有标签时，落在其前相邻的标签上
+  ctrl.search("an".to_string());
+  assert_eq!(cursor_tag(&mut log_hub, &mut ctrl).await, "banana");
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_tag_cursor_kept_on_reopen() {
+  let tags = [
+    "apple", "banana", "cherry", "date", "elder", "fig", "grape", "hazel", "ivy",
+  ];
+  let root = write_tags_log("tag_reopen", &tags);
+  let mut ctrl = TagController::new(TagKind::Tag);
+  let mut log_hub = open_tags_log(root.to_path_buf(), &mut ctrl).await;
+
+  ctrl.view_mut().ui_mut().want_move_cursor(7);
+  assert_eq!(cursor_tag(&mut log_hub, &mut ctrl).await, "hazel");
+
+  // 页面关闭期间控制器照常运行，光标不会被重置
+  for _ in 0..3 {
+    assert_eq!(cursor_tag(&mut log_hub, &mut ctrl).await, "hazel");
+  }
+
+  // 以更矮的高度重新打开（如从全屏切换为侧边页面）后，光标仍然指向原来的标签
+  ctrl.view_mut().ui_mut().set_height(3);
+  assert_eq!(cursor_tag(&mut log_hub, &mut ctrl).await, "hazel");
+  ctrl.view_mut().ui_mut().set_height(10);
+  assert_eq!(cursor_tag(&mut log_hub, &mut ctrl).await, "hazel");
+
+  log_hub.close().await;
+}
+
+#[tokio::test]
+async fn test_tag_removed_on_eviction() {
+  let root = write_tags_log("tag_evict", &["apple", "banana"]);
+  let mut ctrl = TagController::new(TagKind::Tag);
+  let config = Config::default().with_max_lines(2).with_chunk_capacity(1);
+  let mut log_hub = open_tags_log_with(root.to_path_buf(), config, &mut ctrl).await;
+  assert_eq!(
+    matched_tags(&mut log_hub, &mut ctrl).await,
+    vec!["apple", "banana"]
+  );
+
+  // 取消选中 apple 后，写入新的日志，使得旧的两行都被淘汰
+  ctrl.toggle();
+  let _ = matched_tags(&mut log_hub, &mut ctrl).await;
+  let append = |lines: &[(u32, &str)]| {
+    let content: String = lines
+      .iter()
+      .map(|(i, tag)| format!("2026-02-15T12:00:{i}.000000+08:00 host {tag}[1]: line {i}\n"))
+      .collect();
+    let mut file = std::fs::OpenOptions::new()
+      .append(true)
+      .open(root.join("tags.log"))
+      .unwrap();
+    std::io::Write::write_all(&mut file, content.as_bytes()).unwrap();
+  };
+  append(&[(10, "cherry"), (11, "date")]);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+
+  // 被淘汰的标签不再展示
+  assert_eq!(
+    matched_tags(&mut log_hub, &mut ctrl).await,
+    vec!["cherry", "date"]
+  );
+
+  // 再次出现时恢复之前的选择状态
+  append(&[(12, "apple")]);
+  run_for_a_while(&mut log_hub, &mut ctrl).await;
+  let mut data = log_hub.data().await;
+  ctrl.run_once(&mut data);
+  drop(data);
+  assert_eq!(
+    ctrl.view_mut().data().iter().cloned().collect::<Vec<_>>(),
+    vec![("apple".to_string(), false), ("date".to_string(), true)]
+  );
+
+  log_hub.close().await;
+}